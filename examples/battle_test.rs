@@ -1,5 +1,8 @@
 use poke_engine::engine::battle_environment::{run_random_battle, MctsPlayer, RandomPlayer};
+use poke_engine::mcts::MctsConfig;
+use poke_engine::rng::random_seed;
 use std::fs;
+use std::time::Duration;
 
 fn main() {
     // Load the JSON data
@@ -10,7 +13,10 @@ fn main() {
     let movedex_json = fs::read_to_string("data/moves.json").expect("Failed to read moves.json");
 
     // Create players - demonstrate MCTS player
-    let player_one = Box::new(MctsPlayer::new("MCTSBot".to_string(), 200)); // 200ms search time
+    let player_one = Box::new(MctsPlayer::new(
+        "MCTSBot".to_string(),
+        MctsConfig::with_max_time(Duration::from_millis(200)),
+    ));
     let player_two = Box::new(RandomPlayer::new("RandomBot".to_string()));
 
     // Run a battle
@@ -22,6 +28,7 @@ fn main() {
         player_two,
         100,  // max turns
         true, // verbose
+        random_seed(),
     );
 
     // Print summary
@@ -32,6 +39,7 @@ fn main() {
         None => println!("Draw"),
     }
     println!("Total turns: {}", result.turn_count);
+    println!("Seed: {}", result.seed);
 
     // Print some turn details
     println!("\n=== Turn History Sample ===");
@@ -15,6 +15,7 @@ from ._poke_engine import (
     calculate_damage as _calculate_damage,
     mcts as _mcts,
     id as _id,
+    MctsConfig as _MctsConfig,
 )
 
 
@@ -32,12 +33,23 @@ class IterativeDeepeningResult:
     :type matrix: int
     :param depth_searched: The depth that was searched to
     :type depth_searched: int
+    :param side_one_probabilities: How often side_one plays each of its moves
+        in the Nash equilibrium of the matrix
+    :type side_one_probabilities: list[float]
+    :param side_two_probabilities: How often side_two plays each of its moves
+        in the Nash equilibrium of the matrix
+    :type side_two_probabilities: list[float]
+    :param nash_value: side_one's expected score in the Nash equilibrium
+    :type nash_value: float
     """
 
     side_one: list[str]
     side_two: list[str]
     matrix: list[float]
     depth_searched: int
+    side_one_probabilities: list[float]
+    side_two_probabilities: list[float]
+    nash_value: float
 
     @classmethod
     def _from_rust(cls, rust_result):
@@ -46,6 +58,9 @@ class IterativeDeepeningResult:
             side_two=rust_result.s2,
             matrix=rust_result.matrix,
             depth_searched=rust_result.depth_searched,
+            side_one_probabilities=rust_result.s1_probabilities,
+            side_two_probabilities=rust_result.s2_probabilities,
+            nash_value=rust_result.nash_value,
         )
 
     def get_safest_move(self) -> str:
@@ -138,7 +153,56 @@ def generate_instructions(state: State, side_one_move: str, side_two_move: str):
     return _gi(state._into_rust_obj(), side_one_move, side_two_move)
 
 
-def monte_carlo_tree_search(state: State, duration_ms: int = 1000) -> MctsResult:
+@dataclass
+class MctsConfig:
+    """
+    Parameters of a Monte Carlo Tree Search
+
+    :param exploration_constant: c in the UCB1 exploration term sqrt(c * ln(parent visits) / visits)
+    :type exploration_constant: float
+    :param eval_scale: How quickly evaluations relative to the root are squashed towards 0 or 1
+    :type eval_scale: float
+    :param max_iterations: The search stops after this many iterations, even if time remains
+    :type max_iterations: int
+    :param threads: Number of independent trees searched in parallel and merged
+    :type threads: int
+    :param move_selection: How the move is picked from the result: "most-visits" or "best-average"
+    :type move_selection: str
+    :param rollout_policy: How new nodes are scored: "static" evaluates them directly,
+        "random" and "greedy-damage" play turns forward first
+    :type rollout_policy: str
+    :param rollout_turns: The most turns a "random" or "greedy-damage" rollout plays
+    :type rollout_turns: int
+    :param damage_bucket: Outcomes that lead to the same state, or whose damage rolls are in
+        the same bucket of this many hp, are merged into one node. None keeps every outcome
+    :type damage_bucket: int
+    """
+
+    exploration_constant: float = 2.0
+    eval_scale: float = 0.0125
+    max_iterations: int = 10_000_000
+    threads: int = 1
+    move_selection: str = "most-visits"
+    rollout_policy: str = "static"
+    rollout_turns: int = 10
+    damage_bucket: int = None
+
+    def _into_rust_obj(self):
+        return _MctsConfig(
+            exploration_constant=self.exploration_constant,
+            eval_scale=self.eval_scale,
+            max_iterations=self.max_iterations,
+            threads=self.threads,
+            move_selection=self.move_selection,
+            rollout_policy=self.rollout_policy,
+            rollout_turns=self.rollout_turns,
+            damage_bucket=self.damage_bucket,
+        )
+
+
+def monte_carlo_tree_search(
+    state: State, duration_ms: int = 1000, config: MctsConfig = None, seed: int = None
+) -> MctsResult:
     """
     Perform monte-carlo-tree-search on the given state and for the given duration
 
@@ -146,10 +210,18 @@ def monte_carlo_tree_search(state: State, duration_ms: int = 1000) -> MctsResult
     :type state: State
     :param duration_ms: time in milliseconds to run the search
     :type duration_ms: int
+    :param config: parameters of the search, defaults are used if not given
+    :type config: MctsConfig
+    :param seed: seed for the search's random choices, a search limited by
+        `config.max_iterations` gives the same result every time it is run with the same seed
+    :type seed: int
     :return: the result of the search
     :rtype: MctsResult
     """
-    return MctsResult._from_rust(_mcts(state._into_rust_obj(), duration_ms))
+    rust_config = config._into_rust_obj() if config is not None else None
+    return MctsResult._from_rust(
+        _mcts(state._into_rust_obj(), duration_ms, rust_config, seed)
+    )
 
 
 def iterative_deepening_expectiminimax(
@@ -62,6 +62,10 @@ class Pokemon:
     :type nature: str
     :param evs: The evs of the Pokemon
     :type evs: tuple[int, int, int, int, int, int]
+    :param ivs: The ivs of the Pokemon
+    :type ivs: tuple[int, int, int, int, int, int]
+    :param gender: The gender of the Pokemon: male, female or genderless
+    :type gender: str
     :param attack: The attack stat of the Pokemon
     :type attack: int
     :param defense: The defense stat of the Pokemon
@@ -86,6 +90,8 @@ class Pokemon:
     :type terastallized: bool
     :param tera_type: The tera-type of the Pokemon
     :type tera_type: str
+    :param gigantamax: Whether the Pokemon changes into its G-Max forme when it dynamaxes
+    :type gigantamax: bool
     """
 
     id: str = ""
@@ -99,6 +105,8 @@ class Pokemon:
     item: str = "none"
     nature: str = "serious"
     evs: tuple[int, int, int, int, int, int] = (85, 85, 85, 85, 85, 85)
+    ivs: tuple[int, int, int, int, int, int] = (31, 31, 31, 31, 31, 31)
+    gender: str = "genderless"
     attack: int = 100
     defense: int = 100
     special_attack: int = 100
@@ -111,6 +119,7 @@ class Pokemon:
     moves: list[Move] = field(default_factory=list)
     terastallized: bool = False
     tera_type: str = "typeless"
+    gigantamax: bool = False
 
     def _into_rust_obj(self):
         if len(self.types) == 1:
@@ -137,6 +146,8 @@ class Pokemon:
             item=self.item,
             nature=self.nature,
             evs=self.evs,
+            ivs=self.ivs,
+            gender=self.gender,
             attack=self.attack,
             defense=self.defense,
             special_attack=self.special_attack,
@@ -149,6 +160,7 @@ class Pokemon:
             moves=[m._into_rust_obj() for m in self.moves],
             terastallized=self.terastallized,
             tera_type=self.tera_type,
+            gigantamax=self.gigantamax,
         )
 
 
@@ -258,6 +270,8 @@ class VolatileStatusDurations:
     :type taunt: int
     :param yawn:
     :type yawn: int
+    :param dynamax:
+    :type dynamax: int
     """
 
     confusion: int = 0
@@ -266,6 +280,7 @@ class VolatileStatusDurations:
     slowstart: int = 0
     taunt: int = 0
     yawn: int = 0
+    dynamax: int = 0
 
     def _into_rust_obj(self):
         return _VolatileStatusDurations(
@@ -275,6 +290,7 @@ class VolatileStatusDurations:
             slowstart=self.slowstart,
             taunt=self.taunt,
             yawn=self.yawn,
+            dynamax=self.dynamax,
         )
 
 
@@ -331,6 +347,10 @@ class Side:
     :param switch_out_move_second_saved_move: If `slow_uturn_move` is active,
         this is the move that will be executed after the switch
     :type switch_out_move_second_saved_move: str
+    :param dynamax_used: Whether this side has already used its dynamax
+    :type dynamax_used: bool
+    :param z_move_used: Whether this side has already used its Z-Move
+    :type z_move_used: bool
     """
 
     active_index: str = "0"
@@ -357,6 +377,8 @@ class Side:
     last_used_move: str = "move:none"
     slow_uturn_move: bool = False
     switch_out_move_second_saved_move: str = "none"
+    dynamax_used: bool = False
+    z_move_used: bool = False
 
     def _into_rust_obj(self):
         return _Side(
@@ -371,6 +393,8 @@ class Side:
             force_switch=self.force_switch,
             force_trapped=self.force_trapped,
             slow_uturn_move=self.slow_uturn_move,
+            dynamax_used=self.dynamax_used,
+            z_move_used=self.z_move_used,
             volatile_statuses=self.volatile_statuses,
             substitute_health=self.substitute_health,
             attack_boost=self.attack_boost,
@@ -436,6 +460,13 @@ class State:
     def to_string(self):
         return self._into_rust_obj().to_string()
 
+    def to_json(self):
+        return self._into_rust_obj().to_json()
+
     @classmethod
     def from_string(cls, state_str: str):
+        """
+        :param state_str: a state from `to_string` or `to_json`
+        :type state_str: str
+        """
         return _state_from_string(state_str)
@@ -5,6 +5,7 @@ from poke_engine import (
     Side,
     Move,
     Pokemon,
+    MctsConfig,
     monte_carlo_tree_search,
     generate_instructions,
     calculate_damage,
@@ -75,14 +76,50 @@ def test_state_can_be_converted_to_and_from_a_string():
     State.from_string(serialized)
 
 
+def test_state_can_be_converted_to_and_from_json():
+    json_state = state.to_json()
+    assert State.from_string(json_state).to_string() == state.to_string()
+
+
+def test_invalid_state_string_raises_value_error():
+    serialized = state.to_string().replace("/NONE;", "/NOTAWEATHER;", 1)
+    with pytest.raises(ValueError, match="invalid weather.weather_type: NOTAWEATHER"):
+        State.from_string(serialized)
+
+
 def test_monte_carlo_search():
     monte_carlo_tree_search(state, 10)
 
 
+def test_monte_carlo_search_with_config():
+    config = MctsConfig(max_iterations=1000, threads=2, move_selection="best-average")
+    result = monte_carlo_tree_search(state, 10, config)
+    assert result.total_visits <= 2000
+
+
+def test_monte_carlo_search_errors_with_unknown_move_selection():
+    with pytest.raises(ValueError):
+        monte_carlo_tree_search(state, 10, MctsConfig(move_selection="unknown"))
+
+
+def test_monte_carlo_search_with_random_rollouts():
+    config = MctsConfig(max_iterations=100, rollout_policy="random", rollout_turns=3)
+    result = monte_carlo_tree_search(state, 10, config)
+    assert result.total_visits <= 100
+
+
 def test_iterative_deepening_search():
     iterative_deepening_expectiminimax(state, 10)
 
 
+def test_iterative_deepening_search_returns_a_mixed_strategy():
+    result = iterative_deepening_expectiminimax(state, 10)
+    assert len(result.side_one_probabilities) == len(result.side_one)
+    assert len(result.side_two_probabilities) == len(result.side_two)
+    assert abs(sum(result.side_one_probabilities) - 1.0) < 0.001
+    assert abs(sum(result.side_two_probabilities) - 1.0) < 0.001
+
+
 def test_get_instructions():
     generate_instructions(state, "watergun", "ember")
 
@@ -1,3 +1,4 @@
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use pyo3::{pyfunction, pymethods, pymodule, wrap_pyfunction, Bound, PyResult};
 use std::collections::HashSet;
@@ -9,18 +10,30 @@ use poke_engine::engine::generate_instructions::{
 };
 use poke_engine::engine::items::Items;
 use poke_engine::engine::state::{MoveChoice, PokemonVolatileStatus, Terrain, Weather};
+use poke_engine::evaluator::DefaultEvaluator;
 use poke_engine::instruction::{Instruction, StateInstructions};
-use poke_engine::mcts::{perform_mcts, MctsResult, MctsSideResult};
+use poke_engine::mcts::{
+    perform_parallel_mcts, MctsConfig, MctsResult, MctsSideResult, MoveSelectionPolicy,
+    RolloutPolicy, DEFAULT_EVAL_SCALE, DEFAULT_EXPLORATION_CONSTANT, DEFAULT_MAX_ITERATIONS,
+    DEFAULT_ROLLOUT_TURNS,
+};
 use poke_engine::pokemon::PokemonName;
-use poke_engine::search::iterative_deepen_expectiminimax;
+use poke_engine::rng::{random_seed, seeded_rng};
+use poke_engine::search::{iterative_deepen_expectiminimax, solve_nash_equilibrium};
 use poke_engine::state::{
-    LastUsedMove, Move, Pokemon, PokemonIndex, PokemonMoves, PokemonNature, PokemonStatus,
-    PokemonType, Side, SideConditions, SidePokemon, State, StateTerrain, StateTrickRoom,
-    StateWeather, VolatileStatusDurations,
+    LastUsedMove, Move, Pokemon, PokemonGender, PokemonIndex, PokemonMoves, PokemonNature,
+    PokemonStatus, PokemonType, Side, SideConditions, SidePokemon, State, StateHash, StateTerrain,
+    StateTrickRoom, StateWeather, VolatileStatusDurations,
 };
 use std::str::FromStr;
 use std::time::Duration;
 
+fn parse_field<T: FromStr>(field: &str, value: &str) -> PyResult<T> {
+    value
+        .parse::<T>()
+        .map_err(|_| PyValueError::new_err(format!("invalid {}: {}", field, value)))
+}
+
 fn movechoice_to_string(side: &Side, move_choice: &MoveChoice) -> String {
     match move_choice {
         MoveChoice::Switch(_) => {
@@ -49,16 +62,16 @@ impl PyState {
         trick_room: bool,
         trick_room_turns_remaining: i8,
         team_preview: bool,
-    ) -> Self {
+    ) -> PyResult<Self> {
         let mut state = State {
             side_one: side_one.create_side(),
             side_two: side_two.create_side(),
             weather: StateWeather {
-                weather_type: Weather::from_str(&weather).unwrap(),
+                weather_type: parse_field::<Weather>("weather", &weather)?,
                 turns_remaining: weather_turns_remaining,
             },
             terrain: StateTerrain {
-                terrain_type: Terrain::from_str(&terrain).unwrap(),
+                terrain_type: parse_field::<Terrain>("terrain", &terrain)?,
                 turns_remaining: terrain_turns_remaining,
             },
             trick_room: StateTrickRoom {
@@ -68,9 +81,10 @@ impl PyState {
             team_preview,
             use_damage_dealt: false,
             use_last_used_move: false,
+            hash: StateHash::default(),
         };
         state.set_conditional_mechanics();
-        PyState { state }
+        Ok(PyState { state })
     }
 
     fn apply_one_instruction(&mut self, instruction: PyInstruction) {
@@ -86,6 +100,10 @@ impl PyState {
     fn to_string(&self) -> String {
         self.state.serialize()
     }
+
+    fn to_json(&self) -> String {
+        self.state.to_json()
+    }
 }
 
 #[derive(Clone)]
@@ -115,6 +133,8 @@ impl PySide {
         force_switch: bool,
         force_trapped: bool,
         slow_uturn_move: bool,
+        dynamax_used: bool,
+        z_move_used: bool,
         volatile_statuses: Vec<String>,
         substitute_health: i16,
         attack_boost: i8,
@@ -126,17 +146,21 @@ impl PySide {
         evasion_boost: i8,
         last_used_move: String,
         switch_out_move_second_saved_move: String,
-    ) -> Self {
+    ) -> PyResult<Self> {
         let mut vs_hashset = HashSet::new();
         for vs in volatile_statuses {
-            vs_hashset.insert(PokemonVolatileStatus::from_str(&vs).unwrap());
+            vs_hashset.insert(parse_field::<PokemonVolatileStatus>(
+                "volatile_status",
+                &vs,
+            )?);
         }
         while pokemon.len() < 6 {
             pokemon.push(PyPokemon::create_fainted());
         }
-        PySide {
+        Ok(PySide {
             side: Side {
-                active_index: PokemonIndex::deserialize(&active_index),
+                active_index: PokemonIndex::try_deserialize(&active_index)
+                    .map_err(|e| PyValueError::new_err(e.in_field("active_index").to_string()))?,
                 baton_passing,
                 shed_tailing,
                 pokemon: SidePokemon {
@@ -149,10 +173,17 @@ impl PySide {
                 },
                 side_conditions: side_conditions.create_side_conditions(),
                 wish,
-                future_sight: (future_sight.0, PokemonIndex::deserialize(&future_sight.1)),
+                future_sight: (
+                    future_sight.0,
+                    PokemonIndex::try_deserialize(&future_sight.1).map_err(|e| {
+                        PyValueError::new_err(e.in_field("future_sight").to_string())
+                    })?,
+                ),
                 force_switch,
                 force_trapped,
                 slow_uturn_move,
+                dynamax_used,
+                z_move_used,
                 volatile_statuses: vs_hashset,
                 volatile_status_durations: volatile_status_durations
                     .create_volatile_status_durations(),
@@ -164,14 +195,16 @@ impl PySide {
                 speed_boost,
                 accuracy_boost,
                 evasion_boost,
-                last_used_move: LastUsedMove::deserialize(&last_used_move),
+                last_used_move: LastUsedMove::try_deserialize(&last_used_move)
+                    .map_err(|e| PyValueError::new_err(e.in_field("last_used_move").to_string()))?,
                 damage_dealt: Default::default(),
                 switch_out_move_second_saved_move: Choices::from_str(
                     &switch_out_move_second_saved_move,
                 )
                 .unwrap(),
+                ally_position: None,
             },
-        }
+        })
     }
 }
 
@@ -197,6 +230,7 @@ impl PyVolatileStatusDurations {
         slowstart: i8,
         taunt: i8,
         yawn: i8,
+        dynamax: i8,
     ) -> PyVolatileStatusDurations {
         PyVolatileStatusDurations {
             volatile_status_durations: VolatileStatusDurations {
@@ -206,6 +240,7 @@ impl PyVolatileStatusDurations {
                 slowstart,
                 taunt,
                 yawn,
+                dynamax,
             },
         }
     }
@@ -305,6 +340,8 @@ impl PyPokemon {
         item: String,
         nature: String,
         evs: (u8, u8, u8, u8, u8, u8),
+        ivs: (u8, u8, u8, u8, u8, u8),
+        gender: String,
         attack: i16,
         defense: i16,
         special_attack: i16,
@@ -317,11 +354,12 @@ impl PyPokemon {
         mut moves: Vec<PyMove>,
         terastallized: bool,
         tera_type: String,
-    ) -> Self {
+        gigantamax: bool,
+    ) -> PyResult<Self> {
         while moves.len() < 4 {
             moves.push(PyMove::create_empty_move());
         }
-        PyPokemon {
+        Ok(PyPokemon {
             pokemon: Pokemon {
                 id: PokemonName::from_str(&id).unwrap(),
                 level,
@@ -338,14 +376,16 @@ impl PyPokemon {
                 ability: Abilities::from_str(&ability).unwrap(),
                 base_ability: Abilities::from_str(&base_ability).unwrap(),
                 item: Items::from_str(&item).unwrap(),
-                nature: PokemonNature::from_str(&nature).unwrap(),
+                nature: parse_field::<PokemonNature>("nature", &nature)?,
                 evs: (evs.0, evs.1, evs.2, evs.3, evs.4, evs.5),
+                ivs: (ivs.0, ivs.1, ivs.2, ivs.3, ivs.4, ivs.5),
+                gender: parse_field::<PokemonGender>("gender", &gender)?,
                 attack,
                 defense,
                 special_attack,
                 special_defense,
                 speed,
-                status: PokemonStatus::from_str(&status).unwrap(),
+                status: parse_field::<PokemonStatus>("status", &status)?,
                 rest_turns,
                 sleep_turns,
                 weight_kg,
@@ -357,8 +397,9 @@ impl PyPokemon {
                 },
                 terastallized,
                 tera_type: PokemonType::from_str(&tera_type).unwrap(),
+                gigantamax,
             },
-        }
+        })
     }
 }
 
@@ -447,6 +488,9 @@ struct PyIterativeDeepeningResult {
     s2: Vec<String>,
     matrix: Vec<f32>,
     depth_searched: i8,
+    s1_probabilities: Vec<f32>,
+    s2_probabilities: Vec<f32>,
+    nash_value: f32,
 }
 
 impl PyIterativeDeepeningResult {
@@ -454,6 +498,7 @@ impl PyIterativeDeepeningResult {
         result: (Vec<MoveChoice>, Vec<MoveChoice>, Vec<f32>, i8),
         state: &State,
     ) -> Self {
+        let equilibrium = solve_nash_equilibrium(&result.2, result.0.len(), result.1.len());
         PyIterativeDeepeningResult {
             s1: result
                 .0
@@ -467,15 +512,102 @@ impl PyIterativeDeepeningResult {
                 .collect(),
             matrix: result.2,
             depth_searched: result.3,
+            s1_probabilities: equilibrium.s1,
+            s2_probabilities: equilibrium.s2,
+            nash_value: equilibrium.value,
         }
     }
 }
 
+#[derive(Clone)]
+#[pyclass(name = "MctsConfig")]
+struct PyMctsConfig {
+    exploration_constant: f32,
+    eval_scale: f32,
+    max_iterations: i64,
+    threads: usize,
+    move_selection: String,
+    rollout_policy: String,
+    rollout_turns: usize,
+    damage_bucket: Option<i16>,
+}
+
+#[pymethods]
+impl PyMctsConfig {
+    #[new]
+    #[pyo3(signature = (
+        exploration_constant=DEFAULT_EXPLORATION_CONSTANT,
+        eval_scale=DEFAULT_EVAL_SCALE,
+        max_iterations=DEFAULT_MAX_ITERATIONS,
+        threads=1,
+        move_selection="most-visits".to_string(),
+        rollout_policy="static".to_string(),
+        rollout_turns=DEFAULT_ROLLOUT_TURNS,
+        damage_bucket=None
+    ))]
+    fn new(
+        exploration_constant: f32,
+        eval_scale: f32,
+        max_iterations: i64,
+        threads: usize,
+        move_selection: String,
+        rollout_policy: String,
+        rollout_turns: usize,
+        damage_bucket: Option<i16>,
+    ) -> Self {
+        PyMctsConfig {
+            exploration_constant,
+            eval_scale,
+            max_iterations,
+            threads,
+            move_selection,
+            rollout_policy,
+            rollout_turns,
+            damage_bucket,
+        }
+    }
+}
+
+impl PyMctsConfig {
+    fn create_config(&self, max_time: Duration) -> PyResult<MctsConfig> {
+        Ok(MctsConfig {
+            exploration_constant: self.exploration_constant,
+            eval_scale: self.eval_scale,
+            max_iterations: self.max_iterations,
+            max_time,
+            threads: self.threads,
+            move_selection: MoveSelectionPolicy::from_str(&self.move_selection)
+                .map_err(pyo3::exceptions::PyValueError::new_err)?,
+            rollout_policy: RolloutPolicy::from_str(&self.rollout_policy)
+                .map_err(pyo3::exceptions::PyValueError::new_err)?,
+            rollout_turns: self.rollout_turns,
+            damage_bucket: self.damage_bucket,
+        })
+    }
+}
+
 #[pyfunction]
-fn mcts(mut py_state: PyState, duration_ms: u64) -> PyResult<PyMctsResult> {
+#[pyo3(signature = (py_state, duration_ms, config=None, seed=None))]
+fn mcts(
+    py_state: PyState,
+    duration_ms: u64,
+    config: Option<PyMctsConfig>,
+    seed: Option<u64>,
+) -> PyResult<PyMctsResult> {
     let duration = Duration::from_millis(duration_ms);
+    let config = match config {
+        Some(config) => config.create_config(duration)?,
+        None => MctsConfig::with_max_time(duration),
+    };
     let (s1_options, s2_options) = py_state.state.root_get_all_options();
-    let mcts_result = perform_mcts(&mut py_state.state, s1_options, s2_options, duration);
+    let mcts_result = perform_parallel_mcts(
+        &py_state.state,
+        s1_options,
+        s2_options,
+        &config,
+        &DefaultEvaluator,
+        &mut seeded_rng(seed.unwrap_or_else(random_seed)),
+    );
 
     let py_mcts_result = PyMctsResult::from_mcts_result(mcts_result, &py_state.state);
     Ok(py_mcts_result)
@@ -625,11 +757,15 @@ fn calculate_damage(
     Ok((s1_py_rolls, s2_py_rolls))
 }
 
+// accepts either a serialized state or the JSON from `State.to_json`
 #[pyfunction]
 fn state_from_string(s: String) -> PyResult<PyState> {
-    Ok(PyState {
-        state: State::deserialize(&s),
-    })
+    if s.trim_start().starts_with('{') {
+        let state = State::from_json(&s).map_err(PyValueError::new_err)?;
+        return Ok(PyState { state });
+    }
+    let state = State::try_deserialize(&s).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(PyState { state })
 }
 
 #[pymodule]
@@ -646,6 +782,7 @@ fn py_poke_engine(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyVolatileStatusDurations>()?;
     m.add_class::<PyPokemon>()?;
     m.add_class::<PyMove>()?;
+    m.add_class::<PyMctsConfig>()?;
     m.add_class::<PyStateInstructions>()?;
     m.add_class::<PyInstruction>()?;
     Ok(())
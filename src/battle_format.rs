@@ -0,0 +1,194 @@
+use crate::choices::{Choice, Choices, MoveCategory, MoveTarget};
+use crate::state::SideReference;
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum BattleFormat {
+    Singles,
+    Doubles,
+}
+
+impl BattleFormat {
+    pub fn active_pokemon_count(&self) -> usize {
+        match self {
+            BattleFormat::Singles => 1,
+            BattleFormat::Doubles => 2,
+        }
+    }
+
+    pub fn team_size(&self) -> usize {
+        6
+    }
+}
+
+/// An active position on the field
+/// `position` is 0 for singles and 0 or 1 for doubles
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct BattlePosition {
+    pub side: SideReference,
+    pub position: u8,
+}
+
+impl BattlePosition {
+    pub fn new(side: SideReference, position: u8) -> BattlePosition {
+        BattlePosition { side, position }
+    }
+
+    pub fn ally(&self) -> BattlePosition {
+        BattlePosition {
+            side: self.side,
+            position: 1 - self.position,
+        }
+    }
+
+    pub fn serialize(&self) -> String {
+        let side = match self.side {
+            SideReference::SideOne => "s1",
+            SideReference::SideTwo => "s2",
+        };
+        format!("{}:{}", side, self.position)
+    }
+}
+
+/// Who a move hits in doubles
+///
+/// `choices::MoveTarget` only describes whether an effect lands on the user or the opponent,
+/// which is all singles needs. Doubles additionally needs to know whether a move hits one
+/// chosen pokemon, both opponents, everyone adjacent or the user's ally.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum DoublesMoveTarget {
+    // the move only affects the user (or the field/its own side)
+    User,
+    // the move hits one opposing pokemon which must be chosen
+    AdjacentFoe,
+    // spread move hitting both opposing pokemon
+    AllAdjacentFoes,
+    // spread move hitting both opposing pokemon and the user's ally
+    AllAdjacent,
+    // the move affects only the user's ally
+    AdjacentAlly,
+    // the move affects both the user and its ally
+    UserAndAllies,
+}
+
+impl DoublesMoveTarget {
+    pub fn requires_target(&self) -> bool {
+        *self == DoublesMoveTarget::AdjacentFoe
+    }
+
+    pub fn is_spread(&self) -> bool {
+        matches!(
+            self,
+            DoublesMoveTarget::AllAdjacentFoes | DoublesMoveTarget::AllAdjacent
+        )
+    }
+}
+
+pub fn doubles_move_target(choice: &Choice) -> DoublesMoveTarget {
+    match choice.move_id {
+        Choices::ACID
+        | Choices::AIRCUTTER
+        | Choices::ASTRALBARRAGE
+        | Choices::BLEAKWINDSTORM
+        | Choices::BLIZZARD
+        | Choices::BREAKINGSWIPE
+        | Choices::BUBBLE
+        | Choices::BURNINGJEALOUSY
+        | Choices::CAPTIVATE
+        | Choices::CLANGINGSCALES
+        | Choices::COREENFORCER
+        | Choices::COTTONSPORE
+        | Choices::DARKVOID
+        | Choices::DAZZLINGGLEAM
+        | Choices::DIAMONDSTORM
+        | Choices::DISARMINGVOICE
+        | Choices::DRAGONENERGY
+        | Choices::ELECTROWEB
+        | Choices::ERUPTION
+        | Choices::FIERYWRATH
+        | Choices::GLACIALLANCE
+        | Choices::GLACIATE
+        | Choices::GROWL
+        | Choices::HEALBLOCK
+        | Choices::HEATWAVE
+        | Choices::HYPERVOICE
+        | Choices::ICYWIND
+        | Choices::INCINERATE
+        | Choices::LANDSWRATH
+        | Choices::LEER
+        | Choices::MAKEITRAIN
+        | Choices::MATCHAGOTCHA
+        | Choices::MORTALSPIN
+        | Choices::MUDDYWATER
+        | Choices::ORIGINPULSE
+        | Choices::OVERDRIVE
+        | Choices::POISONGAS
+        | Choices::POWDERSNOW
+        | Choices::PRECIPICEBLADES
+        | Choices::RAZORLEAF
+        | Choices::RAZORWIND
+        | Choices::RELICSONG
+        | Choices::ROCKSLIDE
+        | Choices::SANDSEARSTORM
+        | Choices::SHELLTRAP
+        | Choices::SNARL
+        | Choices::SPLISHYSPLASH
+        | Choices::SPRINGTIDESTORM
+        | Choices::STRINGSHOT
+        | Choices::STRUGGLEBUG
+        | Choices::SWEETSCENT
+        | Choices::SWIFT
+        | Choices::TAILWHIP
+        | Choices::THOUSANDARROWS
+        | Choices::THOUSANDWAVES
+        | Choices::TWISTER
+        | Choices::VENOMDRENCH
+        | Choices::WATERSPOUT
+        | Choices::WILDBOLTSTORM => DoublesMoveTarget::AllAdjacentFoes,
+
+        Choices::BOOMBURST
+        | Choices::BRUTALSWING
+        | Choices::BULLDOZE
+        | Choices::CORROSIVEGAS
+        | Choices::DISCHARGE
+        | Choices::EARTHQUAKE
+        | Choices::EXPLOSION
+        | Choices::LAVAPLUME
+        | Choices::MAGNITUDE
+        | Choices::MINDBLOWN
+        | Choices::MISTYEXPLOSION
+        | Choices::PARABOLICCHARGE
+        | Choices::PETALBLIZZARD
+        | Choices::SEARINGSHOT
+        | Choices::SELFDESTRUCT
+        | Choices::SLUDGEWAVE
+        | Choices::SPARKLINGARIA
+        | Choices::SURF
+        | Choices::SYNCHRONOISE
+        | Choices::TEETERDANCE => DoublesMoveTarget::AllAdjacent,
+
+        Choices::AROMATICMIST
+        | Choices::COACHING
+        | Choices::DRAGONCHEER
+        | Choices::HELPINGHAND
+        | Choices::HOLDHANDS => DoublesMoveTarget::AdjacentAlly,
+
+        Choices::NONE => DoublesMoveTarget::User,
+
+        // entry hazards are set on the opposing side rather than on an opposing pokemon
+        Choices::SPIKES | Choices::STEALTHROCK | Choices::STICKYWEB | Choices::TOXICSPIKES => {
+            DoublesMoveTarget::User
+        }
+
+        Choices::HOWL | Choices::JUNGLEHEALING | Choices::LIFEDEW | Choices::LUNARBLESSING => {
+            DoublesMoveTarget::UserAndAllies
+        }
+
+        _ => {
+            if choice.category == MoveCategory::Status && choice.target == MoveTarget::User {
+                DoublesMoveTarget::User
+            } else {
+                DoublesMoveTarget::AdjacentFoe
+            }
+        }
+    }
+}
@@ -5,6 +5,7 @@ use crate::state::{
     PokemonType,
 };
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
 
@@ -155,6 +156,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::ACIDDOWNPOUR,
+            Choice {
+                move_id: Choices::ACIDDOWNPOUR,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::POISON,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::ACIDSPRAY,
             Choice {
@@ -331,6 +346,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::ALLOUTPUMMELING,
+            Choice {
+                move_id: Choices::ALLOUTPUMMELING,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::FIGHTING,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::ALLURINGVOICE,
             Choice {
@@ -1327,6 +1356,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::BLACKHOLEECLIPSE,
+            Choice {
+                move_id: Choices::BLACKHOLEECLIPSE,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::DARK,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::BLASTBURN,
             Choice {
@@ -1504,6 +1547,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::BLOOMDOOM,
+            Choice {
+                move_id: Choices::BLOOMDOOM,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::GRASS,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::BLUEFLARE,
             Choice {
@@ -1774,6 +1831,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::BREAKNECKBLITZ,
+            Choice {
+                move_id: Choices::BREAKNECKBLITZ,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::NORMAL,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::BRICKBREAK,
             Choice {
@@ -2219,6 +2290,21 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::CATASTROPIKA,
+            Choice {
+                move_id: Choices::CATASTROPIKA,
+                base_power: 210.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::ELECTRIC,
+                flags: Flags {
+                    contact: true,
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::CEASELESSEDGE,
             Choice {
@@ -2545,6 +2631,32 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::CLANGOROUSSOULBLAZE,
+            Choice {
+                move_id: Choices::CLANGOROUSSOULBLAZE,
+                base_power: 185.0,
+                category: MoveCategory::Special,
+                move_type: PokemonType::DRAGON,
+                flags: Flags {
+                    protect: true,
+                    sound: true,
+                    ..Default::default()
+                },
+                boost: Some(Boost {
+                    target: MoveTarget::User,
+                    boosts: StatBoosts {
+                        attack: 1,
+                        defense: 1,
+                        special_attack: 1,
+                        special_defense: 1,
+                        speed: 1,
+                        accuracy: 0,
+                    },
+                }),
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::CLEARSMOG,
             Choice {
@@ -2813,6 +2925,20 @@ lazy_static! {
             },
         );
         }
+        moves.insert(
+            Choices::CONTINENTALCRUSH,
+            Choice {
+                move_id: Choices::CONTINENTALCRUSH,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::ROCK,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::CONVERSION,
             Choice {
@@ -2862,6 +2988,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::CORKSCREWCRASH,
+            Choice {
+                move_id: Choices::CORKSCREWCRASH,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::STEEL,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::CORROSIVEGAS,
             Choice {
@@ -3486,6 +3626,20 @@ lazy_static! {
             },
         );
 
+        moves.insert(
+            Choices::DEVASTATINGDRAKE,
+            Choice {
+                move_id: Choices::DEVASTATINGDRAKE,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::DRAGON,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         if cfg!(feature = "gen1") || cfg!(feature = "gen2") || cfg!(feature = "gen3") || cfg!(feature = "gen4") || cfg!(feature = "gen5") || cfg!(feature = "gen6") {
             moves.insert(
                 Choices::DIAMONDSTORM,
@@ -4898,6 +5052,29 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::EXTREMEEVOBOOST,
+            Choice {
+                move_id: Choices::EXTREMEEVOBOOST,
+                target: MoveTarget::User,
+                move_type: PokemonType::NORMAL,
+                flags: Flags {
+                    ..Default::default()
+                },
+                boost: Some(Boost {
+                    target: MoveTarget::User,
+                    boosts: StatBoosts {
+                        attack: 2,
+                        defense: 2,
+                        special_attack: 2,
+                        special_defense: 2,
+                        speed: 2,
+                        accuracy: 0,
+                    },
+                }),
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::EXTREMESPEED,
             Choice {
@@ -6353,6 +6530,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::GENESISSUPERNOVA,
+            Choice {
+                move_id: Choices::GENESISSUPERNOVA,
+                base_power: 185.0,
+                category: MoveCategory::Special,
+                move_type: PokemonType::PSYCHIC,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::GEOMANCY,
             Choice {
@@ -6444,6 +6635,20 @@ lazy_static! {
             },
         );
 
+        moves.insert(
+            Choices::GIGAVOLTHAVOC,
+            Choice {
+                move_id: Choices::GIGAVOLTHAVOC,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::ELECTRIC,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         if cfg!(feature = "gen9") {
             moves.insert(
                 Choices::GLACIALLANCE,
@@ -6590,90 +6795,308 @@ lazy_static! {
             },
         );
         moves.insert(
-            Choices::GRASSKNOT,
+            Choices::GMAXCENTIFERNO,
             Choice {
-                move_id: Choices::GRASSKNOT,
-                category: MoveCategory::Special,
-                move_type: PokemonType::GRASS,
+                move_id: Choices::GMAXCENTIFERNO,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::FIRE,
                 flags: Flags {
-                    contact: true,
                     protect: true,
                     ..Default::default()
                 },
+                volatile_status: Some(VolatileStatus {
+                    target: MoveTarget::Opponent,
+                    volatile_status: PokemonVolatileStatus::PARTIALLYTRAPPED,
+                }),
                 ..Default::default()
             },
         );
-        if cfg!(feature = "gen1") || cfg!(feature = "gen2") || cfg!(feature = "gen3") || cfg!(feature = "gen4") || cfg!(feature = "gen5") {
-            moves.insert(
-                Choices::GRASSPLEDGE,
-                Choice {
-                    move_id: Choices::GRASSPLEDGE,
-                    base_power: 50.0,
-                    category: MoveCategory::Special,
-                    move_type: PokemonType::GRASS,
-                    flags: Flags {
-                                protect: true,
-                        ..Default::default()
-                    },
-                    ..Default::default()
-                },
-            );
-        } else {
-            moves.insert(
-                Choices::GRASSPLEDGE,
-                Choice {
-                    move_id: Choices::GRASSPLEDGE,
-                    base_power: 80.0,
-                    category: MoveCategory::Special,
-                    move_type: PokemonType::GRASS,
-                    flags: Flags {
-                                protect: true,
-                        ..Default::default()
-                    },
+        moves.insert(
+            Choices::GMAXDRUMSOLO,
+            Choice {
+                move_id: Choices::GMAXDRUMSOLO,
+                base_power: 160.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::GRASS,
+                flags: Flags {
+                    protect: true,
                     ..Default::default()
                 },
-            );
-        }
+                ..Default::default()
+            },
+        );
         moves.insert(
-            Choices::GRASSWHISTLE,
+            Choices::GMAXFIREBALL,
             Choice {
-                move_id: Choices::GRASSWHISTLE,
-                accuracy: 55.0,
-                status: Some(Status {
-                    target: MoveTarget::Opponent,
-                    status: PokemonStatus::SLEEP,
-                }),
-                move_type: PokemonType::GRASS,
+                move_id: Choices::GMAXFIREBALL,
+                base_power: 160.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::FIRE,
                 flags: Flags {
                     protect: true,
-                    reflectable: true,
-                    sound: true,
                     ..Default::default()
                 },
                 ..Default::default()
             },
         );
-
-        if cfg!(feature = "gen9") {
-            moves.insert(
-                Choices::GRASSYGLIDE,
-                Choice {
-                    move_id: Choices::GRASSYGLIDE,
-                    base_power: 55.0,
-                    category: MoveCategory::Physical,
-                    move_type: PokemonType::GRASS,
-                    flags: Flags {
-                        contact: true,
-                            protect: true,
-                        ..Default::default()
-                    },
+        moves.insert(
+            Choices::GMAXFOAMBURST,
+            Choice {
+                move_id: Choices::GMAXFOAMBURST,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::WATER,
+                flags: Flags {
+                    protect: true,
                     ..Default::default()
                 },
-            );
-        } else {
-            moves.insert(
-                Choices::GRASSYGLIDE,
-                Choice {
+                secondaries: Some(vec![Secondary {
+                    chance: 100.0,
+                    target: MoveTarget::Opponent,
+                    effect: Effect::Boost(StatBoosts {
+                        attack: 0,
+                        defense: 0,
+                        special_attack: 0,
+                        special_defense: 0,
+                        speed: -2,
+                        accuracy: 0,
+                    }),
+                }]),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::GMAXGOLDRUSH,
+            Choice {
+                move_id: Choices::GMAXGOLDRUSH,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::NORMAL,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                secondaries: Some(vec![Secondary {
+                    chance: 100.0,
+                    target: MoveTarget::Opponent,
+                    effect: Effect::VolatileStatus(PokemonVolatileStatus::CONFUSION),
+                }]),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::GMAXHYDROSNIPE,
+            Choice {
+                move_id: Choices::GMAXHYDROSNIPE,
+                base_power: 160.0,
+                category: MoveCategory::Special,
+                move_type: PokemonType::WATER,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::GMAXMALODOR,
+            Choice {
+                move_id: Choices::GMAXMALODOR,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::POISON,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                secondaries: Some(vec![Secondary {
+                    chance: 100.0,
+                    target: MoveTarget::Opponent,
+                    effect: Effect::Status(PokemonStatus::POISON),
+                }]),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::GMAXONEBLOW,
+            Choice {
+                move_id: Choices::GMAXONEBLOW,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::DARK,
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::GMAXRAPIDFLOW,
+            Choice {
+                move_id: Choices::GMAXRAPIDFLOW,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::WATER,
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::GMAXSANDBLAST,
+            Choice {
+                move_id: Choices::GMAXSANDBLAST,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::GROUND,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                volatile_status: Some(VolatileStatus {
+                    target: MoveTarget::Opponent,
+                    volatile_status: PokemonVolatileStatus::PARTIALLYTRAPPED,
+                }),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::GMAXSMITE,
+            Choice {
+                move_id: Choices::GMAXSMITE,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::FAIRY,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                secondaries: Some(vec![Secondary {
+                    chance: 100.0,
+                    target: MoveTarget::Opponent,
+                    effect: Effect::VolatileStatus(PokemonVolatileStatus::CONFUSION),
+                }]),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::GMAXSTONESURGE,
+            Choice {
+                move_id: Choices::GMAXSTONESURGE,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::WATER,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                side_condition: Some(SideCondition {
+                    target: MoveTarget::Opponent,
+                    condition: PokemonSideCondition::Stealthrock,
+                }),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::GMAXVOLTCRASH,
+            Choice {
+                move_id: Choices::GMAXVOLTCRASH,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::ELECTRIC,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                secondaries: Some(vec![Secondary {
+                    chance: 100.0,
+                    target: MoveTarget::Opponent,
+                    effect: Effect::Status(PokemonStatus::PARALYZE),
+                }]),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::GRASSKNOT,
+            Choice {
+                move_id: Choices::GRASSKNOT,
+                category: MoveCategory::Special,
+                move_type: PokemonType::GRASS,
+                flags: Flags {
+                    contact: true,
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        if cfg!(feature = "gen1") || cfg!(feature = "gen2") || cfg!(feature = "gen3") || cfg!(feature = "gen4") || cfg!(feature = "gen5") {
+            moves.insert(
+                Choices::GRASSPLEDGE,
+                Choice {
+                    move_id: Choices::GRASSPLEDGE,
+                    base_power: 50.0,
+                    category: MoveCategory::Special,
+                    move_type: PokemonType::GRASS,
+                    flags: Flags {
+                                protect: true,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            );
+        } else {
+            moves.insert(
+                Choices::GRASSPLEDGE,
+                Choice {
+                    move_id: Choices::GRASSPLEDGE,
+                    base_power: 80.0,
+                    category: MoveCategory::Special,
+                    move_type: PokemonType::GRASS,
+                    flags: Flags {
+                                protect: true,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            );
+        }
+        moves.insert(
+            Choices::GRASSWHISTLE,
+            Choice {
+                move_id: Choices::GRASSWHISTLE,
+                accuracy: 55.0,
+                status: Some(Status {
+                    target: MoveTarget::Opponent,
+                    status: PokemonStatus::SLEEP,
+                }),
+                move_type: PokemonType::GRASS,
+                flags: Flags {
+                    protect: true,
+                    reflectable: true,
+                    sound: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        if cfg!(feature = "gen9") {
+            moves.insert(
+                Choices::GRASSYGLIDE,
+                Choice {
+                    move_id: Choices::GRASSYGLIDE,
+                    base_power: 55.0,
+                    category: MoveCategory::Physical,
+                    move_type: PokemonType::GRASS,
+                    flags: Flags {
+                        contact: true,
+                            protect: true,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            );
+        } else {
+            moves.insert(
+                Choices::GRASSYGLIDE,
+                Choice {
                     move_id: Choices::GRASSYGLIDE,
                     base_power: 70.0,
                     category: MoveCategory::Physical,
@@ -6825,6 +7248,19 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::GUARDIANOFALOLA,
+            Choice {
+                move_id: Choices::GUARDIANOFALOLA,
+                category: MoveCategory::Special,
+                move_type: PokemonType::FAIRY,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::GUARDSPLIT,
             Choice {
@@ -8133,6 +8569,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::HYDROVORTEX,
+            Choice {
+                move_id: Choices::HYDROVORTEX,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::WATER,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::HYPERBEAM,
             Choice {
@@ -8608,13 +9058,27 @@ lazy_static! {
             },
         );
         moves.insert(
-            Choices::INFESTATION,
+            Choices::INFERNOOVERDRIVE,
             Choice {
-                move_id: Choices::INFESTATION,
-                base_power: 20.0,
-                category: MoveCategory::Special,
-                move_type: PokemonType::BUG,
-                flags: Flags {
+                move_id: Choices::INFERNOOVERDRIVE,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::FIRE,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::INFESTATION,
+            Choice {
+                move_id: Choices::INFESTATION,
+                base_power: 20.0,
+                category: MoveCategory::Special,
+                move_type: PokemonType::BUG,
+                flags: Flags {
                     contact: true,
                     protect: true,
                     ..Default::default()
@@ -9317,6 +9781,21 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::LETSSNUGGLEFOREVER,
+            Choice {
+                move_id: Choices::LETSSNUGGLEFOREVER,
+                base_power: 190.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::FAIRY,
+                flags: Flags {
+                    contact: true,
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         if cfg!(feature = "gen1") || cfg!(feature = "gen2") || cfg!(feature = "gen3") || cfg!(feature = "gen4") || cfg!(feature = "gen5") {
             moves.insert(
                 Choices::LICK,
@@ -9931,11 +10410,416 @@ lazy_static! {
             },
         );
         moves.insert(
-            Choices::MAGNITUDE,
+            Choices::MAGNITUDE,
+            Choice {
+                move_id: Choices::MAGNITUDE,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::GROUND,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MAKEITRAIN,
+            Choice {
+                move_id: Choices::MAKEITRAIN,
+                base_power: 120.0,
+                category: MoveCategory::Special,
+                move_type: PokemonType::STEEL,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                boost: Some(Boost {
+                    target: MoveTarget::User,
+                    boosts: StatBoosts {
+                        attack: 0,
+                        defense: 0,
+                        special_attack: -1,
+                        special_defense: 0,
+                        speed: 0,
+                        accuracy: 0,
+                    },
+                }),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MALICIOUSMOONSAULT,
+            Choice {
+                move_id: Choices::MALICIOUSMOONSAULT,
+                base_power: 180.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::DARK,
+                flags: Flags {
+                    contact: true,
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MALIGNANTCHAIN,
+            Choice {
+                move_id: Choices::MALIGNANTCHAIN,
+                base_power: 100.0,
+                category: MoveCategory::Special,
+                move_type: PokemonType::POISON,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                secondaries: Some(vec![Secondary {
+                    chance: 50.0,
+                    target: MoveTarget::Opponent,
+                    effect: Effect::Status(PokemonStatus::TOXIC),
+                }]),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MATBLOCK,
+            Choice {
+                move_id: Choices::MATBLOCK,
+                target: MoveTarget::User,
+                move_type: PokemonType::FIGHTING,
+                flags: Flags {
+                    ..Default::default()
+                },
+                side_condition: Some(SideCondition {
+                    target: MoveTarget::User,
+                    condition: PokemonSideCondition::MatBlock,
+                }),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MATCHAGOTCHA,
+            Choice {
+                move_id: Choices::MATCHAGOTCHA,
+                accuracy: 90.0,
+                base_power: 80.0,
+                category: MoveCategory::Special,
+                move_type: PokemonType::GRASS,
+                flags: Flags {
+                    protect: true,
+                    heal: true,
+                    ..Default::default()
+                },
+                secondaries: Some(vec![Secondary {
+                    chance: 20.0,
+                    target: MoveTarget::Opponent,
+                    effect: Effect::Status(PokemonStatus::BURN),
+                }]),
+                drain: Some(0.5),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MAXAIRSTREAM,
+            Choice {
+                move_id: Choices::MAXAIRSTREAM,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::FLYING,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                secondaries: Some(vec![Secondary {
+                    chance: 100.0,
+                    target: MoveTarget::User,
+                    effect: Effect::Boost(StatBoosts {
+                        attack: 0,
+                        defense: 0,
+                        special_attack: 0,
+                        special_defense: 0,
+                        speed: 1,
+                        accuracy: 0,
+                    }),
+                }]),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MAXDARKNESS,
+            Choice {
+                move_id: Choices::MAXDARKNESS,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::DARK,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                secondaries: Some(vec![Secondary {
+                    chance: 100.0,
+                    target: MoveTarget::Opponent,
+                    effect: Effect::Boost(StatBoosts {
+                        attack: 0,
+                        defense: 0,
+                        special_attack: 0,
+                        special_defense: -1,
+                        speed: 0,
+                        accuracy: 0,
+                    }),
+                }]),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MAXFLARE,
+            Choice {
+                move_id: Choices::MAXFLARE,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::FIRE,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MAXFLUTTERBY,
+            Choice {
+                move_id: Choices::MAXFLUTTERBY,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::BUG,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                secondaries: Some(vec![Secondary {
+                    chance: 100.0,
+                    target: MoveTarget::Opponent,
+                    effect: Effect::Boost(StatBoosts {
+                        attack: 0,
+                        defense: 0,
+                        special_attack: -1,
+                        special_defense: 0,
+                        speed: 0,
+                        accuracy: 0,
+                    }),
+                }]),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MAXGEYSER,
+            Choice {
+                move_id: Choices::MAXGEYSER,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::WATER,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MAXGUARD,
+            Choice {
+                move_id: Choices::MAXGUARD,
+                priority: 4,
+                target: MoveTarget::User,
+                move_type: PokemonType::NORMAL,
+                volatile_status: Some(VolatileStatus {
+                    target: MoveTarget::User,
+                    volatile_status: PokemonVolatileStatus::MAXGUARD,
+                }),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MAXHAILSTORM,
+            Choice {
+                move_id: Choices::MAXHAILSTORM,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::ICE,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MAXKNUCKLE,
+            Choice {
+                move_id: Choices::MAXKNUCKLE,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::FIGHTING,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                secondaries: Some(vec![Secondary {
+                    chance: 100.0,
+                    target: MoveTarget::User,
+                    effect: Effect::Boost(StatBoosts {
+                        attack: 1,
+                        defense: 0,
+                        special_attack: 0,
+                        special_defense: 0,
+                        speed: 0,
+                        accuracy: 0,
+                    }),
+                }]),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MAXLIGHTNING,
+            Choice {
+                move_id: Choices::MAXLIGHTNING,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::ELECTRIC,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MAXMINDSTORM,
+            Choice {
+                move_id: Choices::MAXMINDSTORM,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::PSYCHIC,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MAXOOZE,
+            Choice {
+                move_id: Choices::MAXOOZE,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::POISON,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                secondaries: Some(vec![Secondary {
+                    chance: 100.0,
+                    target: MoveTarget::User,
+                    effect: Effect::Boost(StatBoosts {
+                        attack: 0,
+                        defense: 0,
+                        special_attack: 1,
+                        special_defense: 0,
+                        speed: 0,
+                        accuracy: 0,
+                    }),
+                }]),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MAXOVERGROWTH,
+            Choice {
+                move_id: Choices::MAXOVERGROWTH,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::GRASS,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MAXPHANTASM,
+            Choice {
+                move_id: Choices::MAXPHANTASM,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::GHOST,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                secondaries: Some(vec![Secondary {
+                    chance: 100.0,
+                    target: MoveTarget::Opponent,
+                    effect: Effect::Boost(StatBoosts {
+                        attack: 0,
+                        defense: -1,
+                        special_attack: 0,
+                        special_defense: 0,
+                        speed: 0,
+                        accuracy: 0,
+                    }),
+                }]),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MAXQUAKE,
+            Choice {
+                move_id: Choices::MAXQUAKE,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::GROUND,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                secondaries: Some(vec![Secondary {
+                    chance: 100.0,
+                    target: MoveTarget::User,
+                    effect: Effect::Boost(StatBoosts {
+                        attack: 0,
+                        defense: 0,
+                        special_attack: 0,
+                        special_defense: 1,
+                        speed: 0,
+                        accuracy: 0,
+                    }),
+                }]),
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MAXROCKFALL,
+            Choice {
+                move_id: Choices::MAXROCKFALL,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::ROCK,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        moves.insert(
+            Choices::MAXSTARFALL,
             Choice {
-                move_id: Choices::MAGNITUDE,
+                move_id: Choices::MAXSTARFALL,
+                base_power: 10.0,
                 category: MoveCategory::Physical,
-                move_type: PokemonType::GROUND,
+                move_type: PokemonType::FAIRY,
                 flags: Flags {
                     protect: true,
                     ..Default::default()
@@ -9944,84 +10828,80 @@ lazy_static! {
             },
         );
         moves.insert(
-            Choices::MAKEITRAIN,
+            Choices::MAXSTEELSPIKE,
             Choice {
-                move_id: Choices::MAKEITRAIN,
-                base_power: 120.0,
-                category: MoveCategory::Special,
+                move_id: Choices::MAXSTEELSPIKE,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
                 move_type: PokemonType::STEEL,
                 flags: Flags {
                     protect: true,
                     ..Default::default()
                 },
-                boost: Some(Boost {
+                secondaries: Some(vec![Secondary {
+                    chance: 100.0,
                     target: MoveTarget::User,
-                    boosts: StatBoosts {
+                    effect: Effect::Boost(StatBoosts {
                         attack: 0,
-                        defense: 0,
-                        special_attack: -1,
+                        defense: 1,
+                        special_attack: 0,
                         special_defense: 0,
                         speed: 0,
                         accuracy: 0,
-                    },
-                }),
+                    }),
+                }]),
                 ..Default::default()
             },
         );
         moves.insert(
-            Choices::MALIGNANTCHAIN,
+            Choices::MAXSTRIKE,
             Choice {
-                move_id: Choices::MALIGNANTCHAIN,
-                base_power: 100.0,
-                category: MoveCategory::Special,
-                move_type: PokemonType::POISON,
+                move_id: Choices::MAXSTRIKE,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::NORMAL,
                 flags: Flags {
                     protect: true,
                     ..Default::default()
                 },
                 secondaries: Some(vec![Secondary {
-                    chance: 50.0,
+                    chance: 100.0,
                     target: MoveTarget::Opponent,
-                    effect: Effect::Status(PokemonStatus::TOXIC),
+                    effect: Effect::Boost(StatBoosts {
+                        attack: 0,
+                        defense: 0,
+                        special_attack: 0,
+                        special_defense: 0,
+                        speed: -1,
+                        accuracy: 0,
+                    }),
                 }]),
                 ..Default::default()
             },
         );
         moves.insert(
-            Choices::MATBLOCK,
-            Choice {
-                move_id: Choices::MATBLOCK,
-                target: MoveTarget::User,
-                move_type: PokemonType::FIGHTING,
-                flags: Flags {
-                    ..Default::default()
-                },
-                side_condition: Some(SideCondition {
-                    target: MoveTarget::User,
-                    condition: PokemonSideCondition::MatBlock,
-                }),
-                ..Default::default()
-            },
-        );
-        moves.insert(
-            Choices::MATCHAGOTCHA,
+            Choices::MAXWYRMWIND,
             Choice {
-                move_id: Choices::MATCHAGOTCHA,
-                accuracy: 90.0,
-                base_power: 80.0,
-                category: MoveCategory::Special,
-                move_type: PokemonType::GRASS,
+                move_id: Choices::MAXWYRMWIND,
+                base_power: 10.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::DRAGON,
                 flags: Flags {
                     protect: true,
-                    heal: true,
                     ..Default::default()
                 },
                 secondaries: Some(vec![Secondary {
-                    chance: 20.0,
+                    chance: 100.0,
                     target: MoveTarget::Opponent,
-                    effect: Effect::Status(PokemonStatus::BURN),
+                    effect: Effect::Boost(StatBoosts {
+                        attack: -1,
+                        defense: 0,
+                        special_attack: 0,
+                        special_defense: 0,
+                        speed: 0,
+                        accuracy: 0,
+                    }),
                 }]),
-                drain: Some(0.5),
                 ..Default::default()
             },
         );
@@ -10164,6 +11044,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::MENACINGMOONRAZEMAELSTROM,
+            Choice {
+                move_id: Choices::MENACINGMOONRAZEMAELSTROM,
+                base_power: 200.0,
+                category: MoveCategory::Special,
+                move_type: PokemonType::GHOST,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::METALBURST,
             Choice {
@@ -11058,6 +11952,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::NEVERENDINGNIGHTMARE,
+            Choice {
+                move_id: Choices::NEVERENDINGNIGHTMARE,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::GHOST,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::NIGHTDAZE,
             Choice {
@@ -11266,6 +12174,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::OCEANICOPERETTA,
+            Choice {
+                move_id: Choices::OCEANICOPERETTA,
+                base_power: 195.0,
+                category: MoveCategory::Special,
+                move_type: PokemonType::WATER,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::OCTAZOOKA,
             Choice {
@@ -12722,6 +13644,21 @@ lazy_static! {
                 },
             );
         }
+        moves.insert(
+            Choices::PULVERIZINGPANCAKE,
+            Choice {
+                move_id: Choices::PULVERIZINGPANCAKE,
+                base_power: 210.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::NORMAL,
+                flags: Flags {
+                    contact: true,
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::PUNISHMENT,
             Choice {
@@ -13937,6 +14874,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::SAVAGESPINOUT,
+            Choice {
+                move_id: Choices::SAVAGESPINOUT,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::BUG,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::SCALD,
             Choice {
@@ -14114,6 +15065,21 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::SEARINGSUNRAZESMASH,
+            Choice {
+                move_id: Choices::SEARINGSUNRAZESMASH,
+                base_power: 200.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::STEEL,
+                flags: Flags {
+                    contact: true,
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::SECRETPOWER,
             Choice {
@@ -14401,6 +15367,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::SHATTEREDPSYCHE,
+            Choice {
+                move_id: Choices::SHATTEREDPSYCHE,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::PSYCHIC,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::SHEDTAIL,
             Choice {
@@ -14657,6 +15637,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::SINISTERARROWRAID,
+            Choice {
+                move_id: Choices::SINISTERARROWRAID,
+                base_power: 180.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::GHOST,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::SIZZLYSLIDE,
             Choice {
@@ -15318,6 +16312,21 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::SOULSTEALING7STARSTRIKE,
+            Choice {
+                move_id: Choices::SOULSTEALING7STARSTRIKE,
+                base_power: 195.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::GHOST,
+                flags: Flags {
+                    contact: true,
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::SPACIALREND,
             Choice {
@@ -15604,6 +16613,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::SPLINTEREDSTORMSHARDS,
+            Choice {
+                move_id: Choices::SPLINTEREDSTORMSHARDS,
+                base_power: 190.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::ROCK,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::SPLISHYSPLASH,
             Choice {
@@ -15837,6 +16860,25 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::STOKEDSPARKSURFER,
+            Choice {
+                move_id: Choices::STOKEDSPARKSURFER,
+                base_power: 175.0,
+                category: MoveCategory::Special,
+                move_type: PokemonType::ELECTRIC,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                secondaries: Some(vec![Secondary {
+                    chance: 100.0,
+                    target: MoveTarget::Opponent,
+                    effect: Effect::Status(PokemonStatus::PARALYZE),
+                }]),
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::STOMP,
             Choice {
@@ -16163,6 +17205,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::SUBZEROSLAMMER,
+            Choice {
+                move_id: Choices::SUBZEROSLAMMER,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::ICE,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         if cfg!(feature = "gen1") || cfg!(feature = "gen2") || cfg!(feature = "gen3") || cfg!(feature = "gen4") || cfg!(feature = "gen5") || cfg!(feature = "gen6") {
             moves.insert(
                 Choices::SUCKERPUNCH,
@@ -16301,6 +17357,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::SUPERSONICSKYSTRIKE,
+            Choice {
+                move_id: Choices::SUPERSONICSKYSTRIKE,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::FLYING,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         if cfg!(feature = "gen1") || cfg!(feature = "gen2") || cfg!(feature = "gen3") || cfg!(feature = "gen4") || cfg!(feature = "gen5") {
             moves.insert(
                 Choices::SURF,
@@ -16858,6 +17928,20 @@ lazy_static! {
                 },
             );
         }
+        moves.insert(
+            Choices::TECTONICRAGE,
+            Choice {
+                move_id: Choices::TECTONICRAGE,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::GROUND,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::TEETERDANCE,
             Choice {
@@ -17848,6 +18932,20 @@ lazy_static! {
                 ..Default::default()
             },
         );
+        moves.insert(
+            Choices::TWINKLETACKLE,
+            Choice {
+                move_id: Choices::TWINKLETACKLE,
+                base_power: 1.0,
+                category: MoveCategory::Physical,
+                move_type: PokemonType::FAIRY,
+                flags: Flags {
+                    protect: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
         moves.insert(
             Choices::TWISTER,
             Choice {
@@ -18902,7 +20000,7 @@ lazy_static! {
     };
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum MoveCategory {
     Physical,
     Special,
@@ -19087,13 +20185,14 @@ pub enum MultiAccuracyMove {
 
 define_enum_with_from_str! {
     #[repr(u16)]
-    #[derive(Eq, PartialEq, Debug, Hash, Copy, Clone)]
+    #[derive(Eq, PartialEq, Debug, Hash, Copy, Clone, Serialize, Deserialize)]
     Choices {
         NONE,
         ABSORB,
         ACCELEROCK,
         ACID,
         ACIDARMOR,
+        ACIDDOWNPOUR,
         ACIDSPRAY,
         ACROBATICS,
         ACUPRESSURE,
@@ -19103,6 +20202,7 @@ define_enum_with_from_str! {
         AGILITY,
         AIRCUTTER,
         AIRSLASH,
+        ALLOUTPUMMELING,
         ALLURINGVOICE,
         ALLYSWITCH,
         AMNESIA,
@@ -19150,6 +20250,7 @@ define_enum_with_from_str! {
         BITE,
         BITTERBLADE,
         BITTERMALICE,
+        BLACKHOLEECLIPSE,
         BLASTBURN,
         BLAZEKICK,
         BLAZINGTORQUE,
@@ -19157,6 +20258,7 @@ define_enum_with_from_str! {
         BLIZZARD,
         BLOCK,
         BLOODMOON,
+        BLOOMDOOM,
         BLUEFLARE,
         BODYPRESS,
         BODYSLAM,
@@ -19171,6 +20273,7 @@ define_enum_with_from_str! {
         BRANCHPOKE,
         BRAVEBIRD,
         BREAKINGSWIPE,
+        BREAKNECKBLITZ,
         BRICKBREAK,
         BRINE,
         BRUTALSWING,
@@ -19189,6 +20292,7 @@ define_enum_with_from_str! {
         CALMMIND,
         CAMOUFLAGE,
         CAPTIVATE,
+        CATASTROPIKA,
         CEASELESSEDGE,
         CELEBRATE,
         CHARGE,
@@ -19203,6 +20307,7 @@ define_enum_with_from_str! {
         CLAMP,
         CLANGINGSCALES,
         CLANGOROUSSOUL,
+        CLANGOROUSSOULBLAZE,
         CLEARSMOG,
         CLOSECOMBAT,
         COACHING,
@@ -19215,10 +20320,12 @@ define_enum_with_from_str! {
         CONFUSERAY,
         CONFUSION,
         CONSTRICT,
+        CONTINENTALCRUSH,
         CONVERSION,
         CONVERSION2,
         COPYCAT,
         COREENFORCER,
+        CORKSCREWCRASH,
         CORROSIVEGAS,
         COSMICPOWER,
         COTTONGUARD,
@@ -19245,6 +20352,7 @@ define_enum_with_from_str! {
         DEFOG,
         DESTINYBOND,
         DETECT,
+        DEVASTATINGDRAKE,
         DIAMONDSTORM,
         DIG,
         DIRECLAW,
@@ -19310,6 +20418,7 @@ define_enum_with_from_str! {
         EXPANDINGFORCE,
         EXPLOSION,
         EXTRASENSORY,
+        EXTREMEEVOBOOST,
         EXTREMESPEED,
         FACADE,
         FAIRYLOCK,
@@ -19378,15 +20487,30 @@ define_enum_with_from_str! {
         GASTROACID,
         GEARGRIND,
         GEARUP,
+        GENESISSUPERNOVA,
         GEOMANCY,
         GIGADRAIN,
         GIGAIMPACT,
         GIGATONHAMMER,
+        GIGAVOLTHAVOC,
         GLACIALLANCE,
         GLACIATE,
         GLAIVERUSH,
         GLARE,
         GLITZYGLOW,
+        GMAXCENTIFERNO,
+        GMAXDRUMSOLO,
+        GMAXFIREBALL,
+        GMAXFOAMBURST,
+        GMAXGOLDRUSH,
+        GMAXHYDROSNIPE,
+        GMAXMALODOR,
+        GMAXONEBLOW,
+        GMAXRAPIDFLOW,
+        GMAXSANDBLAST,
+        GMAXSMITE,
+        GMAXSTONESURGE,
+        GMAXVOLTCRASH,
         GRASSKNOT,
         GRASSPLEDGE,
         GRASSWHISTLE,
@@ -19397,6 +20521,7 @@ define_enum_with_from_str! {
         GROWL,
         GROWTH,
         GRUDGE,
+        GUARDIANOFALOLA,
         GUARDSPLIT,
         GUARDSWAP,
         GUILLOTINE,
@@ -19471,6 +20596,7 @@ define_enum_with_from_str! {
         HYDROCANNON,
         HYDROPUMP,
         HYDROSTEAM,
+        HYDROVORTEX,
         HYPERBEAM,
         HYPERDRILL,
         HYPERFANG,
@@ -19493,6 +20619,7 @@ define_enum_with_from_str! {
         INCINERATE,
         INFERNALPARADE,
         INFERNO,
+        INFERNOOVERDRIVE,
         INFESTATION,
         INGRAIN,
         INSTRUCT,
@@ -19524,6 +20651,7 @@ define_enum_with_from_str! {
         LEECHLIFE,
         LEECHSEED,
         LEER,
+        LETSSNUGGLEFOREVER,
         LICK,
         LIFEDEW,
         LIGHTOFRUIN,
@@ -19551,9 +20679,29 @@ define_enum_with_from_str! {
         MAGNETRISE,
         MAGNITUDE,
         MAKEITRAIN,
+        MALICIOUSMOONSAULT,
         MALIGNANTCHAIN,
         MATBLOCK,
         MATCHAGOTCHA,
+        MAXAIRSTREAM,
+        MAXDARKNESS,
+        MAXFLARE,
+        MAXFLUTTERBY,
+        MAXGEYSER,
+        MAXGUARD,
+        MAXHAILSTORM,
+        MAXKNUCKLE,
+        MAXLIGHTNING,
+        MAXMINDSTORM,
+        MAXOOZE,
+        MAXOVERGROWTH,
+        MAXPHANTASM,
+        MAXQUAKE,
+        MAXROCKFALL,
+        MAXSTARFALL,
+        MAXSTEELSPIKE,
+        MAXSTRIKE,
+        MAXWYRMWIND,
         MEANLOOK,
         MEDITATE,
         MEFIRST,
@@ -19562,6 +20710,7 @@ define_enum_with_from_str! {
         MEGAKICK,
         MEGAPUNCH,
         MEMENTO,
+        MENACINGMOONRAZEMAELSTROM,
         METALBURST,
         METALCLAW,
         METALSOUND,
@@ -19602,6 +20751,7 @@ define_enum_with_from_str! {
         NATUREPOWER,
         NATURESMADNESS,
         NEEDLEARM,
+        NEVERENDINGNIGHTMARE,
         NIGHTDAZE,
         NIGHTMARE,
         NIGHTSHADE,
@@ -19613,6 +20763,7 @@ define_enum_with_from_str! {
         NUZZLE,
         OBLIVIONWING,
         OBSTRUCT,
+        OCEANICOPERETTA,
         OCTAZOOKA,
         OCTOLOCK,
         ODORSLEUTH,
@@ -19679,6 +20830,7 @@ define_enum_with_from_str! {
         PSYSHOCK,
         PSYSTRIKE,
         PSYWAVE,
+        PULVERIZINGPANCAKE,
         PUNISHMENT,
         PURIFY,
         PURSUIT,
@@ -19739,6 +20891,7 @@ define_enum_with_from_str! {
         SANDSTORM,
         SANDTOMB,
         SAPPYSEED,
+        SAVAGESPINOUT,
         SCALD,
         SCALESHOT,
         SCARYFACE,
@@ -19746,6 +20899,7 @@ define_enum_with_from_str! {
         SCRATCH,
         SCREECH,
         SEARINGSHOT,
+        SEARINGSUNRAZESMASH,
         SECRETPOWER,
         SECRETSWORD,
         SEEDBOMB,
@@ -19760,6 +20914,7 @@ define_enum_with_from_str! {
         SHADOWSNEAK,
         SHADOWSTRIKE,
         SHARPEN,
+        SHATTEREDPSYCHE,
         SHEDTAIL,
         SHEERCOLD,
         SHELLSIDEARM,
@@ -19774,6 +20929,7 @@ define_enum_with_from_str! {
         SILVERWIND,
         SIMPLEBEAM,
         SING,
+        SINISTERARROWRAID,
         SIZZLYSLIDE,
         SKETCH,
         SKILLSWAP,
@@ -19806,6 +20962,7 @@ define_enum_with_from_str! {
         SOLARBEAM,
         SOLARBLADE,
         SONICBOOM,
+        SOULSTEALING7STARSTRIKE,
         SPACIALREND,
         SPARK,
         SPARKLINGARIA,
@@ -19823,6 +20980,7 @@ define_enum_with_from_str! {
         SPITE,
         SPITUP,
         SPLASH,
+        SPLINTEREDSTORMSHARDS,
         SPLISHYSPLASH,
         SPORE,
         SPOTLIGHT,
@@ -19835,6 +20993,7 @@ define_enum_with_from_str! {
         STEELWING,
         STICKYWEB,
         STOCKPILE,
+        STOKEDSPARKSURFER,
         STOMP,
         STOMPINGTANTRUM,
         STONEAXE,
@@ -19851,6 +21010,7 @@ define_enum_with_from_str! {
         STUNSPORE,
         SUBMISSION,
         SUBSTITUTE,
+        SUBZEROSLAMMER,
         SUCKERPUNCH,
         SUNNYDAY,
         SUNSTEELSTRIKE,
@@ -19858,6 +21018,7 @@ define_enum_with_from_str! {
         SUPERFANG,
         SUPERPOWER,
         SUPERSONIC,
+        SUPERSONICSKYSTRIKE,
         SURF,
         SURGINGSTRIKES,
         SWAGGER,
@@ -19883,6 +21044,7 @@ define_enum_with_from_str! {
         TEARFULLOOK,
         TEATIME,
         TECHNOBLAST,
+        TECTONICRAGE,
         TEETERDANCE,
         TELEKINESIS,
         TELEPORT,
@@ -19926,6 +21088,7 @@ define_enum_with_from_str! {
         TRUMPCARD,
         TWINBEAM,
         TWINEEDLE,
+        TWINKLETACKLE,
         TWISTER,
         UPPERHAND,
         UPROAR,
@@ -20086,6 +21249,7 @@ pub struct Choice {
 
     pub first_move: bool,
     pub sleep_talk_move: bool,
+    pub z_move: bool,
 }
 
 impl fmt::Debug for Choice {
@@ -20215,6 +21379,7 @@ impl Default for Choice {
             target: MoveTarget::Opponent,
             first_move: true,
             sleep_talk_move: false,
+            z_move: false,
         }
     }
 }
@@ -0,0 +1,375 @@
+use crate::choices::Choices;
+use crate::engine::abilities::Abilities;
+use crate::engine::battle_environment::{
+    create_pokemon, parse_packed_team, MoveDexEntry, PackedTeamEntry, PokemonDexEntry,
+};
+use crate::engine::items::Items;
+use crate::engine::state::MoveChoice;
+use crate::mcts::{perform_mcts, MctsConfig};
+use crate::pokemon::PokemonName;
+use crate::rng::seeded_rng;
+use crate::search::{expectiminimax_search, pick_safest};
+use crate::state::{pokemon_index_iter, Pokemon, PokemonMoveIndex, Side, State};
+use rand::distributions::WeightedIndex;
+use rand::prelude::*;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+const MOVE_INDICES: [PokemonMoveIndex; 4] = [
+    PokemonMoveIndex::M0,
+    PokemonMoveIndex::M1,
+    PokemonMoveIndex::M2,
+    PokemonMoveIndex::M3,
+];
+
+struct WeightedSet {
+    pokemon: Pokemon,
+    weight: f64,
+}
+
+/// How likely each set is for each species
+///
+/// Sets that are seen more often in the source data are more likely to be sampled
+pub struct SetDistribution {
+    sets: HashMap<PokemonName, Vec<WeightedSet>>,
+    // used to recalculate the stats of revealed pokemon from a sampled set's spread
+    dex_entries: HashMap<PokemonName, PokemonDexEntry>,
+    // the order species were first seen in, so that sampling does not depend on hashing order
+    species: Vec<PokemonName>,
+}
+
+fn set_key(pkmn: &Pokemon) -> String {
+    let mut moves: Vec<String> = pkmn
+        .moves
+        .into_iter()
+        .map(|m| format!("{:?}", m.id))
+        .collect();
+    moves.sort();
+    format!(
+        "{:?}|{:?}|{:?}|{:?}|{}",
+        pkmn.id,
+        pkmn.item,
+        pkmn.ability,
+        pkmn.tera_type,
+        moves.join(",")
+    )
+}
+
+fn set_has_move(set: &Pokemon, move_id: Choices) -> bool {
+    set.moves.into_iter().any(|m| m.id == move_id)
+}
+
+// Whether a sampled set agrees with everything that has been revealed about a pokemon
+fn set_is_consistent(set: &Pokemon, revealed: &Pokemon) -> bool {
+    if revealed
+        .moves
+        .into_iter()
+        .any(|m| m.id != Choices::NONE && !set_has_move(set, m.id))
+    {
+        return false;
+    }
+    // `Items::NONE` means the item was revealed and then lost, which any set is consistent with
+    if revealed.item != Items::UNKNOWNITEM
+        && revealed.item != Items::NONE
+        && revealed.item != set.item
+    {
+        return false;
+    }
+    if revealed.ability != Abilities::NONE && revealed.ability != set.ability {
+        return false;
+    }
+    if revealed.terastallized && revealed.tera_type != set.tera_type {
+        return false;
+    }
+    true
+}
+
+// Fills in whatever has not been revealed about `revealed` using `set`
+// Revealed information and status are kept. Stats are recalculated from the set's spread at the
+// revealed level, keeping the fraction of hp that is left
+fn fill_unknowns(revealed: &mut Pokemon, set: &Pokemon, dex_entry: &PokemonDexEntry) {
+    for set_move in set.moves.into_iter() {
+        if set_move.id == Choices::NONE || set_has_move(revealed, set_move.id) {
+            continue;
+        }
+        if let Some(move_index) = MOVE_INDICES
+            .iter()
+            .find(|i| revealed.moves[*i].id == Choices::NONE)
+        {
+            revealed.moves[move_index] = set_move.clone();
+        }
+    }
+    if revealed.item == Items::UNKNOWNITEM {
+        revealed.item = set.item;
+    }
+    if revealed.ability == Abilities::NONE {
+        revealed.ability = set.ability;
+        revealed.base_ability = set.base_ability;
+    }
+    if !revealed.terastallized {
+        revealed.tera_type = set.tera_type;
+    }
+    revealed.nature = set.nature.clone();
+    revealed.evs = set.evs;
+    revealed.ivs = set.ivs;
+    let stats = dex_entry.calculate_stats(revealed.level, set.ivs, set.evs);
+    if revealed.hp > 0 && revealed.maxhp > 0 {
+        let hp = (stats.0 as f32 * revealed.hp as f32 / revealed.maxhp as f32).round() as i16;
+        revealed.hp = hp.max(1);
+    }
+    revealed.maxhp = stats.0;
+    revealed.attack = stats.1;
+    revealed.defense = stats.2;
+    revealed.special_attack = stats.3;
+    revealed.special_defense = stats.4;
+    revealed.speed = stats.5;
+}
+
+impl SetDistribution {
+    pub fn from_random_teams(
+        random_teams_json: &str,
+        pokedex_json: &str,
+        movedex_json: &str,
+    ) -> Result<SetDistribution, String> {
+        let random_teams: Vec<PackedTeamEntry> = serde_json::from_str(random_teams_json)
+            .map_err(|e| format!("Invalid random teams JSON: {}", e))?;
+        let pokedex: HashMap<String, PokemonDexEntry> = serde_json::from_str(pokedex_json)
+            .map_err(|e| format!("Invalid pokedex JSON: {}", e))?;
+        let movedex: HashMap<String, MoveDexEntry> = serde_json::from_str(movedex_json)
+            .map_err(|e| format!("Invalid movedex JSON: {}", e))?;
+
+        let mut sets: HashMap<PokemonName, Vec<WeightedSet>> = HashMap::new();
+        let mut species = vec![];
+        let mut dex_entries = HashMap::new();
+        let mut set_indices: HashMap<String, usize> = HashMap::new();
+        for team in random_teams.iter() {
+            for packed in parse_packed_team(&team.packed_team).iter() {
+                let species_name = packed.species.as_ref().unwrap_or(&packed.nickname);
+                if !pokedex.contains_key(species_name) {
+                    return Err(format!("Pokemon not found in pokedex: {}", species_name));
+                }
+                let pkmn = create_pokemon(packed, &pokedex, &movedex);
+                let key = set_key(&pkmn);
+                if !sets.contains_key(&pkmn.id) {
+                    species.push(pkmn.id);
+                    dex_entries.insert(pkmn.id, pokedex[species_name].clone());
+                }
+                let species_sets = sets.entry(pkmn.id).or_default();
+                match set_indices.get(&key) {
+                    Some(index) => species_sets[*index].weight += 1.0,
+                    None => {
+                        set_indices.insert(key, species_sets.len());
+                        species_sets.push(WeightedSet {
+                            pokemon: pkmn,
+                            weight: 1.0,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(SetDistribution {
+            sets,
+            dex_entries,
+            species,
+        })
+    }
+
+    pub fn number_of_species(&self) -> usize {
+        self.species.len()
+    }
+
+    fn sample_set<'a, R: Rng>(&'a self, revealed: &Pokemon, rng: &mut R) -> Option<&'a Pokemon> {
+        let species_sets = self.sets.get(&revealed.id)?;
+        let consistent: Vec<&WeightedSet> = species_sets
+            .iter()
+            .filter(|s| set_is_consistent(&s.pokemon, revealed))
+            .collect();
+        // nothing agrees with what was revealed (the source is missing the set),
+        // so fall back to any set of the same species
+        let candidates = if consistent.is_empty() {
+            species_sets.iter().collect()
+        } else {
+            consistent
+        };
+        let dist = WeightedIndex::new(candidates.iter().map(|s| s.weight)).unwrap();
+        Some(&candidates[dist.sample(rng)].pokemon)
+    }
+
+    fn sample_unrevealed_pokemon<R: Rng>(
+        &self,
+        excluded_species: &[PokemonName],
+        rng: &mut R,
+    ) -> Option<Pokemon> {
+        let candidates: Vec<&WeightedSet> = self
+            .species
+            .iter()
+            .filter(|species| !excluded_species.contains(species))
+            .flat_map(|species| self.sets[species].iter())
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        let dist = WeightedIndex::new(candidates.iter().map(|s| s.weight)).unwrap();
+        Some(candidates[dist.sample(rng)].pokemon.clone())
+    }
+
+    /// Returns a copy of `side` where everything that is unknown has been sampled
+    ///
+    /// Unknowns are marked the way `showdown_log::ShowdownLogParser` leaves them:
+    /// `PokemonName::NONE` pokemon, `Choices::NONE` moves, `Items::UNKNOWNITEM` and
+    /// `Abilities::NONE`. `PokemonName::NONE` pokemon with 0 hp are empty slots and are not sampled
+    pub fn sample_side<R: Rng>(&self, side: &Side, rng: &mut R) -> Side {
+        let mut sampled = side.clone();
+        let mut species_on_team: Vec<PokemonName> = pokemon_index_iter()
+            .map(|i| side.pokemon[i].id)
+            .filter(|id| *id != PokemonName::NONE)
+            .collect();
+
+        for pkmn_index in pokemon_index_iter() {
+            let pkmn = &mut sampled.pokemon[pkmn_index];
+            if pkmn.id == PokemonName::NONE {
+                if pkmn.hp == 0 {
+                    continue;
+                }
+                if let Some(new_pkmn) = self.sample_unrevealed_pokemon(&species_on_team, rng) {
+                    species_on_team.push(new_pkmn.id);
+                    *pkmn = new_pkmn;
+                }
+            } else if let Some(set) = self.sample_set(pkmn, rng) {
+                fill_unknowns(pkmn, set, &self.dex_entries[&pkmn.id]);
+            }
+        }
+        sampled
+    }
+
+    /// Returns a copy of `state` where the unknowns on side two have been sampled
+    pub fn determinize<R: Rng>(&self, state: &State, rng: &mut R) -> State {
+        let mut determinized = state.clone();
+        determinized.side_two = self.sample_side(&state.side_two, rng);
+        determinized
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DeterminizedMoveScore {
+    pub move_choice: MoveChoice,
+    pub total_score: f32,
+    pub samples: usize,
+}
+
+impl DeterminizedMoveScore {
+    pub fn average_score(&self) -> f32 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+        self.total_score / self.samples as f32
+    }
+}
+
+/// Side one's scores aggregated over every sampled state
+///
+/// An option that is only available in some of the samples (e.g. because of a sampled trapping
+/// ability) is only averaged over the samples it was available in
+pub struct DeterminizationResult {
+    pub s1: Vec<DeterminizedMoveScore>,
+    pub samples: usize,
+}
+
+impl DeterminizationResult {
+    fn new() -> DeterminizationResult {
+        DeterminizationResult {
+            s1: vec![],
+            samples: 0,
+        }
+    }
+
+    fn add_score(&mut self, move_choice: MoveChoice, score: f32) {
+        match self.s1.iter_mut().find(|s| s.move_choice == move_choice) {
+            Some(move_score) => {
+                move_score.total_score += score;
+                move_score.samples += 1;
+            }
+            None => self.s1.push(DeterminizedMoveScore {
+                move_choice,
+                total_score: score,
+                samples: 1,
+            }),
+        }
+    }
+
+    pub fn best_move(&self) -> Option<MoveChoice> {
+        self.s1
+            .iter()
+            .max_by(|a, b| a.average_score().partial_cmp(&b.average_score()).unwrap())
+            .map(|s| s.move_choice)
+    }
+}
+
+/// Runs MCTS with `config` on each of `samples` determinizations of `state`
+///
+/// The score of a side one move in one sample is the fraction of root visits it received,
+/// which is how `MctsPlayer` picks a move from a single search
+pub fn determinized_mcts<R: Rng>(
+    state: &State,
+    set_distribution: &SetDistribution,
+    samples: usize,
+    config: &MctsConfig,
+    rng: &mut R,
+) -> DeterminizationResult {
+    let mut result = DeterminizationResult::new();
+    for _ in 0..samples {
+        let mut determinized = set_distribution.determinize(state, rng);
+        let (s1_options, s2_options) = determinized.root_get_all_options();
+        let mcts_result = perform_mcts(
+            &mut determinized,
+            s1_options,
+            s2_options,
+            config,
+            &mut seeded_rng(rng.gen()),
+        );
+        let total_visits: i64 = mcts_result.s1.iter().map(|s| s.visits).sum();
+        for side_result in mcts_result.s1.iter() {
+            result.add_score(
+                side_result.move_choice,
+                side_result.visits as f32 / total_visits.max(1) as f32,
+            );
+        }
+        result.samples += 1;
+    }
+    result
+}
+
+/// Runs expectiminimax on `samples` determinizations of `state`
+///
+/// The score of a side one move in one sample is its worst case over side two's replies.
+/// Alpha-beta pruning is not used because it only gives bounds for the pruned moves, and the
+/// scores of every move are needed to average them
+pub fn determinized_expectiminimax<R: Rng>(
+    state: &State,
+    set_distribution: &SetDistribution,
+    samples: usize,
+    depth: i8,
+    rng: &mut R,
+) -> DeterminizationResult {
+    let mut result = DeterminizationResult::new();
+    for _ in 0..samples {
+        let mut determinized = set_distribution.determinize(state, rng);
+        let (s1_options, s2_options) = determinized.root_get_all_options();
+        let num_s2_moves = s2_options.len();
+        let scores = expectiminimax_search(
+            &mut determinized,
+            depth,
+            s1_options.clone(),
+            s2_options,
+            false,
+            &Arc::new(Mutex::new(true)),
+        );
+        for (s1_index, s1_move) in s1_options.iter().enumerate() {
+            let row = &scores[s1_index * num_s2_moves..(s1_index + 1) * num_s2_moves];
+            let (_, worst_case) = pick_safest(&row.to_vec(), 1, num_s2_moves);
+            result.add_score(*s1_move, worst_case);
+        }
+        result.samples += 1;
+    }
+    result
+}
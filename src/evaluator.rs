@@ -0,0 +1,20 @@
+use crate::engine::evaluate::evaluate;
+use crate::state::State;
+
+/// Scores a state from side one's perspective, higher is better for side one
+///
+/// Search and MCTS are generic over this so that a different heuristic can be swapped in
+/// without changing either of them
+pub trait Evaluator: Send + Sync {
+    fn evaluate(&self, state: &State) -> f32;
+}
+
+/// The built-in heuristic, `engine::evaluate::evaluate`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultEvaluator;
+
+impl Evaluator for DefaultEvaluator {
+    fn evaluate(&self, state: &State) -> f32 {
+        evaluate(state)
+    }
+}
@@ -1,9 +1,10 @@
 #![allow(unused_variables)]
 use crate::define_enum_with_from_str;
+use serde::{Deserialize, Serialize};
 
 define_enum_with_from_str! {
     #[repr(i16)]
-    #[derive(PartialEq, Debug, Clone, Copy)]
+    #[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
     Abilities {
         NONE,
         NOABILITY,
@@ -4,6 +4,7 @@ use crate::choices::Choices;
 use crate::define_enum_with_from_str;
 use crate::instruction::{DisableMoveInstruction, Instruction};
 use crate::state::{Pokemon, SideReference};
+use serde::{Deserialize, Serialize};
 
 pub fn get_choice_move_disable_instructions(
     pkmn: &Pokemon,
@@ -25,7 +26,7 @@ pub fn get_choice_move_disable_instructions(
 
 define_enum_with_from_str! {
     #[repr(u8)]
-    #[derive(Debug, PartialEq, Clone, Copy)]
+    #[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
     Items {
         NONE,
         UNKNOWNITEM,
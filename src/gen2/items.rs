@@ -7,11 +7,12 @@ use crate::instruction::{
 };
 use crate::pokemon::PokemonName;
 use crate::state::{Pokemon, PokemonStatus, PokemonType, Side, SideReference, State};
+use serde::{Deserialize, Serialize};
 use std::cmp;
 
 define_enum_with_from_str! {
     #[repr(u8)]
-    #[derive(Debug, PartialEq, Clone, Copy)]
+    #[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
     Items {
         NONE,
         UNKNOWNITEM,
@@ -9,6 +9,7 @@ use crate::state::{
     PokemonSideCondition, PokemonStatus, PokemonType, Side, SideReference, State,
 };
 use core::panic;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
 fn multiply_boost(boost_num: i8, stat_value: i16) -> i16 {
@@ -30,7 +31,7 @@ fn multiply_boost(boost_num: i8, stat_value: i16) -> i16 {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
 pub enum MoveChoice {
     Move(PokemonMoveIndex),
     Switch(PokemonIndex),
@@ -76,7 +77,7 @@ impl MoveChoice {
 
 define_enum_with_from_str! {
     #[repr(u8)]
-    #[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+    #[derive(PartialEq, Eq, Hash, Debug, Copy, Clone, Serialize, Deserialize)]
     PokemonVolatileStatus {
         NONE,
         AQUARING,
@@ -186,7 +187,7 @@ define_enum_with_from_str! {
 
 define_enum_with_from_str! {
     #[repr(u8)]
-    #[derive(Debug, PartialEq, Copy, Clone)]
+    #[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
     Weather {
         NONE,
         SUN,
@@ -201,7 +202,7 @@ define_enum_with_from_str! {
 
 define_enum_with_from_str! {
     #[repr(u8)]
-    #[derive(Debug, PartialEq, Copy, Clone)]
+    #[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
     Terrain {
         NONE,
         ELECTRICTERRAIN,
@@ -12,13 +12,14 @@ use crate::instruction::{
     DamageInstruction, HealInstruction, Instruction, StateInstructions,
 };
 use crate::state::{PokemonBoostableStat, PokemonStatus, PokemonType, SideReference, State};
+use serde::{Deserialize, Serialize};
 use std::cmp;
 
 pub const WEATHER_ABILITY_TURNS: i8 = -1;
 
 define_enum_with_from_str! {
     #[repr(i16)]
-    #[derive(PartialEq, Debug, Clone, Copy)]
+    #[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
     Abilities {
         NONE,
         AIRLOCK,
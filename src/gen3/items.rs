@@ -10,11 +10,12 @@ use crate::pokemon::PokemonName;
 use crate::state::{
     Pokemon, PokemonBoostableStat, PokemonStatus, PokemonType, Side, SideReference, State,
 };
+use serde::{Deserialize, Serialize};
 use std::cmp;
 
 define_enum_with_from_str! {
     #[repr(u8)]
-    #[derive(Debug, PartialEq, Clone, Copy)]
+    #[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
     Items {
         NONE,
         UNKNOWNITEM,
@@ -16,9 +16,10 @@ use crate::instruction::{
 };
 use crate::pokemon::PokemonName;
 use crate::state::{
-    PokemonBoostableStat, PokemonSideCondition, PokemonStatus, PokemonType, Side, SideReference,
-    State,
+    PokemonBoostableStat, PokemonGender, PokemonSideCondition, PokemonStatus, PokemonType, Side,
+    SideReference, State,
 };
+use serde::{Deserialize, Serialize};
 use std::cmp;
 
 #[cfg(any(feature = "gen3", feature = "gen4", feature = "gen5"))]
@@ -29,7 +30,7 @@ pub const WEATHER_ABILITY_TURNS: i8 = 5;
 
 define_enum_with_from_str! {
     #[repr(i16)]
-    #[derive(PartialEq, Debug, Clone, Copy)]
+    #[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
     Abilities {
         NONE,
         ARMORTAIL,
@@ -1923,6 +1924,16 @@ pub fn ability_modify_attack_being_used(
                 attacker_choice.base_power *= 1.5;
             }
         }
+        Abilities::RIVALRY => {
+            let defender_gender = defending_side.get_active_immutable().gender;
+            if attacking_pkmn.gender.is_opposite_of(&defender_gender) {
+                attacker_choice.base_power *= 0.75;
+            } else if attacking_pkmn.gender == defender_gender
+                && defender_gender != PokemonGender::GENDERLESS
+            {
+                attacker_choice.base_power *= 1.25;
+            }
+        }
         #[cfg(any(feature = "gen9", feature = "gen8", feature = "gen7"))]
         Abilities::REFRIGERATE => {
             if attacker_choice.move_type == PokemonType::NORMAL {
@@ -2582,6 +2593,17 @@ pub fn ability_modify_attack_against(
                 })
             }
         }
+        Abilities::CUTECHARM => {
+            if attacker_choice.flags.contact
+                && attacking_pkmn.gender.is_opposite_of(&target_pkmn.gender)
+            {
+                attacker_choice.add_or_create_secondaries(Secondary {
+                    chance: 30.0,
+                    target: MoveTarget::User,
+                    effect: Effect::VolatileStatus(PokemonVolatileStatus::ATTRACT),
+                })
+            }
+        }
         Abilities::WONDERSKIN => {
             if attacker_choice.category == MoveCategory::Status && attacker_choice.accuracy > 50.0 {
                 attacker_choice.accuracy = 50.0;
@@ -15,6 +15,100 @@ impl PokemonName {
             PokemonName::PALAFINHERO => (100, 160, 97, 106, 87, 100),
             PokemonName::EISCUE => (75, 80, 110, 65, 90, 50),
             PokemonName::EISCUENOICE => (75, 80, 70, 65, 50, 130),
+
+            // mega evolutions and the formes they evolve from
+            PokemonName::VENUSAUR => (80, 82, 83, 100, 100, 80),
+            PokemonName::VENUSAURMEGA => (80, 100, 123, 122, 120, 80),
+            PokemonName::CHARIZARD => (78, 84, 78, 109, 85, 100),
+            PokemonName::CHARIZARDMEGAX => (78, 130, 111, 130, 85, 100),
+            PokemonName::CHARIZARDMEGAY => (78, 104, 78, 159, 115, 100),
+            PokemonName::BLASTOISE => (79, 83, 100, 85, 105, 78),
+            PokemonName::BLASTOISEMEGA => (79, 103, 120, 135, 115, 78),
+            PokemonName::BEEDRILL => (65, 90, 40, 45, 80, 75),
+            PokemonName::BEEDRILLMEGA => (65, 150, 40, 15, 80, 145),
+            PokemonName::PIDGEOT => (83, 80, 75, 70, 70, 101),
+            PokemonName::PIDGEOTMEGA => (83, 80, 80, 135, 80, 121),
+            PokemonName::ALAKAZAM => (55, 50, 45, 135, 95, 120),
+            PokemonName::ALAKAZAMMEGA => (55, 50, 65, 175, 105, 150),
+            PokemonName::SLOWBRO => (95, 75, 110, 100, 80, 30),
+            PokemonName::SLOWBROMEGA => (95, 75, 180, 130, 80, 30),
+            PokemonName::GENGAR => (60, 65, 60, 130, 75, 110),
+            PokemonName::GENGARMEGA => (60, 65, 80, 170, 95, 130),
+            PokemonName::KANGASKHAN => (105, 95, 80, 40, 80, 90),
+            PokemonName::KANGASKHANMEGA => (105, 125, 100, 60, 100, 100),
+            PokemonName::PINSIR => (65, 125, 100, 55, 70, 85),
+            PokemonName::PINSIRMEGA => (65, 155, 120, 65, 90, 105),
+            PokemonName::GYARADOS => (95, 125, 79, 60, 100, 81),
+            PokemonName::GYARADOSMEGA => (95, 155, 109, 70, 130, 81),
+            PokemonName::AERODACTYL => (80, 105, 65, 60, 75, 130),
+            PokemonName::AERODACTYLMEGA => (80, 135, 85, 70, 95, 150),
+            PokemonName::MEWTWO => (106, 110, 90, 154, 90, 130),
+            PokemonName::MEWTWOMEGAX => (106, 190, 100, 154, 100, 130),
+            PokemonName::MEWTWOMEGAY => (106, 150, 70, 194, 120, 140),
+            PokemonName::AMPHAROS => (90, 75, 85, 115, 90, 55),
+            PokemonName::AMPHAROSMEGA => (90, 95, 105, 165, 110, 45),
+            PokemonName::STEELIX => (75, 85, 200, 55, 65, 30),
+            PokemonName::STEELIXMEGA => (75, 125, 230, 55, 95, 30),
+            PokemonName::SCIZOR => (70, 130, 100, 55, 80, 65),
+            PokemonName::SCIZORMEGA => (70, 150, 140, 65, 100, 75),
+            PokemonName::HERACROSS => (80, 125, 75, 40, 95, 85),
+            PokemonName::HERACROSSMEGA => (80, 185, 115, 40, 105, 75),
+            PokemonName::HOUNDOOM => (75, 90, 50, 110, 80, 95),
+            PokemonName::HOUNDOOMMEGA => (75, 90, 90, 140, 90, 115),
+            PokemonName::TYRANITAR => (100, 134, 110, 95, 100, 61),
+            PokemonName::TYRANITARMEGA => (100, 164, 150, 95, 120, 71),
+            PokemonName::SCEPTILE => (70, 85, 65, 105, 85, 120),
+            PokemonName::SCEPTILEMEGA => (70, 110, 75, 145, 85, 145),
+            PokemonName::BLAZIKEN => (80, 120, 70, 110, 70, 80),
+            PokemonName::BLAZIKENMEGA => (80, 160, 80, 130, 80, 100),
+            PokemonName::SWAMPERT => (100, 110, 90, 85, 90, 60),
+            PokemonName::SWAMPERTMEGA => (100, 150, 110, 95, 110, 70),
+            PokemonName::GARDEVOIR => (68, 65, 65, 125, 115, 80),
+            PokemonName::GARDEVOIRMEGA => (68, 85, 65, 165, 135, 100),
+            PokemonName::SABLEYE => (50, 75, 75, 65, 65, 50),
+            PokemonName::SABLEYEMEGA => (50, 85, 125, 85, 115, 20),
+            PokemonName::MAWILE => (50, 85, 85, 55, 55, 50),
+            PokemonName::MAWILEMEGA => (50, 105, 125, 55, 95, 50),
+            PokemonName::AGGRON => (70, 110, 180, 60, 60, 50),
+            PokemonName::AGGRONMEGA => (70, 140, 230, 60, 80, 50),
+            PokemonName::MEDICHAM => (60, 60, 75, 60, 75, 80),
+            PokemonName::MEDICHAMMEGA => (60, 100, 85, 80, 85, 100),
+            PokemonName::MANECTRIC => (70, 75, 60, 105, 60, 105),
+            PokemonName::MANECTRICMEGA => (70, 75, 80, 135, 80, 135),
+            PokemonName::SHARPEDO => (70, 120, 40, 95, 40, 95),
+            PokemonName::SHARPEDOMEGA => (70, 140, 70, 110, 65, 105),
+            PokemonName::CAMERUPT => (70, 100, 70, 105, 75, 40),
+            PokemonName::CAMERUPTMEGA => (70, 120, 100, 145, 105, 20),
+            PokemonName::ALTARIA => (75, 70, 90, 70, 105, 80),
+            PokemonName::ALTARIAMEGA => (75, 110, 110, 110, 105, 80),
+            PokemonName::BANETTE => (64, 115, 65, 83, 63, 65),
+            PokemonName::BANETTEMEGA => (64, 165, 75, 93, 83, 75),
+            PokemonName::ABSOL => (65, 130, 60, 75, 60, 75),
+            PokemonName::ABSOLMEGA => (65, 150, 60, 115, 60, 115),
+            PokemonName::GLALIE => (80, 80, 80, 80, 80, 80),
+            PokemonName::GLALIEMEGA => (80, 120, 80, 120, 80, 100),
+            PokemonName::SALAMENCE => (95, 135, 80, 110, 80, 100),
+            PokemonName::SALAMENCEMEGA => (95, 145, 130, 120, 90, 120),
+            PokemonName::METAGROSS => (80, 135, 130, 95, 90, 70),
+            PokemonName::METAGROSSMEGA => (80, 145, 150, 105, 110, 110),
+            PokemonName::LATIAS => (80, 80, 90, 110, 130, 110),
+            PokemonName::LATIASMEGA => (80, 100, 120, 140, 150, 110),
+            PokemonName::LATIOS => (80, 90, 80, 130, 110, 110),
+            PokemonName::LATIOSMEGA => (80, 130, 100, 160, 120, 110),
+            PokemonName::LOPUNNY => (65, 76, 84, 54, 96, 105),
+            PokemonName::LOPUNNYMEGA => (65, 136, 94, 54, 96, 135),
+            PokemonName::GARCHOMP => (108, 130, 95, 80, 85, 102),
+            PokemonName::GARCHOMPMEGA => (108, 170, 115, 120, 95, 92),
+            PokemonName::LUCARIO => (70, 110, 70, 115, 70, 90),
+            PokemonName::LUCARIOMEGA => (70, 145, 88, 140, 70, 112),
+            PokemonName::ABOMASNOW => (90, 92, 75, 92, 85, 60),
+            PokemonName::ABOMASNOWMEGA => (90, 132, 105, 132, 105, 30),
+            PokemonName::GALLADE => (68, 125, 65, 65, 115, 80),
+            PokemonName::GALLADEMEGA => (68, 165, 95, 65, 115, 110),
+            PokemonName::AUDINO => (103, 60, 86, 60, 86, 50),
+            PokemonName::AUDINOMEGA => (103, 60, 126, 80, 126, 50),
+            PokemonName::DIANCIE => (50, 100, 150, 100, 150, 50),
+            PokemonName::DIANCIEMEGA => (50, 160, 110, 160, 110, 110),
             _ => panic!("Base stats not implemented for {}", self),
         }
     }
@@ -1,21 +1,35 @@
-use crate::choices::Choices;
+use crate::battle_format::BattleFormat;
+use crate::choices::{Choice, Choices};
 use crate::engine::abilities::Abilities;
-use crate::engine::generate_instructions::generate_instructions_from_move_pair;
+use crate::engine::choice_effects::{convert_to_max_move, convert_to_z_move};
+use crate::engine::generate_instructions::{
+    add_dynamax_instructions, add_mega_evolution_instructions, calculate_damage_rolls,
+    generate_instructions_from_move_pair,
+};
 use crate::engine::items::Items;
 use crate::engine::state::{MoveChoice, Terrain, Weather};
+use crate::evaluator::{DefaultEvaluator, Evaluator};
 use crate::instruction::StateInstructions;
-use crate::mcts::perform_mcts;
+use crate::mcts::{
+    perform_mcts_with_tree, perform_parallel_mcts, MctsConfig, MctsResult, MctsTree,
+};
 use crate::pokemon::PokemonName;
-use crate::state::{LastUsedMove, SideReference, VolatileStatusDurations};
+use crate::replay::Replay;
+use crate::rng::{battle_seed, random_seed, seeded_rng, BattleRng};
+use crate::search::{
+    expectiminimax_search_with_table, iterative_deepen_expectiminimax_with_evaluator,
+    solve_nash_equilibrium, TranspositionTable, DEFAULT_TRANSPOSITION_TABLE_SIZE,
+};
+use crate::state::{PositionState, SideReference, StateHash};
 use crate::state::{
-    Move, Pokemon, PokemonIndex, PokemonMoves, PokemonNature, PokemonStatus, PokemonType, Side,
-    SideConditions, SidePokemon, State, StateTerrain, StateTrickRoom, StateWeather,
+    Move, Pokemon, PokemonGender, PokemonIndex, PokemonMoves, PokemonNature, PokemonStatus, PokemonType, Side,
+    SidePokemon, State, StateTerrain, StateTrickRoom, StateWeather,
 };
 use deunicode::deunicode;
-use rand::{thread_rng, Rng};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::Deserialize;
 use std::collections::HashMap;
-use std::collections::HashSet;
 use std::io::Write;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
@@ -23,21 +37,24 @@ use std::thread;
 use std::time::Duration;
 
 #[derive(Deserialize)]
-struct PackedTeamEntry {
+pub struct PackedTeamEntry {
     #[serde(rename = "packedTeam")]
-    packed_team: String,
+    pub packed_team: String,
 }
 
-#[derive(Deserialize)]
-struct PokemonDexEntry {
+#[derive(Deserialize, Clone)]
+pub struct PokemonDexEntry {
     types: Vec<String>,
     #[serde(rename = "baseStats")]
     base_stats: BaseStats,
     weightkg: f32,
+    // only present for species that are always the same gender, `N` for genderless
+    #[serde(default)]
+    gender: Option<String>,
 }
 
-#[derive(Deserialize)]
-struct BaseStats {
+#[derive(Deserialize, Clone)]
+pub struct BaseStats {
     hp: u16,
     attack: u16,
     defense: u16,
@@ -64,25 +81,52 @@ fn normalize_name(name: &str) -> String {
 }
 
 #[derive(Debug)]
-struct PackedPokemon {
-    nickname: String,
-    species: Option<String>, // None if identical to nickname
-    item: String,
-    ability: String,
-    moves: Vec<String>,
-    nature: String,
-    evs: Vec<u8>,
-    tera_type: String,
+pub struct PackedPokemon {
+    pub nickname: String,
+    pub species: Option<String>, // None if identical to nickname
+    pub item: String,
+    pub ability: String,
+    pub moves: Vec<String>,
+    pub nature: String,
+    pub evs: Vec<u8>,
+    pub ivs: Vec<u8>,
+    // `M`, `F`, or empty if not given
+    pub gender: String,
+    pub tera_type: String,
+    pub gigantamax: bool,
+}
+
+impl PokemonDexEntry {
+    // hp, attack, defense, special attack, special defense and speed
+    pub fn calculate_stats(
+        &self,
+        level: i8,
+        ivs: (u8, u8, u8, u8, u8, u8),
+        evs: (u8, u8, u8, u8, u8, u8),
+    ) -> (i16, i16, i16, i16, i16, i16) {
+        let level = level as u16;
+        let stat_value =
+            |base: u16, iv: u8, ev: u8| (2 * base + iv as u16 + ev as u16 / 4) * level / 100;
+        let base_stats = &self.base_stats;
+        (
+            (stat_value(base_stats.hp, ivs.0, evs.0) + level + 10) as i16,
+            (stat_value(base_stats.attack, ivs.1, evs.1) + 5) as i16,
+            (stat_value(base_stats.defense, ivs.2, evs.2) + 5) as i16,
+            (stat_value(base_stats.special_attack, ivs.3, evs.3) + 5) as i16,
+            (stat_value(base_stats.special_defense, ivs.4, evs.4) + 5) as i16,
+            (stat_value(base_stats.speed, ivs.5, evs.5) + 5) as i16,
+        )
+    }
 }
 
 #[derive(Deserialize)]
-struct MoveDexEntry {
+pub struct MoveDexEntry {
     pp: i8,
     #[serde(flatten)]
     _other: HashMap<String, serde_json::Value>,
 }
 
-fn parse_packed_team(packed_team: &str) -> Vec<PackedPokemon> {
+pub fn parse_packed_team(packed_team: &str) -> Vec<PackedPokemon> {
     packed_team
         .split(']')
         .filter(|s| !s.is_empty())
@@ -104,18 +148,18 @@ fn parse_packed_team(packed_team: &str) -> Vec<PackedPokemon> {
             };
 
             // Parse IVs
-            // let ivs = if parts.len() > 8 && !parts[8].is_empty() {
-            //     let mut iv_list = parts[8]
-            //         .split(',')
-            //         .map(|s| s.parse::<u8>().unwrap_or(31))
-            //         .collect::<Vec<_>>();
-            //     while iv_list.len() < 6 {
-            //         iv_list.push(31);
-            //     }
-            //     iv_list
-            // } else {
-            //     vec![31, 31, 31, 31, 31, 31]
-            // };
+            let ivs = if parts.len() > 8 && !parts[8].is_empty() {
+                let mut iv_list = parts[8]
+                    .split(',')
+                    .map(|s| s.parse::<u8>().unwrap_or(31))
+                    .collect::<Vec<_>>();
+                while iv_list.len() < 6 {
+                    iv_list.push(31);
+                }
+                iv_list
+            } else {
+                vec![31, 31, 31, 31, 31, 31]
+            };
 
             // // Get level
             // let level = if parts.len() > 10 && !parts[10].is_empty() {
@@ -137,6 +181,9 @@ fn parse_packed_team(packed_team: &str) -> Vec<PackedPokemon> {
                 "".to_string()
             };
 
+            // the gigantamax flag is the fourth comma-separated value in the last part
+            let gigantamax = parts.last().unwrap().split(',').nth(3) == Some("G");
+
             PackedPokemon {
                 nickname: normalize_name(parts[0]),
                 species: if parts[1].is_empty() {
@@ -153,17 +200,32 @@ fn parse_packed_team(packed_team: &str) -> Vec<PackedPokemon> {
                     normalize_name(parts[5])
                 },
                 evs,
-                // gender: parts[7].to_string(),
-                // ivs,
+                ivs,
+                gender: parts.get(7).unwrap_or(&"").to_string(),
                 // shiny: parts.len() > 9 && parts[9] == "S",
                 // level,
                 tera_type,
+                gigantamax,
             }
         })
         .collect()
 }
 
-fn create_pokemon(
+// a gender that was not given is only known for species that are always the same gender,
+// otherwise the pokemon is treated as genderless
+fn packed_gender(packed: &PackedPokemon, dex_entry: &PokemonDexEntry) -> PokemonGender {
+    let gender = match dex_entry.gender.as_deref() {
+        Some(gender) if packed.gender.is_empty() => gender,
+        _ => packed.gender.as_str(),
+    };
+    match gender {
+        "M" => PokemonGender::MALE,
+        "F" => PokemonGender::FEMALE,
+        _ => PokemonGender::GENDERLESS,
+    }
+}
+
+pub fn create_pokemon(
     packed: &PackedPokemon,
     pokedex: &HashMap<String, PokemonDexEntry>,
     movedex: &HashMap<String, MoveDexEntry>,
@@ -224,6 +286,25 @@ fn create_pokemon(
         }
     }
 
+    let evs = (
+        packed.evs[0],
+        packed.evs[1],
+        packed.evs[2],
+        packed.evs[3],
+        packed.evs[4],
+        packed.evs[5],
+    );
+    let ivs = (
+        packed.ivs[0],
+        packed.ivs[1],
+        packed.ivs[2],
+        packed.ivs[3],
+        packed.ivs[4],
+        packed.ivs[5],
+    );
+    // every pokemon is level 100
+    let stats = dex_entry.calculate_stats(100, ivs, evs);
+
     // Rest of the Pokemon creation remains the same
     Pokemon {
         id: PokemonName::from_str(
@@ -237,42 +318,22 @@ fn create_pokemon(
         level: 100,
         types: (type1, type2),
         base_types: (type1, type2),
-        hp: ((2 * dex_entry.base_stats.hp + 31 + (packed.evs[0] as u16 / 4)) * 100 / 100 + 110)
-            as i16,
-        maxhp: ((2 * dex_entry.base_stats.hp + 31 + (packed.evs[0] as u16 / 4)) * 100 / 100 + 110)
-            as i16,
+        hp: stats.0,
+        maxhp: stats.0,
         ability: Abilities::from_str(&normalize_name(&packed.ability)).unwrap_or(Abilities::NONE),
         base_ability: Abilities::from_str(&normalize_name(&packed.ability))
             .unwrap_or(Abilities::NONE),
         item: Items::from_str(&normalize_name(&packed.item)).unwrap_or(Items::NONE),
         nature: PokemonNature::from_str(&normalize_name(&packed.nature))
             .unwrap_or(PokemonNature::SERIOUS),
-        evs: (
-            packed.evs[0],
-            packed.evs[1],
-            packed.evs[2],
-            packed.evs[3],
-            packed.evs[4],
-            packed.evs[5],
-        ),
-        attack: ((2 * dex_entry.base_stats.attack + 31 + (packed.evs[1] as u16 / 4)) * 100 / 100
-            + 5) as i16,
-        defense: ((2 * dex_entry.base_stats.defense + 31 + (packed.evs[2] as u16 / 4)) * 100 / 100
-            + 5) as i16,
-        special_attack: ((2 * dex_entry.base_stats.special_attack
-            + 31
-            + (packed.evs[3] as u16 / 4))
-            * 100
-            / 100
-            + 5) as i16,
-        special_defense: ((2 * dex_entry.base_stats.special_defense
-            + 31
-            + (packed.evs[4] as u16 / 4))
-            * 100
-            / 100
-            + 5) as i16,
-        speed: ((2 * dex_entry.base_stats.speed + 31 + (packed.evs[5] as u16 / 4)) * 100 / 100 + 5)
-            as i16,
+        evs,
+        ivs,
+        gender: packed_gender(packed, dex_entry),
+        attack: stats.1,
+        defense: stats.2,
+        special_attack: stats.3,
+        special_defense: stats.4,
+        speed: stats.5,
         status: PokemonStatus::NONE,
         rest_turns: 0,
         sleep_turns: 0,
@@ -280,14 +341,17 @@ fn create_pokemon(
         terastallized: false,
         tera_type: PokemonType::from_str(&normalize_name(&packed.tera_type))
             .unwrap_or(PokemonType::NORMAL),
+        gigantamax: packed.gigantamax,
         moves,
     }
 }
 
+// Two teams are picked from `random_teams_json` using `rng`
 pub fn initialize_battle_state(
     random_teams_json: &str,
     pokedex_json: &str,
     movedex_json: &str,
+    rng: &mut BattleRng,
 ) -> State {
     let random_teams: Vec<PackedTeamEntry> =
         serde_json::from_str(random_teams_json).expect("Failed to parse random teams JSON");
@@ -300,105 +364,229 @@ pub fn initialize_battle_state(
 
     // Team selection logic remains the same
     use rand::seq::SliceRandom;
-    let mut rng = rand::thread_rng();
-    let selected_teams: Vec<_> = random_teams.choose_multiple(&mut rng, 2).collect();
+    let selected_teams: Vec<_> = random_teams.choose_multiple(rng, 2).collect();
 
     let team1 = parse_packed_team(&selected_teams[0].packed_team);
     let team2 = parse_packed_team(&selected_teams[1].packed_team);
 
-    assert_eq!(team1.len(), 6, "Team 1 doesn't have 6 Pokemon: {:?}", team1);
-    assert_eq!(team2.len(), 6, "Team 2 doesn't have 6 Pokemon: {:?}", team2);
-
-    // Create Pokemon with movedex
-    let side_one_pokemon = SidePokemon {
-        p0: create_pokemon(&team1[0], &pokedex, &movedex),
-        p1: create_pokemon(&team1[1], &pokedex, &movedex),
-        p2: create_pokemon(&team1[2], &pokedex, &movedex),
-        p3: create_pokemon(&team1[3], &pokedex, &movedex),
-        p4: create_pokemon(&team1[4], &pokedex, &movedex),
-        p5: create_pokemon(&team1[5], &pokedex, &movedex),
-    };
+    let team1 = team1
+        .iter()
+        .map(|p| create_pokemon(p, &pokedex, &movedex))
+        .collect();
+    let team2 = team2
+        .iter()
+        .map(|p| create_pokemon(p, &pokedex, &movedex))
+        .collect();
 
-    let side_two_pokemon = SidePokemon {
-        p0: create_pokemon(&team2[0], &pokedex, &movedex),
-        p1: create_pokemon(&team2[1], &pokedex, &movedex),
-        p2: create_pokemon(&team2[2], &pokedex, &movedex),
-        p3: create_pokemon(&team2[3], &pokedex, &movedex),
-        p4: create_pokemon(&team2[4], &pokedex, &movedex),
-        p5: create_pokemon(&team2[5], &pokedex, &movedex),
-    };
+    BattleStateBuilder::new(team1, team2)
+        .with_seed(rng.gen())
+        .build()
+        .unwrap()
+}
 
-    // Rest of the state initialization remains the same
-    let side_one = Side {
-        active_index: PokemonIndex::P0,
-        baton_passing: false,
-        shed_tailing: false,
-        pokemon: side_one_pokemon,
-        side_conditions: SideConditions::default(),
-        wish: (0, 0),
-        future_sight: (0, PokemonIndex::P0),
-        force_switch: false,
-        force_trapped: false,
-        slow_uturn_move: false,
-        volatile_statuses: HashSet::new(),
-        substitute_health: 0,
-        attack_boost: 0,
-        defense_boost: 0,
-        special_attack_boost: 0,
-        special_defense_boost: 0,
-        speed_boost: 0,
-        accuracy_boost: 0,
-        evasion_boost: 0,
-        last_used_move: LastUsedMove::None,
-        damage_dealt: Default::default(),
-        switch_out_move_second_saved_move: Choices::NONE,
-        volatile_status_durations: VolatileStatusDurations::default(),
-    };
+// The generation this build of the engine simulates
+#[cfg(feature = "gen4")]
+pub const GENERATION: u8 = 4;
+#[cfg(feature = "gen5")]
+pub const GENERATION: u8 = 5;
+#[cfg(feature = "gen6")]
+pub const GENERATION: u8 = 6;
+#[cfg(feature = "gen7")]
+pub const GENERATION: u8 = 7;
+#[cfg(feature = "gen8")]
+pub const GENERATION: u8 = 8;
+#[cfg(feature = "gen9")]
+pub const GENERATION: u8 = 9;
+
+/// Which pokemon start the battle on each side
+#[derive(Debug, Clone, PartialEq)]
+pub enum LeadSelection {
+    /// The first pokemon of each team lead, as in random battles
+    TeamOrder,
+    /// The pokemon at these positions of side one's and side two's teams lead.
+    /// Doubles needs two leads per side
+    Fixed(Vec<usize>, Vec<usize>),
+    /// Leads are picked at random using the builder's seed
+    Random,
+    /// `State::team_preview` is set and the players pick their leads when the battle starts.
+    /// Singles only
+    TeamPreview,
+}
 
-    let side_two = Side {
-        active_index: PokemonIndex::P0,
-        baton_passing: false,
-        shed_tailing: false,
-        pokemon: side_two_pokemon,
-        side_conditions: SideConditions::default(),
-        wish: (0, 0),
-        future_sight: (0, PokemonIndex::P0),
-        force_switch: false,
-        force_trapped: false,
-        slow_uturn_move: false,
-        volatile_statuses: HashSet::new(),
-        substitute_health: 0,
-        attack_boost: 0,
-        defense_boost: 0,
-        special_attack_boost: 0,
-        special_defense_boost: 0,
-        speed_boost: 0,
-        accuracy_boost: 0,
-        evasion_boost: 0,
-        last_used_move: LastUsedMove::None,
-        damage_dealt: Default::default(),
-        switch_out_move_second_saved_move: Choices::NONE,
-        volatile_status_durations: VolatileStatusDurations::default(),
-    };
+/// Builds the starting `State` of a battle between two given teams
+///
+/// ```ignore
+/// let state = BattleStateBuilder::new(team_one, team_two)
+///     .with_seed(42)
+///     .with_lead_selection(LeadSelection::Random)
+///     .build()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct BattleStateBuilder {
+    team_one: Vec<Pokemon>,
+    team_two: Vec<Pokemon>,
+    seed: u64,
+    generation: u8,
+    format: BattleFormat,
+    lead_selection: LeadSelection,
+}
+
+impl BattleStateBuilder {
+    pub fn new(team_one: Vec<Pokemon>, team_two: Vec<Pokemon>) -> Self {
+        BattleStateBuilder {
+            team_one,
+            team_two,
+            seed: 0,
+            generation: GENERATION,
+            format: BattleFormat::Singles,
+            lead_selection: LeadSelection::TeamOrder,
+        }
+    }
 
-    State {
-        side_one,
-        side_two,
-        weather: StateWeather {
-            weather_type: Weather::NONE,
-            turns_remaining: 0,
-        },
-        terrain: StateTerrain {
-            terrain_type: Terrain::NONE,
-            turns_remaining: 0,
-        },
-        trick_room: StateTrickRoom {
-            active: false,
-            turns_remaining: 0,
-        },
-        team_preview: false,
-        use_last_used_move: false,
-        use_damage_dealt: false,
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    // the generation is chosen at compile time, this only checks that the teams
+    // are built for the engine that was compiled
+    pub fn with_generation(mut self, generation: u8) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    pub fn with_format(mut self, format: BattleFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_lead_selection(mut self, lead_selection: LeadSelection) -> Self {
+        self.lead_selection = lead_selection;
+        self
+    }
+
+    // the positions in `team` of the pokemon that lead
+    fn leads<R: Rng>(
+        &self,
+        team: &[Pokemon],
+        fixed_leads: Option<&Vec<usize>>,
+        rng: &mut R,
+    ) -> Result<Vec<usize>, String> {
+        let lead_count = self.format.active_pokemon_count();
+        let leads = match fixed_leads {
+            Some(leads) => leads.clone(),
+            None if self.lead_selection == LeadSelection::Random => {
+                rand::seq::index::sample(rng, team.len(), lead_count.min(team.len())).into_vec()
+            }
+            None => (0..lead_count.min(team.len())).collect(),
+        };
+        if leads.len() != lead_count.min(team.len()) {
+            return Err(format!(
+                "Expected {} leads, got {}",
+                lead_count.min(team.len()),
+                leads.len()
+            ));
+        }
+        for (i, lead) in leads.iter().enumerate() {
+            if *lead >= team.len() {
+                return Err(format!("Lead {} is not on a team of {}", lead, team.len()));
+            }
+            if leads[..i].contains(lead) {
+                return Err(format!("Lead {} was chosen twice", lead));
+            }
+        }
+        Ok(leads)
+    }
+
+    fn build_side(&self, team: &[Pokemon], leads: &[usize]) -> Side {
+        // leads are moved to the front, the rest of the team keeps its order.
+        // Missing team members are fainted placeholders
+        let mut ordered: Vec<Pokemon> = leads.iter().map(|i| team[*i].clone()).collect();
+        ordered.extend(
+            (0..team.len())
+                .filter(|i| !leads.contains(i))
+                .map(|i| team[i].clone()),
+        );
+        ordered.resize(
+            self.format.team_size(),
+            Pokemon {
+                hp: 0,
+                ..Pokemon::default()
+            },
+        );
+        let mut ordered = ordered.into_iter();
+        let mut next = || ordered.next().unwrap();
+        let pokemon = SidePokemon {
+            p0: next(),
+            p1: next(),
+            p2: next(),
+            p3: next(),
+            p4: next(),
+            p5: next(),
+        };
+
+        Side {
+            active_index: PokemonIndex::P0,
+            pokemon,
+            ally_position: match self.format {
+                BattleFormat::Singles => None,
+                BattleFormat::Doubles => Some(PositionState::new(PokemonIndex::P1)),
+            },
+            ..Side::default()
+        }
+    }
+
+    pub fn build(&self) -> Result<State, String> {
+        if self.generation != GENERATION {
+            return Err(format!(
+                "Cannot build a gen {} battle with an engine compiled for gen {}",
+                self.generation, GENERATION
+            ));
+        }
+        for (name, team) in [("Team 1", &self.team_one), ("Team 2", &self.team_two)] {
+            if team.is_empty() || team.len() > self.format.team_size() {
+                return Err(format!(
+                    "{} must have between 1 and {} pokemon, it has {}",
+                    name,
+                    self.format.team_size(),
+                    team.len()
+                ));
+            }
+        }
+        if self.lead_selection == LeadSelection::TeamPreview
+            && self.format != BattleFormat::Singles
+        {
+            return Err("Team preview is only supported in singles".to_string());
+        }
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let (side_one_leads, side_two_leads) = match &self.lead_selection {
+            LeadSelection::Fixed(side_one, side_two) => (Some(side_one), Some(side_two)),
+            _ => (None, None),
+        };
+        let side_one_leads = self.leads(&self.team_one, side_one_leads, &mut rng)?;
+        let side_two_leads = self.leads(&self.team_two, side_two_leads, &mut rng)?;
+
+        Ok(State {
+            side_one: self.build_side(&self.team_one, &side_one_leads),
+            side_two: self.build_side(&self.team_two, &side_two_leads),
+            weather: StateWeather {
+                weather_type: Weather::NONE,
+                turns_remaining: 0,
+            },
+            terrain: StateTerrain {
+                terrain_type: Terrain::NONE,
+                turns_remaining: 0,
+            },
+            trick_room: StateTrickRoom {
+                active: false,
+                turns_remaining: 0,
+            },
+            team_preview: self.lead_selection == LeadSelection::TeamPreview,
+            use_last_used_move: false,
+            use_damage_dealt: false,
+            hash: StateHash::default(),
+        })
     }
 }
 
@@ -409,6 +597,7 @@ pub trait Player: Send + Sync + 'static {
         state: &State,
         side_ref: SideReference,
         options: &[MoveChoice],
+        rng: &mut BattleRng,
     ) -> MoveChoice;
     fn name(&self) -> &str;
 }
@@ -430,8 +619,8 @@ impl Player for RandomPlayer {
         _state: &State,
         _side_ref: SideReference,
         options: &[MoveChoice],
+        rng: &mut BattleRng,
     ) -> MoveChoice {
-        let mut rng = thread_rng();
         options[rng.gen_range(0..options.len())]
     }
 
@@ -457,6 +646,7 @@ impl Player for FirstMovePlayer {
         _state: &State,
         _side_ref: SideReference,
         options: &[MoveChoice],
+        _rng: &mut BattleRng,
     ) -> MoveChoice {
         options[0]
     }
@@ -466,42 +656,123 @@ impl Player for FirstMovePlayer {
     }
 }
 
-// Simple damage maximizer - picks the move that would do most damage
+// Damage maximizer - picks the move with the highest expected damage from the damage calculator
+//
+// When nothing does meaningful damage it switches to whichever pokemon takes
+// the least damage from the opponent's active pokemon, if that is better than staying in
 pub struct DamageMaximizer {
     name: String,
 }
 
+// expected damage as a percentage of the defender's max hp below which a move is not worth
+// staying in for
+const MEANINGFUL_DAMAGE_PERCENT: f32 = 10.0;
+// a certain knockout is worth this many percentage points on top of the damage it does
+const KNOCKOUT_BONUS: f32 = 100.0;
+const CRIT_CHANCE: f32 = 1.0 / 24.0;
+
 impl DamageMaximizer {
     pub fn new(name: String) -> Self {
         DamageMaximizer { name }
     }
 
-    fn estimate_damage(
-        &self,
-        state: &State,
-        side_ref: SideReference,
-        move_choice: &MoveChoice,
-    ) -> f32 {
-        match move_choice {
-            MoveChoice::Move(move_index) | MoveChoice::MoveTera(move_index) => {
-                let side = match side_ref {
-                    SideReference::SideOne => &state.side_one,
-                    SideReference::SideTwo => &state.side_two,
-                };
-                let active = side.get_active_immutable();
-                let move_data = &active.moves[move_index];
+    // the expected damage of `choice` as a percentage of the defender's max hp
+    // plus `KNOCKOUT_BONUS` times the chance that it knocks the defender out
+    //
+    // both include the move's accuracy, every damage roll and the chance of a critical hit
+    pub fn score_damage(state: &State, attacking_side_ref: SideReference, choice: &Choice) -> f32 {
+        let defender = state
+            .get_side_immutable(&attacking_side_ref.get_other_side())
+            .get_active_immutable();
+        if defender.hp <= 0 || defender.maxhp <= 0 {
+            return 0.0;
+        }
 
-                // Get move info from MOVES constant
-                if let Some(move_info) = crate::choices::MOVES.get(&move_data.id) {
-                    // Simple damage estimate based on base power
-                    move_info.base_power as f32
-                } else {
-                    0.0
-                }
+        // the damage calc gives the highest roll and the highest critical hit roll,
+        // or a single amount for moves that do fixed damage
+        let rolls = match calculate_damage_rolls(
+            state.clone(),
+            &attacking_side_ref,
+            choice.clone(),
+            &Choice::default(),
+        ) {
+            Some(rolls) => rolls,
+            None => return 0.0,
+        };
+        let outcomes: Vec<(i32, f32)> = match rolls.as_slice() {
+            [damage, crit_damage] => (85..=100)
+                .flat_map(|roll| {
+                    [
+                        (*damage as i32 * roll / 100, (1.0 - CRIT_CHANCE) / 16.0),
+                        (*crit_damage as i32 * roll / 100, CRIT_CHANCE / 16.0),
+                    ]
+                })
+                .collect(),
+            [damage] => vec![(*damage as i32, 1.0)],
+            _ => return 0.0,
+        };
+
+        let defender_hp = defender.hp as i32;
+        let mut expected_damage = 0.0;
+        let mut knockout_chance = 0.0;
+        for (damage, chance) in outcomes {
+            expected_damage += chance * damage.min(defender_hp) as f32;
+            if damage >= defender_hp {
+                knockout_chance += chance;
             }
-            MoveChoice::Switch(_) => -10.0, // Slight penalty for switching
-            MoveChoice::None => 0.0,
         }
+        let hit_chance = (choice.accuracy / 100.0).clamp(0.0, 1.0);
+        hit_chance
+            * (100.0 * expected_damage / defender.maxhp as f32 + KNOCKOUT_BONUS * knockout_chance)
+    }
+
+    fn score_move(state: &State, side_ref: SideReference, move_choice: &MoveChoice) -> Option<f32> {
+        let mut state = state.clone();
+        let side = state.get_side(&side_ref);
+        let move_index = match move_choice {
+            MoveChoice::Move(move_index) => move_index,
+            MoveChoice::MoveTera(move_index) => {
+                side.get_active().terastallized = true;
+                move_index
+            }
+            MoveChoice::MoveMega(move_index) => {
+                let mut mega_evolution = StateInstructions::default();
+                add_mega_evolution_instructions(&mut state, &side_ref, &mut mega_evolution);
+                move_index
+            }
+            MoveChoice::MoveDynamax(move_index) => {
+                let mut dynamax = StateInstructions::default();
+                add_dynamax_instructions(
+                    state.get_side_immutable(&side_ref).get_active_immutable(),
+                    &side_ref,
+                    &mut dynamax,
+                );
+                state.apply_instructions(&dynamax.instruction_list);
+                move_index
+            }
+            MoveChoice::MoveZ(move_index) => move_index,
+            MoveChoice::Switch(_) | MoveChoice::None => return None,
+        };
+        let side = state.get_side_immutable(&side_ref);
+        let mut choice = side.get_active_immutable().moves[move_index].choice.clone();
+        convert_to_max_move(side, &mut choice);
+        if let MoveChoice::MoveZ(_) = move_choice {
+            convert_to_z_move(side.get_active_immutable(), &mut choice);
+        }
+        Some(DamageMaximizer::score_damage(&state, side_ref, &choice))
+    }
+
+    // the best damage score the opponent's active pokemon has against `side_ref`'s active pokemon
+    fn incoming_damage(state: &State, side_ref: SideReference) -> f32 {
+        let opponent_ref = side_ref.get_other_side();
+        state
+            .get_side_immutable(&opponent_ref)
+            .get_active_immutable()
+            .moves
+            .into_iter()
+            .filter(|m| !m.disabled && m.pp > 0)
+            .map(|m| DamageMaximizer::score_damage(state, opponent_ref, &m.choice))
+            .fold(0.0, f32::max)
     }
 }
 
@@ -511,19 +782,42 @@ impl Player for DamageMaximizer {
         state: &State,
         side_ref: SideReference,
         options: &[MoveChoice],
+        _rng: &mut BattleRng,
     ) -> MoveChoice {
-        let mut best_move = options[0];
-        let mut best_damage = self.estimate_damage(state, side_ref, &options[0]);
-
-        for option in options.iter().skip(1) {
-            let damage = self.estimate_damage(state, side_ref, option);
-            if damage > best_damage {
-                best_damage = damage;
-                best_move = *option;
+        let mut best_move = None;
+        let mut best_damage = f32::MIN;
+        for option in options.iter() {
+            if let Some(damage) = DamageMaximizer::score_move(state, side_ref, option) {
+                if damage > best_damage {
+                    best_damage = damage;
+                    best_move = Some(*option);
+                }
             }
         }
+        if best_damage >= MEANINGFUL_DAMAGE_PERCENT {
+            return best_move.unwrap();
+        }
 
-        best_move
+        // nothing does meaningful damage, so switch to whichever pokemon resists the opponent best
+        let active = state.get_side_immutable(&side_ref).get_active_immutable();
+        let mut least_incoming_damage = if active.hp > 0 && !state.team_preview {
+            DamageMaximizer::incoming_damage(state, side_ref)
+        } else {
+            f32::MAX
+        };
+        for option in options.iter() {
+            if let MoveChoice::Switch(pkmn_index) = option {
+                let mut switched_state = state.clone();
+                switched_state.get_side(&side_ref).active_index = *pkmn_index;
+                let incoming_damage = DamageMaximizer::incoming_damage(&switched_state, side_ref);
+                if incoming_damage < least_incoming_damage {
+                    least_incoming_damage = incoming_damage;
+                    best_move = Some(*option);
+                }
+            }
+        }
+
+        best_move.unwrap_or(options[0])
     }
 
     fn name(&self) -> &str {
@@ -534,16 +828,67 @@ impl Player for DamageMaximizer {
 // MCTS player - uses Monte Carlo Tree Search to choose moves
 pub struct MctsPlayer {
     name: String,
-    search_time_ms: u64,
+    config: MctsConfig,
+    evaluator: Arc<dyn Evaluator>,
+    // the state searched on the previous decision and its tree, so that the part of the tree
+    // below what actually happened can be reused
+    previous_search: Mutex<Option<(State, MctsTree)>>,
 }
 
 impl MctsPlayer {
-    pub fn new(name: String, search_time_ms: u64) -> Self {
+    // trees are only reused between decisions when searching with a single thread
+    pub fn new(name: String, config: MctsConfig) -> Self {
         MctsPlayer {
             name,
-            search_time_ms,
+            config,
+            evaluator: Arc::new(DefaultEvaluator),
+            previous_search: Mutex::new(None),
         }
     }
+
+    pub fn with_evaluator(mut self, evaluator: Arc<dyn Evaluator>) -> Self {
+        self.evaluator = evaluator;
+        self
+    }
+
+    fn search(
+        &self,
+        state: &State,
+        side_one_options: Vec<MoveChoice>,
+        side_two_options: Vec<MoveChoice>,
+        rng: &mut BattleRng,
+    ) -> MctsResult {
+        if self.config.threads > 1 {
+            return perform_parallel_mcts(
+                state,
+                side_one_options,
+                side_two_options,
+                &self.config,
+                self.evaluator.as_ref(),
+                rng,
+            );
+        }
+
+        let mut previous_search = self.previous_search.lock().unwrap();
+        let mut root_state = state.clone();
+        let mut tree = previous_search
+            .take()
+            .and_then(|(mut previous_state, tree)| {
+                tree.reuse_subtree(&mut previous_state, &mut root_state)
+            })
+            .filter(|tree| tree.root_options_match(&side_one_options, &side_two_options))
+            .unwrap_or_else(|| MctsTree::new(side_one_options, side_two_options));
+
+        let result = perform_mcts_with_tree(
+            &mut root_state,
+            &mut tree,
+            &self.config,
+            self.evaluator.as_ref(),
+            rng,
+        );
+        *previous_search = Some((root_state, tree));
+        result
+    }
 }
 
 impl Player for MctsPlayer {
@@ -552,20 +897,17 @@ impl Player for MctsPlayer {
         state: &State,
         side_ref: SideReference,
         options: &[MoveChoice],
+        rng: &mut BattleRng,
     ) -> MoveChoice {
-        // Clone the state for MCTS
-        let mut mcts_state = state.clone();
-
-        // Get both sides' options
-        let (side_one_options, side_two_options) = mcts_state.get_all_options();
+        // Get both sides' options, which are the possible leads at team preview
+        let (side_one_options, side_two_options) = if state.team_preview {
+            state.root_get_all_options()
+        } else {
+            state.get_all_options()
+        };
 
         // Run MCTS
-        let mcts_result = perform_mcts(
-            &mut mcts_state,
-            side_one_options.clone(),
-            side_two_options.clone(),
-            Duration::from_millis(self.search_time_ms),
-        );
+        let mcts_result = self.search(state, side_one_options, side_two_options, rng);
 
         // Log MCTS results
         if let Ok(log_file_path) = std::env::var("BATTLE_LOG_FILE") {
@@ -609,8 +951,8 @@ impl Player for MctsPlayer {
                 }
                 
                 // Highlight the selected move
-                if let Some(best_move) = sorted_results.first() {
-                    writeln!(file, "\n>>> SELECTED: {} (most visits) <<<", best_move.move_choice.to_string(side)).ok();
+                if let Some(best_move) = self.config.move_selection.select(results) {
+                    writeln!(file, "\n>>> SELECTED: {} ({:?}) <<<", best_move.to_string(side), self.config.move_selection).ok();
                 }
                     writeln!(file, "==================\n").ok();
                 }
@@ -622,17 +964,16 @@ impl Player for MctsPlayer {
         //     self.name, mcts_result.iteration_count
         // );
 
-        // Select the move with the most visits
+        // Select the move using the configured policy
         let results = match side_ref {
             SideReference::SideOne => &mcts_result.s1,
             SideReference::SideTwo => &mcts_result.s2,
         };
 
-        // Find the move with the most visits
-        let best_move = results
-            .iter()
-            .max_by_key(|result| result.visits)
-            .map(|result| result.move_choice)
+        let best_move = self
+            .config
+            .move_selection
+            .select(results)
             .unwrap_or(options[0]);
 
         best_move
@@ -643,6 +984,157 @@ impl Player for MctsPlayer {
     }
 }
 
+// The option `side_ref` plays most often in the equilibrium of a searched score matrix,
+// falling back to `fallback` if the search found nothing
+fn choose_from_search_result(
+    side_ref: SideReference,
+    side_one_options: &[MoveChoice],
+    side_two_options: &[MoveChoice],
+    scores: &[f32],
+    fallback: MoveChoice,
+) -> MoveChoice {
+    if side_one_options.is_empty() || side_two_options.is_empty() {
+        return fallback;
+    }
+    let equilibrium =
+        solve_nash_equilibrium(scores, side_one_options.len(), side_two_options.len());
+    match side_ref {
+        SideReference::SideOne => side_one_options[equilibrium.best_s1_index()],
+        SideReference::SideTwo => side_two_options[equilibrium.best_s2_index()],
+    }
+}
+
+// Both sides' options, which are the possible leads at team preview
+fn search_options(state: &State) -> (Vec<MoveChoice>, Vec<MoveChoice>) {
+    if state.team_preview {
+        state.root_get_all_options()
+    } else {
+        state.get_all_options()
+    }
+}
+
+// Expectiminimax player - searches every move pair to a fixed depth
+pub struct ExpectiminimaxPlayer {
+    name: String,
+    depth: i8,
+    ab_prune: bool,
+    evaluator: Arc<dyn Evaluator>,
+}
+
+impl ExpectiminimaxPlayer {
+    pub fn new(name: String, depth: i8) -> Self {
+        ExpectiminimaxPlayer {
+            name,
+            depth,
+            ab_prune: true,
+            evaluator: Arc::new(DefaultEvaluator),
+        }
+    }
+
+    pub fn with_ab_prune(mut self, ab_prune: bool) -> Self {
+        self.ab_prune = ab_prune;
+        self
+    }
+
+    pub fn with_evaluator(mut self, evaluator: Arc<dyn Evaluator>) -> Self {
+        self.evaluator = evaluator;
+        self
+    }
+}
+
+impl Player for ExpectiminimaxPlayer {
+    fn choose_move(
+        &self,
+        state: &State,
+        side_ref: SideReference,
+        options: &[MoveChoice],
+        _rng: &mut BattleRng,
+    ) -> MoveChoice {
+        let (side_one_options, side_two_options) = search_options(state);
+        let mut state = state.clone();
+        state.rehash();
+        let scores = expectiminimax_search_with_table(
+            &mut state,
+            self.depth,
+            side_one_options.clone(),
+            side_two_options.clone(),
+            self.ab_prune,
+            &Arc::new(Mutex::new(true)),
+            &mut TranspositionTable::new(DEFAULT_TRANSPOSITION_TABLE_SIZE),
+            self.evaluator.as_ref(),
+            None,
+        );
+        choose_from_search_result(
+            side_ref,
+            &side_one_options,
+            &side_two_options,
+            &scores,
+            options[0],
+        )
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+// Iterative deepening player - searches deeper and deeper until `max_time` runs out
+//
+// Like a time limited `MctsPlayer`, battles with this player are not replayed exactly by a seed
+pub struct IterativeDeepeningPlayer {
+    name: String,
+    max_time: Duration,
+    evaluator: Arc<dyn Evaluator>,
+}
+
+impl IterativeDeepeningPlayer {
+    pub fn new(name: String, max_time: Duration) -> Self {
+        IterativeDeepeningPlayer {
+            name,
+            max_time,
+            evaluator: Arc::new(DefaultEvaluator),
+        }
+    }
+
+    pub fn with_evaluator(mut self, evaluator: Arc<dyn Evaluator>) -> Self {
+        self.evaluator = evaluator;
+        self
+    }
+}
+
+impl Player for IterativeDeepeningPlayer {
+    fn choose_move(
+        &self,
+        state: &State,
+        side_ref: SideReference,
+        options: &[MoveChoice],
+        _rng: &mut BattleRng,
+    ) -> MoveChoice {
+        let (side_one_options, side_two_options) = search_options(state);
+        // the options come back in the order they were last searched in
+        let (side_one_options, side_two_options, scores, _) =
+            iterative_deepen_expectiminimax_with_evaluator(
+                &mut state.clone(),
+                side_one_options,
+                side_two_options,
+                self.max_time,
+                Arc::clone(&self.evaluator),
+                None,
+            );
+        choose_from_search_result(
+            side_ref,
+            &side_one_options,
+            &side_two_options,
+            &scores,
+            options[0],
+        )
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
 // Battle result
 #[derive(Debug, Clone)]
 pub struct BattleResult {
@@ -650,6 +1142,8 @@ pub struct BattleResult {
     pub turn_count: usize,
     pub final_state: State,
     pub turn_history: Vec<TurnInfo>,
+    // the seed the battle was run with, see `BattleEnvironment::with_seed`
+    pub seed: u64,
 }
 
 // Turn information for observability
@@ -670,6 +1164,8 @@ pub struct BattleEnvironment {
     pub max_turns: usize,
     pub verbose: bool,
     pub log_file: Option<String>,
+    pub seed: Option<u64>,
+    pub replay_file: Option<String>,
 }
 
 impl BattleEnvironment {
@@ -685,14 +1181,29 @@ impl BattleEnvironment {
             max_turns,
             verbose,
             log_file: None,
+            seed: None,
+            replay_file: None,
         }
     }
 
+    // every random choice in the battle is drawn from a generator seeded with `seed`,
+    // without one a random seed is used and returned in the `BattleResult`
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
     pub fn with_log_file(mut self, log_file: String) -> Self {
         self.log_file = Some(log_file);
         self
     }
 
+    // writes a `Replay` of every battle run to `replay_file`, see the `replay` subcommand
+    pub fn with_replay_file(mut self, replay_file: String) -> Self {
+        self.replay_file = Some(replay_file);
+        self
+    }
+
     // Generate initial switch-in instructions
     fn generate_initial_instructions(state: &mut State) -> Vec<StateInstructions> {
         // Both players' first Pokemon are already active (P0)
@@ -710,11 +1221,32 @@ impl BattleEnvironment {
         )
     }
 
+    // Both players pick their lead from the switches `root_get_all_options` gives at team preview
+    fn choose_leads(&self, state: &mut State, rng: &mut BattleRng) {
+        let (side_one_options, side_two_options) = state.root_get_all_options();
+        let side_one_lead =
+            self.player_one
+                .choose_move(state, SideReference::SideOne, &side_one_options, rng);
+        let side_two_lead =
+            self.player_two
+                .choose_move(state, SideReference::SideTwo, &side_two_options, rng);
+
+        if let MoveChoice::Switch(pkmn_index) = side_one_lead {
+            state.side_one.active_index = pkmn_index;
+        }
+        if let MoveChoice::Switch(pkmn_index) = side_two_lead {
+            state.side_two.active_index = pkmn_index;
+        }
+        state.team_preview = false;
+    }
+
     // Run a complete battle
     pub fn run_battle(&self, initial_state: State) -> BattleResult {
         let mut state = initial_state.clone();
         let mut turn_history = Vec::new();
         let mut turn_count = 0;
+        let seed = self.seed.unwrap_or_else(random_seed);
+        let mut rng = seeded_rng(seed);
 
         // Create log file if verbose using OpenOptions for consistency
         let mut log_file = if self.verbose && self.log_file.is_some() {
@@ -750,11 +1282,32 @@ impl BattleEnvironment {
             }
         }
 
+        if state.team_preview {
+            self.choose_leads(&mut state, &mut rng);
+        }
+
+        let mut replay = self.replay_file.as_ref().map(|_| {
+            Replay::new(seed, self.player_one.name(), self.player_two.name(), &state)
+        });
+
         // Generate and apply initial switch-in instructions
+        let state_before = state.clone();
         let initial_instructions = Self::generate_initial_instructions(&mut state);
+        let mut applied_instructions = StateInstructions::default();
         if !initial_instructions.is_empty() {
-            let chosen_index = self.sample_instruction_index(&initial_instructions);
+            let chosen_index = self.sample_instruction_index(&initial_instructions, &mut rng);
             state.apply_instructions(&initial_instructions[chosen_index].instruction_list);
+            applied_instructions = initial_instructions[chosen_index].clone();
+        }
+        if let Some(replay) = replay.as_mut() {
+            replay.push_turn(
+                0,
+                MoveChoice::None.to_string(&state_before.side_one),
+                MoveChoice::None.to_string(&state_before.side_two),
+                &state_before,
+                applied_instructions,
+                &state,
+            );
         }
 
         // Main battle loop
@@ -800,10 +1353,10 @@ impl BattleEnvironment {
             // Now players choose their moves (and write MCTS results)
             let side_one_choice =
                 self.player_one
-                    .choose_move(&state, SideReference::SideOne, &side_one_options);
+                    .choose_move(&state, SideReference::SideOne, &side_one_options, &mut rng);
             let side_two_choice =
                 self.player_two
-                    .choose_move(&state, SideReference::SideTwo, &side_two_options);
+                    .choose_move(&state, SideReference::SideTwo, &side_two_options, &mut rng);
 
             // Reopen log file to write the selected moves
             if self.verbose && self.log_file.is_some() {
@@ -835,9 +1388,21 @@ impl BattleEnvironment {
             );
 
             // Apply the instructions (sampling from possibilities)
+            let mut applied_instructions = StateInstructions::default();
             if !instructions.is_empty() {
-                let chosen_index = self.sample_instruction_index(&instructions);
+                let chosen_index = self.sample_instruction_index(&instructions, &mut rng);
                 state.apply_instructions(&instructions[chosen_index].instruction_list);
+                applied_instructions = instructions[chosen_index].clone();
+            }
+            if let Some(replay) = replay.as_mut() {
+                replay.push_turn(
+                    turn_count,
+                    side_one_choice.to_string(&state_before.side_one),
+                    side_two_choice.to_string(&state_before.side_two),
+                    &state_before,
+                    applied_instructions,
+                    &state,
+                );
             }
 
             // Record turn information
@@ -882,6 +1447,13 @@ impl BattleEnvironment {
             }
         }
 
+        if let (Some(mut replay), Some(replay_file)) = (replay, &self.replay_file) {
+            replay.finish(winner, turn_count);
+            replay
+                .write_to_file(replay_file)
+                .expect("Failed to write replay file");
+        }
+
         // Clear the environment variable
         if self.log_file.is_some() {
             std::env::remove_var("BATTLE_LOG_FILE");
@@ -892,16 +1464,20 @@ impl BattleEnvironment {
             turn_count,
             final_state: state,
             turn_history,
+            seed,
         }
     }
 
     // Sample from possible instruction outcomes based on their probabilities
-    fn sample_instruction_index(&self, state_instructions: &[StateInstructions]) -> usize {
+    fn sample_instruction_index(
+        &self,
+        state_instructions: &[StateInstructions],
+        rng: &mut BattleRng,
+    ) -> usize {
         if state_instructions.len() == 1 {
             return 0;
         }
 
-        let mut rng = thread_rng();
         let total_percentage: f32 = state_instructions.iter().map(|si| si.percentage).sum();
         let mut random_value = rng.gen::<f32>() * total_percentage;
 
@@ -917,6 +1493,9 @@ impl BattleEnvironment {
 }
 
 // Helper function to create a battle from JSON files and run it
+// The teams and everything random in the battle are drawn from `seed`,
+// which is also the `BattleResult::seed` of the battle
+#[allow(clippy::too_many_arguments)]
 pub fn run_random_battle(
     random_teams_json: &str,
     pokedex_json: &str,
@@ -925,10 +1504,16 @@ pub fn run_random_battle(
     player_two: Box<dyn Player>,
     max_turns: usize,
     verbose: bool,
+    seed: u64,
 ) -> BattleResult {
-    let initial_state =
-        initialize_battle_state(random_teams_json, pokedex_json, movedex_json).clone();
-    let env = BattleEnvironment::new(player_one, player_two, max_turns, verbose);
+    let initial_state = initialize_battle_state(
+        random_teams_json,
+        pokedex_json,
+        movedex_json,
+        &mut seeded_rng(seed),
+    );
+    let env =
+        BattleEnvironment::new(player_one, player_two, max_turns, verbose).with_seed(seed);
     env.run_battle(initial_state)
 }
 
@@ -947,6 +1532,7 @@ pub fn run_parallel_battles_with_states<F1, F2>(
     player_one_factory: F1,
     player_two_factory: F2,
     max_turns: usize,
+    seed: u64,
 ) -> ParallelBattleResults
 where
     F1: Fn() -> Box<dyn Player> + Send + Sync + 'static,
@@ -991,7 +1577,8 @@ where
                         p2_factory(),
                         max_turns,
                         false, // Not verbose for parallel runs
-                    );
+                    )
+                    .with_seed(battle_seed(seed, state_idx));
 
                     let result = env.run_battle(initial_state);
 
@@ -1015,6 +1602,7 @@ where
 }
 
 // Keep the old function for backwards compatibility
+#[allow(clippy::too_many_arguments)]
 pub fn run_parallel_battles<F1, F2>(
     num_battles: usize,
     num_threads: usize,
@@ -1024,6 +1612,7 @@ pub fn run_parallel_battles<F1, F2>(
     random_teams_json: &str,
     pokedex_json: &str,
     movedex_json: &str,
+    seed: u64,
 ) -> ParallelBattleResults
 where
     F1: Fn() -> Box<dyn Player> + Send + Sync + 'static,
@@ -1054,6 +1643,7 @@ where
             let pdex_json = Arc::clone(&pokedex_json);
             let mdex_json = Arc::clone(&movedex_json);
 
+            let thread_start = thread_id * battles_per_thread + thread_id.min(remainder);
             let thread_battles = if thread_id < remainder {
                 battles_per_thread + 1
             } else {
@@ -1061,15 +1651,21 @@ where
             };
 
             thread::spawn(move || {
-                for _ in 0..thread_battles {
-                    let initial_state =
-                        initialize_battle_state(&teams_json, &pdex_json, &mdex_json);
+                for i in 0..thread_battles {
+                    let seed = battle_seed(seed, thread_start + i);
+                    let initial_state = initialize_battle_state(
+                        &teams_json,
+                        &pdex_json,
+                        &mdex_json,
+                        &mut seeded_rng(seed),
+                    );
                     let env = BattleEnvironment::new(
                         p1_factory(),
                         p2_factory(),
                         max_turns,
                         false, // Not verbose for parallel runs
-                    );
+                    )
+                    .with_seed(seed);
 
                     let result = env.run_battle(initial_state);
 
@@ -1,10 +1,12 @@
 use super::abilities::Abilities;
 use super::damage_calc::type_effectiveness_modifier;
+use super::dynamax::max_move_base_power;
 use super::generate_instructions::{add_remove_status_instructions, get_boost_instruction};
 use super::items::{get_choice_move_disable_instructions, Items};
 use super::state::{PokemonVolatileStatus, Terrain, Weather};
+use super::z_moves::{z_move_base_power, ZPowerEffect};
 use crate::choices::{
-    Boost, Choice, Choices, Effect, Heal, MoveCategory, MoveTarget, Secondary, StatBoosts,
+    Boost, Choice, Choices, Effect, Heal, MoveCategory, MoveTarget, Secondary, StatBoosts, MOVES,
 };
 use crate::instruction::{
     ApplyVolatileStatusInstruction, BoostInstruction, ChangeItemInstruction,
@@ -15,11 +17,151 @@ use crate::instruction::{
 };
 use crate::pokemon::PokemonName;
 use crate::state::{
-    pokemon_index_iter, LastUsedMove, PokemonBoostableStat, PokemonSideCondition, PokemonStatus,
-    PokemonType, Side, SideReference, State,
+    pokemon_index_iter, LastUsedMove, Pokemon, PokemonBoostableStat, PokemonSideCondition,
+    PokemonStatus, PokemonType, Side, SideReference, State,
 };
 use std::cmp;
 
+/*
+A dynamaxed pokemon uses Max Moves: status moves become Max Guard and damaging moves become the
+Max Move of their type, or the G-Max Move of a G-Max forme
+The Max Move keeps the category of the move it replaces
+Incomplete: type changing abilities like Pixilate are not applied before picking the Max Move
+*/
+pub fn convert_to_max_move(attacking_side: &Side, choice: &mut Choice) {
+    if !attacking_side
+        .volatile_statuses
+        .contains(&PokemonVolatileStatus::DYNAMAX)
+        || choice.category == MoveCategory::Switch
+        || choice.move_id == Choices::NONE
+        || choice.move_id.is_max_move()
+    {
+        return;
+    }
+    let max_move_id = if choice.category == MoveCategory::Status {
+        Choices::MAXGUARD
+    } else {
+        match attacking_side.get_active_immutable().id.gigantamax_move() {
+            Some((move_type, gmax_move)) if move_type == choice.move_type => gmax_move,
+            _ => choice.move_type.max_move(),
+        }
+    };
+    let mut max_move = MOVES.get(&max_move_id).unwrap().clone();
+    max_move.move_index = choice.move_index;
+    max_move.first_move = choice.first_move;
+    if choice.category != MoveCategory::Status {
+        max_move.category = choice.category;
+        max_move.move_type = choice.move_type;
+        if !max_move_id.has_fixed_max_move_power() {
+            max_move.base_power = max_move_base_power(&choice.move_type, choice.base_power);
+        }
+    }
+    *choice = max_move;
+}
+
+/*
+A damaging move used with a Z-crystal becomes the Z-Move of its type, or a signature Z-Move,
+and a status move keeps its effect and gets a Z-Power effect when it is used
+The Z-Move of a type keeps the category of the move it replaces
+*/
+pub fn convert_to_z_move(attacking_pokemon: &Pokemon, choice: &mut Choice) {
+    let z_move_id = match attacking_pokemon.z_move(choice) {
+        Some(z_move_id) => z_move_id,
+        None => return,
+    };
+    if z_move_id != choice.move_id {
+        let mut z_move = MOVES.get(&z_move_id).unwrap().clone();
+        z_move.move_index = choice.move_index;
+        z_move.first_move = choice.first_move;
+        if z_move_id == choice.move_type.z_move() {
+            z_move.category = choice.category;
+            z_move.base_power = z_move_base_power(&choice.move_id, choice.base_power);
+        }
+        *choice = z_move;
+    }
+    choice.z_move = true;
+}
+
+pub fn z_power_effect(
+    state: &mut State,
+    choice: &Choice,
+    attacking_side_ref: &SideReference,
+    instructions: &mut StateInstructions,
+) {
+    let z_power_effect = match choice.move_id.z_power_effect() {
+        Some(z_power_effect) if choice.z_move => z_power_effect,
+        _ => return,
+    };
+    match z_power_effect {
+        ZPowerEffect::Boost(boosts) => {
+            for (stat, boost) in boosts.get_as_pokemon_boostable().iter() {
+                if let Some(boost_instruction) = get_boost_instruction(
+                    state.get_side_immutable(attacking_side_ref),
+                    stat,
+                    boost,
+                    attacking_side_ref,
+                    attacking_side_ref,
+                ) {
+                    state.apply_one_instruction(&boost_instruction);
+                    instructions.instruction_list.push(boost_instruction);
+                }
+            }
+        }
+        ZPowerEffect::ClearNegativeBoosts => {
+            for stat in [
+                PokemonBoostableStat::Attack,
+                PokemonBoostableStat::Defense,
+                PokemonBoostableStat::SpecialAttack,
+                PokemonBoostableStat::SpecialDefense,
+                PokemonBoostableStat::Speed,
+                PokemonBoostableStat::Accuracy,
+                PokemonBoostableStat::Evasion,
+            ] {
+                let current_boost = state
+                    .get_side_immutable(attacking_side_ref)
+                    .get_boost_from_boost_enum(&stat);
+                if current_boost < 0 {
+                    let boost_instruction = Instruction::Boost(BoostInstruction {
+                        side_ref: *attacking_side_ref,
+                        stat,
+                        amount: -current_boost,
+                    });
+                    state.apply_one_instruction(&boost_instruction);
+                    instructions.instruction_list.push(boost_instruction);
+                }
+            }
+        }
+        ZPowerEffect::Heal => {
+            let active = state.get_side(attacking_side_ref).get_active();
+            if active.hp < active.maxhp {
+                instructions
+                    .instruction_list
+                    .push(Instruction::Heal(HealInstruction {
+                        side_ref: *attacking_side_ref,
+                        heal_amount: active.maxhp - active.hp,
+                    }));
+                active.hp = active.maxhp;
+            }
+        }
+        ZPowerEffect::FollowMe => {
+            let side = state.get_side(attacking_side_ref);
+            if side
+                .volatile_statuses
+                .insert(PokemonVolatileStatus::FOLLOWME)
+            {
+                instructions
+                    .instruction_list
+                    .push(Instruction::ApplyVolatileStatus(
+                        ApplyVolatileStatusInstruction {
+                            side_ref: *attacking_side_ref,
+                            volatile_status: PokemonVolatileStatus::FOLLOWME,
+                        },
+                    ));
+            }
+        }
+    }
+}
+
 pub fn modify_choice(
     state: &State,
     attacker_choice: &mut Choice,
@@ -36,6 +178,15 @@ pub fn modify_choice(
                 attacker_choice.remove_all_effects();
             }
         }
+        Choices::CAPTIVATE => {
+            if !attacking_side
+                .get_active_immutable()
+                .gender
+                .is_opposite_of(&defending_side.get_active_immutable().gender)
+            {
+                attacker_choice.remove_all_effects();
+            }
+        }
         Choices::BURNUP => {
             if !attacking_side
                 .get_active_immutable()
@@ -608,6 +759,28 @@ pub fn choice_after_damage_hit(
             .insert(PokemonVolatileStatus::TRUANT);
     }
     match choice.move_id {
+        Choices::MAXFLARE | Choices::MAXGEYSER | Choices::MAXHAILSTORM | Choices::MAXROCKFALL => {
+            let new_weather = match choice.move_id {
+                Choices::MAXFLARE => Weather::SUN,
+                Choices::MAXGEYSER => Weather::RAIN,
+                Choices::MAXHAILSTORM => Weather::HAIL,
+                _ => Weather::SAND,
+            };
+            set_weather(state, new_weather, attacking_side_ref, instructions);
+        }
+        Choices::MAXLIGHTNING
+        | Choices::MAXMINDSTORM
+        | Choices::MAXOVERGROWTH
+        | Choices::MAXSTARFALL
+        | Choices::GENESISSUPERNOVA => {
+            let new_terrain = match choice.move_id {
+                Choices::MAXLIGHTNING => Terrain::ELECTRICTERRAIN,
+                Choices::MAXMINDSTORM | Choices::GENESISSUPERNOVA => Terrain::PSYCHICTERRAIN,
+                Choices::MAXOVERGROWTH => Terrain::GRASSYTERRAIN,
+                _ => Terrain::MISTYTERRAIN,
+            };
+            set_terrain(state, new_terrain, attacking_side_ref, instructions);
+        }
         Choices::DOUBLESHOCK => {
             let attacker_active = attacking_side.get_active_immutable();
             let instruction = if attacker_active.types.0 == PokemonType::ELECTRIC {
@@ -770,7 +943,7 @@ pub fn choice_after_damage_hit(
                 &mut instructions.instruction_list,
             );
         }
-        Choices::ICESPINNER => {
+        Choices::ICESPINNER | Choices::SPLINTEREDSTORMSHARDS => {
             if state.terrain.terrain_type != Terrain::NONE && state.terrain.turns_remaining > 0 {
                 instructions
                     .instruction_list
@@ -1360,6 +1533,28 @@ pub fn choice_special_effect(
                 }));
             target_pkmn.hp = target_hp;
         }
+        Choices::GUARDIANOFALOLA => {
+            let protected = [
+                PokemonVolatileStatus::PROTECT,
+                PokemonVolatileStatus::BANEFULBUNKER,
+                PokemonVolatileStatus::SPIKYSHIELD,
+            ]
+            .iter()
+            .any(|vs| defending_side.volatile_statuses.contains(vs));
+            let target_pkmn = defending_side.get_active();
+            let mut damage_amount = cmp::max(target_pkmn.hp * 3 / 4, 1);
+            // like other Z-Moves, only a quarter of the damage goes through protect
+            if protected {
+                damage_amount = cmp::max((damage_amount + 1) / 4, 1);
+            }
+            instructions
+                .instruction_list
+                .push(Instruction::Damage(DamageInstruction {
+                    side_ref: attacking_side_ref.get_other_side(),
+                    damage_amount,
+                }));
+            target_pkmn.hp -= damage_amount;
+        }
         Choices::NIGHTSHADE => {
             let (attacking_side, defending_side) = state.get_both_sides(attacking_side_ref);
             let attacker_level = attacking_side.get_active_immutable().level;
@@ -1570,79 +1765,95 @@ pub fn choice_special_effect(
                 .push(change_defender_item_instruction);
         }
         Choices::SUNNYDAY => {
-            if state.weather.weather_type != Weather::SUN {
-                instructions
-                    .instruction_list
-                    .push(Instruction::ChangeWeather(ChangeWeather {
-                        new_weather: Weather::SUN,
-                        new_weather_turns_remaining: 5,
-                        previous_weather: state.weather.weather_type,
-                        previous_weather_turns_remaining: state.weather.turns_remaining,
-                    }));
-                state.weather.weather_type = Weather::SUN;
-                state.weather.turns_remaining = 5;
-            }
+            set_weather(state, Weather::SUN, attacking_side_ref, instructions);
         }
         Choices::RAINDANCE => {
-            if state.weather.weather_type != Weather::RAIN {
-                instructions
-                    .instruction_list
-                    .push(Instruction::ChangeWeather(ChangeWeather {
-                        new_weather: Weather::RAIN,
-                        new_weather_turns_remaining: 5,
-                        previous_weather: state.weather.weather_type,
-                        previous_weather_turns_remaining: state.weather.turns_remaining,
-                    }));
-                state.weather.weather_type = Weather::RAIN;
-                state.weather.turns_remaining = 5;
-            }
+            set_weather(state, Weather::RAIN, attacking_side_ref, instructions);
         }
         Choices::SANDSTORM => {
-            if state.weather.weather_type != Weather::SAND {
-                instructions
-                    .instruction_list
-                    .push(Instruction::ChangeWeather(ChangeWeather {
-                        new_weather: Weather::SAND,
-                        new_weather_turns_remaining: 5,
-                        previous_weather: state.weather.weather_type,
-                        previous_weather_turns_remaining: state.weather.turns_remaining,
-                    }));
-                state.weather.weather_type = Weather::SAND;
-                state.weather.turns_remaining = 5;
-            }
+            set_weather(state, Weather::SAND, attacking_side_ref, instructions);
         }
         Choices::HAIL => {
-            if state.weather.weather_type != Weather::HAIL {
-                instructions
-                    .instruction_list
-                    .push(Instruction::ChangeWeather(ChangeWeather {
-                        new_weather: Weather::HAIL,
-                        new_weather_turns_remaining: 5,
-                        previous_weather: state.weather.weather_type,
-                        previous_weather_turns_remaining: state.weather.turns_remaining,
-                    }));
-                state.weather.weather_type = Weather::HAIL;
-                state.weather.turns_remaining = 5;
-            }
+            set_weather(state, Weather::HAIL, attacking_side_ref, instructions);
         }
         Choices::SNOWSCAPE | Choices::CHILLYRECEPTION => {
-            if state.weather.weather_type != Weather::SNOW {
-                instructions
-                    .instruction_list
-                    .push(Instruction::ChangeWeather(ChangeWeather {
-                        new_weather: Weather::SNOW,
-                        new_weather_turns_remaining: 5,
-                        previous_weather: state.weather.weather_type,
-                        previous_weather_turns_remaining: state.weather.turns_remaining,
-                    }));
-                state.weather.weather_type = Weather::SNOW;
-                state.weather.turns_remaining = 5;
-            }
+            set_weather(state, Weather::SNOW, attacking_side_ref, instructions);
         }
         _ => {}
     }
 }
 
+// weather set by a move lasts 8 turns if the user holds the matching rock, and cannot replace
+// the weather of Desolate Land or Primordial Sea
+pub fn set_weather(
+    state: &mut State,
+    new_weather: Weather,
+    attacking_side_ref: &SideReference,
+    instructions: &mut StateInstructions,
+) {
+    if state.weather.weather_type == new_weather
+        || state.weather.weather_type == Weather::HARSHSUN
+        || state.weather.weather_type == Weather::HEAVYRAIN
+    {
+        return;
+    }
+    let new_weather_turns_remaining = match (
+        new_weather,
+        state
+            .get_side_immutable(attacking_side_ref)
+            .get_active_immutable()
+            .item,
+    ) {
+        (Weather::SUN, Items::HEATROCK)
+        | (Weather::RAIN, Items::DAMPROCK)
+        | (Weather::SAND, Items::SMOOTHROCK)
+        | (Weather::HAIL | Weather::SNOW, Items::ICYROCK) => 8,
+        _ => 5,
+    };
+    instructions
+        .instruction_list
+        .push(Instruction::ChangeWeather(ChangeWeather {
+            new_weather,
+            new_weather_turns_remaining,
+            previous_weather: state.weather.weather_type,
+            previous_weather_turns_remaining: state.weather.turns_remaining,
+        }));
+    state.weather.weather_type = new_weather;
+    state.weather.turns_remaining = new_weather_turns_remaining;
+}
+
+// terrain set by a move lasts 8 turns if the user holds a Terrain Extender
+pub fn set_terrain(
+    state: &mut State,
+    new_terrain: Terrain,
+    attacking_side_ref: &SideReference,
+    instructions: &mut StateInstructions,
+) {
+    if state.terrain.terrain_type == new_terrain {
+        return;
+    }
+    let new_terrain_turns_remaining = if state
+        .get_side_immutable(attacking_side_ref)
+        .get_active_immutable()
+        .item
+        == Items::TERRAINEXTENDER
+    {
+        8
+    } else {
+        5
+    };
+    instructions
+        .instruction_list
+        .push(Instruction::ChangeTerrain(ChangeTerrain {
+            new_terrain,
+            new_terrain_turns_remaining,
+            previous_terrain: state.terrain.terrain_type,
+            previous_terrain_turns_remaining: state.terrain.turns_remaining,
+        }));
+    state.terrain.terrain_type = new_terrain;
+    state.terrain.turns_remaining = new_terrain_turns_remaining;
+}
+
 pub fn charge_choice_to_volatile(choice: &Choices) -> PokemonVolatileStatus {
     match choice {
         Choices::BOUNCE => PokemonVolatileStatus::BOUNCE,
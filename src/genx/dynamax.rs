@@ -0,0 +1,202 @@
+use crate::choices::Choices;
+use crate::pokemon::PokemonName;
+use crate::state::PokemonType;
+
+// base formes and the G-Max forme they change into when they dynamax with the Gigantamax factor
+const GIGANTAMAX_FORMES: [(PokemonName, PokemonName); 34] = [
+    (PokemonName::VENUSAUR, PokemonName::VENUSAURGMAX),
+    (PokemonName::CHARIZARD, PokemonName::CHARIZARDGMAX),
+    (PokemonName::BLASTOISE, PokemonName::BLASTOISEGMAX),
+    (PokemonName::BUTTERFREE, PokemonName::BUTTERFREEGMAX),
+    (PokemonName::PIKACHU, PokemonName::PIKACHUGMAX),
+    (PokemonName::MEOWTH, PokemonName::MEOWTHGMAX),
+    (PokemonName::MACHAMP, PokemonName::MACHAMPGMAX),
+    (PokemonName::GENGAR, PokemonName::GENGARGMAX),
+    (PokemonName::KINGLER, PokemonName::KINGLERGMAX),
+    (PokemonName::LAPRAS, PokemonName::LAPRASGMAX),
+    (PokemonName::EEVEE, PokemonName::EEVEEGMAX),
+    (PokemonName::SNORLAX, PokemonName::SNORLAXGMAX),
+    (PokemonName::GARBODOR, PokemonName::GARBODORGMAX),
+    (PokemonName::MELMETAL, PokemonName::MELMETALGMAX),
+    (PokemonName::RILLABOOM, PokemonName::RILLABOOMGMAX),
+    (PokemonName::CINDERACE, PokemonName::CINDERACEGMAX),
+    (PokemonName::INTELEON, PokemonName::INTELEONGMAX),
+    (PokemonName::CORVIKNIGHT, PokemonName::CORVIKNIGHTGMAX),
+    (PokemonName::ORBEETLE, PokemonName::ORBEETLEGMAX),
+    (PokemonName::DREDNAW, PokemonName::DREDNAWGMAX),
+    (PokemonName::COALOSSAL, PokemonName::COALOSSALGMAX),
+    (PokemonName::FLAPPLE, PokemonName::FLAPPLEGMAX),
+    (PokemonName::APPLETUN, PokemonName::APPLETUNGMAX),
+    (PokemonName::SANDACONDA, PokemonName::SANDACONDAGMAX),
+    (PokemonName::TOXTRICITY, PokemonName::TOXTRICITYGMAX),
+    (
+        PokemonName::TOXTRICITYLOWKEY,
+        PokemonName::TOXTRICITYLOWKEYGMAX,
+    ),
+    (PokemonName::CENTISKORCH, PokemonName::CENTISKORCHGMAX),
+    (PokemonName::HATTERENE, PokemonName::HATTERENEGMAX),
+    (PokemonName::GRIMMSNARL, PokemonName::GRIMMSNARLGMAX),
+    (PokemonName::ALCREMIE, PokemonName::ALCREMIEGMAX),
+    (PokemonName::COPPERAJAH, PokemonName::COPPERAJAHGMAX),
+    (PokemonName::DURALUDON, PokemonName::DURALUDONGMAX),
+    (PokemonName::URSHIFU, PokemonName::URSHIFUGMAX),
+    (
+        PokemonName::URSHIFURAPIDSTRIKE,
+        PokemonName::URSHIFURAPIDSTRIKEGMAX,
+    ),
+];
+
+impl PokemonName {
+    // Zacian, Zamazenta and Eternatus cannot dynamax
+    pub fn can_dynamax(&self) -> bool {
+        !matches!(
+            self,
+            PokemonName::ZACIAN
+                | PokemonName::ZACIANCROWNED
+                | PokemonName::ZAMAZENTA
+                | PokemonName::ZAMAZENTACROWNED
+                | PokemonName::ETERNATUS
+                | PokemonName::ETERNATUSETERNAMAX
+        )
+    }
+
+    pub fn gigantamax_forme(&self) -> Option<PokemonName> {
+        GIGANTAMAX_FORMES
+            .iter()
+            .find(|(base_forme, _)| base_forme == self)
+            .map(|(_, gmax_forme)| *gmax_forme)
+    }
+
+    pub fn gigantamax_base_forme(&self) -> Option<PokemonName> {
+        GIGANTAMAX_FORMES
+            .iter()
+            .find(|(_, gmax_forme)| gmax_forme == self)
+            .map(|(base_forme, _)| *base_forme)
+    }
+
+    /*
+    The G-Max Move that replaces the Max Move of its type for a G-Max forme
+    Incomplete: G-Max Moves with effects the engine cannot represent,
+    such as G-Max Wildfire or G-Max Befuddle, are left out and the regular Max Move is used
+    */
+    pub fn gigantamax_move(&self) -> Option<(PokemonType, Choices)> {
+        match self {
+            PokemonName::PIKACHUGMAX => Some((PokemonType::ELECTRIC, Choices::GMAXVOLTCRASH)),
+            PokemonName::MEOWTHGMAX => Some((PokemonType::NORMAL, Choices::GMAXGOLDRUSH)),
+            PokemonName::KINGLERGMAX => Some((PokemonType::WATER, Choices::GMAXFOAMBURST)),
+            PokemonName::GARBODORGMAX => Some((PokemonType::POISON, Choices::GMAXMALODOR)),
+            PokemonName::RILLABOOMGMAX => Some((PokemonType::GRASS, Choices::GMAXDRUMSOLO)),
+            PokemonName::CINDERACEGMAX => Some((PokemonType::FIRE, Choices::GMAXFIREBALL)),
+            PokemonName::INTELEONGMAX => Some((PokemonType::WATER, Choices::GMAXHYDROSNIPE)),
+            PokemonName::DREDNAWGMAX => Some((PokemonType::WATER, Choices::GMAXSTONESURGE)),
+            PokemonName::SANDACONDAGMAX => Some((PokemonType::GROUND, Choices::GMAXSANDBLAST)),
+            PokemonName::CENTISKORCHGMAX => Some((PokemonType::FIRE, Choices::GMAXCENTIFERNO)),
+            PokemonName::HATTERENEGMAX => Some((PokemonType::FAIRY, Choices::GMAXSMITE)),
+            PokemonName::URSHIFUGMAX => Some((PokemonType::DARK, Choices::GMAXONEBLOW)),
+            PokemonName::URSHIFURAPIDSTRIKEGMAX => {
+                Some((PokemonType::WATER, Choices::GMAXRAPIDFLOW))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl PokemonType {
+    // The Max Move that a damaging move of this type becomes
+    pub fn max_move(&self) -> Choices {
+        match self {
+            PokemonType::BUG => Choices::MAXFLUTTERBY,
+            PokemonType::DARK => Choices::MAXDARKNESS,
+            PokemonType::DRAGON => Choices::MAXWYRMWIND,
+            PokemonType::ELECTRIC => Choices::MAXLIGHTNING,
+            PokemonType::FAIRY => Choices::MAXSTARFALL,
+            PokemonType::FIGHTING => Choices::MAXKNUCKLE,
+            PokemonType::FIRE => Choices::MAXFLARE,
+            PokemonType::FLYING => Choices::MAXAIRSTREAM,
+            PokemonType::GHOST => Choices::MAXPHANTASM,
+            PokemonType::GRASS => Choices::MAXOVERGROWTH,
+            PokemonType::GROUND => Choices::MAXQUAKE,
+            PokemonType::ICE => Choices::MAXHAILSTORM,
+            PokemonType::POISON => Choices::MAXOOZE,
+            PokemonType::PSYCHIC => Choices::MAXMINDSTORM,
+            PokemonType::ROCK => Choices::MAXROCKFALL,
+            PokemonType::STEEL => Choices::MAXSTEELSPIKE,
+            PokemonType::WATER => Choices::MAXGEYSER,
+            _ => Choices::MAXSTRIKE,
+        }
+    }
+}
+
+impl Choices {
+    pub fn is_max_move(&self) -> bool {
+        matches!(
+            self,
+            Choices::MAXAIRSTREAM
+                | Choices::MAXDARKNESS
+                | Choices::MAXFLARE
+                | Choices::MAXFLUTTERBY
+                | Choices::MAXGEYSER
+                | Choices::MAXGUARD
+                | Choices::MAXHAILSTORM
+                | Choices::MAXKNUCKLE
+                | Choices::MAXLIGHTNING
+                | Choices::MAXMINDSTORM
+                | Choices::MAXOOZE
+                | Choices::MAXOVERGROWTH
+                | Choices::MAXPHANTASM
+                | Choices::MAXQUAKE
+                | Choices::MAXROCKFALL
+                | Choices::MAXSTARFALL
+                | Choices::MAXSTEELSPIKE
+                | Choices::MAXSTRIKE
+                | Choices::MAXWYRMWIND
+                | Choices::GMAXCENTIFERNO
+                | Choices::GMAXDRUMSOLO
+                | Choices::GMAXFIREBALL
+                | Choices::GMAXFOAMBURST
+                | Choices::GMAXGOLDRUSH
+                | Choices::GMAXHYDROSNIPE
+                | Choices::GMAXMALODOR
+                | Choices::GMAXONEBLOW
+                | Choices::GMAXRAPIDFLOW
+                | Choices::GMAXSANDBLAST
+                | Choices::GMAXSMITE
+                | Choices::GMAXSTONESURGE
+                | Choices::GMAXVOLTCRASH
+        )
+    }
+
+    // these keep their own base power instead of taking it from the move they replace
+    pub fn has_fixed_max_move_power(&self) -> bool {
+        matches!(
+            self,
+            Choices::GMAXDRUMSOLO | Choices::GMAXFIREBALL | Choices::GMAXHYDROSNIPE
+        )
+    }
+}
+
+/*
+The base power of the Max Move that replaces a damaging move, Fighting and Poison Max Moves use
+a lower table
+Moves whose power is decided when they are used, like Grass Knot or Reversal, have a base power
+of 0 here and become a 130 power Max Move, or 100 for Fighting and Poison
+Incomplete: multi-hit moves use the base power of a single hit
+*/
+pub fn max_move_base_power(move_type: &PokemonType, base_power: f32) -> f32 {
+    let (powers, variable_power) = match move_type {
+        PokemonType::FIGHTING | PokemonType::POISON => {
+            ([70.0, 75.0, 80.0, 85.0, 90.0, 95.0, 100.0], 100.0)
+        }
+        _ => ([90.0, 100.0, 110.0, 120.0, 130.0, 140.0, 150.0], 130.0),
+    };
+    match base_power as i16 {
+        0 => variable_power,
+        1..=40 => powers[0],
+        41..=50 => powers[1],
+        51..=60 => powers[2],
+        61..=70 => powers[3],
+        71..=100 => powers[4],
+        101..=140 => powers[5],
+        _ => powers[6],
+    }
+}
@@ -2,10 +2,14 @@ use super::abilities::Abilities;
 use super::items::Items;
 use super::state::PokemonVolatileStatus;
 use crate::choices::MoveCategory;
+use crate::evaluator::Evaluator;
 use crate::state::{Pokemon, PokemonStatus, Side, State};
+use serde::{Deserialize, Serialize};
+use std::fs;
 
 const POKEMON_ALIVE: f32 = 30.0;
 const POKEMON_HP: f32 = 100.0;
+const POKEMON_ITEM: f32 = 10.0;
 pub const USED_TERA: f32 = -75.0;
 
 pub const POKEMON_ATTACK_BOOST: f32 = 30.0;
@@ -51,6 +55,132 @@ const SPIKES: f32 = -7.0;
 const TOXIC_SPIKES: f32 = -7.0;
 const STICKY_WEB: f32 = -25.0;
 
+/// The coefficients used by `evaluate_with_weights`
+///
+/// Fields missing from a weights file keep their default value,
+/// so a file only has to list the coefficients it changes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EvaluationWeights {
+    pub pokemon_alive: f32,
+    pub pokemon_hp: f32,
+    pub pokemon_item: f32,
+    pub used_tera: f32,
+
+    pub attack_boost: f32,
+    pub defense_boost: f32,
+    pub special_attack_boost: f32,
+    pub special_defense_boost: f32,
+    pub speed_boost: f32,
+    // indexed by `boost + 6`, so the first multiplier is for -6 and the last for +6
+    pub boost_multipliers: [f32; 13],
+
+    pub frozen: f32,
+    pub asleep: f32,
+    pub paralyzed: f32,
+    pub toxic: f32,
+    pub poisoned: f32,
+    pub burned: f32,
+
+    pub leech_seed: f32,
+    pub substitute: f32,
+    pub confusion: f32,
+
+    pub reflect: f32,
+    pub light_screen: f32,
+    pub aurora_veil: f32,
+    pub safeguard: f32,
+    pub tailwind: f32,
+    pub healing_wish: f32,
+
+    pub stealth_rock: f32,
+    pub spikes: f32,
+    pub toxic_spikes: f32,
+    pub sticky_web: f32,
+}
+
+pub const DEFAULT_EVALUATION_WEIGHTS: EvaluationWeights = EvaluationWeights {
+    pokemon_alive: POKEMON_ALIVE,
+    pokemon_hp: POKEMON_HP,
+    pokemon_item: POKEMON_ITEM,
+    used_tera: USED_TERA,
+
+    attack_boost: POKEMON_ATTACK_BOOST,
+    defense_boost: POKEMON_DEFENSE_BOOST,
+    special_attack_boost: POKEMON_SPECIAL_ATTACK_BOOST,
+    special_defense_boost: POKEMON_SPECIAL_DEFENSE_BOOST,
+    speed_boost: POKEMON_SPEED_BOOST,
+    boost_multipliers: [
+        POKEMON_BOOST_MULTIPLIER_NEG_6,
+        POKEMON_BOOST_MULTIPLIER_NEG_5,
+        POKEMON_BOOST_MULTIPLIER_NEG_4,
+        POKEMON_BOOST_MULTIPLIER_NEG_3,
+        POKEMON_BOOST_MULTIPLIER_NEG_2,
+        POKEMON_BOOST_MULTIPLIER_NEG_1,
+        POKEMON_BOOST_MULTIPLIER_0,
+        POKEMON_BOOST_MULTIPLIER_1,
+        POKEMON_BOOST_MULTIPLIER_2,
+        POKEMON_BOOST_MULTIPLIER_3,
+        POKEMON_BOOST_MULTIPLIER_4,
+        POKEMON_BOOST_MULTIPLIER_5,
+        POKEMON_BOOST_MULTIPLIER_6,
+    ],
+
+    frozen: POKEMON_FROZEN,
+    asleep: POKEMON_ASLEEP,
+    paralyzed: POKEMON_PARALYZED,
+    toxic: POKEMON_TOXIC,
+    poisoned: POKEMON_POISONED,
+    burned: POKEMON_BURNED,
+
+    leech_seed: LEECH_SEED,
+    substitute: SUBSTITUTE,
+    confusion: CONFUSION,
+
+    reflect: REFLECT,
+    light_screen: LIGHT_SCREEN,
+    aurora_veil: AURORA_VEIL,
+    safeguard: SAFE_GUARD,
+    tailwind: TAILWIND,
+    healing_wish: HEALING_WISH,
+
+    stealth_rock: STEALTH_ROCK,
+    spikes: SPIKES,
+    toxic_spikes: TOXIC_SPIKES,
+    sticky_web: STICKY_WEB,
+};
+
+impl Default for EvaluationWeights {
+    fn default() -> EvaluationWeights {
+        DEFAULT_EVALUATION_WEIGHTS
+    }
+}
+
+impl EvaluationWeights {
+    pub fn from_json(json: &str) -> Result<EvaluationWeights, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid evaluation weights: {}", e))
+    }
+
+    pub fn from_file(path: &str) -> Result<EvaluationWeights, String> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read evaluation weights from {}: {}", path, e))?;
+        EvaluationWeights::from_json(&json)
+    }
+
+    pub fn boost_multiplier(&self, boost: i8) -> f32 {
+        if !(-6..=6).contains(&boost) {
+            panic!("Invalid boost value: {}", boost);
+        }
+        self.boost_multipliers[(boost + 6) as usize]
+    }
+}
+
+impl Evaluator for EvaluationWeights {
+    fn evaluate(&self, state: &State) -> f32 {
+        evaluate_with_weights(state, self)
+    }
+}
+
 fn evaluate_poison(pokemon: &Pokemon, base_score: f32) -> f32 {
     match pokemon.ability {
         Abilities::POISONHEAL => 15.0,
@@ -63,14 +193,12 @@ fn evaluate_poison(pokemon: &Pokemon, base_score: f32) -> f32 {
     }
 }
 
-fn evaluate_burned(pokemon: &Pokemon) -> f32 {
+fn evaluate_burned(pokemon: &Pokemon, burned: f32) -> f32 {
     // burn is not as punishing in certain situations
 
     // guts, marvel scale, quick feet will result in a positive evaluation
     match pokemon.ability {
-        Abilities::GUTS | Abilities::MARVELSCALE | Abilities::QUICKFEET => {
-            return -2.0 * POKEMON_BURNED
-        }
+        Abilities::GUTS | Abilities::MARVELSCALE | Abilities::QUICKFEET => return -2.0 * burned,
         _ => {}
     }
 
@@ -86,41 +214,26 @@ fn evaluate_burned(pokemon: &Pokemon) -> f32 {
         multiplier /= 2.0;
     }
 
-    multiplier * POKEMON_BURNED
+    multiplier * burned
 }
 
 pub fn get_boost_multiplier(boost: i8) -> f32 {
-    match boost {
-        6 => POKEMON_BOOST_MULTIPLIER_6,
-        5 => POKEMON_BOOST_MULTIPLIER_5,
-        4 => POKEMON_BOOST_MULTIPLIER_4,
-        3 => POKEMON_BOOST_MULTIPLIER_3,
-        2 => POKEMON_BOOST_MULTIPLIER_2,
-        1 => POKEMON_BOOST_MULTIPLIER_1,
-        0 => POKEMON_BOOST_MULTIPLIER_0,
-        -1 => POKEMON_BOOST_MULTIPLIER_NEG_1,
-        -2 => POKEMON_BOOST_MULTIPLIER_NEG_2,
-        -3 => POKEMON_BOOST_MULTIPLIER_NEG_3,
-        -4 => POKEMON_BOOST_MULTIPLIER_NEG_4,
-        -5 => POKEMON_BOOST_MULTIPLIER_NEG_5,
-        -6 => POKEMON_BOOST_MULTIPLIER_NEG_6,
-        _ => panic!("Invalid boost value: {}", boost),
-    }
+    DEFAULT_EVALUATION_WEIGHTS.boost_multiplier(boost)
 }
 
-fn evaluate_hazards(pokemon: &Pokemon, side: &Side) -> f32 {
+fn evaluate_hazards(pokemon: &Pokemon, side: &Side, weights: &EvaluationWeights) -> f32 {
     let mut score = 0.0;
     let pkmn_is_grounded = pokemon.is_grounded();
     if pokemon.item != Items::HEAVYDUTYBOOTS {
         if pokemon.ability != Abilities::MAGICGUARD {
-            score += side.side_conditions.stealth_rock as f32 * STEALTH_ROCK;
+            score += side.side_conditions.stealth_rock as f32 * weights.stealth_rock;
             if pkmn_is_grounded {
-                score += side.side_conditions.spikes as f32 * SPIKES;
-                score += side.side_conditions.toxic_spikes as f32 * TOXIC_SPIKES;
+                score += side.side_conditions.spikes as f32 * weights.spikes;
+                score += side.side_conditions.toxic_spikes as f32 * weights.toxic_spikes;
             }
         }
         if pkmn_is_grounded {
-            score += side.side_conditions.sticky_web as f32 * STICKY_WEB;
+            score += side.side_conditions.sticky_web as f32 * weights.sticky_web;
         }
     }
 
@@ -128,21 +241,25 @@ fn evaluate_hazards(pokemon: &Pokemon, side: &Side) -> f32 {
 }
 
 pub fn evaluate_pokemon(pokemon: &Pokemon) -> f32 {
+    evaluate_pokemon_with_weights(pokemon, &DEFAULT_EVALUATION_WEIGHTS)
+}
+
+pub fn evaluate_pokemon_with_weights(pokemon: &Pokemon, weights: &EvaluationWeights) -> f32 {
     let mut score = 0.0;
-    score += POKEMON_HP * pokemon.hp as f32 / pokemon.maxhp as f32;
+    score += weights.pokemon_hp * pokemon.hp as f32 / pokemon.maxhp as f32;
 
     match pokemon.status {
-        PokemonStatus::BURN => score += evaluate_burned(pokemon),
-        PokemonStatus::FREEZE => score += POKEMON_FROZEN,
-        PokemonStatus::SLEEP => score += POKEMON_ASLEEP,
-        PokemonStatus::PARALYZE => score += POKEMON_PARALYZED,
-        PokemonStatus::TOXIC => score += evaluate_poison(pokemon, POKEMON_TOXIC),
-        PokemonStatus::POISON => score += evaluate_poison(pokemon, POKEMON_POISONED),
+        PokemonStatus::BURN => score += evaluate_burned(pokemon, weights.burned),
+        PokemonStatus::FREEZE => score += weights.frozen,
+        PokemonStatus::SLEEP => score += weights.asleep,
+        PokemonStatus::PARALYZE => score += weights.paralyzed,
+        PokemonStatus::TOXIC => score += evaluate_poison(pokemon, weights.toxic),
+        PokemonStatus::POISON => score += evaluate_poison(pokemon, weights.poisoned),
         PokemonStatus::NONE => {}
     }
 
     if pokemon.item != Items::NONE {
-        score += 10.0;
+        score += weights.pokemon_item;
     }
 
     // without this a low hp pokemon could get a negative score and incentivize the other side
@@ -151,37 +268,43 @@ pub fn evaluate_pokemon(pokemon: &Pokemon) -> f32 {
         score = 0.0;
     }
 
-    score += POKEMON_ALIVE;
+    score += weights.pokemon_alive;
 
     score
 }
 
 pub fn evaluate(state: &State) -> f32 {
+    evaluate_with_weights(state, &DEFAULT_EVALUATION_WEIGHTS)
+}
+
+pub fn evaluate_with_weights(state: &State, weights: &EvaluationWeights) -> f32 {
     let mut score = 0.0;
 
     let mut iter = state.side_one.pokemon.into_iter();
     let mut s1_used_tera = false;
     while let Some(pkmn) = iter.next() {
         if pkmn.hp > 0 {
-            score += evaluate_pokemon(pkmn);
-            score += evaluate_hazards(pkmn, &state.side_one);
+            score += evaluate_pokemon_with_weights(pkmn, weights);
+            score += evaluate_hazards(pkmn, &state.side_one, weights);
             if iter.pokemon_index == state.side_one.active_index {
                 for vs in state.side_one.volatile_statuses.iter() {
                     match vs {
-                        PokemonVolatileStatus::LEECHSEED => score += LEECH_SEED,
-                        PokemonVolatileStatus::SUBSTITUTE => score += SUBSTITUTE,
-                        PokemonVolatileStatus::CONFUSION => score += CONFUSION,
+                        PokemonVolatileStatus::LEECHSEED => score += weights.leech_seed,
+                        PokemonVolatileStatus::SUBSTITUTE => score += weights.substitute,
+                        PokemonVolatileStatus::CONFUSION => score += weights.confusion,
                         _ => {}
                     }
                 }
 
-                score += get_boost_multiplier(state.side_one.attack_boost) * POKEMON_ATTACK_BOOST;
-                score += get_boost_multiplier(state.side_one.defense_boost) * POKEMON_DEFENSE_BOOST;
-                score += get_boost_multiplier(state.side_one.special_attack_boost)
-                    * POKEMON_SPECIAL_ATTACK_BOOST;
-                score += get_boost_multiplier(state.side_one.special_defense_boost)
-                    * POKEMON_SPECIAL_DEFENSE_BOOST;
-                score += get_boost_multiplier(state.side_one.speed_boost) * POKEMON_SPEED_BOOST;
+                score +=
+                    weights.boost_multiplier(state.side_one.attack_boost) * weights.attack_boost;
+                score +=
+                    weights.boost_multiplier(state.side_one.defense_boost) * weights.defense_boost;
+                score += weights.boost_multiplier(state.side_one.special_attack_boost)
+                    * weights.special_attack_boost;
+                score += weights.boost_multiplier(state.side_one.special_defense_boost)
+                    * weights.special_defense_boost;
+                score += weights.boost_multiplier(state.side_one.speed_boost) * weights.speed_boost;
             }
         }
         if pkmn.terastallized {
@@ -189,32 +312,34 @@ pub fn evaluate(state: &State) -> f32 {
         }
     }
     if s1_used_tera {
-        score += USED_TERA;
+        score += weights.used_tera;
     }
     let mut iter = state.side_two.pokemon.into_iter();
     let mut s2_used_tera = false;
     while let Some(pkmn) = iter.next() {
         if pkmn.hp > 0 {
-            score -= evaluate_pokemon(pkmn);
-            score -= evaluate_hazards(pkmn, &state.side_two);
+            score -= evaluate_pokemon_with_weights(pkmn, weights);
+            score -= evaluate_hazards(pkmn, &state.side_two, weights);
 
             if iter.pokemon_index == state.side_two.active_index {
                 for vs in state.side_two.volatile_statuses.iter() {
                     match vs {
-                        PokemonVolatileStatus::LEECHSEED => score -= LEECH_SEED,
-                        PokemonVolatileStatus::SUBSTITUTE => score -= SUBSTITUTE,
-                        PokemonVolatileStatus::CONFUSION => score -= CONFUSION,
+                        PokemonVolatileStatus::LEECHSEED => score -= weights.leech_seed,
+                        PokemonVolatileStatus::SUBSTITUTE => score -= weights.substitute,
+                        PokemonVolatileStatus::CONFUSION => score -= weights.confusion,
                         _ => {}
                     }
                 }
 
-                score -= get_boost_multiplier(state.side_two.attack_boost) * POKEMON_ATTACK_BOOST;
-                score -= get_boost_multiplier(state.side_two.defense_boost) * POKEMON_DEFENSE_BOOST;
-                score -= get_boost_multiplier(state.side_two.special_attack_boost)
-                    * POKEMON_SPECIAL_ATTACK_BOOST;
-                score -= get_boost_multiplier(state.side_two.special_defense_boost)
-                    * POKEMON_SPECIAL_DEFENSE_BOOST;
-                score -= get_boost_multiplier(state.side_two.speed_boost) * POKEMON_SPEED_BOOST;
+                score -=
+                    weights.boost_multiplier(state.side_two.attack_boost) * weights.attack_boost;
+                score -=
+                    weights.boost_multiplier(state.side_two.defense_boost) * weights.defense_boost;
+                score -= weights.boost_multiplier(state.side_two.special_attack_boost)
+                    * weights.special_attack_boost;
+                score -= weights.boost_multiplier(state.side_two.special_defense_boost)
+                    * weights.special_defense_boost;
+                score -= weights.boost_multiplier(state.side_two.speed_boost) * weights.speed_boost;
             }
         }
         if pkmn.terastallized {
@@ -222,22 +347,22 @@ pub fn evaluate(state: &State) -> f32 {
         }
     }
     if s2_used_tera {
-        score -= USED_TERA;
-    }
-
-    score += state.side_one.side_conditions.reflect as f32 * REFLECT;
-    score += state.side_one.side_conditions.light_screen as f32 * LIGHT_SCREEN;
-    score += state.side_one.side_conditions.aurora_veil as f32 * AURORA_VEIL;
-    score += state.side_one.side_conditions.safeguard as f32 * SAFE_GUARD;
-    score += state.side_one.side_conditions.tailwind as f32 * TAILWIND;
-    score += state.side_one.side_conditions.healing_wish as f32 * HEALING_WISH;
-
-    score -= state.side_two.side_conditions.reflect as f32 * REFLECT;
-    score -= state.side_two.side_conditions.light_screen as f32 * LIGHT_SCREEN;
-    score -= state.side_two.side_conditions.aurora_veil as f32 * AURORA_VEIL;
-    score -= state.side_two.side_conditions.safeguard as f32 * SAFE_GUARD;
-    score -= state.side_two.side_conditions.tailwind as f32 * TAILWIND;
-    score -= state.side_two.side_conditions.healing_wish as f32 * HEALING_WISH;
+        score -= weights.used_tera;
+    }
+
+    score += state.side_one.side_conditions.reflect as f32 * weights.reflect;
+    score += state.side_one.side_conditions.light_screen as f32 * weights.light_screen;
+    score += state.side_one.side_conditions.aurora_veil as f32 * weights.aurora_veil;
+    score += state.side_one.side_conditions.safeguard as f32 * weights.safeguard;
+    score += state.side_one.side_conditions.tailwind as f32 * weights.tailwind;
+    score += state.side_one.side_conditions.healing_wish as f32 * weights.healing_wish;
+
+    score -= state.side_two.side_conditions.reflect as f32 * weights.reflect;
+    score -= state.side_two.side_conditions.light_screen as f32 * weights.light_screen;
+    score -= state.side_two.side_conditions.aurora_veil as f32 * weights.aurora_veil;
+    score -= state.side_two.side_conditions.safeguard as f32 * weights.safeguard;
+    score -= state.side_two.side_conditions.tailwind as f32 * weights.tailwind;
+    score -= state.side_two.side_conditions.healing_wish as f32 * weights.healing_wish;
 
     score
 }
@@ -5,7 +5,7 @@ use super::abilities::{
 };
 use super::choice_effects::{
     charge_choice_to_volatile, choice_after_damage_hit, choice_before_move, choice_hazard_clear,
-    choice_special_effect, modify_choice,
+    choice_special_effect, convert_to_z_move, modify_choice, z_power_effect,
 };
 use crate::choices::{
     Boost, Choices, Effect, Heal, MoveTarget, MultiHitMove, Secondary, SideCondition, StatBoosts,
@@ -13,6 +13,10 @@ use crate::choices::{
 };
 use crate::instruction::DecrementFutureSightInstruction;
 use crate::instruction::ToggleTerastallizedInstruction;
+use crate::instruction::ToggleZMoveUsedInstruction;
+use crate::instruction::{ChangeAbilityInstruction, ChangeType, MegaEvolveInstruction};
+use crate::instruction::{DynamaxInstruction, FormeChangeInstruction};
+use crate::instruction::SwapActivePositionInstruction;
 use crate::instruction::{
     ApplyVolatileStatusInstruction, BoostInstruction, ChangeDamageDealtDamageInstruction,
     ChangeDamageDealtMoveCategoryInstruction, ChangeItemInstruction,
@@ -31,19 +35,22 @@ use super::items::{
     item_before_move, item_end_of_turn, item_modify_attack_against, item_modify_attack_being_used,
     item_on_switch_in, Items,
 };
-use super::state::{MoveChoice, PokemonVolatileStatus, Terrain, Weather};
+use super::state::{DoublesMoveChoice, MoveChoice, PokemonVolatileStatus, Terrain, Weather};
+use crate::battle_format::{doubles_move_target, BattlePosition, DoublesMoveTarget};
 use crate::choices::{Choice, MoveCategory};
 use crate::instruction::{
     ChangeStatusInstruction, DamageInstruction, Instruction, StateInstructions, SwitchInstruction,
 };
 use crate::state::{
-    LastUsedMove, PokemonBoostableStat, PokemonIndex, PokemonMoveIndex, PokemonSideCondition,
-    PokemonStatus, PokemonType, Side, SideMovesFirst, SideReference, State,
+    LastUsedMove, Pokemon, PokemonBoostableStat, PokemonIndex, PokemonMoveIndex,
+    PokemonSideCondition, PokemonStatus, PokemonType, Side, SideMovesFirst, SideReference, State,
 };
 use std::cmp;
 
 #[cfg(feature = "terastallization")]
 use crate::choices::MultiAccuracyMove;
+#[cfg(feature = "gen8")]
+use super::choice_effects::convert_to_max_move;
 
 #[cfg(any(feature = "gen3", feature = "gen4", feature = "gen5", feature = "gen6"))]
 pub const BASE_CRIT_CHANCE: f32 = 1.0 / 16.0;
@@ -584,6 +591,17 @@ fn get_instructions_from_volatile_statuses(
     {
         return;
     }
+    // infatuation needs the pokemon on the other side to be the opposite gender
+    if volatile_status.volatile_status == PokemonVolatileStatus::ATTRACT {
+        let (affected_side, other_side) = state.get_both_sides_immutable(&target_side);
+        if !affected_side
+            .get_active_immutable()
+            .gender
+            .is_opposite_of(&other_side.get_active_immutable().gender)
+        {
+            return;
+        }
+    }
     let side = state.get_side(&target_side);
     let affected_pkmn = side.get_active_immutable();
     if affected_pkmn.volatile_status_can_be_applied(
@@ -1559,9 +1577,23 @@ fn before_move(
             .contains(&PokemonVolatileStatus::BURNINGBULWARK)
         || defending_side
             .volatile_statuses
-            .contains(&PokemonVolatileStatus::SILKTRAP))
+            .contains(&PokemonVolatileStatus::SILKTRAP)
+        || defending_side
+            .volatile_statuses
+            .contains(&PokemonVolatileStatus::MAXGUARD))
         && choice.flags.protect
     {
+        // Max Moves and damaging Z-Moves break through protect for a quarter of their damage,
+        // only Max Guard stops them completely
+        if (choice.move_id.is_max_move()
+            || (choice.z_move && choice.category != MoveCategory::Status))
+            && !defending_side
+                .volatile_statuses
+                .contains(&PokemonVolatileStatus::MAXGUARD)
+        {
+            choice.base_power *= 0.25;
+            return;
+        }
         choice.remove_effects_for_protect();
         if choice.crash.is_some() {
             choice.accuracy = 0.0;
@@ -2176,6 +2208,7 @@ pub fn generate_instructions_from_move(
         return;
     }
 
+    z_power_effect(state, choice, &attacking_side, &mut incoming_instructions);
     if move_has_no_effect(state, &choice, &attacking_side) {
         state.reverse_instructions(&incoming_instructions.instruction_list);
         final_instructions.push(incoming_instructions);
@@ -2675,9 +2708,23 @@ fn on_terrain_end(
 }
 
 fn add_end_of_turn_instructions(
+    state: &mut State,
+    incoming_instructions: &mut StateInstructions,
+    first_move_side: &SideReference,
+) {
+    add_end_of_turn_instructions_with_field_effects(
+        state,
+        incoming_instructions,
+        first_move_side,
+        true,
+    );
+}
+
+fn add_end_of_turn_instructions_with_field_effects(
     state: &mut State,
     mut incoming_instructions: &mut StateInstructions,
     first_move_side: &SideReference,
+    include_field_effects: bool,
 ) {
     state.apply_instructions(&incoming_instructions.instruction_list);
     if state.side_one.force_switch || state.side_two.force_switch {
@@ -2687,8 +2734,19 @@ fn add_end_of_turn_instructions(
 
     let sides = [first_move_side, &first_move_side.get_other_side()];
 
+    // In doubles this runs once for each active position
+    // Effects belonging to the field or to a whole side must only happen on the first run
+    let field_effect_sides: &[&SideReference] = if include_field_effects {
+        &sides
+    } else {
+        &[]
+    };
+
     // Weather decrement / dissipation
-    if state.weather.turns_remaining > 0 && state.weather.weather_type != Weather::NONE {
+    if include_field_effects
+        && state.weather.turns_remaining > 0
+        && state.weather.weather_type != Weather::NONE
+    {
         let weather_dissipate_instruction = Instruction::DecrementWeatherTurnsRemaining;
         incoming_instructions
             .instruction_list
@@ -2710,7 +2768,7 @@ fn add_end_of_turn_instructions(
     }
 
     // Trick Room decrement / dissipation
-    if state.trick_room.turns_remaining > 0 && state.trick_room.active {
+    if include_field_effects && state.trick_room.turns_remaining > 0 && state.trick_room.active {
         incoming_instructions
             .instruction_list
             .push(Instruction::DecrementTrickRoomTurnsRemaining);
@@ -2728,7 +2786,10 @@ fn add_end_of_turn_instructions(
     }
 
     // Terrain decrement / dissipation
-    if state.terrain.turns_remaining > 0 && state.terrain.terrain_type != Terrain::NONE {
+    if include_field_effects
+        && state.terrain.turns_remaining > 0
+        && state.terrain.terrain_type != Terrain::NONE
+    {
         let terrain_dissipate_instruction = Instruction::DecrementTerrainTurnsRemaining;
         incoming_instructions
             .instruction_list
@@ -2750,7 +2811,7 @@ fn add_end_of_turn_instructions(
     }
 
     // Side Condition decrement
-    for side_ref in sides {
+    for &side_ref in field_effect_sides {
         let side = state.get_side(side_ref);
         if side.side_conditions.reflect > 0 {
             incoming_instructions
@@ -2851,7 +2912,7 @@ fn add_end_of_turn_instructions(
     }
 
     // future sight
-    for side_ref in sides {
+    for &side_ref in field_effect_sides {
         let (attacking_side, defending_side) = state.get_both_sides(side_ref);
         if attacking_side.future_sight.0 > 0 {
             let decrement_future_sight_instruction =
@@ -2883,7 +2944,7 @@ fn add_end_of_turn_instructions(
     }
 
     // wish
-    for side_ref in sides {
+    for &side_ref in field_effect_sides {
         let side = state.get_side(side_ref);
         let side_wish = side.wish;
         let active_pkmn = side.get_active();
@@ -3150,6 +3211,37 @@ fn add_end_of_turn_instructions(
             }
         }
 
+        if side
+            .volatile_statuses
+            .contains(&PokemonVolatileStatus::DYNAMAX)
+        {
+            // dynamax lasts 3 turns including the turn the pokemon dynamaxed
+            if side.volatile_status_durations.dynamax == 2 {
+                side.end_dynamax(side_ref, &mut incoming_instructions.instruction_list);
+                side.volatile_statuses
+                    .remove(&PokemonVolatileStatus::DYNAMAX);
+                incoming_instructions
+                    .instruction_list
+                    .push(Instruction::RemoveVolatileStatus(
+                        RemoveVolatileStatusInstruction {
+                            side_ref: *side_ref,
+                            volatile_status: PokemonVolatileStatus::DYNAMAX,
+                        },
+                    ));
+            } else {
+                side.volatile_status_durations.dynamax += 1;
+                incoming_instructions.instruction_list.push(
+                    Instruction::ChangeVolatileStatusDuration(
+                        ChangeVolatileStatusDurationInstruction {
+                            side_ref: *side_ref,
+                            volatile_status: PokemonVolatileStatus::DYNAMAX,
+                            amount: 1,
+                        },
+                    ),
+                );
+            }
+        }
+
         if side
             .volatile_statuses
             .contains(&PokemonVolatileStatus::YAWN)
@@ -3376,6 +3468,7 @@ fn add_end_of_turn_instructions(
             PokemonVolatileStatus::BURNINGBULWARK,
             PokemonVolatileStatus::SPIKYSHIELD,
             PokemonVolatileStatus::SILKTRAP,
+            PokemonVolatileStatus::MAXGUARD,
             PokemonVolatileStatus::ENDURE,
         ];
 
@@ -3533,18 +3626,20 @@ fn run_move(
                         .instruction_list
                         .push(Instruction::ToggleSideOneForceSwitch);
 
+                    let saved_move =
+                        switch_out_move_second_saved_move(&state.side_two, defender_choice);
                     if choice.first_move {
                         instructions.instruction_list.push(
                             Instruction::SetSideTwoMoveSecondSwitchOutMove(
                                 SetSecondMoveSwitchOutMoveInstruction {
-                                    new_choice: defender_choice.move_id,
+                                    new_choice: saved_move,
                                     previous_choice: state
                                         .side_two
                                         .switch_out_move_second_saved_move,
                                 },
                             ),
                         );
-                        state.side_two.switch_out_move_second_saved_move = defender_choice.move_id;
+                        state.side_two.switch_out_move_second_saved_move = saved_move;
                     } else {
                         instructions.instruction_list.push(
                             Instruction::SetSideTwoMoveSecondSwitchOutMove(
@@ -3556,7 +3651,7 @@ fn run_move(
                                 },
                             ),
                         );
-                        state.side_two.switch_out_move_second_saved_move = defender_choice.move_id;
+                        state.side_two.switch_out_move_second_saved_move = saved_move;
                     }
                 }
             }
@@ -3586,18 +3681,20 @@ fn run_move(
                         .instruction_list
                         .push(Instruction::ToggleSideTwoForceSwitch);
 
+                    let saved_move =
+                        switch_out_move_second_saved_move(&state.side_one, defender_choice);
                     if choice.first_move {
                         instructions.instruction_list.push(
                             Instruction::SetSideOneMoveSecondSwitchOutMove(
                                 SetSecondMoveSwitchOutMoveInstruction {
-                                    new_choice: defender_choice.move_id,
+                                    new_choice: saved_move,
                                     previous_choice: state
                                         .side_one
                                         .switch_out_move_second_saved_move,
                                 },
                             ),
                         );
-                        state.side_one.switch_out_move_second_saved_move = defender_choice.move_id;
+                        state.side_one.switch_out_move_second_saved_move = saved_move;
                     } else {
                         instructions.instruction_list.push(
                             Instruction::SetSideOneMoveSecondSwitchOutMove(
@@ -3609,7 +3706,7 @@ fn run_move(
                                 },
                             ),
                         );
-                        state.side_one.switch_out_move_second_saved_move = defender_choice.move_id;
+                        state.side_one.switch_out_move_second_saved_move = saved_move;
                     }
                 }
             }
@@ -3670,6 +3767,93 @@ fn handle_both_moves(
     }
 }
 
+// A Max Move or Z-Move is saved as the move it replaced
+// A Max Move becomes a Max Move again when it is used
+fn switch_out_move_second_saved_move(side: &Side, defender_choice: &Choice) -> Choices {
+    if defender_choice.move_id.is_max_move() || defender_choice.z_move {
+        side.get_active_immutable().moves[&defender_choice.move_index].id
+    } else {
+        defender_choice.move_id
+    }
+}
+
+// Types and ability changed since switching in are reset first so that the
+// MegaEvolve instruction always starts from the base forme and can be reversed exactly
+// The new ability activates like it does on switching in, e.g. Drought or Intimidate, so the
+// instructions are applied to `state` as they are generated
+pub fn add_mega_evolution_instructions(
+    state: &mut State,
+    side_ref: &SideReference,
+    instructions: &mut StateInstructions,
+) {
+    let mut mega_evolution = StateInstructions::default();
+    let pkmn = state.get_side_immutable(side_ref).get_active_immutable();
+    let mega = pkmn
+        .held_mega_evolution()
+        .unwrap_or_else(|| panic!("{} cannot mega evolve with {}", pkmn.id, pkmn.item));
+    if pkmn.types != pkmn.base_types {
+        mega_evolution
+            .instruction_list
+            .push(Instruction::ChangeType(ChangeType {
+                side_ref: *side_ref,
+                new_types: pkmn.base_types,
+                old_types: pkmn.types,
+            }));
+    }
+    if pkmn.ability != pkmn.base_ability {
+        mega_evolution
+            .instruction_list
+            .push(Instruction::ChangeAbility(ChangeAbilityInstruction {
+                side_ref: *side_ref,
+                ability_change: pkmn.base_ability as i16 - pkmn.ability as i16,
+            }));
+    }
+    mega_evolution
+        .instruction_list
+        .push(Instruction::MegaEvolve(MegaEvolveInstruction {
+            side_ref: *side_ref,
+            ability_change: mega.ability as i16 - pkmn.base_ability as i16,
+        }));
+    state.apply_instructions(&mega_evolution.instruction_list);
+    ability_on_switch_in(state, side_ref, &mut mega_evolution);
+    instructions
+        .instruction_list
+        .extend(mega_evolution.instruction_list);
+}
+
+// Dynamax doubles the current hp along with the maxhp
+// A pokemon with the Gigantamax factor also changes into its G-Max forme
+pub fn add_dynamax_instructions(
+    pkmn: &Pokemon,
+    side_ref: &SideReference,
+    instructions: &mut StateInstructions,
+) {
+    instructions
+        .instruction_list
+        .push(Instruction::Dynamax(DynamaxInstruction {
+            side_ref: *side_ref,
+            hp_change: pkmn.hp,
+        }));
+    instructions
+        .instruction_list
+        .push(Instruction::ApplyVolatileStatus(
+            ApplyVolatileStatusInstruction {
+                side_ref: *side_ref,
+                volatile_status: PokemonVolatileStatus::DYNAMAX,
+            },
+        ));
+    if pkmn.gigantamax {
+        if let Some(gigantamax_forme) = pkmn.id.gigantamax_forme() {
+            instructions
+                .instruction_list
+                .push(Instruction::FormeChange(FormeChangeInstruction {
+                    side_ref: *side_ref,
+                    name_change: gigantamax_forme as i16 - pkmn.id as i16,
+                }));
+        }
+    }
+}
+
 pub fn generate_instructions_from_move_pair(
     state: &mut State,
     side_one_move: &MoveChoice,
@@ -3678,6 +3862,9 @@ pub fn generate_instructions_from_move_pair(
 ) -> Vec<StateInstructions> {
     let mut side_one_choice;
     let mut s1_tera = false;
+    let mut s1_mega = false;
+    let mut s1_dynamax = false;
+    let mut s1_z_move = false;
     match side_one_move {
         MoveChoice::Switch(switch_id) => {
             side_one_choice = Choice::default();
@@ -3693,6 +3880,22 @@ pub fn generate_instructions_from_move_pair(
             side_one_choice.move_index = *move_index;
             s1_tera = true;
         }
+        MoveChoice::MoveMega(move_index) => {
+            side_one_choice = state.side_one.get_active().moves[move_index].choice.clone();
+            side_one_choice.move_index = *move_index;
+            s1_mega = true;
+        }
+        MoveChoice::MoveDynamax(move_index) => {
+            side_one_choice = state.side_one.get_active().moves[move_index].choice.clone();
+            side_one_choice.move_index = *move_index;
+            s1_dynamax = true;
+        }
+        MoveChoice::MoveZ(move_index) => {
+            side_one_choice = state.side_one.get_active().moves[move_index].choice.clone();
+            side_one_choice.move_index = *move_index;
+            convert_to_z_move(state.side_one.get_active_immutable(), &mut side_one_choice);
+            s1_z_move = true;
+        }
         MoveChoice::None => {
             side_one_choice = Choice::default();
         }
@@ -3700,6 +3903,9 @@ pub fn generate_instructions_from_move_pair(
 
     let mut side_two_choice;
     let mut s2_tera = false;
+    let mut s2_mega = false;
+    let mut s2_dynamax = false;
+    let mut s2_z_move = false;
     match side_two_move {
         MoveChoice::Switch(switch_id) => {
             side_two_choice = Choice::default();
@@ -3715,6 +3921,22 @@ pub fn generate_instructions_from_move_pair(
             side_two_choice.move_index = *move_index;
             s2_tera = true;
         }
+        MoveChoice::MoveMega(move_index) => {
+            side_two_choice = state.side_two.get_active().moves[move_index].choice.clone();
+            side_two_choice.move_index = *move_index;
+            s2_mega = true;
+        }
+        MoveChoice::MoveDynamax(move_index) => {
+            side_two_choice = state.side_two.get_active().moves[move_index].choice.clone();
+            side_two_choice.move_index = *move_index;
+            s2_dynamax = true;
+        }
+        MoveChoice::MoveZ(move_index) => {
+            side_two_choice = state.side_two.get_active().moves[move_index].choice.clone();
+            side_two_choice.move_index = *move_index;
+            convert_to_z_move(state.side_two.get_active_immutable(), &mut side_two_choice);
+            s2_z_move = true;
+        }
         MoveChoice::None => {
             side_two_choice = Choice::default();
         }
@@ -3744,15 +3966,80 @@ pub fn generate_instructions_from_move_pair(
                 },
             ));
     }
+    let mut mega_evolution = StateInstructions::default();
+    if s1_mega {
+        add_mega_evolution_instructions(state, &SideReference::SideOne, &mut mega_evolution);
+    }
+    if s2_mega {
+        add_mega_evolution_instructions(state, &SideReference::SideTwo, &mut mega_evolution);
+    }
+    state.reverse_instructions(&mega_evolution.instruction_list);
+    incoming_instructions
+        .instruction_list
+        .extend(mega_evolution.instruction_list);
+
+    if s1_dynamax {
+        add_dynamax_instructions(
+            state.side_one.get_active_immutable(),
+            &SideReference::SideOne,
+            &mut incoming_instructions,
+        );
+    }
+    if s2_dynamax {
+        add_dynamax_instructions(
+            state.side_two.get_active_immutable(),
+            &SideReference::SideTwo,
+            &mut incoming_instructions,
+        );
+    }
+
+    if s1_z_move {
+        incoming_instructions
+            .instruction_list
+            .push(Instruction::ToggleZMoveUsed(ToggleZMoveUsedInstruction {
+                side_ref: SideReference::SideOne,
+            }));
+    }
+    if s2_z_move {
+        incoming_instructions
+            .instruction_list
+            .push(Instruction::ToggleZMoveUsed(ToggleZMoveUsedInstruction {
+                side_ref: SideReference::SideTwo,
+            }));
+    }
+
+    // from gen7 the turn order uses the speed and priority of a pokemon's mega forme
+    // on the turn it mega evolves
+    #[cfg(feature = "gen7")]
+    let mega_evolution_instructions = incoming_instructions.instruction_list.clone();
+    #[cfg(feature = "gen7")]
+    state.apply_instructions(&mega_evolution_instructions);
+
+    // the priority of a Max Move is used for the turn order, not the priority of the move it replaces
+    #[cfg(feature = "gen8")]
+    let dynamax_instructions = incoming_instructions.instruction_list.clone();
+    #[cfg(feature = "gen8")]
+    {
+        state.apply_instructions(&dynamax_instructions);
+        convert_to_max_move(&state.side_one, &mut side_one_choice);
+        convert_to_max_move(&state.side_two, &mut side_two_choice);
+    }
 
     modify_choice_priority(&state, &SideReference::SideOne, &mut side_one_choice);
     modify_choice_priority(&state, &SideReference::SideTwo, &mut side_two_choice);
-    match moves_first(
+    let side_moves_first = moves_first(
         &state,
         &side_one_choice,
         &side_two_choice,
         &mut incoming_instructions,
-    ) {
+    );
+
+    #[cfg(feature = "gen7")]
+    state.reverse_instructions(&mega_evolution_instructions);
+    #[cfg(feature = "gen8")]
+    state.reverse_instructions(&dynamax_instructions);
+
+    match side_moves_first {
         SideMovesFirst::SideOne => {
             handle_both_moves(
                 state,
@@ -3830,6 +4117,875 @@ pub fn generate_instructions_from_move_pair(
     state_instructions_vec
 }
 
+#[derive(Clone)]
+struct DoublesAction {
+    position: BattlePosition,
+    move_choice: DoublesMoveChoice,
+    choice: Choice,
+    tera: bool,
+    mega: bool,
+    dynamax: bool,
+    z_move: bool,
+    speed: i16,
+}
+
+// The self-inverse SwapActivePosition instruction puts a position in front of its side so the
+// singles machinery can operate on it. Toggling the same position a second time undoes it.
+fn add_position_focus_instruction(position: &BattlePosition, instructions: &mut StateInstructions) {
+    if position.position == 1 {
+        instructions
+            .instruction_list
+            .push(Instruction::SwapActivePosition(
+                SwapActivePositionInstruction {
+                    side_ref: position.side,
+                },
+            ));
+    }
+}
+
+fn apply_position_focus_instruction(
+    state: &mut State,
+    position: &BattlePosition,
+    instructions: &mut StateInstructions,
+) {
+    if position.position == 1 {
+        state.get_side(&position.side).swap_active_position();
+    }
+    add_position_focus_instruction(position, instructions);
+}
+
+fn get_doubles_action(
+    state: &mut State,
+    position: BattlePosition,
+    move_choice: &DoublesMoveChoice,
+) -> DoublesAction {
+    if position.position == 1 {
+        state.get_side(&position.side).swap_active_position();
+    }
+
+    let mut tera = false;
+    let mut mega = false;
+    let mut dynamax = false;
+    let mut z_move = false;
+    let mut choice;
+    match move_choice.move_choice {
+        MoveChoice::Switch(switch_id) => {
+            choice = Choice::default();
+            choice.switch_id = switch_id;
+            choice.category = MoveCategory::Switch;
+        }
+        MoveChoice::Move(move_index) => {
+            choice = state.get_side(&position.side).get_active().moves[&move_index]
+                .choice
+                .clone();
+            choice.move_index = move_index;
+        }
+        MoveChoice::MoveTera(move_index) => {
+            choice = state.get_side(&position.side).get_active().moves[&move_index]
+                .choice
+                .clone();
+            choice.move_index = move_index;
+            tera = true;
+        }
+        MoveChoice::MoveMega(move_index) => {
+            choice = state.get_side(&position.side).get_active().moves[&move_index]
+                .choice
+                .clone();
+            choice.move_index = move_index;
+            mega = true;
+        }
+        MoveChoice::MoveDynamax(move_index) => {
+            choice = state.get_side(&position.side).get_active().moves[&move_index]
+                .choice
+                .clone();
+            choice.move_index = move_index;
+            dynamax = true;
+        }
+        MoveChoice::MoveZ(move_index) => {
+            let active = state.get_side(&position.side).get_active();
+            choice = active.moves[&move_index].choice.clone();
+            choice.move_index = move_index;
+            convert_to_z_move(active, &mut choice);
+            z_move = true;
+        }
+        MoveChoice::None => {
+            choice = Choice::default();
+        }
+    }
+
+    // from gen7 the turn order uses the speed and priority of a pokemon's mega forme
+    // on the turn it mega evolves
+    #[cfg(feature = "gen7")]
+    let mut mega_evolution = StateInstructions::default();
+    #[cfg(feature = "gen7")]
+    if mega {
+        add_mega_evolution_instructions(state, &position.side, &mut mega_evolution);
+    }
+
+    #[cfg(feature = "gen8")]
+    let mut dynamax_instructions = StateInstructions::default();
+    #[cfg(feature = "gen8")]
+    {
+        if dynamax {
+            add_dynamax_instructions(
+                state.get_side_immutable(&position.side).get_active_immutable(),
+                &position.side,
+                &mut dynamax_instructions,
+            );
+            state.apply_instructions(&dynamax_instructions.instruction_list);
+        }
+        convert_to_max_move(state.get_side_immutable(&position.side), &mut choice);
+    }
+
+    modify_choice_priority(state, &position.side, &mut choice);
+    let speed = get_effective_speed(state, &position.side);
+
+    #[cfg(feature = "gen7")]
+    state.reverse_instructions(&mega_evolution.instruction_list);
+    #[cfg(feature = "gen8")]
+    state.reverse_instructions(&dynamax_instructions.instruction_list);
+
+    if position.position == 1 {
+        state.get_side(&position.side).swap_active_position();
+    }
+    DoublesAction {
+        position,
+        move_choice: *move_choice,
+        choice,
+        tera,
+        mega,
+        dynamax,
+        z_move,
+        speed,
+    }
+}
+
+fn doubles_position_is_alive(state: &State, position: &BattlePosition) -> bool {
+    state
+        .get_side_immutable(&position.side)
+        .get_position_active_immutable(position.position)
+        .hp
+        > 0
+}
+
+// Single-target moves are drawn to a pokemon that used Follow Me, Rage Powder or was hit by
+// Spotlight, and Water/Electric moves to Storm Drain/Lightning Rod.
+// Otherwise the chosen target is used, or the other opponent if the chosen one has fainted
+fn get_doubles_single_target(
+    state: &State,
+    action: &DoublesAction,
+    alive_foes: &[BattlePosition],
+) -> Option<BattlePosition> {
+    let attacker = state
+        .get_side_immutable(&action.position.side)
+        .get_position_active_immutable(action.position.position);
+    for foe in alive_foes {
+        let volatile_statuses = state
+            .get_side_immutable(&foe.side)
+            .get_position_volatile_statuses(foe.position);
+        if volatile_statuses.contains(&PokemonVolatileStatus::FOLLOWME)
+            || volatile_statuses.contains(&PokemonVolatileStatus::SPOTLIGHT)
+        {
+            return Some(*foe);
+        }
+        if volatile_statuses.contains(&PokemonVolatileStatus::RAGEPOWDER)
+            && !attacker.has_type(&PokemonType::GRASS)
+            && attacker.ability != Abilities::OVERCOAT
+        {
+            return Some(*foe);
+        }
+    }
+    for foe in alive_foes {
+        let foe_ability = state
+            .get_side_immutable(&foe.side)
+            .get_position_active_immutable(foe.position)
+            .ability;
+        if (action.choice.move_type == PokemonType::WATER && foe_ability == Abilities::STORMDRAIN)
+            || (action.choice.move_type == PokemonType::ELECTRIC
+                && foe_ability == Abilities::LIGHTNINGROD)
+        {
+            return Some(*foe);
+        }
+    }
+    match action.move_choice.target {
+        Some(target) if alive_foes.contains(&target) => Some(target),
+        _ => alive_foes.first().copied(),
+    }
+}
+
+// Moves that cost the user hp as part of the move. When a spread move hits more than one target
+// the cost is only paid once
+fn doubles_move_costs_user_hp(choice: &Choice) -> bool {
+    matches!(
+        choice.move_id,
+        Choices::EXPLOSION | Choices::SELFDESTRUCT | Choices::MISTYEXPLOSION | Choices::MINDBLOWN
+    )
+}
+
+// Runs the singles move generation for `attacker` -> `target`
+// `incoming_instructions` must already put the attacker in front of its side
+#[allow(clippy::too_many_arguments)]
+fn generate_instructions_from_doubles_move(
+    state: &mut State,
+    actions: &[DoublesAction],
+    action_index: usize,
+    choice: &Choice,
+    target: BattlePosition,
+    mut incoming_instructions: StateInstructions,
+    final_instructions: &mut Vec<StateInstructions>,
+    branch_on_damage: bool,
+) {
+    let action = &actions[action_index];
+    add_position_focus_instruction(&target, &mut incoming_instructions);
+
+    let mut choice = choice.clone();
+    let defender_choice = match actions.iter().find(|a| a.position == target) {
+        Some(target_action) => target_action.choice.clone(),
+        None => Choice::default(),
+    };
+    choice.first_move = if target.side == action.position.side {
+        action_index + 1 < actions.len()
+    } else {
+        !actions[..action_index]
+            .iter()
+            .any(|a| a.position == target)
+    };
+
+    // A pokemon that used a pivot move earlier in the turn switches out after every
+    // position has acted, so its pending switch must not cancel this move
+    state.apply_instructions(&incoming_instructions.instruction_list);
+    if state.get_side_immutable(&target.side).force_switch {
+        choice.first_move = true;
+    }
+    state.reverse_instructions(&incoming_instructions.instruction_list);
+
+    let mut move_instructions = Vec::with_capacity(4);
+    generate_instructions_from_move(
+        state,
+        &mut choice,
+        &defender_choice,
+        action.position.side,
+        incoming_instructions,
+        &mut move_instructions,
+        branch_on_damage,
+    );
+    for state_instructions in move_instructions.iter_mut() {
+        add_position_focus_instruction(&target, state_instructions);
+    }
+    final_instructions.extend(move_instructions);
+}
+
+// Applies the user-targeted effects of a support move (Helping Hand, Coaching, Life Dew, etc.)
+// to the ally of the attacker. `incoming_instructions` must put the attacker in front
+fn add_doubles_ally_support_instructions(
+    state: &mut State,
+    attacking_side: &SideReference,
+    choice: &Choice,
+    incoming_instructions: &mut StateInstructions,
+) {
+    state.apply_instructions(&incoming_instructions.instruction_list);
+    state.get_side(attacking_side).swap_active_position();
+    incoming_instructions
+        .instruction_list
+        .push(Instruction::SwapActivePosition(
+            SwapActivePositionInstruction {
+                side_ref: *attacking_side,
+            },
+        ));
+
+    if state.get_side(attacking_side).get_active_immutable().hp > 0 {
+        if let Some(volatile_status) = &choice.volatile_status {
+            get_instructions_from_volatile_statuses(
+                state,
+                choice,
+                volatile_status,
+                attacking_side,
+                incoming_instructions,
+            );
+        }
+        if let Some(boost) = &choice.boost {
+            get_instructions_from_boosts(state, boost, attacking_side, incoming_instructions);
+        }
+        if let Some(heal) = &choice.heal {
+            get_instructions_from_heal(state, heal, attacking_side, incoming_instructions);
+        }
+    }
+
+    state.get_side(attacking_side).swap_active_position();
+    incoming_instructions
+        .instruction_list
+        .push(Instruction::SwapActivePosition(
+            SwapActivePositionInstruction {
+                side_ref: *attacking_side,
+            },
+        ));
+    state.reverse_instructions(&incoming_instructions.instruction_list);
+}
+
+// Spread moves that hit every adjacent pokemon also hit the attacker's ally
+// Only the damage is applied to the ally. `incoming_instructions` must put the attacker in front
+fn add_doubles_ally_damage_instructions(
+    state: &mut State,
+    attacking_side: &SideReference,
+    choice: &Choice,
+    incoming_instructions: &mut StateInstructions,
+) {
+    state.apply_instructions(&incoming_instructions.instruction_list);
+
+    let side = state.get_side_immutable(attacking_side);
+    let ally = side.get_position_active_immutable(1);
+    let ally_volatile_statuses = side.get_position_volatile_statuses(1);
+    let ally_protected = [
+        PokemonVolatileStatus::PROTECT,
+        PokemonVolatileStatus::BANEFULBUNKER,
+        PokemonVolatileStatus::BURNINGBULWARK,
+        PokemonVolatileStatus::SPIKYSHIELD,
+        PokemonVolatileStatus::SILKTRAP,
+        PokemonVolatileStatus::MAXGUARD,
+    ]
+    .iter()
+    .any(|vs| ally_volatile_statuses.contains(vs));
+
+    let mut damage_amount = 0;
+    if ally.hp > 0
+        && !ally_protected
+        && (choice.move_type != PokemonType::GROUND || ally.is_grounded())
+    {
+        // the damage calculation only knows how to hit the opposing side,
+        // so calculate against a copy of the state where the ally is the defender
+        let mut ally_side = side.clone();
+        ally_side.swap_active_position();
+        let mut ally_state = state.clone();
+        *ally_state.get_side(&attacking_side.get_other_side()) = ally_side;
+        if let Some((damage, _)) =
+            calculate_damage(&ally_state, attacking_side, choice, DamageRolls::Average)
+        {
+            damage_amount = cmp::min(damage, ally.hp);
+        }
+    }
+    state.reverse_instructions(&incoming_instructions.instruction_list);
+
+    if damage_amount > 0 {
+        let swap_instruction = Instruction::SwapActivePosition(SwapActivePositionInstruction {
+            side_ref: *attacking_side,
+        });
+        incoming_instructions
+            .instruction_list
+            .push(swap_instruction.clone());
+        incoming_instructions
+            .instruction_list
+            .push(Instruction::Damage(DamageInstruction {
+                side_ref: *attacking_side,
+                damage_amount,
+            }));
+        incoming_instructions.instruction_list.push(swap_instruction);
+    }
+}
+
+fn generate_instructions_from_doubles_action(
+    state: &mut State,
+    actions: &[DoublesAction],
+    action_index: usize,
+    mut incoming_instructions: StateInstructions,
+    final_instructions: &mut Vec<StateInstructions>,
+    branch_on_damage: bool,
+) {
+    let action = &actions[action_index];
+    let attacking_side = action.position.side;
+    let mut choice = action.choice.clone();
+
+    state.apply_instructions(&incoming_instructions.instruction_list);
+
+    // a pokemon that fainted earlier in the turn does not get to move
+    if choice.category != MoveCategory::Switch
+        && !doubles_position_is_alive(state, &action.position)
+    {
+        state.reverse_instructions(&incoming_instructions.instruction_list);
+        final_instructions.push(incoming_instructions);
+        return;
+    }
+
+    let move_target = if choice.category == MoveCategory::Switch || choice.move_id == Choices::NONE
+    {
+        DoublesMoveTarget::User
+    } else {
+        doubles_move_target(&choice)
+    };
+    let opposing_side = attacking_side.get_other_side();
+    let alive_foes: Vec<BattlePosition> = [0, 1]
+        .iter()
+        .map(|p| BattlePosition::new(opposing_side, *p))
+        .filter(|p| doubles_position_is_alive(state, p))
+        .collect();
+    let ally_alive = doubles_position_is_alive(state, &action.position.ally());
+
+    let targets: Vec<BattlePosition> = match move_target {
+        DoublesMoveTarget::AdjacentFoe => get_doubles_single_target(state, action, &alive_foes)
+            .into_iter()
+            .collect(),
+        DoublesMoveTarget::AllAdjacentFoes | DoublesMoveTarget::AllAdjacent => alive_foes.clone(),
+        _ => vec![],
+    };
+    let hits_ally = ally_alive
+        && matches!(
+            move_target,
+            DoublesMoveTarget::AllAdjacent
+                | DoublesMoveTarget::AdjacentAlly
+                | DoublesMoveTarget::UserAndAllies
+        );
+
+    if move_target.is_spread()
+        && targets.len() + (move_target == DoublesMoveTarget::AllAdjacent && hits_ally) as usize > 1
+    {
+        choice.base_power *= 0.75;
+    }
+    if state
+        .get_side_immutable(&attacking_side)
+        .get_position_volatile_statuses(action.position.position)
+        .contains(&PokemonVolatileStatus::HELPINGHAND)
+    {
+        choice.base_power *= 1.5;
+    }
+    let attacker_hp_before_move = state
+        .get_side_immutable(&attacking_side)
+        .get_position_active_immutable(action.position.position)
+        .hp;
+    state.reverse_instructions(&incoming_instructions.instruction_list);
+
+    add_position_focus_instruction(&action.position, &mut incoming_instructions);
+    let default_target = alive_foes
+        .first()
+        .copied()
+        .unwrap_or(BattlePosition::new(opposing_side, 0));
+
+    let mut action_instructions = Vec::with_capacity(4);
+    if move_target == DoublesMoveTarget::User
+        || (move_target == DoublesMoveTarget::UserAndAllies && !hits_ally)
+        || (targets.len() == 1 && !hits_ally)
+    {
+        let target = targets.first().copied().unwrap_or(default_target);
+        generate_instructions_from_doubles_move(
+            state,
+            actions,
+            action_index,
+            &choice,
+            target,
+            incoming_instructions,
+            &mut action_instructions,
+            branch_on_damage,
+        );
+    } else if targets.is_empty() && !hits_ally {
+        // there is nothing left to hit
+        action_instructions.push(incoming_instructions);
+    } else {
+        // The move affects more than one pokemon. Existing status conditions (paralysis,
+        // sleep, confusion, etc.) are rolled once here, and every target is then hit
+        // with those rolls already made.
+        // `sleep_talk_move` tells the singles generation to not roll them again
+        state.apply_instructions(&incoming_instructions.instruction_list);
+        generate_instructions_from_existing_status_conditions(
+            state,
+            &attacking_side,
+            &choice,
+            &mut incoming_instructions,
+            &mut action_instructions,
+        );
+        state.reverse_instructions(&incoming_instructions.instruction_list);
+        choice.sleep_talk_move = true;
+
+        let mut continuing_instructions = vec![];
+        if incoming_instructions.percentage > 0.0 {
+            continuing_instructions.push(incoming_instructions);
+        }
+        if move_target == DoublesMoveTarget::UserAndAllies {
+            let mut next_instructions = Vec::with_capacity(continuing_instructions.len());
+            for state_instructions in continuing_instructions {
+                generate_instructions_from_doubles_move(
+                    state,
+                    actions,
+                    action_index,
+                    &choice,
+                    default_target,
+                    state_instructions,
+                    &mut next_instructions,
+                    branch_on_damage,
+                );
+            }
+            continuing_instructions = next_instructions;
+        }
+        for (target_number, target) in targets.iter().enumerate() {
+            let mut next_instructions = Vec::with_capacity(continuing_instructions.len());
+            for mut state_instructions in continuing_instructions {
+                if target_number > 0 && doubles_move_costs_user_hp(&choice) {
+                    state.apply_instructions(&state_instructions.instruction_list);
+                    let hp_lost = attacker_hp_before_move
+                        - state.get_side(&attacking_side).get_active_immutable().hp;
+                    state.reverse_instructions(&state_instructions.instruction_list);
+                    if hp_lost > 0 {
+                        state_instructions
+                            .instruction_list
+                            .push(Instruction::Heal(HealInstruction {
+                                side_ref: attacking_side,
+                                heal_amount: hp_lost,
+                            }));
+                    }
+                }
+                generate_instructions_from_doubles_move(
+                    state,
+                    actions,
+                    action_index,
+                    &choice,
+                    *target,
+                    state_instructions,
+                    &mut next_instructions,
+                    branch_on_damage,
+                );
+            }
+            continuing_instructions = next_instructions;
+        }
+        if hits_ally {
+            for state_instructions in continuing_instructions.iter_mut() {
+                match move_target {
+                    DoublesMoveTarget::AllAdjacent => add_doubles_ally_damage_instructions(
+                        state,
+                        &attacking_side,
+                        &choice,
+                        state_instructions,
+                    ),
+                    _ => add_doubles_ally_support_instructions(
+                        state,
+                        &attacking_side,
+                        &choice,
+                        state_instructions,
+                    ),
+                }
+            }
+        }
+        action_instructions.extend(continuing_instructions);
+    }
+
+    for mut state_instructions in action_instructions {
+        add_position_focus_instruction(&action.position, &mut state_instructions);
+        final_instructions.push(state_instructions);
+    }
+}
+
+fn doubles_end_of_turn_triggered(actions: &[DoublesAction]) -> bool {
+    // a turn where fainted pokemon are only being replaced does not have an end of turn
+    let only_switches_or_nothing = actions.iter().all(|a| {
+        matches!(
+            a.move_choice.move_choice,
+            MoveChoice::Switch(_) | MoveChoice::None
+        )
+    });
+    let anyone_did_nothing = actions
+        .iter()
+        .any(|a| a.move_choice.move_choice == MoveChoice::None);
+    !(only_switches_or_nothing && anyone_did_nothing)
+}
+
+// Follow Me, Rage Powder, Spotlight and Helping Hand only last for the turn they are used
+fn remove_doubles_turn_volatile_statuses(
+    state: &mut State,
+    incoming_instructions: &mut StateInstructions,
+) {
+    state.apply_instructions(&incoming_instructions.instruction_list);
+    for side_ref in [SideReference::SideOne, SideReference::SideTwo].iter() {
+        for position in 0..2 {
+            let position = BattlePosition::new(*side_ref, position);
+            apply_position_focus_instruction(state, &position, incoming_instructions);
+            let side = state.get_side(side_ref);
+            for volatile_status in [
+                PokemonVolatileStatus::FOLLOWME,
+                PokemonVolatileStatus::RAGEPOWDER,
+                PokemonVolatileStatus::SPOTLIGHT,
+                PokemonVolatileStatus::HELPINGHAND,
+            ]
+            .iter()
+            {
+                if side.volatile_statuses.remove(volatile_status) {
+                    incoming_instructions
+                        .instruction_list
+                        .push(Instruction::RemoveVolatileStatus(
+                            RemoveVolatileStatusInstruction {
+                                side_ref: *side_ref,
+                                volatile_status: *volatile_status,
+                            },
+                        ));
+                }
+            }
+            apply_position_focus_instruction(state, &position, incoming_instructions);
+        }
+    }
+    state.reverse_instructions(&incoming_instructions.instruction_list);
+}
+
+// Focusing positions leaves many back-to-back SwapActivePosition instructions that cancel out.
+// Swaps on different sides commute, so only the parity of each side's swaps between two other
+// instructions matters
+fn remove_redundant_position_swaps(state_instructions: &mut StateInstructions) {
+    let mut instruction_list = Vec::with_capacity(state_instructions.instruction_list.len());
+    let mut pending_swaps = [false, false];
+    for instruction in state_instructions.instruction_list.drain(..) {
+        match instruction {
+            Instruction::SwapActivePosition(i) => match i.side_ref {
+                SideReference::SideOne => pending_swaps[0] = !pending_swaps[0],
+                SideReference::SideTwo => pending_swaps[1] = !pending_swaps[1],
+            },
+            instruction => {
+                flush_position_swaps(&mut pending_swaps, &mut instruction_list);
+                instruction_list.push(instruction);
+            }
+        }
+    }
+    flush_position_swaps(&mut pending_swaps, &mut instruction_list);
+    state_instructions.instruction_list = instruction_list;
+}
+
+fn flush_position_swaps(pending_swaps: &mut [bool; 2], instruction_list: &mut Vec<Instruction>) {
+    for (side_ref, pending) in [SideReference::SideOne, SideReference::SideTwo]
+        .iter()
+        .zip(pending_swaps.iter_mut())
+    {
+        if *pending {
+            instruction_list.push(Instruction::SwapActivePosition(
+                SwapActivePositionInstruction { side_ref: *side_ref },
+            ));
+            *pending = false;
+        }
+    }
+}
+
+/// The doubles equivalent of `generate_instructions_from_move_pair`
+///
+/// The four actions are ordered by switches, priority and speed. Speed ties branch into every
+/// order of the tied actions with an equal chance. Each action is generated with the singles machinery by moving
+/// the acting and targeted positions in front of their side with `SwapActivePosition`
+/// instructions. Spread moves hit every target with 0.75x base power.
+///
+/// Approximations:
+///   - damage to the user's ally from moves like Earthquake does not apply secondary effects
+///   - support moves targeting the ally do not roll the user's status conditions
+///   - a pivot move's switch happens after all positions have acted
+pub fn generate_instructions_from_doubles_move_quad(
+    state: &mut State,
+    side_one_choices: &[DoublesMoveChoice; 2],
+    side_two_choices: &[DoublesMoveChoice; 2],
+    branch_on_damage: bool,
+) -> Vec<StateInstructions> {
+    let mut actions = Vec::with_capacity(4);
+    for (side_ref, choices) in [
+        (SideReference::SideOne, side_one_choices),
+        (SideReference::SideTwo, side_two_choices),
+    ]
+    .iter()
+    {
+        for (position, move_choice) in choices.iter().enumerate() {
+            actions.push(get_doubles_action(
+                state,
+                BattlePosition::new(*side_ref, position as u8),
+                move_choice,
+            ));
+        }
+    }
+
+    let trick_room = state.trick_room.active;
+    actions.sort_by(|a, b| {
+        let speed_order = if trick_room {
+            a.speed.cmp(&b.speed)
+        } else {
+            b.speed.cmp(&a.speed)
+        };
+        doubles_action_switches(b)
+            .cmp(&doubles_action_switches(a))
+            .then(b.choice.priority.cmp(&a.choice.priority))
+            .then(speed_order)
+    });
+
+    // Only create terastallization, mega evolution, dynamax and Z-Move instructions, don't apply them
+    // generate_instructions_from_move() assumes instructions have not been applied
+    let mut incoming_instructions = StateInstructions::default();
+    for action in actions.iter().filter(|a| a.tera) {
+        add_position_focus_instruction(&action.position, &mut incoming_instructions);
+        incoming_instructions
+            .instruction_list
+            .push(Instruction::ToggleTerastallized(
+                ToggleTerastallizedInstruction {
+                    side_ref: action.position.side,
+                },
+            ));
+        add_position_focus_instruction(&action.position, &mut incoming_instructions);
+    }
+    let mut mega_evolution = StateInstructions::default();
+    for action in actions.iter().filter(|a| a.mega) {
+        apply_position_focus_instruction(state, &action.position, &mut mega_evolution);
+        add_mega_evolution_instructions(state, &action.position.side, &mut mega_evolution);
+        apply_position_focus_instruction(state, &action.position, &mut mega_evolution);
+    }
+    state.reverse_instructions(&mega_evolution.instruction_list);
+    incoming_instructions
+        .instruction_list
+        .extend(mega_evolution.instruction_list);
+    for action in actions.iter().filter(|a| a.dynamax) {
+        add_position_focus_instruction(&action.position, &mut incoming_instructions);
+        add_dynamax_instructions(
+            state
+                .get_side_immutable(&action.position.side)
+                .get_position_active_immutable(action.position.position),
+            &action.position.side,
+            &mut incoming_instructions,
+        );
+        add_position_focus_instruction(&action.position, &mut incoming_instructions);
+    }
+    for action in actions.iter().filter(|a| a.z_move) {
+        incoming_instructions
+            .instruction_list
+            .push(Instruction::ToggleZMoveUsed(ToggleZMoveUsedInstruction {
+                side_ref: action.position.side,
+            }));
+    }
+
+    let action_orders = doubles_speed_tie_orders(&actions);
+    let order_percentage = 1.0 / action_orders.len() as f32;
+    let mut state_instructions_vec = Vec::with_capacity(action_orders.len());
+    for action_order in action_orders {
+        let ordered_actions: Vec<DoublesAction> =
+            action_order.iter().map(|i| actions[*i].clone()).collect();
+        let mut order_instructions = incoming_instructions.clone();
+        order_instructions.update_percentage(order_percentage);
+        state_instructions_vec.extend(generate_instructions_from_ordered_doubles_actions(
+            state,
+            &ordered_actions,
+            order_instructions,
+            branch_on_damage,
+        ));
+    }
+
+    for state_instructions in state_instructions_vec.iter_mut() {
+        remove_redundant_position_swaps(state_instructions);
+    }
+    combine_duplicate_instructions(&mut state_instructions_vec);
+    state_instructions_vec
+}
+
+fn doubles_action_switches(action: &DoublesAction) -> bool {
+    action.choice.category == MoveCategory::Switch
+}
+
+// Every order the sorted actions can happen in. Actions that tie on switching, priority and
+// speed can happen in any order within their group.
+// The order of positions that do nothing does not matter, so they keep their place
+fn doubles_speed_tie_orders(actions: &[DoublesAction]) -> Vec<Vec<usize>> {
+    let acting: Vec<usize> = (0..actions.len())
+        .filter(|i| actions[*i].move_choice.move_choice != MoveChoice::None)
+        .collect();
+    let mut acting_orders = vec![vec![]];
+    let mut group_start = 0;
+    for index in 1..=acting.len() {
+        let a = &actions[acting[group_start]];
+        let tied = match acting.get(index) {
+            Some(b) => {
+                let b = &actions[*b];
+                doubles_action_switches(a) == doubles_action_switches(b)
+                    && a.choice.priority == b.choice.priority
+                    && a.speed == b.speed
+            }
+            None => false,
+        };
+        if tied {
+            continue;
+        }
+        let group = &acting[group_start..index];
+        acting_orders = acting_orders
+            .iter()
+            .flat_map(|order| {
+                permutations(group).into_iter().map(move |group_order| {
+                    let mut order = order.clone();
+                    order.extend(group_order);
+                    order
+                })
+            })
+            .collect();
+        group_start = index;
+    }
+    acting_orders
+        .into_iter()
+        .map(|acting_order| {
+            let mut order: Vec<usize> = (0..actions.len()).collect();
+            for (slot, action_index) in acting.iter().zip(acting_order) {
+                order[*slot] = action_index;
+            }
+            order
+        })
+        .collect()
+}
+
+fn permutations(items: &[usize]) -> Vec<Vec<usize>> {
+    if items.len() <= 1 {
+        return vec![items.to_vec()];
+    }
+    let mut result = vec![];
+    for (index, first) in items.iter().enumerate() {
+        let mut rest = items.to_vec();
+        rest.remove(index);
+        for mut permutation in permutations(&rest) {
+            permutation.insert(0, *first);
+            result.push(permutation);
+        }
+    }
+    result
+}
+
+fn generate_instructions_from_ordered_doubles_actions(
+    state: &mut State,
+    actions: &[DoublesAction],
+    incoming_instructions: StateInstructions,
+    branch_on_damage: bool,
+) -> Vec<StateInstructions> {
+    let mut state_instructions_vec = vec![incoming_instructions];
+    for action_index in 0..actions.len() {
+        let mut next_state_instructions_vec = Vec::with_capacity(state_instructions_vec.len());
+        for state_instructions in state_instructions_vec {
+            generate_instructions_from_doubles_action(
+                state,
+                actions,
+                action_index,
+                state_instructions,
+                &mut next_state_instructions_vec,
+                branch_on_damage,
+            );
+        }
+        state_instructions_vec = next_state_instructions_vec;
+    }
+
+    if doubles_end_of_turn_triggered(actions) {
+        let first_move_side = actions[0].position.side;
+        let second_positions = [
+            BattlePosition::new(SideReference::SideOne, 1),
+            BattlePosition::new(SideReference::SideTwo, 1),
+        ];
+        for state_instructions in state_instructions_vec.iter_mut() {
+            add_end_of_turn_instructions(state, state_instructions, &first_move_side);
+
+            for position in second_positions.iter() {
+                add_position_focus_instruction(position, state_instructions);
+            }
+            add_end_of_turn_instructions_with_field_effects(
+                state,
+                state_instructions,
+                &first_move_side,
+                false,
+            );
+            for position in second_positions.iter() {
+                add_position_focus_instruction(position, state_instructions);
+            }
+
+            remove_doubles_turn_volatile_statuses(state, state_instructions);
+        }
+    }
+    state_instructions_vec
+}
+
 pub fn calculate_damage_rolls(
     mut state: State,
     attacking_side_ref: &SideReference,
@@ -3896,6 +5052,9 @@ pub fn calculate_damage_rolls(
         Choices::SUPERFANG | Choices::NATURESMADNESS | Choices::RUINATION => {
             return Some(vec![defender_active.hp / 2]);
         }
+        Choices::GUARDIANOFALOLA => {
+            return Some(vec![cmp::max(defender_active.hp * 3 / 4, 1)]);
+        }
         Choices::SUCKERPUNCH | Choices::THUNDERCLAP => {
             defending_choice = MOVES.get(&Choices::TACKLE).unwrap();
         }
@@ -3969,8 +5128,8 @@ mod tests {
         DamageInstruction, EnableMoveInstruction, SwitchInstruction,
     };
     use crate::state::{
-        Move, PokemonBoostableStat, PokemonIndex, PokemonMoveIndex, PokemonSideCondition,
-        PokemonStatus, SideReference, State,
+        Move, PokemonBoostableStat, PokemonGender, PokemonIndex, PokemonMoveIndex,
+        PokemonSideCondition, PokemonStatus, SideReference, State,
     };
 
     #[test]
@@ -4445,6 +5604,8 @@ mod tests {
     #[test]
     fn test_basic_volatile_status_applied_to_opponent() {
         let mut state: State = State::default();
+        state.side_one.get_active().gender = PokemonGender::MALE;
+        state.side_two.get_active().gender = PokemonGender::FEMALE;
         let mut choice = MOVES.get(&Choices::ATTRACT).unwrap().to_owned();
 
         let mut instructions = vec![];
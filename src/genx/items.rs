@@ -14,6 +14,7 @@ use crate::pokemon::PokemonName;
 use crate::state::{
     Pokemon, PokemonBoostableStat, PokemonStatus, PokemonType, Side, SideReference, State,
 };
+use serde::{Deserialize, Serialize};
 use std::cmp;
 
 #[cfg(feature = "gen4")]
@@ -21,7 +22,7 @@ use super::state::PokemonVolatileStatus;
 
 define_enum_with_from_str! {
     #[repr(u8)]
-    #[derive(Debug, PartialEq, Clone, Copy)]
+    #[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
     Items {
         NONE,
         UNKNOWNITEM,
@@ -179,6 +180,91 @@ define_enum_with_from_str! {
         LOADEDDICE,
         RUSTEDSWORD,
         RUSTEDSHIELD,
+        HEATROCK,
+        DAMPROCK,
+        SMOOTHROCK,
+        ICYROCK,
+        TERRAINEXTENDER,
+        ABOMASITE,
+        ABSOLITE,
+        AERODACTYLITE,
+        AGGRONITE,
+        ALAKAZITE,
+        ALTARIANITE,
+        AMPHAROSITE,
+        AUDINITE,
+        BANETTITE,
+        BEEDRILLITE,
+        BLASTOISINITE,
+        BLAZIKENITE,
+        CAMERUPTITE,
+        CHARIZARDITEX,
+        CHARIZARDITEY,
+        DIANCITE,
+        GALLADITE,
+        GARCHOMPITE,
+        GARDEVOIRITE,
+        GENGARITE,
+        GLALITITE,
+        GYARADOSITE,
+        HERACRONITE,
+        HOUNDOOMINITE,
+        KANGASKHANITE,
+        LATIASITE,
+        LATIOSITE,
+        LOPUNNITE,
+        LUCARIONITE,
+        MANECTITE,
+        MAWILITE,
+        MEDICHAMITE,
+        METAGROSSITE,
+        MEWTWONITEX,
+        MEWTWONITEY,
+        PIDGEOTITE,
+        PINSIRITE,
+        SABLENITE,
+        SALAMENCITE,
+        SCEPTILITE,
+        SCIZORITE,
+        SHARPEDONITE,
+        SLOWBRONITE,
+        STEELIXITE,
+        SWAMPERTITE,
+        TYRANITARITE,
+        VENUSAURITE,
+        BUGINIUMZ,
+        DARKINIUMZ,
+        DRAGONIUMZ,
+        ELECTRIUMZ,
+        FAIRIUMZ,
+        FIGHTINIUMZ,
+        FIRIUMZ,
+        FLYINIUMZ,
+        GHOSTIUMZ,
+        GRASSIUMZ,
+        GROUNDIUMZ,
+        ICIUMZ,
+        NORMALIUMZ,
+        POISONIUMZ,
+        PSYCHIUMZ,
+        ROCKIUMZ,
+        STEELIUMZ,
+        WATERIUMZ,
+        ALORAICHIUMZ,
+        DECIDIUMZ,
+        EEVIUMZ,
+        INCINIUMZ,
+        KOMMONIUMZ,
+        LUNALIUMZ,
+        LYCANIUMZ,
+        MARSHADIUMZ,
+        MEWNIUMZ,
+        MIMIKIUMZ,
+        PIKANIUMZ,
+        PRIMARIUMZ,
+        SNORLIUMZ,
+        SOLGANIUMZ,
+        TAPUNIUMZ,
     },
     default = UNKNOWNITEM
 }
@@ -0,0 +1,427 @@
+use super::abilities::Abilities;
+use super::items::Items;
+use crate::pokemon::PokemonName;
+use crate::state::PokemonType;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct MegaEvolution {
+    pub base_forme: PokemonName,
+    pub mega_forme: PokemonName,
+    pub base_types: (PokemonType, PokemonType),
+    pub mega_types: (PokemonType, PokemonType),
+    pub ability: Abilities,
+}
+
+fn mega(
+    base_forme: PokemonName,
+    mega_forme: PokemonName,
+    base_types: (PokemonType, PokemonType),
+    mega_types: (PokemonType, PokemonType),
+    ability: Abilities,
+) -> Option<MegaEvolution> {
+    Some(MegaEvolution {
+        base_forme,
+        mega_forme,
+        base_types,
+        mega_types,
+        ability,
+    })
+}
+
+impl Items {
+    /*
+    The Mega Evolution unlocked by a mega stone
+    Rayquaza does not need a stone so it is not here
+    */
+    pub fn mega_evolution(&self) -> Option<MegaEvolution> {
+        use PokemonType::*;
+        match self {
+            Items::VENUSAURITE => mega(
+                PokemonName::VENUSAUR,
+                PokemonName::VENUSAURMEGA,
+                (GRASS, POISON),
+                (GRASS, POISON),
+                Abilities::THICKFAT,
+            ),
+            Items::CHARIZARDITEX => mega(
+                PokemonName::CHARIZARD,
+                PokemonName::CHARIZARDMEGAX,
+                (FIRE, FLYING),
+                (FIRE, DRAGON),
+                Abilities::TOUGHCLAWS,
+            ),
+            Items::CHARIZARDITEY => mega(
+                PokemonName::CHARIZARD,
+                PokemonName::CHARIZARDMEGAY,
+                (FIRE, FLYING),
+                (FIRE, FLYING),
+                Abilities::DROUGHT,
+            ),
+            Items::BLASTOISINITE => mega(
+                PokemonName::BLASTOISE,
+                PokemonName::BLASTOISEMEGA,
+                (WATER, TYPELESS),
+                (WATER, TYPELESS),
+                Abilities::MEGALAUNCHER,
+            ),
+            Items::BEEDRILLITE => mega(
+                PokemonName::BEEDRILL,
+                PokemonName::BEEDRILLMEGA,
+                (BUG, POISON),
+                (BUG, POISON),
+                Abilities::ADAPTABILITY,
+            ),
+            Items::PIDGEOTITE => mega(
+                PokemonName::PIDGEOT,
+                PokemonName::PIDGEOTMEGA,
+                (NORMAL, FLYING),
+                (NORMAL, FLYING),
+                Abilities::NOGUARD,
+            ),
+            Items::ALAKAZITE => mega(
+                PokemonName::ALAKAZAM,
+                PokemonName::ALAKAZAMMEGA,
+                (PSYCHIC, TYPELESS),
+                (PSYCHIC, TYPELESS),
+                Abilities::TRACE,
+            ),
+            Items::SLOWBRONITE => mega(
+                PokemonName::SLOWBRO,
+                PokemonName::SLOWBROMEGA,
+                (WATER, PSYCHIC),
+                (WATER, PSYCHIC),
+                Abilities::SHELLARMOR,
+            ),
+            Items::GENGARITE => mega(
+                PokemonName::GENGAR,
+                PokemonName::GENGARMEGA,
+                (GHOST, POISON),
+                (GHOST, POISON),
+                Abilities::SHADOWTAG,
+            ),
+            Items::KANGASKHANITE => mega(
+                PokemonName::KANGASKHAN,
+                PokemonName::KANGASKHANMEGA,
+                (NORMAL, TYPELESS),
+                (NORMAL, TYPELESS),
+                Abilities::PARENTALBOND,
+            ),
+            Items::PINSIRITE => mega(
+                PokemonName::PINSIR,
+                PokemonName::PINSIRMEGA,
+                (BUG, TYPELESS),
+                (BUG, FLYING),
+                Abilities::AERILATE,
+            ),
+            Items::GYARADOSITE => mega(
+                PokemonName::GYARADOS,
+                PokemonName::GYARADOSMEGA,
+                (WATER, FLYING),
+                (WATER, DARK),
+                Abilities::MOLDBREAKER,
+            ),
+            Items::AERODACTYLITE => mega(
+                PokemonName::AERODACTYL,
+                PokemonName::AERODACTYLMEGA,
+                (ROCK, FLYING),
+                (ROCK, FLYING),
+                Abilities::TOUGHCLAWS,
+            ),
+            Items::MEWTWONITEX => mega(
+                PokemonName::MEWTWO,
+                PokemonName::MEWTWOMEGAX,
+                (PSYCHIC, TYPELESS),
+                (PSYCHIC, FIGHTING),
+                Abilities::STEADFAST,
+            ),
+            Items::MEWTWONITEY => mega(
+                PokemonName::MEWTWO,
+                PokemonName::MEWTWOMEGAY,
+                (PSYCHIC, TYPELESS),
+                (PSYCHIC, TYPELESS),
+                Abilities::INSOMNIA,
+            ),
+            Items::AMPHAROSITE => mega(
+                PokemonName::AMPHAROS,
+                PokemonName::AMPHAROSMEGA,
+                (ELECTRIC, TYPELESS),
+                (ELECTRIC, DRAGON),
+                Abilities::MOLDBREAKER,
+            ),
+            Items::STEELIXITE => mega(
+                PokemonName::STEELIX,
+                PokemonName::STEELIXMEGA,
+                (STEEL, GROUND),
+                (STEEL, GROUND),
+                Abilities::SANDFORCE,
+            ),
+            Items::SCIZORITE => mega(
+                PokemonName::SCIZOR,
+                PokemonName::SCIZORMEGA,
+                (BUG, STEEL),
+                (BUG, STEEL),
+                Abilities::TECHNICIAN,
+            ),
+            Items::HERACRONITE => mega(
+                PokemonName::HERACROSS,
+                PokemonName::HERACROSSMEGA,
+                (BUG, FIGHTING),
+                (BUG, FIGHTING),
+                Abilities::SKILLLINK,
+            ),
+            Items::HOUNDOOMINITE => mega(
+                PokemonName::HOUNDOOM,
+                PokemonName::HOUNDOOMMEGA,
+                (DARK, FIRE),
+                (DARK, FIRE),
+                Abilities::SOLARPOWER,
+            ),
+            Items::TYRANITARITE => mega(
+                PokemonName::TYRANITAR,
+                PokemonName::TYRANITARMEGA,
+                (ROCK, DARK),
+                (ROCK, DARK),
+                Abilities::SANDSTREAM,
+            ),
+            Items::SCEPTILITE => mega(
+                PokemonName::SCEPTILE,
+                PokemonName::SCEPTILEMEGA,
+                (GRASS, TYPELESS),
+                (GRASS, DRAGON),
+                Abilities::LIGHTNINGROD,
+            ),
+            Items::BLAZIKENITE => mega(
+                PokemonName::BLAZIKEN,
+                PokemonName::BLAZIKENMEGA,
+                (FIRE, FIGHTING),
+                (FIRE, FIGHTING),
+                Abilities::SPEEDBOOST,
+            ),
+            Items::SWAMPERTITE => mega(
+                PokemonName::SWAMPERT,
+                PokemonName::SWAMPERTMEGA,
+                (WATER, GROUND),
+                (WATER, GROUND),
+                Abilities::SWIFTSWIM,
+            ),
+            Items::GARDEVOIRITE => mega(
+                PokemonName::GARDEVOIR,
+                PokemonName::GARDEVOIRMEGA,
+                (PSYCHIC, FAIRY),
+                (PSYCHIC, FAIRY),
+                Abilities::PIXILATE,
+            ),
+            Items::SABLENITE => mega(
+                PokemonName::SABLEYE,
+                PokemonName::SABLEYEMEGA,
+                (DARK, GHOST),
+                (DARK, GHOST),
+                Abilities::MAGICBOUNCE,
+            ),
+            Items::MAWILITE => mega(
+                PokemonName::MAWILE,
+                PokemonName::MAWILEMEGA,
+                (STEEL, FAIRY),
+                (STEEL, FAIRY),
+                Abilities::HUGEPOWER,
+            ),
+            Items::AGGRONITE => mega(
+                PokemonName::AGGRON,
+                PokemonName::AGGRONMEGA,
+                (STEEL, ROCK),
+                (STEEL, TYPELESS),
+                Abilities::FILTER,
+            ),
+            Items::MEDICHAMITE => mega(
+                PokemonName::MEDICHAM,
+                PokemonName::MEDICHAMMEGA,
+                (FIGHTING, PSYCHIC),
+                (FIGHTING, PSYCHIC),
+                Abilities::PUREPOWER,
+            ),
+            Items::MANECTITE => mega(
+                PokemonName::MANECTRIC,
+                PokemonName::MANECTRICMEGA,
+                (ELECTRIC, TYPELESS),
+                (ELECTRIC, TYPELESS),
+                Abilities::INTIMIDATE,
+            ),
+            Items::SHARPEDONITE => mega(
+                PokemonName::SHARPEDO,
+                PokemonName::SHARPEDOMEGA,
+                (WATER, DARK),
+                (WATER, DARK),
+                Abilities::STRONGJAW,
+            ),
+            Items::CAMERUPTITE => mega(
+                PokemonName::CAMERUPT,
+                PokemonName::CAMERUPTMEGA,
+                (FIRE, GROUND),
+                (FIRE, GROUND),
+                Abilities::SHEERFORCE,
+            ),
+            Items::ALTARIANITE => mega(
+                PokemonName::ALTARIA,
+                PokemonName::ALTARIAMEGA,
+                (DRAGON, FLYING),
+                (DRAGON, FAIRY),
+                Abilities::PIXILATE,
+            ),
+            Items::BANETTITE => mega(
+                PokemonName::BANETTE,
+                PokemonName::BANETTEMEGA,
+                (GHOST, TYPELESS),
+                (GHOST, TYPELESS),
+                Abilities::PRANKSTER,
+            ),
+            Items::ABSOLITE => mega(
+                PokemonName::ABSOL,
+                PokemonName::ABSOLMEGA,
+                (DARK, TYPELESS),
+                (DARK, TYPELESS),
+                Abilities::MAGICBOUNCE,
+            ),
+            Items::GLALITITE => mega(
+                PokemonName::GLALIE,
+                PokemonName::GLALIEMEGA,
+                (ICE, TYPELESS),
+                (ICE, TYPELESS),
+                Abilities::REFRIGERATE,
+            ),
+            Items::SALAMENCITE => mega(
+                PokemonName::SALAMENCE,
+                PokemonName::SALAMENCEMEGA,
+                (DRAGON, FLYING),
+                (DRAGON, FLYING),
+                Abilities::AERILATE,
+            ),
+            Items::METAGROSSITE => mega(
+                PokemonName::METAGROSS,
+                PokemonName::METAGROSSMEGA,
+                (STEEL, PSYCHIC),
+                (STEEL, PSYCHIC),
+                Abilities::TOUGHCLAWS,
+            ),
+            Items::LATIASITE => mega(
+                PokemonName::LATIAS,
+                PokemonName::LATIASMEGA,
+                (DRAGON, PSYCHIC),
+                (DRAGON, PSYCHIC),
+                Abilities::LEVITATE,
+            ),
+            Items::LATIOSITE => mega(
+                PokemonName::LATIOS,
+                PokemonName::LATIOSMEGA,
+                (DRAGON, PSYCHIC),
+                (DRAGON, PSYCHIC),
+                Abilities::LEVITATE,
+            ),
+            Items::LOPUNNITE => mega(
+                PokemonName::LOPUNNY,
+                PokemonName::LOPUNNYMEGA,
+                (NORMAL, TYPELESS),
+                (NORMAL, FIGHTING),
+                Abilities::SCRAPPY,
+            ),
+            Items::GARCHOMPITE => mega(
+                PokemonName::GARCHOMP,
+                PokemonName::GARCHOMPMEGA,
+                (DRAGON, GROUND),
+                (DRAGON, GROUND),
+                Abilities::SANDFORCE,
+            ),
+            Items::LUCARIONITE => mega(
+                PokemonName::LUCARIO,
+                PokemonName::LUCARIOMEGA,
+                (FIGHTING, STEEL),
+                (FIGHTING, STEEL),
+                Abilities::ADAPTABILITY,
+            ),
+            Items::ABOMASITE => mega(
+                PokemonName::ABOMASNOW,
+                PokemonName::ABOMASNOWMEGA,
+                (GRASS, ICE),
+                (GRASS, ICE),
+                Abilities::SNOWWARNING,
+            ),
+            Items::GALLADITE => mega(
+                PokemonName::GALLADE,
+                PokemonName::GALLADEMEGA,
+                (PSYCHIC, FIGHTING),
+                (PSYCHIC, FIGHTING),
+                Abilities::INNERFOCUS,
+            ),
+            Items::AUDINITE => mega(
+                PokemonName::AUDINO,
+                PokemonName::AUDINOMEGA,
+                (NORMAL, TYPELESS),
+                (NORMAL, FAIRY),
+                Abilities::HEALER,
+            ),
+            Items::DIANCITE => mega(
+                PokemonName::DIANCIE,
+                PokemonName::DIANCIEMEGA,
+                (ROCK, FAIRY),
+                (ROCK, FAIRY),
+                Abilities::MAGICBOUNCE,
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl PokemonName {
+    pub fn is_mega(&self) -> bool {
+        matches!(
+            self,
+            PokemonName::ABOMASNOWMEGA
+                | PokemonName::ABSOLMEGA
+                | PokemonName::AERODACTYLMEGA
+                | PokemonName::AGGRONMEGA
+                | PokemonName::ALAKAZAMMEGA
+                | PokemonName::ALTARIAMEGA
+                | PokemonName::AMPHAROSMEGA
+                | PokemonName::AUDINOMEGA
+                | PokemonName::BANETTEMEGA
+                | PokemonName::BEEDRILLMEGA
+                | PokemonName::BLASTOISEMEGA
+                | PokemonName::BLAZIKENMEGA
+                | PokemonName::CAMERUPTMEGA
+                | PokemonName::CHARIZARDMEGAX
+                | PokemonName::CHARIZARDMEGAY
+                | PokemonName::DIANCIEMEGA
+                | PokemonName::GALLADEMEGA
+                | PokemonName::GARCHOMPMEGA
+                | PokemonName::GARDEVOIRMEGA
+                | PokemonName::GENGARMEGA
+                | PokemonName::GLALIEMEGA
+                | PokemonName::GYARADOSMEGA
+                | PokemonName::HERACROSSMEGA
+                | PokemonName::HOUNDOOMMEGA
+                | PokemonName::KANGASKHANMEGA
+                | PokemonName::LATIASMEGA
+                | PokemonName::LATIOSMEGA
+                | PokemonName::LOPUNNYMEGA
+                | PokemonName::LUCARIOMEGA
+                | PokemonName::MANECTRICMEGA
+                | PokemonName::MAWILEMEGA
+                | PokemonName::MEDICHAMMEGA
+                | PokemonName::METAGROSSMEGA
+                | PokemonName::MEWTWOMEGAX
+                | PokemonName::MEWTWOMEGAY
+                | PokemonName::PIDGEOTMEGA
+                | PokemonName::PINSIRMEGA
+                | PokemonName::RAYQUAZAMEGA
+                | PokemonName::SABLEYEMEGA
+                | PokemonName::SALAMENCEMEGA
+                | PokemonName::SCEPTILEMEGA
+                | PokemonName::SCIZORMEGA
+                | PokemonName::SHARPEDOMEGA
+                | PokemonName::SLOWBROMEGA
+                | PokemonName::STEELIXMEGA
+                | PokemonName::SWAMPERTMEGA
+                | PokemonName::TYRANITARMEGA
+                | PokemonName::VENUSAURMEGA
+        )
+    }
+}
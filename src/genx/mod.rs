@@ -3,7 +3,10 @@ pub mod base_stats;
 pub mod battle_environment;
 pub mod choice_effects;
 pub mod damage_calc;
+pub mod dynamax;
 pub mod evaluate;
 pub mod generate_instructions;
 pub mod items;
+pub mod mega_evolution;
 pub mod state;
+pub mod z_moves;
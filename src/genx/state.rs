@@ -1,12 +1,14 @@
 use super::abilities::Abilities;
 use super::choice_effects::charge_volatile_to_choice;
 use super::items::Items;
-use crate::choices::{Choices, MoveCategory};
+use super::mega_evolution::MegaEvolution;
+use crate::battle_format::{doubles_move_target, BattlePosition, DoublesMoveTarget};
+use crate::choices::{Choice, Choices, MoveCategory};
 use crate::define_enum_with_from_str;
 use crate::instruction::{
     ChangeSideConditionInstruction, ChangeStatInstruction, ChangeType,
-    ChangeVolatileStatusDurationInstruction, Instruction, RemoveVolatileStatusInstruction,
-    StateInstructions,
+    ChangeVolatileStatusDurationInstruction, DynamaxInstruction, FormeChangeInstruction,
+    Instruction, RemoveVolatileStatusInstruction, StateInstructions,
 };
 use crate::pokemon::PokemonName;
 use crate::state::{
@@ -14,11 +16,11 @@ use crate::state::{
     PokemonSideCondition, PokemonStatus, PokemonType, Side, SideReference, State,
 };
 use core::panic;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
-fn common_pkmn_stat_calc(stat: u16, ev: u16, level: u16) -> u16 {
-    // 31 IV always used
-    ((2 * stat + 31 + (ev / 4)) * level) / 100
+fn common_pkmn_stat_calc(stat: u16, iv: u16, ev: u16, level: u16) -> u16 {
+    ((2 * stat + iv + (ev / 4)) * level) / 100
 }
 
 fn multiply_boost(boost_num: i8, stat_value: i16) -> i16 {
@@ -40,9 +42,12 @@ fn multiply_boost(boost_num: i8, stat_value: i16) -> i16 {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash, Serialize, Deserialize)]
 pub enum MoveChoice {
     MoveTera(PokemonMoveIndex),
+    MoveMega(PokemonMoveIndex),
+    MoveDynamax(PokemonMoveIndex),
+    MoveZ(PokemonMoveIndex),
     Move(PokemonMoveIndex),
     Switch(PokemonIndex),
     None,
@@ -54,6 +59,15 @@ impl MoveChoice {
             MoveChoice::MoveTera(index) => {
                 format!("{}-tera", side.get_active_immutable().moves[&index].id).to_lowercase()
             }
+            MoveChoice::MoveMega(index) => {
+                format!("{}-mega", side.get_active_immutable().moves[&index].id).to_lowercase()
+            }
+            MoveChoice::MoveDynamax(index) => {
+                format!("{}-dynamax", side.get_active_immutable().moves[&index].id).to_lowercase()
+            }
+            MoveChoice::MoveZ(index) => {
+                format!("{}-zmove", side.get_active_immutable().moves[&index].id).to_lowercase()
+            }
             MoveChoice::Move(index) => {
                 format!("{}", side.get_active_immutable().moves[&index].id).to_lowercase()
             }
@@ -76,8 +90,8 @@ impl MoveChoice {
             }
         }
 
-        // check if s endswith `-tera`
-        // if it does, find the move with the name and return MoveChoice::MoveTera
+        // check if s endswith `-tera`, `-mega`, `-dynamax` or `-zmove`
+        // if it does, find the move with the name and return MoveChoice::MoveTera, MoveMega, MoveDynamax or MoveZ
         // if it doesn't, find the move with the name and return MoveChoice::Move
         let mut move_iter = side.get_active_immutable().moves.into_iter();
         let mut move_name = s;
@@ -88,6 +102,27 @@ impl MoveChoice {
                     return Some(MoveChoice::MoveTera(move_iter.pokemon_move_index));
                 }
             }
+        } else if move_name.ends_with("-mega") {
+            move_name = move_name[..move_name.len() - 5].to_string();
+            while let Some(mv) = move_iter.next() {
+                if format!("{:?}", mv.id).to_lowercase() == move_name {
+                    return Some(MoveChoice::MoveMega(move_iter.pokemon_move_index));
+                }
+            }
+        } else if move_name.ends_with("-dynamax") {
+            move_name = move_name[..move_name.len() - 8].to_string();
+            while let Some(mv) = move_iter.next() {
+                if format!("{:?}", mv.id).to_lowercase() == move_name {
+                    return Some(MoveChoice::MoveDynamax(move_iter.pokemon_move_index));
+                }
+            }
+        } else if move_name.ends_with("-zmove") {
+            move_name = move_name[..move_name.len() - 6].to_string();
+            while let Some(mv) = move_iter.next() {
+                if format!("{:?}", mv.id).to_lowercase() == move_name {
+                    return Some(MoveChoice::MoveZ(move_iter.pokemon_move_index));
+                }
+            }
         } else {
             while let Some(mv) = move_iter.next() {
                 if format!("{:?}", mv.id).to_lowercase() == move_name {
@@ -100,9 +135,39 @@ impl MoveChoice {
     }
 }
 
+/// A doubles decision for one active position
+/// `target` is only set for moves that hit a single chosen pokemon, or the ally
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub struct DoublesMoveChoice {
+    pub move_choice: MoveChoice,
+    pub target: Option<BattlePosition>,
+}
+
+impl DoublesMoveChoice {
+    pub fn new(move_choice: MoveChoice, target: Option<BattlePosition>) -> DoublesMoveChoice {
+        DoublesMoveChoice {
+            move_choice,
+            target,
+        }
+    }
+    pub fn to_string(&self, side: &Side, position: u8) -> String {
+        let move_string = if position == 0 {
+            self.move_choice.to_string(side)
+        } else {
+            let mut side = side.clone();
+            side.swap_active_position();
+            self.move_choice.to_string(&side)
+        };
+        match self.target {
+            Some(target) => format!("{}@{}", move_string, target.serialize()),
+            None => move_string,
+        }
+    }
+}
+
 define_enum_with_from_str! {
     #[repr(u8)]
-    #[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+    #[derive(PartialEq, Eq, Hash, Debug, Copy, Clone, Serialize, Deserialize)]
     PokemonVolatileStatus {
         NONE,
         AQUARING,
@@ -120,6 +185,7 @@ define_enum_with_from_str! {
         DIG,
         DISABLE,
         DIVE,
+        DYNAMAX,
         ELECTRIFY,
         ELECTROSHOT,
         EMBARGO,
@@ -214,7 +280,7 @@ define_enum_with_from_str! {
 
 define_enum_with_from_str! {
     #[repr(u8)]
-    #[derive(Debug, PartialEq, Copy, Clone, Eq, Hash, PartialOrd, Ord)]
+    #[derive(Debug, PartialEq, Copy, Clone, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
     Weather {
         NONE,
         SUN,
@@ -229,7 +295,7 @@ define_enum_with_from_str! {
 
 define_enum_with_from_str! {
     #[repr(u8)]
-    #[derive(Debug, PartialEq, Copy, Clone, Eq, Hash, PartialOrd, Ord)]
+    #[derive(Debug, PartialEq, Copy, Clone, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
     Terrain {
         NONE,
         ELECTRICTERRAIN,
@@ -289,21 +355,27 @@ impl Pokemon {
         }
     }
     pub fn calculate_stats_from_base_stats(&self) -> (i16, i16, i16, i16, i16, i16) {
-        let base_stats = self.id.base_stats();
+        self.calculate_stats_for_forme(&self.id)
+    }
+    pub fn calculate_stats_for_forme(&self, forme: &PokemonName) -> (i16, i16, i16, i16, i16, i16) {
+        let base_stats = forme.base_stats();
+        let level = self.level as u16;
+        let stat = |base: u16, iv: u8, ev: u8| {
+            (common_pkmn_stat_calc(base, iv as u16, ev as u16, level) + 5) as i16
+        };
         (
-            (common_pkmn_stat_calc(base_stats.0 as u16, self.evs.0 as u16, self.level as u16)
-                + self.level as u16
+            (common_pkmn_stat_calc(
+                base_stats.0 as u16,
+                self.ivs.0 as u16,
+                self.evs.0 as u16,
+                level,
+            ) + level
                 + 10) as i16,
-            (common_pkmn_stat_calc(base_stats.1 as u16, self.evs.1 as u16, self.level as u16) + 5)
-                as i16,
-            (common_pkmn_stat_calc(base_stats.2 as u16, self.evs.2 as u16, self.level as u16) + 5)
-                as i16,
-            (common_pkmn_stat_calc(base_stats.3 as u16, self.evs.3 as u16, self.level as u16) + 5)
-                as i16,
-            (common_pkmn_stat_calc(base_stats.4 as u16, self.evs.4 as u16, self.level as u16) + 5)
-                as i16,
-            (common_pkmn_stat_calc(base_stats.5 as u16, self.evs.5 as u16, self.level as u16) + 5)
-                as i16,
+            stat(base_stats.1 as u16, self.ivs.1, self.evs.1),
+            stat(base_stats.2 as u16, self.ivs.2, self.evs.2),
+            stat(base_stats.3 as u16, self.ivs.3, self.evs.3),
+            stat(base_stats.4 as u16, self.ivs.4, self.evs.4),
+            stat(base_stats.5 as u16, self.ivs.5, self.evs.5),
         )
     }
     pub fn add_available_moves(
@@ -313,6 +385,9 @@ impl Pokemon {
         encored: bool,
         taunted: bool,
         can_tera: bool,
+        can_mega_evolve: bool,
+        can_dynamax: bool,
+        can_use_z_move: bool,
     ) {
         let mut iter = self.moves.into_iter();
         while let Some(p) = iter.next() {
@@ -343,6 +418,15 @@ impl Pokemon {
                 if can_tera {
                     vec.push(MoveChoice::MoveTera(iter.pokemon_move_index));
                 }
+                if can_mega_evolve {
+                    vec.push(MoveChoice::MoveMega(iter.pokemon_move_index));
+                }
+                if can_dynamax {
+                    vec.push(MoveChoice::MoveDynamax(iter.pokemon_move_index));
+                }
+                if can_use_z_move && self.z_move(&p.choice).is_some() {
+                    vec.push(MoveChoice::MoveZ(iter.pokemon_move_index));
+                }
             }
         }
     }
@@ -356,6 +440,72 @@ impl Pokemon {
         }
     }
 
+    /*
+    The Z-Move a move becomes with the held Z-crystal, if any
+    A status move keeps its id and gets a Z-Power effect instead
+    */
+    pub fn z_move(&self, choice: &Choice) -> Option<Choices> {
+        if let Some(signature) = self.item.signature_z_move() {
+            if choice.move_id == signature.base_move && signature.pokemon.contains(&self.id) {
+                return Some(signature.z_move);
+            }
+        }
+        match self.item.z_crystal_type() {
+            Some(z_crystal_type) if z_crystal_type == choice.move_type => {
+                if choice.category == MoveCategory::Status {
+                    if choice.move_id.has_unsupported_z_power_effect() {
+                        None
+                    } else {
+                        Some(choice.move_id)
+                    }
+                } else {
+                    Some(choice.move_type.z_move())
+                }
+            }
+            _ => None,
+        }
+    }
+
+    // the mega evolution of the held mega stone, if the stone belongs to this pokemon
+    pub fn held_mega_evolution(&self) -> Option<MegaEvolution> {
+        self.item
+            .mega_evolution()
+            .filter(|mega| self.id == mega.base_forme || self.id == mega.mega_forme)
+    }
+
+    pub fn can_mega_evolve(&self) -> bool {
+        match self.held_mega_evolution() {
+            // reverting sets the types back to the base forme's types
+            Some(mega) => self.id == mega.base_forme && self.base_types == mega.base_types,
+            None => false,
+        }
+    }
+
+    // the forme the pokemon is currently in decides which way this goes, so it is its own inverse
+    // stats change by the difference between the two formes so that reverting is exact
+    pub fn toggle_mega_evolution(&mut self, ability_change: i16) {
+        let mega = self
+            .held_mega_evolution()
+            .unwrap_or_else(|| panic!("{} cannot mega evolve with {}", self.id, self.item));
+        let base_stats = self.calculate_stats_for_forme(&mega.base_forme);
+        let mega_stats = self.calculate_stats_for_forme(&mega.mega_forme);
+        let (direction, forme, types) = if self.id == mega.base_forme {
+            (1, mega.mega_forme, mega.mega_types)
+        } else {
+            (-1, mega.base_forme, mega.base_types)
+        };
+        self.id = forme;
+        self.types = types;
+        self.base_types = types;
+        self.ability = Abilities::from(self.ability as i16 + direction * ability_change);
+        self.base_ability = Abilities::from(self.base_ability as i16 + direction * ability_change);
+        self.attack += direction * (mega_stats.1 - base_stats.1);
+        self.defense += direction * (mega_stats.2 - base_stats.2);
+        self.special_attack += direction * (mega_stats.3 - base_stats.3);
+        self.special_defense += direction * (mega_stats.4 - base_stats.4);
+        self.speed += direction * (mega_stats.5 - base_stats.5);
+    }
+
     #[cfg(feature = "terastallization")]
     pub fn has_type(&self, pkmn_type: &PokemonType) -> bool {
         if self.terastallized {
@@ -428,7 +578,7 @@ impl Pokemon {
                 self.id == PokemonName::OGERPONWELLSPRING
                     || self.id == PokemonName::OGERPONWELLSPRINGTERA
             }
-            _ => false,
+            _ => self.held_mega_evolution().is_some() || self.item.is_z_crystal(),
         }
     }
 
@@ -478,12 +628,21 @@ impl Pokemon {
             }
             PokemonVolatileStatus::SUBSTITUTE => self.hp > self.maxhp / 4,
             PokemonVolatileStatus::FLINCH => {
-                if !first_move || [Abilities::INNERFOCUS].contains(&self.ability) {
+                if !first_move
+                    || [Abilities::INNERFOCUS].contains(&self.ability)
+                    || active_volatiles.contains(&PokemonVolatileStatus::DYNAMAX)
+                {
                     return false;
                 }
                 true
             }
             PokemonVolatileStatus::PROTECT => first_move,
+            // a dynamaxed pokemon cannot be flinched, encored or tormented
+            PokemonVolatileStatus::TORMENT | PokemonVolatileStatus::ENCORE
+                if active_volatiles.contains(&PokemonVolatileStatus::DYNAMAX) =>
+            {
+                false
+            }
             PokemonVolatileStatus::TAUNT
             | PokemonVolatileStatus::TORMENT
             | PokemonVolatileStatus::ENCORE
@@ -666,6 +825,74 @@ impl Side {
         true
     }
 
+    #[cfg(not(any(feature = "gen6", feature = "gen7")))]
+    pub fn can_mega_evolve(&self) -> bool {
+        false
+    }
+
+    // only one pokemon per side can mega evolve and a mega forme stays after switching out
+    #[cfg(any(feature = "gen6", feature = "gen7"))]
+    pub fn can_mega_evolve(&self) -> bool {
+        for p in self.pokemon.into_iter() {
+            if p.id.is_mega() {
+                return false;
+            }
+        }
+        self.get_active_immutable().can_mega_evolve()
+    }
+
+    #[cfg(not(feature = "gen8"))]
+    pub fn can_dynamax(&self) -> bool {
+        false
+    }
+
+    // only one pokemon per side can dynamax in a battle
+    #[cfg(feature = "gen8")]
+    pub fn can_dynamax(&self) -> bool {
+        !self.dynamax_used && self.get_active_immutable().id.can_dynamax()
+    }
+
+    #[cfg(not(feature = "gen7"))]
+    pub fn can_use_z_move(&self) -> bool {
+        false
+    }
+
+    // only one Z-Move can be used per side in a battle
+    #[cfg(feature = "gen7")]
+    pub fn can_use_z_move(&self) -> bool {
+        !self.z_move_used
+    }
+
+    // The DYNAMAX volatile itself is removed by the caller
+    // hp is halved rounding up, and a G-Max forme goes back to its base forme
+    pub fn end_dynamax(&mut self, side_ref: &SideReference, instructions: &mut Vec<Instruction>) {
+        if self.volatile_status_durations.dynamax != 0 {
+            instructions.push(Instruction::ChangeVolatileStatusDuration(
+                ChangeVolatileStatusDurationInstruction {
+                    side_ref: *side_ref,
+                    volatile_status: PokemonVolatileStatus::DYNAMAX,
+                    amount: -self.volatile_status_durations.dynamax,
+                },
+            ));
+            self.volatile_status_durations.dynamax = 0;
+        }
+        let active = self.get_active();
+        let hp_change = (active.hp + 1) / 2 - active.hp;
+        instructions.push(Instruction::EndDynamax(DynamaxInstruction {
+            side_ref: *side_ref,
+            hp_change,
+        }));
+        active.maxhp /= 2;
+        active.hp += hp_change;
+        if let Some(base_forme) = active.id.gigantamax_base_forme() {
+            instructions.push(Instruction::FormeChange(FormeChangeInstruction {
+                side_ref: *side_ref,
+                name_change: base_forme as i16 - active.id as i16,
+            }));
+            active.id = base_forme;
+        }
+    }
+
     pub fn add_switches(&self, vec: &mut Vec<MoveChoice>) {
         let mut iter = self.pokemon.into_iter();
         while let Some(p) = iter.next() {
@@ -716,6 +943,37 @@ impl Side {
     }
 }
 
+fn combine_doubles_position_options(
+    position_one_options: &[DoublesMoveChoice],
+    position_two_options: &[DoublesMoveChoice],
+) -> Vec<[DoublesMoveChoice; 2]> {
+    let mut combined = Vec::with_capacity(position_one_options.len() * position_two_options.len());
+    for first in position_one_options {
+        for second in position_two_options {
+            match (first.move_choice, second.move_choice) {
+                // both positions cannot switch to the same pokemon
+                (MoveChoice::Switch(a), MoveChoice::Switch(b)) if a == b => continue,
+                // only one pokemon per side can terastallize
+                (MoveChoice::MoveTera(_), MoveChoice::MoveTera(_)) => continue,
+                // or mega evolve
+                (MoveChoice::MoveMega(_), MoveChoice::MoveMega(_)) => continue,
+                // or dynamax
+                (MoveChoice::MoveDynamax(_), MoveChoice::MoveDynamax(_)) => continue,
+                // or use a Z-Move
+                (MoveChoice::MoveZ(_), MoveChoice::MoveZ(_)) => continue,
+                _ => combined.push([*first, *second]),
+            }
+        }
+    }
+    // leaving both positions empty only makes sense if there is nothing else to do
+    if combined.len() > 1 {
+        combined.retain(|c| {
+            !(c[0].move_choice == MoveChoice::None && c[1].move_choice == MoveChoice::None)
+        });
+    }
+    combined
+}
+
 impl State {
     pub fn root_get_all_options(&self) -> (Vec<MoveChoice>, Vec<MoveChoice>) {
         if self.team_preview {
@@ -741,7 +999,11 @@ impl State {
 
         if self.side_one.force_trapped {
             s1_options.retain(|x| match x {
-                MoveChoice::Move(_) | MoveChoice::MoveTera(_) => true,
+                MoveChoice::Move(_)
+                | MoveChoice::MoveTera(_)
+                | MoveChoice::MoveMega(_)
+                | MoveChoice::MoveDynamax(_)
+                | MoveChoice::MoveZ(_) => true,
                 MoveChoice::Switch(_) => false,
                 MoveChoice::None => true,
             });
@@ -762,12 +1024,19 @@ impl State {
                 encored,
                 taunted,
                 self.side_one.can_use_tera(),
+                self.side_one.can_mega_evolve(),
+                self.side_one.can_dynamax(),
+                self.side_one.can_use_z_move(),
             );
         }
 
         if self.side_two.force_trapped {
             s2_options.retain(|x| match x {
-                MoveChoice::Move(_) | MoveChoice::MoveTera(_) => true,
+                MoveChoice::Move(_)
+                | MoveChoice::MoveTera(_)
+                | MoveChoice::MoveMega(_)
+                | MoveChoice::MoveDynamax(_)
+                | MoveChoice::MoveZ(_) => true,
                 MoveChoice::Switch(_) => false,
                 MoveChoice::None => true,
             });
@@ -788,6 +1057,9 @@ impl State {
                 encored,
                 taunted,
                 self.side_two.can_use_tera(),
+                self.side_two.can_mega_evolve(),
+                self.side_two.can_dynamax(),
+                self.side_two.can_use_z_move(),
             );
         }
 
@@ -876,6 +1148,9 @@ impl State {
                 encored,
                 taunted,
                 self.side_one.can_use_tera(),
+                self.side_one.can_mega_evolve(),
+                self.side_one.can_dynamax(),
+                self.side_one.can_use_z_move(),
             );
             if !self.side_one.trapped(side_two_active) {
                 self.side_one.add_switches(&mut side_one_options);
@@ -905,6 +1180,9 @@ impl State {
                 encored,
                 taunted,
                 self.side_two.can_use_tera(),
+                self.side_two.can_mega_evolve(),
+                self.side_two.can_dynamax(),
+                self.side_two.can_use_z_move(),
             );
             if !self.side_two.trapped(side_one_active) {
                 self.side_two.add_switches(&mut side_two_options);
@@ -921,6 +1199,156 @@ impl State {
         (side_one_options, side_two_options)
     }
 
+    /// Options for a doubles state, one `[DoublesMoveChoice; 2]` per combination of
+    /// decisions for the two active positions of a side
+    pub fn get_all_doubles_options(
+        &self,
+    ) -> (Vec<[DoublesMoveChoice; 2]>, Vec<[DoublesMoveChoice; 2]>) {
+        let replacing = self.doubles_position_needs_replacement(&SideReference::SideOne, 0)
+            || self.doubles_position_needs_replacement(&SideReference::SideOne, 1)
+            || self.doubles_position_needs_replacement(&SideReference::SideTwo, 0)
+            || self.doubles_position_needs_replacement(&SideReference::SideTwo, 1);
+
+        let mut options = [SideReference::SideOne, SideReference::SideTwo].map(|side_ref| {
+            let position_options = [0, 1].map(|position| {
+                if replacing {
+                    self.get_doubles_replacement_options(&side_ref, position)
+                } else {
+                    self.get_doubles_position_options(&side_ref, position)
+                }
+            });
+            combine_doubles_position_options(&position_options[0], &position_options[1])
+        });
+
+        (
+            std::mem::take(&mut options[0]),
+            std::mem::take(&mut options[1]),
+        )
+    }
+
+    fn doubles_position_needs_replacement(&self, side_ref: &SideReference, position: u8) -> bool {
+        let side = self.get_side_immutable(side_ref);
+        let force_switch = match (position, &side.ally_position) {
+            (0, _) => side.force_switch,
+            (_, Some(ally_position)) => ally_position.force_switch,
+            (_, None) => false,
+        };
+        (force_switch || side.get_position_active_immutable(position).hp <= 0)
+            && !side.get_alive_pkmn_indices().is_empty()
+    }
+
+    fn get_doubles_replacement_options(
+        &self,
+        side_ref: &SideReference,
+        position: u8,
+    ) -> Vec<DoublesMoveChoice> {
+        let mut options = vec![];
+        if self.doubles_position_needs_replacement(side_ref, position) {
+            for pkmn_index in self.get_side_immutable(side_ref).get_alive_pkmn_indices() {
+                options.push(DoublesMoveChoice::new(MoveChoice::Switch(pkmn_index), None));
+            }
+        }
+        // a position may also stay empty when there are fewer reserves than empty positions
+        if options.len() < 2 {
+            options.push(DoublesMoveChoice::new(MoveChoice::None, None));
+        }
+        options
+    }
+
+    fn get_doubles_position_options(
+        &self,
+        side_ref: &SideReference,
+        position: u8,
+    ) -> Vec<DoublesMoveChoice> {
+        let mut side = self.get_side_immutable(side_ref).clone();
+        let opposing_side = self.get_side_immutable(&side_ref.get_other_side());
+        if position == 1 {
+            side.swap_active_position();
+        }
+        let active = side.get_active_immutable();
+        if active.hp <= 0 {
+            return vec![DoublesMoveChoice::new(MoveChoice::None, None)];
+        }
+
+        let mut move_choices = Vec::with_capacity(9);
+        if side
+            .volatile_statuses
+            .contains(&PokemonVolatileStatus::MUSTRECHARGE)
+        {
+            move_choices.push(MoveChoice::None);
+        } else if let Some(mv_index) = side.active_is_charging_move() {
+            move_choices.push(MoveChoice::Move(mv_index));
+        } else {
+            let encored = side
+                .volatile_statuses
+                .contains(&PokemonVolatileStatus::ENCORE);
+            let taunted = side.volatile_statuses.contains(&PokemonVolatileStatus::TAUNT);
+            active.add_available_moves(
+                &mut move_choices,
+                &side.last_used_move,
+                encored,
+                taunted,
+                side.can_use_tera(),
+                side.can_mega_evolve(),
+                side.can_dynamax(),
+                side.can_use_z_move(),
+            );
+            let trapped = side.trapped(opposing_side.get_position_active_immutable(0))
+                || side.trapped(opposing_side.get_position_active_immutable(1));
+            if !trapped {
+                for pkmn_index in side.get_alive_pkmn_indices() {
+                    move_choices.push(MoveChoice::Switch(pkmn_index));
+                }
+            }
+        }
+
+        let opposing_side_ref = side_ref.get_other_side();
+        let alive_foes: Vec<BattlePosition> = [0, 1]
+            .iter()
+            .copied()
+            .filter(|p| opposing_side.get_position_active_immutable(*p).hp > 0)
+            .map(|p| BattlePosition::new(opposing_side_ref, p))
+            .collect();
+        let ally = BattlePosition::new(*side_ref, 1 - position);
+        let ally_alive = self
+            .get_side_immutable(side_ref)
+            .get_position_active_immutable(ally.position)
+            .hp
+            > 0;
+
+        let mut options = Vec::with_capacity(move_choices.len() * 2);
+        for move_choice in move_choices {
+            let move_index = match move_choice {
+                MoveChoice::Move(move_index)
+                | MoveChoice::MoveTera(move_index)
+                | MoveChoice::MoveMega(move_index)
+                | MoveChoice::MoveDynamax(move_index)
+                | MoveChoice::MoveZ(move_index) => move_index,
+                MoveChoice::Switch(_) | MoveChoice::None => {
+                    options.push(DoublesMoveChoice::new(move_choice, None));
+                    continue;
+                }
+            };
+            match doubles_move_target(&active.moves[&move_index].choice) {
+                DoublesMoveTarget::AdjacentFoe => {
+                    for foe in alive_foes.iter() {
+                        options.push(DoublesMoveChoice::new(move_choice, Some(*foe)));
+                    }
+                }
+                DoublesMoveTarget::AdjacentAlly => {
+                    if ally_alive {
+                        options.push(DoublesMoveChoice::new(move_choice, Some(ally)));
+                    }
+                }
+                _ => options.push(DoublesMoveChoice::new(move_choice, None)),
+            }
+        }
+        if options.is_empty() {
+            options.push(DoublesMoveChoice::new(MoveChoice::None, None));
+        }
+        options
+    }
+
     pub fn reset_toxic_count(
         &mut self,
         side_ref: &SideReference,
@@ -980,6 +1408,10 @@ impl State {
                     side.volatile_status_durations.lockedmove = 0;
                     false
                 }
+                PokemonVolatileStatus::DYNAMAX => {
+                    side.end_dynamax(side_ref, instructions);
+                    false
+                }
                 PokemonVolatileStatus::YAWN => {
                     instructions.push(Instruction::ChangeVolatileStatusDuration(
                         ChangeVolatileStatusDurationInstruction {
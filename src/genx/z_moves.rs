@@ -0,0 +1,449 @@
+use super::items::Items;
+use crate::choices::{Choices, StatBoosts};
+use crate::pokemon::PokemonName;
+use crate::state::PokemonType;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct SignatureZMove {
+    pub pokemon: &'static [PokemonName],
+    pub base_move: Choices,
+    pub z_move: Choices,
+}
+
+fn signature(
+    pokemon: &'static [PokemonName],
+    base_move: Choices,
+    z_move: Choices,
+) -> Option<SignatureZMove> {
+    Some(SignatureZMove {
+        pokemon,
+        base_move,
+        z_move,
+    })
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ZPowerEffect {
+    Boost(StatBoosts),
+    ClearNegativeBoosts,
+    Heal,
+    FollowMe,
+}
+
+fn boost(boosts: StatBoosts) -> Option<ZPowerEffect> {
+    Some(ZPowerEffect::Boost(boosts))
+}
+
+impl Items {
+    // The type of move a Z-crystal turns into a Z-Move
+    pub fn z_crystal_type(&self) -> Option<PokemonType> {
+        match self {
+            Items::BUGINIUMZ => Some(PokemonType::BUG),
+            Items::DARKINIUMZ => Some(PokemonType::DARK),
+            Items::DRAGONIUMZ => Some(PokemonType::DRAGON),
+            Items::ELECTRIUMZ => Some(PokemonType::ELECTRIC),
+            Items::FAIRIUMZ => Some(PokemonType::FAIRY),
+            Items::FIGHTINIUMZ => Some(PokemonType::FIGHTING),
+            Items::FIRIUMZ => Some(PokemonType::FIRE),
+            Items::FLYINIUMZ => Some(PokemonType::FLYING),
+            Items::GHOSTIUMZ => Some(PokemonType::GHOST),
+            Items::GRASSIUMZ => Some(PokemonType::GRASS),
+            Items::GROUNDIUMZ => Some(PokemonType::GROUND),
+            Items::ICIUMZ => Some(PokemonType::ICE),
+            Items::NORMALIUMZ => Some(PokemonType::NORMAL),
+            Items::POISONIUMZ => Some(PokemonType::POISON),
+            Items::PSYCHIUMZ => Some(PokemonType::PSYCHIC),
+            Items::ROCKIUMZ => Some(PokemonType::ROCK),
+            Items::STEELIUMZ => Some(PokemonType::STEEL),
+            Items::WATERIUMZ => Some(PokemonType::WATER),
+            _ => None,
+        }
+    }
+
+    /*
+    The signature Z-Move unlocked by a Z-crystal
+    Incomplete: Ultranecrozium Z needs Ultra Burst and Pikashunium Z needs the cap Pikachu formes,
+    neither of which exist in the engine, so they are not here
+    */
+    pub fn signature_z_move(&self) -> Option<SignatureZMove> {
+        match self {
+            Items::ALORAICHIUMZ => signature(
+                &[PokemonName::RAICHUALOLA],
+                Choices::THUNDERBOLT,
+                Choices::STOKEDSPARKSURFER,
+            ),
+            Items::DECIDIUMZ => signature(
+                &[PokemonName::DECIDUEYE],
+                Choices::SPIRITSHACKLE,
+                Choices::SINISTERARROWRAID,
+            ),
+            Items::EEVIUMZ => signature(
+                &[PokemonName::EEVEE],
+                Choices::LASTRESORT,
+                Choices::EXTREMEEVOBOOST,
+            ),
+            Items::INCINIUMZ => signature(
+                &[PokemonName::INCINEROAR],
+                Choices::DARKESTLARIAT,
+                Choices::MALICIOUSMOONSAULT,
+            ),
+            Items::KOMMONIUMZ => signature(
+                &[PokemonName::KOMMOO],
+                Choices::CLANGINGSCALES,
+                Choices::CLANGOROUSSOULBLAZE,
+            ),
+            Items::LUNALIUMZ => signature(
+                &[PokemonName::LUNALA, PokemonName::NECROZMADAWNWINGS],
+                Choices::MOONGEISTBEAM,
+                Choices::MENACINGMOONRAZEMAELSTROM,
+            ),
+            Items::LYCANIUMZ => signature(
+                &[
+                    PokemonName::LYCANROC,
+                    PokemonName::LYCANROCMIDNIGHT,
+                    PokemonName::LYCANROCDUSK,
+                ],
+                Choices::STONEEDGE,
+                Choices::SPLINTEREDSTORMSHARDS,
+            ),
+            Items::MARSHADIUMZ => signature(
+                &[PokemonName::MARSHADOW],
+                Choices::SPECTRALTHIEF,
+                Choices::SOULSTEALING7STARSTRIKE,
+            ),
+            Items::MEWNIUMZ => signature(
+                &[PokemonName::MEW],
+                Choices::PSYCHIC,
+                Choices::GENESISSUPERNOVA,
+            ),
+            Items::MIMIKIUMZ => signature(
+                &[PokemonName::MIMIKYU, PokemonName::MIMIKYUBUSTED],
+                Choices::PLAYROUGH,
+                Choices::LETSSNUGGLEFOREVER,
+            ),
+            Items::PIKANIUMZ => signature(
+                &[PokemonName::PIKACHU],
+                Choices::VOLTTACKLE,
+                Choices::CATASTROPIKA,
+            ),
+            Items::PRIMARIUMZ => signature(
+                &[PokemonName::PRIMARINA],
+                Choices::SPARKLINGARIA,
+                Choices::OCEANICOPERETTA,
+            ),
+            Items::SNORLIUMZ => signature(
+                &[PokemonName::SNORLAX],
+                Choices::GIGAIMPACT,
+                Choices::PULVERIZINGPANCAKE,
+            ),
+            Items::SOLGANIUMZ => signature(
+                &[PokemonName::SOLGALEO, PokemonName::NECROZMADUSKMANE],
+                Choices::SUNSTEELSTRIKE,
+                Choices::SEARINGSUNRAZESMASH,
+            ),
+            Items::TAPUNIUMZ => signature(
+                &[
+                    PokemonName::TAPUKOKO,
+                    PokemonName::TAPULELE,
+                    PokemonName::TAPUBULU,
+                    PokemonName::TAPUFINI,
+                ],
+                Choices::NATURESMADNESS,
+                Choices::GUARDIANOFALOLA,
+            ),
+            _ => None,
+        }
+    }
+
+    pub fn is_z_crystal(&self) -> bool {
+        self.z_crystal_type().is_some() || self.signature_z_move().is_some()
+    }
+}
+
+impl PokemonType {
+    // The Z-Move that a damaging move of this type becomes
+    pub fn z_move(&self) -> Choices {
+        match self {
+            PokemonType::BUG => Choices::SAVAGESPINOUT,
+            PokemonType::DARK => Choices::BLACKHOLEECLIPSE,
+            PokemonType::DRAGON => Choices::DEVASTATINGDRAKE,
+            PokemonType::ELECTRIC => Choices::GIGAVOLTHAVOC,
+            PokemonType::FAIRY => Choices::TWINKLETACKLE,
+            PokemonType::FIGHTING => Choices::ALLOUTPUMMELING,
+            PokemonType::FIRE => Choices::INFERNOOVERDRIVE,
+            PokemonType::FLYING => Choices::SUPERSONICSKYSTRIKE,
+            PokemonType::GHOST => Choices::NEVERENDINGNIGHTMARE,
+            PokemonType::GRASS => Choices::BLOOMDOOM,
+            PokemonType::GROUND => Choices::TECTONICRAGE,
+            PokemonType::ICE => Choices::SUBZEROSLAMMER,
+            PokemonType::POISON => Choices::ACIDDOWNPOUR,
+            PokemonType::PSYCHIC => Choices::SHATTEREDPSYCHE,
+            PokemonType::ROCK => Choices::CONTINENTALCRUSH,
+            PokemonType::STEEL => Choices::CORKSCREWCRASH,
+            PokemonType::WATER => Choices::HYDROVORTEX,
+            _ => Choices::BREAKNECKBLITZ,
+        }
+    }
+}
+
+impl Choices {
+    pub fn is_z_move(&self) -> bool {
+        matches!(
+            self,
+            Choices::ACIDDOWNPOUR
+                | Choices::ALLOUTPUMMELING
+                | Choices::BLACKHOLEECLIPSE
+                | Choices::BLOOMDOOM
+                | Choices::BREAKNECKBLITZ
+                | Choices::CONTINENTALCRUSH
+                | Choices::CORKSCREWCRASH
+                | Choices::DEVASTATINGDRAKE
+                | Choices::GIGAVOLTHAVOC
+                | Choices::HYDROVORTEX
+                | Choices::INFERNOOVERDRIVE
+                | Choices::NEVERENDINGNIGHTMARE
+                | Choices::SAVAGESPINOUT
+                | Choices::SHATTEREDPSYCHE
+                | Choices::SUBZEROSLAMMER
+                | Choices::SUPERSONICSKYSTRIKE
+                | Choices::TECTONICRAGE
+                | Choices::TWINKLETACKLE
+                | Choices::CATASTROPIKA
+                | Choices::CLANGOROUSSOULBLAZE
+                | Choices::EXTREMEEVOBOOST
+                | Choices::GENESISSUPERNOVA
+                | Choices::GUARDIANOFALOLA
+                | Choices::LETSSNUGGLEFOREVER
+                | Choices::MALICIOUSMOONSAULT
+                | Choices::MENACINGMOONRAZEMAELSTROM
+                | Choices::OCEANICOPERETTA
+                | Choices::PULVERIZINGPANCAKE
+                | Choices::SEARINGSUNRAZESMASH
+                | Choices::SINISTERARROWRAID
+                | Choices::SOULSTEALING7STARSTRIKE
+                | Choices::SPLINTEREDSTORMSHARDS
+                | Choices::STOKEDSPARKSURFER
+        )
+    }
+
+    /*
+    Status moves whose Z-Power effect heals the pokemon switching in or raises the critical hit
+    ratio. Neither effect is implemented so these are not offered as Z-Moves
+    */
+    pub fn has_unsupported_z_power_effect(&self) -> bool {
+        matches!(
+            self,
+            Choices::ACUPRESSURE
+                | Choices::FORESIGHT
+                | Choices::HEARTSWAP
+                | Choices::MEMENTO
+                | Choices::PARTINGSHOT
+                | Choices::SLEEPTALK
+                | Choices::TAILWIND
+        )
+    }
+
+    /*
+    The effect a status move gets on top of its regular effect when it is used as a Z-Move
+    Incomplete: only the common status moves are here, the rest are used as Z-Moves without a
+    Z-Power effect
+    */
+    pub fn z_power_effect(&self) -> Option<ZPowerEffect> {
+        match self {
+            Choices::BULKUP
+            | Choices::HONECLAWS
+            | Choices::HOWL
+            | Choices::LEER
+            | Choices::MEDITATE
+            | Choices::SCREECH
+            | Choices::SHARPEN
+            | Choices::TAILWHIP
+            | Choices::TAUNT
+            | Choices::WILLOWISP
+            | Choices::WORKUP => boost(StatBoosts {
+                attack: 1,
+                ..Default::default()
+            }),
+            Choices::SPLASH => boost(StatBoosts {
+                attack: 3,
+                ..Default::default()
+            }),
+            Choices::CHARM
+            | Choices::FEATHERDANCE
+            | Choices::GRASSYTERRAIN
+            | Choices::GROWL
+            | Choices::PAINSPLIT
+            | Choices::REFLECT
+            | Choices::ROAR
+            | Choices::SPIKES
+            | Choices::STEALTHROCK
+            | Choices::TOXIC
+            | Choices::TOXICSPIKES => boost(StatBoosts {
+                defense: 1,
+                ..Default::default()
+            }),
+            Choices::CONFUSERAY
+            | Choices::FAKETEARS
+            | Choices::GROWTH
+            | Choices::METALSOUND
+            | Choices::PSYCHICTERRAIN => boost(StatBoosts {
+                special_attack: 1,
+                ..Default::default()
+            }),
+            Choices::CHARGE
+            | Choices::GLARE
+            | Choices::LIGHTSCREEN
+            | Choices::MEANLOOK
+            | Choices::MISTYTERRAIN
+            | Choices::STUNSPORE
+            | Choices::THUNDERWAVE
+            | Choices::WHIRLWIND
+            | Choices::WISH => boost(StatBoosts {
+                special_defense: 1,
+                ..Default::default()
+            }),
+            Choices::AURORAVEIL
+            | Choices::ELECTRICTERRAIN
+            | Choices::ENCORE
+            | Choices::HAIL
+            | Choices::HYPNOSIS
+            | Choices::RAINDANCE
+            | Choices::SANDSTORM
+            | Choices::SCARYFACE
+            | Choices::SLEEPPOWDER
+            | Choices::STICKYWEB
+            | Choices::STRINGSHOT
+            | Choices::SUNNYDAY
+            | Choices::YAWN => boost(StatBoosts {
+                speed: 1,
+                ..Default::default()
+            }),
+            Choices::SWITCHEROO | Choices::TRICK => boost(StatBoosts {
+                speed: 2,
+                ..Default::default()
+            }),
+            Choices::DEFOG | Choices::TRICKROOM => boost(StatBoosts {
+                accuracy: 1,
+                ..Default::default()
+            }),
+            Choices::CELEBRATE | Choices::CONVERSION | Choices::GEOMANCY | Choices::HAPPYHOUR => {
+                boost(StatBoosts {
+                    attack: 1,
+                    defense: 1,
+                    special_attack: 1,
+                    special_defense: 1,
+                    speed: 1,
+                    accuracy: 0,
+                })
+            }
+            Choices::AGILITY
+            | Choices::AMNESIA
+            | Choices::AUTOTOMIZE
+            | Choices::BANEFULBUNKER
+            | Choices::BATONPASS
+            | Choices::CALMMIND
+            | Choices::COIL
+            | Choices::COTTONGUARD
+            | Choices::DETECT
+            | Choices::DRAGONDANCE
+            | Choices::HEALORDER
+            | Choices::IRONDEFENSE
+            | Choices::KINGSSHIELD
+            | Choices::LEECHSEED
+            | Choices::MILKDRINK
+            | Choices::MOONLIGHT
+            | Choices::MORNINGSUN
+            | Choices::NASTYPLOT
+            | Choices::PERISHSONG
+            | Choices::PROTECT
+            | Choices::QUIVERDANCE
+            | Choices::RECOVER
+            | Choices::REST
+            | Choices::ROCKPOLISH
+            | Choices::ROOST
+            | Choices::SHELLSMASH
+            | Choices::SHOREUP
+            | Choices::SLACKOFF
+            | Choices::SOFTBOILED
+            | Choices::SPIKYSHIELD
+            | Choices::SUBSTITUTE
+            | Choices::SWAGGER
+            | Choices::SWORDSDANCE
+            | Choices::SYNTHESIS => Some(ZPowerEffect::ClearNegativeBoosts),
+            Choices::AROMATHERAPY | Choices::BELLYDRUM | Choices::HAZE | Choices::HEALBELL => {
+                Some(ZPowerEffect::Heal)
+            }
+            Choices::DESTINYBOND | Choices::GRUDGE => Some(ZPowerEffect::FollowMe),
+            _ => None,
+        }
+    }
+}
+
+/*
+The base power of a Z-Move is decided by the base power of the move it replaces
+Moves with a variable or fixed damage amount have their own Z-Move base power
+*/
+pub fn z_move_base_power(move_id: &Choices, base_power: f32) -> f32 {
+    match move_id {
+        Choices::COUNTER
+        | Choices::DRAGONRAGE
+        | Choices::METALBURST
+        | Choices::MIRRORCOAT
+        | Choices::NATURESMADNESS
+        | Choices::NIGHTSHADE
+        | Choices::PSYWAVE
+        | Choices::SEISMICTOSS
+        | Choices::SONICBOOM
+        | Choices::SUPERFANG => 100.0,
+        Choices::MEGADRAIN => 120.0,
+        Choices::BONERUSH
+        | Choices::BULLETSEED
+        | Choices::COREENFORCER
+        | Choices::DOUBLEHIT
+        | Choices::ICICLESPEAR
+        | Choices::PINMISSILE
+        | Choices::ROCKBLAST
+        | Choices::TAILSLAP => 140.0,
+        Choices::ELECTROBALL
+        | Choices::ENDEAVOR
+        | Choices::FLAIL
+        | Choices::FRUSTRATION
+        | Choices::GRASSKNOT
+        | Choices::GYROBALL
+        | Choices::HEATCRASH
+        | Choices::HEAVYSLAM
+        | Choices::HEX
+        | Choices::LOWKICK
+        | Choices::PUNISHMENT
+        | Choices::RETURN
+        | Choices::REVERSAL
+        | Choices::WEATHERBALL => 160.0,
+        Choices::FLYINGPRESS => 170.0,
+        Choices::FINALGAMBIT | Choices::GEARGRIND => 180.0,
+        Choices::CRUSHGRIP | Choices::WRINGOUT => 190.0,
+        Choices::VCREATE => 220.0,
+        _ => {
+            if base_power >= 140.0 {
+                200.0
+            } else if base_power >= 130.0 {
+                195.0
+            } else if base_power >= 120.0 {
+                190.0
+            } else if base_power >= 110.0 {
+                185.0
+            } else if base_power >= 100.0 {
+                180.0
+            } else if base_power >= 90.0 {
+                175.0
+            } else if base_power >= 80.0 {
+                160.0
+            } else if base_power >= 70.0 {
+                140.0
+            } else if base_power >= 60.0 {
+                120.0
+            } else {
+                100.0
+            }
+        }
+    }
+}
@@ -5,10 +5,11 @@ use crate::state::{
     LastUsedMove, PokemonBoostableStat, PokemonIndex, PokemonMoveIndex, PokemonSideCondition,
     PokemonStatus, PokemonType, SideReference,
 };
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fmt::Formatter;
 
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
 pub struct StateInstructions {
     pub percentage: f32,
     pub instruction_list: Vec<Instruction>,
@@ -40,7 +41,7 @@ impl StateInstructions {
 }
 
 // https://stackoverflow.com/questions/50686411/whats-the-usual-way-to-create-a-vector-of-different-structs
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
 pub enum Instruction {
     Switch(SwitchInstruction),
     ApplyVolatileStatus(ApplyVolatileStatusInstruction),
@@ -89,6 +90,11 @@ pub enum Instruction {
     ToggleSideOneForceSwitch,
     ToggleSideTwoForceSwitch,
     ToggleTerastallized(ToggleTerastallizedInstruction),
+    MegaEvolve(MegaEvolveInstruction),
+    Dynamax(DynamaxInstruction),
+    EndDynamax(DynamaxInstruction),
+    ToggleZMoveUsed(ToggleZMoveUsedInstruction),
+    SwapActivePosition(SwapActivePositionInstruction),
 }
 
 impl fmt::Debug for Instruction {
@@ -282,6 +288,21 @@ impl fmt::Debug for Instruction {
             Instruction::ToggleTerastallized(s) => {
                 write!(f, "ToggleTerastallized {:?}", s.side_ref)
             }
+            Instruction::MegaEvolve(s) => {
+                write!(f, "MegaEvolve {:?} {}", s.side_ref, s.ability_change)
+            }
+            Instruction::Dynamax(s) => {
+                write!(f, "Dynamax {:?} {}", s.side_ref, s.hp_change)
+            }
+            Instruction::EndDynamax(s) => {
+                write!(f, "EndDynamax {:?} {}", s.side_ref, s.hp_change)
+            }
+            Instruction::ToggleZMoveUsed(s) => {
+                write!(f, "ToggleZMoveUsed {:?}", s.side_ref)
+            }
+            Instruction::SwapActivePosition(s) => {
+                write!(f, "SwapActivePosition {:?}", s.side_ref)
+            }
             Instruction::SetLastUsedMove(s) => {
                 write!(
                     f,
@@ -336,54 +357,54 @@ impl fmt::Debug for Instruction {
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ChangeDamageDealtDamageInstruction {
     pub side_ref: SideReference,
     pub damage_change: i16,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ChangeDamageDealtMoveCategoryInstruction {
     pub side_ref: SideReference,
     pub move_category: MoveCategory,
     pub previous_move_category: MoveCategory,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ToggleDamageDealtHitSubstituteInstruction {
     pub side_ref: SideReference,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct DecrementPPInstruction {
     pub side_ref: SideReference,
     pub move_index: PokemonMoveIndex,
     pub amount: i8,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct SetLastUsedMoveInstruction {
     pub side_ref: SideReference,
     pub last_used_move: LastUsedMove,
     pub previous_last_used_move: LastUsedMove,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ToggleBatonPassingInstruction {
     pub side_ref: SideReference,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ToggleShedTailingInstruction {
     pub side_ref: SideReference,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct DecrementRestTurnsInstruction {
     pub side_ref: SideReference,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct SetSleepTurnsInstruction {
     pub side_ref: SideReference,
     pub pokemon_index: PokemonIndex,
@@ -391,79 +412,79 @@ pub struct SetSleepTurnsInstruction {
     pub previous_turns: i8,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct SetSecondMoveSwitchOutMoveInstruction {
     pub new_choice: Choices,
     pub previous_choice: Choices,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ChangeWishInstruction {
     pub side_ref: SideReference,
     pub wish_amount_change: i16,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct DecrementWishInstruction {
     pub side_ref: SideReference,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct SetFutureSightInstruction {
     pub side_ref: SideReference,
     pub pokemon_index: PokemonIndex,
     pub previous_pokemon_index: PokemonIndex,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct DecrementFutureSightInstruction {
     pub side_ref: SideReference,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct EnableMoveInstruction {
     pub side_ref: SideReference,
     pub move_index: PokemonMoveIndex,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct DisableMoveInstruction {
     pub side_ref: SideReference,
     pub move_index: PokemonMoveIndex,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ChangeItemInstruction {
     pub side_ref: SideReference,
     pub current_item: Items,
     pub new_item: Items,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ChangeStatInstruction {
     pub side_ref: SideReference,
     pub amount: i16,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct HealInstruction {
     pub side_ref: SideReference,
     pub heal_amount: i16,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct DamageInstruction {
     pub side_ref: SideReference,
     pub damage_amount: i16,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ChangeSubsituteHealthInstruction {
     pub side_ref: SideReference,
     pub health_change: i16,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct FormeChangeInstruction {
     pub side_ref: SideReference,
 
@@ -472,7 +493,7 @@ pub struct FormeChangeInstruction {
     pub name_change: i16,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct SwitchInstruction {
     pub side_ref: SideReference,
     pub previous_index: PokemonIndex,
@@ -481,7 +502,7 @@ pub struct SwitchInstruction {
 
 // pokemon_index is present because even reserve pokemon can have their status
 // changed (i.e. healbell)
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ChangeStatusInstruction {
     pub side_ref: SideReference,
     pub pokemon_index: PokemonIndex,
@@ -489,40 +510,40 @@ pub struct ChangeStatusInstruction {
     pub new_status: PokemonStatus,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ApplyVolatileStatusInstruction {
     pub side_ref: SideReference,
     pub volatile_status: PokemonVolatileStatus,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct RemoveVolatileStatusInstruction {
     pub side_ref: SideReference,
     pub volatile_status: PokemonVolatileStatus,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct BoostInstruction {
     pub side_ref: SideReference,
     pub stat: PokemonBoostableStat,
     pub amount: i8,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ChangeSideConditionInstruction {
     pub side_ref: SideReference,
     pub side_condition: PokemonSideCondition,
     pub amount: i8,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ChangeVolatileStatusDurationInstruction {
     pub side_ref: SideReference,
     pub volatile_status: PokemonVolatileStatus,
     pub amount: i8,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ChangeWeather {
     pub new_weather: Weather,
     pub new_weather_turns_remaining: i8,
@@ -530,7 +551,7 @@ pub struct ChangeWeather {
     pub previous_weather_turns_remaining: i8,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ChangeTerrain {
     pub new_terrain: Terrain,
     pub new_terrain_turns_remaining: i8,
@@ -538,26 +559,61 @@ pub struct ChangeTerrain {
     pub previous_terrain_turns_remaining: i8,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ToggleTrickRoomInstruction {
     pub currently_active: bool,
     pub new_trickroom_turns_remaining: i8,
     pub previous_trickroom_turns_remaining: i8,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ToggleTerastallizedInstruction {
     pub side_ref: SideReference,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+// Changes the active pokemon between its base forme and the mega forme unlocked by its held
+// mega stone, along with its stats, types and ability
+// This is its own inverse
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct MegaEvolveInstruction {
+    pub side_ref: SideReference,
+
+    // Abilities enum is an i16
+    // This is the amount the ability has changed by when mega evolving
+    pub ability_change: i16,
+}
+
+// Dynamax doubles the active pokemon's maxhp and uses up the side's dynamax
+// EndDynamax halves it again
+// Both change the hp by `hp_change` so that rounding the hp can be reversed exactly
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct DynamaxInstruction {
+    pub side_ref: SideReference,
+    pub hp_change: i16,
+}
+
+// A side can only use one Z-Move per battle
+// This is its own inverse
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ToggleZMoveUsedInstruction {
+    pub side_ref: SideReference,
+}
+
+// Doubles only: exchanges which of a side's two active positions is in front
+// This is its own inverse
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SwapActivePositionInstruction {
+    pub side_ref: SideReference,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ChangeType {
     pub side_ref: SideReference,
     pub new_types: (PokemonType, PokemonType),
     pub old_types: (PokemonType, PokemonType),
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ChangeAbilityInstruction {
     pub side_ref: SideReference,
 
@@ -1,17 +1,33 @@
 use crate::choices::{Choice, Choices, MoveCategory, MOVES};
 use crate::engine::battle_environment::{
-    initialize_battle_state, BattleEnvironment, DamageMaximizer, FirstMovePlayer, 
-    MctsPlayer, Player, RandomPlayer,
+    initialize_battle_state, BattleEnvironment, DamageMaximizer, ExpectiminimaxPlayer,
+    FirstMovePlayer, IterativeDeepeningPlayer, MctsPlayer, Player, RandomPlayer,
 };
 use crate::engine::evaluate::evaluate;
+#[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+use crate::engine::evaluate::EvaluationWeights;
 use crate::engine::generate_instructions::{
     calculate_both_damage_rolls, generate_instructions_from_move_pair,
 };
 use crate::engine::state::MoveChoice;
+use crate::evaluator::{DefaultEvaluator, Evaluator};
 use crate::instruction::{Instruction, StateInstructions};
-use crate::mcts::{perform_mcts, MctsResult};
-use crate::search::{expectiminimax_search, iterative_deepen_expectiminimax, pick_safest};
-use crate::state::{PokemonIndex, SideReference, State};
+use crate::mcts::{
+    perform_parallel_mcts, MctsConfig, MctsResult, MoveSelectionPolicy, RolloutPolicy,
+    DEFAULT_EVAL_SCALE, DEFAULT_EXPLORATION_CONSTANT, DEFAULT_MAX_ITERATIONS,
+    DEFAULT_ROLLOUT_TURNS,
+};
+use crate::protocol::{run_protocol, ProtocolSession};
+use crate::replay::{Replay, ReplayViewer};
+use crate::rng::{battle_seed, random_seed, seeded_rng};
+use crate::search::{
+    expectiminimax_search, expectiminimax_search_with_table, iterative_deepen_expectiminimax,
+    iterative_deepen_expectiminimax_with_evaluator, pick_safest, solve_nash_equilibrium,
+    TranspositionTable, DEFAULT_TRANSPOSITION_TABLE_SIZE,
+};
+use crate::state::{PokemonIndex, Side, SideReference, State};
+#[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+use crate::tournament::{run_tournament, TournamentConfig, TournamentPlayer};
 use clap::Parser;
 use std::fs;
 use std::io;
@@ -27,9 +43,13 @@ struct IOData {
     last_instructions_generated: Vec<StateInstructions>,
 }
 
+const STATE_HELP: &str = "The state as a serialized string or as JSON";
+const DAMAGE_BUCKET_HELP: &str =
+    "Merge outcomes that lead to the same state or whose damage rolls are within a bucket of this many hp";
+
 #[derive(Parser)]
 struct Cli {
-    #[clap(short, long, default_value = "")]
+    #[clap(short, long, default_value = "", help = STATE_HELP)]
     state: String,
 
     #[clap(subcommand)]
@@ -44,12 +64,16 @@ enum SubCommand {
     CalculateDamage(CalculateDamage),
     GenerateInstructions(GenerateInstructions),
     Battle(Battle),
+    Replay(ViewReplay),
+    #[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+    Tournament(Tournament),
+    Protocol(Protocol),
 }
 
 
 #[derive(Parser)]
 struct Expectiminimax {
-    #[clap(short, long, required = true)]
+    #[clap(short, long, required = true, help = STATE_HELP)]
     state: String,
 
     #[clap(short, long, default_value_t = false)]
@@ -57,29 +81,114 @@ struct Expectiminimax {
 
     #[clap(short, long, default_value_t = 2)]
     depth: i8,
+
+    #[clap(long, help = "JSON file of evaluation weights to search with")]
+    weights: Option<String>,
+
+    #[clap(long, help = DAMAGE_BUCKET_HELP)]
+    damage_bucket: Option<i16>,
 }
 
 #[derive(Parser)]
 struct IterativeDeepening {
-    #[clap(short, long, required = true)]
+    #[clap(short, long, required = true, help = STATE_HELP)]
     state: String,
 
     #[clap(short, long, default_value_t = 5000)]
     time_to_search_ms: u64,
+
+    #[clap(long, help = "JSON file of evaluation weights to search with")]
+    weights: Option<String>,
+
+    #[clap(long, help = DAMAGE_BUCKET_HELP)]
+    damage_bucket: Option<i16>,
 }
 
 #[derive(Parser)]
 struct MonteCarloTreeSearch {
-    #[clap(short, long, required = true)]
+    #[clap(short, long, required = true, help = STATE_HELP)]
     state: String,
 
     #[clap(short, long, default_value_t = 5000)]
     time_to_search_ms: u64,
+
+    #[clap(short = 'j', long, default_value_t = 1)]
+    threads: usize,
+
+    #[clap(short, long, default_value_t = DEFAULT_EXPLORATION_CONSTANT)]
+    exploration_constant: f32,
+
+    #[clap(long, default_value_t = DEFAULT_EVAL_SCALE)]
+    eval_scale: f32,
+
+    #[clap(long, default_value_t = DEFAULT_MAX_ITERATIONS)]
+    max_iterations: i64,
+
+    #[clap(
+        short,
+        long,
+        default_value = "most-visits",
+        help = "How the move is chosen from the search: most-visits, best-average"
+    )]
+    move_selection: MoveSelectionPolicy,
+
+    #[clap(
+        short,
+        long,
+        default_value = "static",
+        help = "How new nodes are scored: static, random, greedy-damage"
+    )]
+    rollout_policy: RolloutPolicy,
+
+    #[clap(long, default_value_t = DEFAULT_ROLLOUT_TURNS)]
+    rollout_turns: usize,
+
+    #[clap(long, help = "JSON file of evaluation weights to search with")]
+    weights: Option<String>,
+
+    #[clap(long, help = DAMAGE_BUCKET_HELP)]
+    damage_bucket: Option<i16>,
+}
+
+impl MonteCarloTreeSearch {
+    fn config(&self) -> MctsConfig {
+        MctsConfig {
+            exploration_constant: self.exploration_constant,
+            eval_scale: self.eval_scale,
+            max_iterations: self.max_iterations,
+            max_time: std::time::Duration::from_millis(self.time_to_search_ms),
+            threads: self.threads,
+            move_selection: self.move_selection,
+            rollout_policy: self.rollout_policy.clone(),
+            rollout_turns: self.rollout_turns,
+            damage_bucket: self.damage_bucket,
+        }
+    }
+}
+
+// the built-in heuristic unless a weights file was given
+fn load_evaluator(weights: &Option<String>) -> Arc<dyn Evaluator> {
+    match weights {
+        None => Arc::new(DefaultEvaluator),
+        #[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+        Some(path) => match EvaluationWeights::from_file(path) {
+            Ok(weights) => Arc::new(weights),
+            Err(e) => {
+                println!("{}", e);
+                exit(1);
+            }
+        },
+        #[cfg(any(feature = "gen1", feature = "gen2", feature = "gen3"))]
+        Some(_) => {
+            println!("Evaluation weights are not supported for gen1, gen2 or gen3");
+            exit(1);
+        }
+    }
 }
 
 #[derive(Parser)]
 struct CalculateDamage {
-    #[clap(short, long, required = true)]
+    #[clap(short, long, required = true, help = STATE_HELP)]
     state: String,
 
     #[clap(short = 'o', long, required = true)]
@@ -94,7 +203,7 @@ struct CalculateDamage {
 
 #[derive(Parser)]
 struct GenerateInstructions {
-    #[clap(short, long, required = true)]
+    #[clap(short, long, required = true, help = STATE_HELP)]
     state: String,
 
     #[clap(short = 'o', long, required = true)]
@@ -110,7 +219,7 @@ struct Battle {
         short = 'p',
         long,
         required = true,
-        help = "Player 1 type: random, firstmove, damage, mcts"
+        help = "Player 1 type: random, firstmove, damage, mcts, expectiminimax, iterative-deepening"
     )]
     player_one: String,
     
@@ -118,7 +227,7 @@ struct Battle {
         short = 'q',
         long,
         required = true,
-        help = "Player 2 type: random, firstmove, damage, mcts"
+        help = "Player 2 type: random, firstmove, damage, mcts, expectiminimax, iterative-deepening"
     )]
     player_two: String,
     
@@ -137,7 +246,12 @@ struct Battle {
     #[clap(short = 'j', long, default_value_t = 1)]
     threads: usize,
     
-    #[clap(short = 't', long, default_value_t = 100)]
+    #[clap(
+        short = 't',
+        long,
+        default_value_t = 100,
+        help = "Search time in milliseconds for mcts and iterative-deepening players"
+    )]
     mcts_time: u64,
     
     #[clap(long)]
@@ -145,6 +259,102 @@ struct Battle {
     
     #[clap(long)]
     p2_mcts_time: Option<u64>,
+
+    #[clap(short = 'd', long, default_value_t = 2, help = "Search depth for expectiminimax players")]
+    depth: i8,
+
+    #[clap(long, default_value_t = 1, help = "Threads used by each MCTS player's search")]
+    mcts_threads: usize,
+
+    #[clap(
+        long,
+        help = "Stop each MCTS search after this many iterations instead of after --mcts-time. \
+                Only battles with iteration limited searches are replayed exactly by --seed"
+    )]
+    mcts_iterations: Option<i64>,
+
+    #[clap(
+        long,
+        help = "Seed for the teams and every random choice in the battles, \
+                a random one is used and printed if not given"
+    )]
+    seed: Option<u64>,
+
+    #[clap(
+        long,
+        help = "Write a replay of the battle to this file, with more than one run \
+                battle i is written to <replay-file>.i"
+    )]
+    replay_file: Option<String>,
+}
+
+#[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+#[derive(Parser)]
+struct Tournament {
+    #[clap(
+        short = 'p',
+        long,
+        required = true,
+        value_delimiter = ',',
+        help = "Comma separated player types to rate against each other: \
+                random, firstmove, damage, mcts, expectiminimax, iterative-deepening"
+    )]
+    players: Vec<String>,
+
+    #[clap(
+        long,
+        default_value_t = 10,
+        help = "Number of random initial states, every pair of players battles twice from each one"
+    )]
+    states: usize,
+
+    #[clap(short, long, default_value_t = 100)]
+    max_turns: u16,
+
+    #[clap(short = 'j', long, default_value_t = 1)]
+    threads: usize,
+
+    #[clap(
+        short = 't',
+        long,
+        default_value_t = 100,
+        help = "Search time in milliseconds for mcts and iterative-deepening players"
+    )]
+    mcts_time: u64,
+
+    #[clap(short = 'd', long, default_value_t = 2, help = "Search depth for expectiminimax players")]
+    depth: i8,
+
+    #[clap(long, default_value_t = 1, help = "Threads used by each MCTS player's search")]
+    mcts_threads: usize,
+
+    #[clap(
+        long,
+        help = "Stop each MCTS search after this many iterations instead of after --mcts-time"
+    )]
+    mcts_iterations: Option<i64>,
+
+    #[clap(
+        long,
+        help = "Seed for the initial states and every random choice in the battles, \
+                a random one is used and printed if not given"
+    )]
+    seed: Option<u64>,
+}
+
+#[derive(Parser)]
+struct Protocol {
+    #[clap(
+        long,
+        help = "Seed for the random choices made while searching, a random one is used if not given"
+    )]
+    seed: Option<u64>,
+}
+
+#[derive(Parser)]
+struct ViewReplay {
+    #[clap(required = true, help = "Replay file written by the battle subcommand")]
+    file: String,
 }
 
 impl Default for IOData {
@@ -187,10 +397,37 @@ fn pprint_expectiminimax_result(
         "{:<12}",
         s1_options[safest_choice.0].to_string(&state.side_one)
     );
+
+    let equilibrium = solve_nash_equilibrium(result, s1_len, s2_len);
+    println!("\nNash equilibrium (value {:.2}):", equilibrium.value);
+    println!(
+        "side one: {}",
+        format_mixed_strategy(&equilibrium.s1, s1_options, &state.side_one)
+    );
+    println!(
+        "side two: {}",
+        format_mixed_strategy(&equilibrium.s2, s2_options, &state.side_two)
+    );
+}
+
+// the moves that are played with a non-zero probability, most likely first
+fn format_mixed_strategy(probabilities: &[f32], options: &[MoveChoice], side: &Side) -> String {
+    let mut played: Vec<(f32, &MoveChoice)> = probabilities
+        .iter()
+        .copied()
+        .zip(options.iter())
+        .filter(|(p, _)| *p > 0.001)
+        .collect();
+    played.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+    played
+        .iter()
+        .map(|(p, m)| format!("{} {:.3}", m.to_string(side), p))
+        .collect::<Vec<String>>()
+        .join(", ")
 }
 
 
-fn pprint_mcts_result(state: &State, result: MctsResult) {
+fn pprint_mcts_result(state: &State, result: MctsResult, move_selection: MoveSelectionPolicy) {
     println!("{}", state.pprint());
     println!("\nTotal Iterations: {}\n", result.iteration_count);
     println!("Maximum Depth: {}", result.max_depth);
@@ -225,6 +462,32 @@ fn pprint_mcts_result(state: &State, result: MctsResult) {
             (x.visits as f32 / result.iteration_count as f32) * 100.0
         );
     }
+    if let Some(selected) = move_selection.select(&result.s1) {
+        println!(
+            "\nSelected ({:?}): {}",
+            move_selection,
+            selected.to_string(&state.side_one)
+        );
+    }
+}
+
+// a state given as JSON is told apart from a serialized one by the opening brace
+fn state_from_json_or_serialized(state_string: &str) -> Result<State, String> {
+    if state_string.trim_start().starts_with('{') {
+        State::from_json(state_string)
+    } else {
+        State::try_deserialize(state_string).map_err(|e| format!("Invalid state: {}", e))
+    }
+}
+
+fn parse_state(state_string: &str) -> State {
+    match state_from_json_or_serialized(state_string) {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    }
 }
 
 fn pprint_state_instruction_vector(instructions: &Vec<StateInstructions>) {
@@ -265,6 +528,19 @@ fn print_subcommand_result(
     println!("matrix: {}", joined);
     println!("choice: {}", move_choice.to_string(&state.side_one));
     println!("evaluation: {}", safest.1);
+
+    let equilibrium =
+        solve_nash_equilibrium(result, side_one_options.len(), side_two_options.len());
+    let join_probabilities = |probabilities: &[f32]| {
+        probabilities
+            .iter()
+            .map(|x| format!("{:.3}", x))
+            .collect::<Vec<String>>()
+            .join(",")
+    };
+    println!("side one strategy: {}", join_probabilities(&equilibrium.s1));
+    println!("side two strategy: {}", join_probabilities(&equilibrium.s2));
+    println!("nash value: {}", equilibrium.value);
 }
 
 
@@ -273,7 +549,7 @@ pub fn main() {
     let mut io_data = IOData::default();
 
     if args.state != "" {
-        let state = State::deserialize(args.state.as_str());
+        let state = parse_state(&args.state);
         io_data.state = state;
     }
 
@@ -288,43 +564,55 @@ pub fn main() {
         }
         Some(subcmd) => match subcmd {
             SubCommand::Expectiminimax(expectiminimax) => {
-                state = State::deserialize(expectiminimax.state.as_str());
+                state = parse_state(&expectiminimax.state);
                 (side_one_options, side_two_options) = state.root_get_all_options();
-                result = expectiminimax_search(
+                let evaluator = load_evaluator(&expectiminimax.weights);
+                state.rehash();
+                result = expectiminimax_search_with_table(
                     &mut state,
                     expectiminimax.depth,
                     side_one_options.clone(),
                     side_two_options.clone(),
                     expectiminimax.ab_prune,
                     &Arc::new(Mutex::new(true)),
+                    &mut TranspositionTable::new(DEFAULT_TRANSPOSITION_TABLE_SIZE),
+                    evaluator.as_ref(),
+                    expectiminimax.damage_bucket,
                 );
                 print_subcommand_result(&result, &side_one_options, &side_two_options, &state);
             }
             SubCommand::IterativeDeepening(iterative_deepending) => {
-                state = State::deserialize(iterative_deepending.state.as_str());
+                state = parse_state(&iterative_deepending.state);
                 (side_one_options, side_two_options) = state.root_get_all_options();
-                (side_one_options, side_two_options, result, _) = iterative_deepen_expectiminimax(
-                    &mut state,
-                    side_one_options.clone(),
-                    side_two_options.clone(),
-                    std::time::Duration::from_millis(iterative_deepending.time_to_search_ms),
-                );
+                (side_one_options, side_two_options, result, _) =
+                    iterative_deepen_expectiminimax_with_evaluator(
+                        &mut state,
+                        side_one_options.clone(),
+                        side_two_options.clone(),
+                        std::time::Duration::from_millis(iterative_deepending.time_to_search_ms),
+                        load_evaluator(&iterative_deepending.weights),
+                        iterative_deepending.damage_bucket,
+                    );
                 print_subcommand_result(&result, &side_one_options, &side_two_options, &state);
             }
             SubCommand::MonteCarloTreeSearch(mcts) => {
-                state = State::deserialize(mcts.state.as_str());
+                state = parse_state(&mcts.state);
                 (side_one_options, side_two_options) = state.root_get_all_options();
-                let result = perform_mcts(
-                    &mut state,
+                let config = mcts.config();
+                let evaluator = load_evaluator(&mcts.weights);
+                let result = perform_parallel_mcts(
+                    &state,
                     side_one_options.clone(),
                     side_two_options.clone(),
-                    std::time::Duration::from_millis(mcts.time_to_search_ms),
+                    &config,
+                    evaluator.as_ref(),
+                    &mut seeded_rng(random_seed()),
                 );
-                pprint_mcts_result(&state, result);
+                pprint_mcts_result(&state, result, config.move_selection);
             }
 
             SubCommand::CalculateDamage(calculate_damage) => {
-                state = State::deserialize(calculate_damage.state.as_str());
+                state = parse_state(&calculate_damage.state);
                 let mut s1_choice = MOVES
                     .get(&Choices::from_str(calculate_damage.side_one_move.as_str()).unwrap())
                     .unwrap()
@@ -343,7 +631,7 @@ pub fn main() {
                 calculate_damage_io(&state, s1_choice, s2_choice, s1_moves_first);
             }
             SubCommand::GenerateInstructions(generate_instructions) => {
-                state = State::deserialize(generate_instructions.state.as_str());
+                state = parse_state(&generate_instructions.state);
                 let (s1_movechoice, s2_movechoice);
                 match MoveChoice::from_string(
                     generate_instructions.side_one_move.as_str(),
@@ -382,6 +670,25 @@ pub fn main() {
             SubCommand::Battle(battle) => {
                 run_battle_command(battle);
             }
+            #[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+            SubCommand::Tournament(tournament) => {
+                run_tournament_command(tournament);
+            }
+            SubCommand::Protocol(protocol) => {
+                let mut session = ProtocolSession::new(protocol.seed.unwrap_or_else(random_seed));
+                let stdin = io::stdin();
+                if let Err(e) = run_protocol(stdin.lock(), &mut io::stdout(), &mut session) {
+                    eprintln!("{}", e);
+                    exit(1);
+                }
+            }
+            SubCommand::Replay(view_replay) => match Replay::from_file(&view_replay.file) {
+                Ok(replay) => replay_loop(ReplayViewer::new(replay)),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    exit(1);
+                }
+            },
         },
     }
 
@@ -433,16 +740,17 @@ fn command_loop(mut io_data: IOData) {
 
         match command {
             "state" | "s" => {
-                let state_string;
-                match args.next() {
-                    Some(s) => {
-                        state_string = s;
-                        let state = State::deserialize(state_string);
-                        io_data.state = state;
-                        println!("state initialized");
-                    }
-                    None => {
-                        println!("Expected state string");
+                // JSON states may contain spaces, so the rest of the line is the state
+                let state_string = args.collect::<Vec<&str>>().join(" ");
+                if state_string.is_empty() {
+                    println!("Expected state string");
+                } else {
+                    match state_from_json_or_serialized(&state_string) {
+                        Ok(state) => {
+                            io_data.state = state;
+                            println!("state initialized");
+                        }
+                        Err(e) => println!("{}", e),
                     }
                 }
                 println!("{:?}", io_data.state);
@@ -450,6 +758,9 @@ fn command_loop(mut io_data: IOData) {
             "serialize" | "ser" => {
                 println!("{}", io_data.state.serialize());
             }
+            "json" => {
+                println!("{}", io_data.state.to_json());
+            }
             "matchup" | "m" => {
                 println!("{}", io_data.state.pprint());
             }
@@ -579,22 +890,33 @@ fn command_loop(mut io_data: IOData) {
             "monte-carlo-tree-search" | "mcts" => match args.next() {
                 Some(s) => {
                     let max_time_ms = s.parse::<u64>().unwrap();
+                    let threads = match args.next() {
+                        Some(t) => t.parse::<usize>().unwrap(),
+                        None => 1,
+                    };
                     let (side_one_options, side_two_options) = io_data.state.root_get_all_options();
 
                     let start_time = std::time::Instant::now();
-                    let result = perform_mcts(
-                        &mut io_data.state,
+                    let config = MctsConfig {
+                        max_time: std::time::Duration::from_millis(max_time_ms),
+                        threads,
+                        ..Default::default()
+                    };
+                    let result = perform_parallel_mcts(
+                        &io_data.state,
                         side_one_options.clone(),
                         side_two_options.clone(),
-                        std::time::Duration::from_millis(max_time_ms),
+                        &config,
+                        &DefaultEvaluator,
+                        &mut seeded_rng(random_seed()),
                     );
                     let elapsed = start_time.elapsed();
-                    pprint_mcts_result(&io_data.state, result);
+                    pprint_mcts_result(&io_data.state, result, config.move_selection);
 
                     println!("\nTook: {:?}", elapsed);
                 }
                 None => {
-                    println!("Usage: monte-carlo-tree-search <timeout_ms>");
+                    println!("Usage: monte-carlo-tree-search <timeout_ms> <threads=1>");
                     continue;
                 }
             },
@@ -679,6 +1001,195 @@ fn command_loop(mut io_data: IOData) {
     }
 }
 
+fn print_replay_position(viewer: &ReplayViewer) {
+    match viewer.current_turn() {
+        Some(turn) => {
+            println!(
+                "Turn {} ({}/{})\nSide 1: {}\nSide 2: {}\nInstructions: {:?}",
+                turn.turn_number,
+                viewer.position,
+                viewer.len(),
+                turn.side_one_choice,
+                turn.side_two_choice,
+                turn.instructions
+            );
+        }
+        None => println!("Start of battle (0/{})", viewer.len()),
+    }
+    println!("{}", viewer.state.pprint());
+}
+
+fn replay_loop(mut viewer: ReplayViewer) {
+    println!(
+        "Replay of {} vs {}, seed {}",
+        viewer.replay.header.player_one, viewer.replay.header.player_two, viewer.replay.header.seed
+    );
+    match &viewer.replay.end {
+        Some(end) => println!(
+            "Winner: {}, after {} turns",
+            match end.winner {
+                Some(SideReference::SideOne) => viewer.replay.header.player_one.as_str(),
+                Some(SideReference::SideTwo) => viewer.replay.header.player_two.as_str(),
+                None => "Draw",
+            },
+            end.turn_count
+        ),
+        None => println!("The replay ends before the battle is over"),
+    }
+    print_replay_position(&viewer);
+
+    loop {
+        print!("> ");
+        let _ = io::stdout().flush();
+
+        let mut input = String::new();
+        match io::stdin().read_line(&mut input) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(error) => {
+                println!("Error reading input: {}", error);
+                continue;
+            }
+        }
+        let mut parts = input.split_whitespace();
+        let command = parts.next().unwrap_or("");
+        let count = parts.next().map(|c| c.parse::<usize>());
+
+        match (command, count) {
+            ("next" | "n", None) => {
+                viewer.step_forward();
+            }
+            ("next" | "n", Some(Ok(count))) => viewer.go_to(viewer.position + count),
+            ("previous" | "prev" | "p", None) => {
+                viewer.step_backward();
+            }
+            ("previous" | "prev" | "p", Some(Ok(count))) => {
+                viewer.go_to(viewer.position.saturating_sub(count))
+            }
+            ("goto" | "g", Some(Ok(turn_number))) => {
+                match viewer
+                    .replay
+                    .turns
+                    .iter()
+                    .position(|turn| turn.turn_number == turn_number)
+                {
+                    Some(index) => viewer.go_to(index + 1),
+                    None => {
+                        println!("No turn {} in this replay", turn_number);
+                        continue;
+                    }
+                }
+            }
+            ("start", None) => viewer.go_to(0),
+            ("end", None) => viewer.go_to(viewer.len()),
+            ("serialize" | "ser", None) => {
+                println!("{}", viewer.state.serialize());
+                continue;
+            }
+            ("", _) => continue,
+            ("exit" | "quit" | "q", _) => break,
+            _ => {
+                println!(
+                    "Usage: next [count] | prev [count] | goto <turn> | start | end | serialize | exit"
+                );
+                continue;
+            }
+        }
+        print_replay_position(&viewer);
+    }
+}
+
+fn mcts_player_config(search_time: u64, mcts_iterations: Option<i64>, mcts_threads: usize) -> MctsConfig {
+    match mcts_iterations {
+        Some(max_iterations) => MctsConfig {
+            max_time: std::time::Duration::MAX,
+            max_iterations,
+            threads: mcts_threads,
+            ..Default::default()
+        },
+        None => MctsConfig {
+            max_time: std::time::Duration::from_millis(search_time),
+            threads: mcts_threads,
+            ..Default::default()
+        },
+    }
+}
+
+fn create_player(
+    player_type: &str,
+    name: String,
+    mcts_config: MctsConfig,
+    search_time: u64,
+    depth: i8,
+) -> Result<Box<dyn Player>, String> {
+    match player_type {
+        "random" => Ok(Box::new(RandomPlayer::new(name))),
+        "firstmove" => Ok(Box::new(FirstMovePlayer::new(name))),
+        "damage" => Ok(Box::new(DamageMaximizer::new(name))),
+        "mcts" => Ok(Box::new(MctsPlayer::new(name, mcts_config))),
+        "expectiminimax" => Ok(Box::new(ExpectiminimaxPlayer::new(name, depth))),
+        "iterative-deepening" => Ok(Box::new(IterativeDeepeningPlayer::new(
+            name,
+            std::time::Duration::from_millis(search_time),
+        ))),
+        _ => Err(format!("Unknown player type: {}", player_type)),
+    }
+}
+
+#[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+fn run_tournament_command(tournament: Tournament) {
+    use std::time::Instant;
+
+    let mcts_config = mcts_player_config(tournament.mcts_time, tournament.mcts_iterations, tournament.mcts_threads);
+    let mut players = vec![];
+    for (player_index, player_type) in tournament.players.iter().enumerate() {
+        if let Err(e) = create_player(player_type, player_type.clone(), mcts_config.clone(), tournament.mcts_time, tournament.depth) {
+            eprintln!("{}", e);
+            exit(1);
+        }
+        // the same player type can be entered more than once, e.g. to check the spread of ratings
+        let name = if tournament.players[..player_index].contains(player_type) {
+            format!("{}-{}", player_type, player_index)
+        } else {
+            player_type.clone()
+        };
+        let player_type = player_type.clone();
+        let player_name = name.clone();
+        let mcts_config = mcts_config.clone();
+        let (search_time, depth) = (tournament.mcts_time, tournament.depth);
+        players.push(TournamentPlayer::new(&name, move || {
+            create_player(&player_type, player_name.clone(), mcts_config.clone(), search_time, depth).unwrap()
+        }));
+    }
+    if players.len() < 2 {
+        eprintln!("A tournament needs at least two players");
+        exit(1);
+    }
+
+    let seed = tournament.seed.unwrap_or_else(random_seed);
+    println!("Seed: {}", seed);
+
+    let data_dir = PathBuf::from("data");
+    let random_teams = fs::read_to_string(data_dir.join("random_teams.json"))
+        .expect("Failed to read teams file");
+    let pokedex = fs::read_to_string(data_dir.join("pokedex.json"))
+        .expect("Failed to read pokedex file");
+    let movedex = fs::read_to_string(data_dir.join("moves.json"))
+        .expect("Failed to read moves file");
+    let mut config = TournamentConfig::with_random_states(tournament.states, &random_teams, &pokedex, &movedex, seed);
+    config.max_turns = tournament.max_turns as usize;
+    config.threads = tournament.threads;
+
+    let game_count = players.len() * (players.len() - 1) * tournament.states;
+    println!("Running {} battles with {} threads...", game_count, tournament.threads);
+    let start_time = Instant::now();
+
+    let results = run_tournament(&players, &config);
+
+    println!("\n{}", results.report());
+    println!("Total time: {:.2}s", start_time.elapsed().as_secs_f64());
+}
+
 fn run_battle_command(battle: Battle) {
     use std::time::Instant;
     use rayon::prelude::*;
@@ -690,32 +1201,34 @@ fn run_battle_command(battle: Battle) {
     
     // Create player functions based on types
     let create_player = |player_type: &str, name: String, search_time: u64| -> Box<dyn Player> {
-        match player_type {
-            "random" => Box::new(RandomPlayer::new(name)),
-            "firstmove" => Box::new(FirstMovePlayer::new(name)),
-            "damage" => Box::new(DamageMaximizer::new(name)),
-            "mcts" => Box::new(MctsPlayer::new(name, search_time)),
-            _ => {
-                eprintln!("Unknown player type: {}. Using random player.", player_type);
-                Box::new(RandomPlayer::new(name))
-            }
-        }
+        let mcts_config = mcts_player_config(search_time, battle.mcts_iterations, battle.mcts_threads);
+        create_player(player_type, name.clone(), mcts_config, search_time, battle.depth).unwrap_or_else(|e| {
+            eprintln!("{}. Using random player.", e);
+            Box::new(RandomPlayer::new(name))
+        })
     };
     
     // Determine MCTS search times
     let p1_time = battle.p1_mcts_time.unwrap_or(battle.mcts_time);
     let p2_time = battle.p2_mcts_time.unwrap_or(battle.mcts_time);
+
+    let seed = battle.seed.unwrap_or_else(random_seed);
+    println!("Seed: {}", seed);
     
     if battle.runs == 1 {
         // Single battle - run directly
         let player1 = create_player(&battle.player_one, format!("Player 1 ({})", battle.player_one), p1_time);
         let player2 = create_player(&battle.player_two, format!("Player 2 ({})", battle.player_two), p2_time);
         
-        let mut env = BattleEnvironment::new(player1, player2, battle.max_turns as usize, battle.verbose);
+        let mut env = BattleEnvironment::new(player1, player2, battle.max_turns as usize, battle.verbose)
+            .with_seed(seed);
         
         if let Some(log_file) = &battle.log_file {
             env = env.with_log_file(log_file.clone());
         }
+        if let Some(replay_file) = &battle.replay_file {
+            env = env.with_replay_file(replay_file.clone());
+        }
         
         // Create initial state
         let data_dir = PathBuf::from("data");
@@ -725,7 +1238,7 @@ fn run_battle_command(battle: Battle) {
             .expect("Failed to read pokedex file");
         let movedex = fs::read_to_string(data_dir.join("moves.json"))
             .expect("Failed to read moves file");
-        let initial_state = initialize_battle_state(&random_teams, &pokedex, &movedex);
+        let initial_state = initialize_battle_state(&random_teams, &pokedex, &movedex, &mut seeded_rng(seed));
         
         let result = env.run_battle(initial_state);
         
@@ -746,9 +1259,11 @@ fn run_battle_command(battle: Battle) {
         println!("Running {} battles with {} threads...", battle.runs, battle.threads);
         let start_time = Instant::now();
         
-        // Pre-generate states for all battles
-        let states: Vec<State> = (0..battle.runs)
-            .map(|_| {
+        // Pre-generate states for all battles, battle `i` can be replayed on its own
+        // with `--seed` set to `seed + i`
+        let states: Vec<(State, u64)> = (0..battle.runs)
+            .map(|battle_idx| {
+                let seed = battle_seed(seed, battle_idx);
                 let data_dir = PathBuf::from("data");
                 let random_teams = fs::read_to_string(data_dir.join("random_teams.json"))
                     .expect("Failed to read teams file");
@@ -756,7 +1271,8 @@ fn run_battle_command(battle: Battle) {
                     .expect("Failed to read pokedex file");
                 let movedex = fs::read_to_string(data_dir.join("moves.json"))
                     .expect("Failed to read moves file");
-                initialize_battle_state(&random_teams, &pokedex, &movedex)
+                let state = initialize_battle_state(&random_teams, &pokedex, &movedex, &mut seeded_rng(seed));
+                (state, seed)
             })
             .collect();
         
@@ -764,14 +1280,18 @@ fn run_battle_command(battle: Battle) {
         let results: Vec<_> = states
             .into_par_iter()
             .enumerate()
-            .map(|(battle_idx, state)| {
+            .map(|(battle_idx, (state, seed))| {
                 let p1_name = format!("Player 1 ({}-{})", battle.player_one, battle_idx);
                 let p2_name = format!("Player 2 ({}-{})", battle.player_two, battle_idx);
                 
                 let player1 = create_player(&battle.player_one, p1_name, p1_time);
                 let player2 = create_player(&battle.player_two, p2_name, p2_time);
                 
-                let env = BattleEnvironment::new(player1, player2, battle.max_turns as usize, false);
+                let mut env = BattleEnvironment::new(player1, player2, battle.max_turns as usize, false)
+                    .with_seed(seed);
+                if let Some(replay_file) = &battle.replay_file {
+                    env = env.with_replay_file(format!("{}.{}", replay_file, battle_idx));
+                }
                 env.run_battle(state)
             })
             .collect();
@@ -16,13 +16,25 @@ pub mod engine;
 pub mod engine;
 pub mod selfplay;
 
+pub mod battle_format;
 pub mod choices;
+#[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+pub mod determinization;
+pub mod evaluator;
 pub mod instruction;
 pub mod io;
 pub mod mcts;
 pub mod pokemon;
+pub mod protocol;
+pub mod replay;
+pub mod rng;
 pub mod search;
+pub mod showdown_log;
+#[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+pub mod showdown_team;
 pub mod state;
+#[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+pub mod tournament;
 
 #[macro_export]
 macro_rules! assert_unique_feature {
@@ -111,7 +123,7 @@ macro_rules! define_enum_with_from_str {
                     $(
                         stringify!($variant) => Ok($name::$variant),
                     )+
-                    _ => panic!("Invalid {}: {}", stringify!($name), input.to_uppercase().as_str()),
+                    _ => Err(()),
                 }
             }
         }
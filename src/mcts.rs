@@ -1,24 +1,232 @@
-use crate::engine::evaluate::evaluate;
+#[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+use crate::engine::battle_environment::{DamageMaximizer, Player};
 use crate::engine::generate_instructions::generate_instructions_from_move_pair;
 use crate::engine::state::MoveChoice;
+use crate::evaluator::{DefaultEvaluator, Evaluator};
 use crate::instruction::StateInstructions;
-use crate::state::State;
+use crate::rng::{seeded_rng, BattleRng};
+use crate::state::{SideReference, State};
 use rand::distributions::WeightedIndex;
 use rand::prelude::*;
-use rand::thread_rng;
+use rayon::prelude::*;
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+#[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+use std::sync::Arc;
 use std::time::Duration;
 
+// Tuned so that ~200 points is very close to 1.0
+pub const DEFAULT_EVAL_SCALE: f32 = 0.0125;
+pub const DEFAULT_EXPLORATION_CONSTANT: f32 = 2.0;
+
+/*
+Cut off after 10 million iterations
+
+Under normal circumstances the bot will only run for 2.5-3.5 million iterations
+however towards the end of a battle the bot may perform tens of millions of iterations
+
+Beyond about 30 million iterations some floating point nonsense happens where
+MoveNode.total_score stops updating because f32 does not have enough precision
+
+I can push the problem farther out by using f64 but if the bot is running for 10 million iterations
+then it almost certainly sees a forced win
+*/
+pub const DEFAULT_MAX_ITERATIONS: i64 = 10_000_000;
+
+pub const DEFAULT_ROLLOUT_TURNS: usize = 10;
+
 pub fn sigmoid(x: f32) -> f32 {
-    // Tuned so that ~200 points is very close to 1.0
-    1.0 / (1.0 + (-0.0125 * x).exp())
+    scaled_sigmoid(x, DEFAULT_EVAL_SCALE)
+}
+
+pub fn scaled_sigmoid(x: f32, scale: f32) -> f32 {
+    1.0 / (1.0 + (-scale * x).exp())
+}
+
+/// How the move to play is picked from the root of a finished search
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoveSelectionPolicy {
+    MostVisits,
+    BestAverage,
+}
+
+impl FromStr for MoveSelectionPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "most-visits" | "visits" => Ok(MoveSelectionPolicy::MostVisits),
+            "best-average" | "average" => Ok(MoveSelectionPolicy::BestAverage),
+            _ => Err(format!(
+                "Invalid move selection policy: {}. Expected most-visits or best-average",
+                s
+            )),
+        }
+    }
+}
+
+impl MoveSelectionPolicy {
+    pub fn select(&self, results: &[MctsSideResult]) -> Option<MoveChoice> {
+        match self {
+            MoveSelectionPolicy::MostVisits => results.iter().max_by_key(|r| r.visits),
+            // moves that were never visited have no average to compare
+            MoveSelectionPolicy::BestAverage => results
+                .iter()
+                .filter(|r| r.visits > 0)
+                .max_by(|a, b| a.average_score().partial_cmp(&b.average_score()).unwrap()),
+        }
+        .map(|r| r.move_choice)
+    }
+}
+
+/// How a newly expanded node is scored
+///
+/// Every policy other than `Static` plays turns forward from the node, choosing both sides'
+/// moves with the policy and sampling one branch of the resulting instructions per turn,
+/// until the battle ends or `MctsConfig::rollout_turns` turns were played.
+/// The state reached is scored and the turns are reversed
+#[derive(Clone)]
+pub enum RolloutPolicy {
+    // evaluate the node itself without playing forward
+    Static,
+    // both sides choose uniformly at random
+    Random,
+    // both sides choose the way `DamageMaximizer` does
+    #[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+    GreedyDamage,
+    #[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+    Player(Arc<dyn Player>),
+}
+
+impl fmt::Debug for RolloutPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RolloutPolicy::Static => write!(f, "Static"),
+            RolloutPolicy::Random => write!(f, "Random"),
+            #[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+            RolloutPolicy::GreedyDamage => write!(f, "GreedyDamage"),
+            #[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+            RolloutPolicy::Player(player) => write!(f, "Player({})", player.name()),
+        }
+    }
+}
+
+// `RolloutPolicy::Player` can only be built in code
+impl FromStr for RolloutPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "static" => Ok(RolloutPolicy::Static),
+            "random" => Ok(RolloutPolicy::Random),
+            #[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+            "greedy-damage" | "greedy" => Ok(RolloutPolicy::GreedyDamage),
+            _ => Err(format!(
+                "Invalid rollout policy: {}. Expected static, random or greedy-damage",
+                s
+            )),
+        }
+    }
+}
+
+impl RolloutPolicy {
+    fn choose_move(
+        &self,
+        state: &State,
+        side_ref: SideReference,
+        options: &[MoveChoice],
+        rng: &mut BattleRng,
+    ) -> MoveChoice {
+        match self {
+            RolloutPolicy::Static => options[0],
+            RolloutPolicy::Random => *options.choose(rng).unwrap(),
+            #[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+            RolloutPolicy::GreedyDamage => {
+                DamageMaximizer::new(String::new()).choose_move(state, side_ref, options, rng)
+            }
+            #[cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+            RolloutPolicy::Player(player) => player.choose_move(state, side_ref, options, rng),
+        }
+    }
+
+    // plays up to `max_turns` turns on `state` and returns every instruction list that was
+    // applied, so that they can be reversed in the opposite order
+    fn play_out(
+        &self,
+        state: &mut State,
+        max_turns: usize,
+        rng: &mut BattleRng,
+    ) -> Vec<StateInstructions> {
+        let mut played = Vec::with_capacity(max_turns);
+        while played.len() < max_turns && state.battle_is_over() == 0.0 {
+            let (s1_options, s2_options) = state.get_all_options();
+            let s1_move = self.choose_move(state, SideReference::SideOne, &s1_options, rng);
+            let s2_move = self.choose_move(state, SideReference::SideTwo, &s2_options, rng);
+            let mut instructions =
+                generate_instructions_from_move_pair(state, &s1_move, &s2_move, false);
+            let dist = WeightedIndex::new(instructions.iter().map(|i| i.percentage as f64))
+                .expect("Failed to sample rollout instructions");
+            let state_instructions = instructions.swap_remove(dist.sample(rng));
+            state.apply_instructions(&state_instructions.instruction_list);
+            played.push(state_instructions);
+        }
+        played
+    }
+}
+
+/// Parameters of a monte-carlo-tree-search
+///
+/// The search stops at whichever of `max_time` and `max_iterations` is reached first.
+/// With more than one thread every thread searches its own tree for `max_time`
+/// and `max_iterations`, see `perform_parallel_mcts`
+#[derive(Debug, Clone)]
+pub struct MctsConfig {
+    // `c` in the UCB1 exploration term `sqrt(c * ln(parent visits) / visits)`
+    pub exploration_constant: f32,
+    // how quickly an evaluation relative to the root's is squashed towards 0 or 1
+    pub eval_scale: f32,
+    pub max_iterations: i64,
+    pub max_time: Duration,
+    pub threads: usize,
+    pub move_selection: MoveSelectionPolicy,
+    pub rollout_policy: RolloutPolicy,
+    pub rollout_turns: usize,
+    // outcomes that lead to the same state, or whose damage rolls are in the same bucket of this
+    // many hp, are merged into one child node. `None` keeps every outcome
+    pub damage_bucket: Option<i16>,
+}
+
+impl Default for MctsConfig {
+    fn default() -> MctsConfig {
+        MctsConfig {
+            exploration_constant: DEFAULT_EXPLORATION_CONSTANT,
+            eval_scale: DEFAULT_EVAL_SCALE,
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            max_time: Duration::from_millis(1000),
+            threads: 1,
+            move_selection: MoveSelectionPolicy::MostVisits,
+            rollout_policy: RolloutPolicy::Static,
+            rollout_turns: DEFAULT_ROLLOUT_TURNS,
+            damage_bucket: None,
+        }
+    }
+}
+
+impl MctsConfig {
+    pub fn with_max_time(max_time: Duration) -> MctsConfig {
+        MctsConfig {
+            max_time,
+            ..Default::default()
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct Node {
-    pub root: bool,
-    pub parent: *mut Node,
-    pub children: HashMap<(usize, usize), Vec<Node>>,
+    // index of the parent in `MctsTree::nodes`, `None` for the root
+    pub parent: Option<usize>,
+    pub children: HashMap<(usize, usize), Vec<usize>>,
     pub times_visited: i64,
 
     // represents the instructions & s1/s2 moves that led to this node from the parent
@@ -52,8 +260,7 @@ impl Node {
             .collect();
 
         Node {
-            root: false,
-            parent: std::ptr::null_mut(),
+            parent: None,
             instructions: StateInstructions::default(),
             times_visited: 0,
             children: HashMap::new(),
@@ -64,25 +271,15 @@ impl Node {
         }
     }
 
-    pub fn get_max_depth(&self) -> usize {
-        if self.children.is_empty() {
-            return 0;
-        }
-
-        let mut max_child_depth = 0;
-        for (_, children) in &self.children {
-            for child in children {
-                max_child_depth = max_child_depth.max(child.get_max_depth());
-            }
-        }
-        max_child_depth + 1
+    pub fn is_root(&self) -> bool {
+        self.parent.is_none()
     }
 
-    pub fn maximize_ucb_for_side(&self, side_map: &[MoveNode]) -> usize {
+    pub fn maximize_ucb_for_side(&self, side_map: &[MoveNode], exploration_constant: f32) -> usize {
         let mut choice = 0;
         let mut best_ucb1 = f32::MIN;
         for (index, node) in side_map.iter().enumerate() {
-            let this_ucb1 = node.ucb1(self.times_visited);
+            let this_ucb1 = node.ucb1(self.times_visited, exploration_constant);
             if this_ucb1 > best_ucb1 {
                 best_ucb1 = this_ucb1;
                 choice = index;
@@ -91,52 +288,148 @@ impl Node {
         choice
     }
 
-    pub unsafe fn selection(&mut self, state: &mut State) -> (*mut Node, usize, usize) {
-        let return_node = self as *mut Node;
-
-        let s1_mc_index = self.maximize_ucb_for_side(&self.s1_options);
-        let s2_mc_index = self.maximize_ucb_for_side(&self.s2_options);
-        let child_vector = self.children.get_mut(&(s1_mc_index, s2_mc_index));
-        match child_vector {
-            Some(child_vector) => {
-                let child_vec_ptr = child_vector as *mut Vec<Node>;
-                let chosen_child = self.sample_node(child_vec_ptr);
-                state.apply_instructions(&(*chosen_child).instructions.instruction_list);
-                (*chosen_child).selection(state)
+    pub fn rollout<E: Evaluator + ?Sized>(
+        &self,
+        state: &mut State,
+        root_eval: &f32,
+        config: &MctsConfig,
+        evaluator: &E,
+        rng: &mut BattleRng,
+    ) -> f32 {
+        let played = match config.rollout_policy {
+            RolloutPolicy::Static => vec![],
+            _ => config
+                .rollout_policy
+                .play_out(state, config.rollout_turns, rng),
+        };
+
+        let battle_is_over = state.battle_is_over();
+        let score = if battle_is_over == 0.0 {
+            let eval = evaluator.evaluate(state);
+            scaled_sigmoid(eval - root_eval, config.eval_scale)
+        } else if battle_is_over == -1.0 {
+            0.0
+        } else {
+            battle_is_over
+        };
+
+        for state_instructions in played.iter().rev() {
+            state.reverse_instructions(&state_instructions.instruction_list);
+        }
+        score
+    }
+}
+
+/// The search tree, stored as an arena of nodes that refer to each other by index
+///
+/// The root is always `nodes[0]`
+#[derive(Debug)]
+pub struct MctsTree {
+    pub nodes: Vec<Node>,
+}
+
+impl MctsTree {
+    pub const ROOT: usize = 0;
+
+    pub fn new(s1_options: Vec<MoveChoice>, s2_options: Vec<MoveChoice>) -> MctsTree {
+        MctsTree {
+            nodes: vec![Node::new(s1_options, s2_options)],
+        }
+    }
+
+    pub fn root(&self) -> &Node {
+        &self.nodes[MctsTree::ROOT]
+    }
+
+    // whether the root was created for exactly these options, in this order
+    pub fn root_options_match(&self, s1_options: &[MoveChoice], s2_options: &[MoveChoice]) -> bool {
+        let root = self.root();
+        root.s1_options
+            .iter()
+            .map(|m| m.move_choice)
+            .eq(s1_options.iter().copied())
+            && root
+                .s2_options
+                .iter()
+                .map(|m| m.move_choice)
+                .eq(s2_options.iter().copied())
+    }
+
+    pub fn get_max_depth(&self) -> usize {
+        let mut max_depth = 0;
+        let mut stack = vec![(MctsTree::ROOT, 0)];
+        while let Some((node_index, depth)) = stack.pop() {
+            max_depth = max_depth.max(depth);
+            for children in self.nodes[node_index].children.values() {
+                stack.extend(children.iter().map(|c| (*c, depth + 1)));
             }
-            None => (return_node, s1_mc_index, s2_mc_index),
         }
+        max_depth
     }
 
-    unsafe fn sample_node(&self, move_vector: *mut Vec<Node>) -> *mut Node {
-        let mut rng = thread_rng();
-        let weights: Vec<f64> = (*move_vector)
+    fn sample_node(&self, node_indices: &[usize], rng: &mut BattleRng) -> usize {
+        let weights: Vec<f64> = node_indices
             .iter()
-            .map(|x| x.instructions.percentage as f64)
+            .map(|i| self.nodes[*i].instructions.percentage as f64)
             .collect();
         let dist = WeightedIndex::new(weights).unwrap();
-        let chosen_node = &mut (*move_vector)[dist.sample(&mut rng)];
-        let chosen_node_ptr = chosen_node as *mut Node;
-        chosen_node_ptr
+        node_indices[dist.sample(rng)]
     }
 
-    pub unsafe fn expand(
+    // walks down from the root applying the instructions of each node visited to `state`
+    // returns the node to expand and the move pair to expand it with
+    pub fn selection(
+        &self,
+        state: &mut State,
+        exploration_constant: f32,
+        rng: &mut BattleRng,
+    ) -> (usize, usize, usize) {
+        let mut node_index = MctsTree::ROOT;
+        loop {
+            let node = &self.nodes[node_index];
+            let s1_mc_index = node.maximize_ucb_for_side(&node.s1_options, exploration_constant);
+            let s2_mc_index = node.maximize_ucb_for_side(&node.s2_options, exploration_constant);
+            match node.children.get(&(s1_mc_index, s2_mc_index)) {
+                Some(children) => {
+                    node_index = self.sample_node(children, rng);
+                    state.apply_instructions(&self.nodes[node_index].instructions.instruction_list);
+                }
+                None => return (node_index, s1_mc_index, s2_mc_index),
+            }
+        }
+    }
+
+    pub fn expand(
         &mut self,
+        node_index: usize,
         state: &mut State,
         s1_move_index: usize,
         s2_move_index: usize,
-    ) -> *mut Node {
-        let s1_move = &self.s1_options[s1_move_index].move_choice;
-        let s2_move = &self.s2_options[s2_move_index].move_choice;
+        damage_bucket: Option<i16>,
+        rng: &mut BattleRng,
+    ) -> usize {
+        let node = &self.nodes[node_index];
+        let s1_move = node.s1_options[s1_move_index].move_choice;
+        let s2_move = node.s2_options[s2_move_index].move_choice;
         // if the battle is over or both moves are none there is no need to expand
-        if (state.battle_is_over() != 0.0 && !self.root)
-            || (s1_move == &MoveChoice::None && s2_move == &MoveChoice::None)
+        if (state.battle_is_over() != 0.0 && !node.is_root())
+            || (s1_move == MoveChoice::None && s2_move == MoveChoice::None)
         {
-            return self as *mut Node;
+            return node_index;
+        }
+        let should_branch_on_damage = match node.parent {
+            None => true,
+            Some(parent_index) => self.nodes[parent_index].is_root(),
+        };
+        let mut new_instructions = generate_instructions_from_move_pair(
+            state,
+            &s1_move,
+            &s2_move,
+            should_branch_on_damage,
+        );
+        if let Some(damage_bucket) = damage_bucket {
+            state.merge_equivalent_instructions(&mut new_instructions, damage_bucket);
         }
-        let should_branch_on_damage = self.root || (*self.parent).root;
-        let mut new_instructions =
-            generate_instructions_from_move_pair(state, s1_move, s2_move, should_branch_on_damage);
         let mut this_pair_vec = Vec::with_capacity(new_instructions.len());
         for state_instructions in new_instructions.drain(..) {
             state.apply_instructions(&state_instructions.instruction_list);
@@ -144,53 +437,150 @@ impl Node {
             state.reverse_instructions(&state_instructions.instruction_list);
 
             let mut new_node = Node::new(s1_options, s2_options);
-            new_node.parent = self;
+            new_node.parent = Some(node_index);
             new_node.instructions = state_instructions;
             new_node.s1_choice = s1_move_index;
             new_node.s2_choice = s2_move_index;
 
-            this_pair_vec.push(new_node);
+            this_pair_vec.push(self.nodes.len());
+            self.nodes.push(new_node);
         }
 
         // sample a node from the new instruction list.
         // this is the node that the rollout will be done on
-        let new_node_ptr = self.sample_node(&mut this_pair_vec);
-        state.apply_instructions(&(*new_node_ptr).instructions.instruction_list);
-        self.children
+        let new_node_index = self.sample_node(&this_pair_vec, rng);
+        state.apply_instructions(&self.nodes[new_node_index].instructions.instruction_list);
+        self.nodes[node_index]
+            .children
             .insert((s1_move_index, s2_move_index), this_pair_vec);
-        new_node_ptr
+        new_node_index
     }
 
-    pub unsafe fn backpropagate(&mut self, score: f32, state: &mut State) {
-        self.times_visited += 1;
-        if self.root {
-            return;
+    // walks back up to the root reversing the instructions applied during selection
+    pub fn backpropagate(&mut self, node_index: usize, score: f32, state: &mut State) {
+        let mut node_index = node_index;
+        loop {
+            let node = &mut self.nodes[node_index];
+            node.times_visited += 1;
+            let parent_index = match node.parent {
+                Some(parent_index) => parent_index,
+                None => return,
+            };
+            let (s1_choice, s2_choice) = (node.s1_choice, node.s2_choice);
+            state.reverse_instructions(&node.instructions.instruction_list);
+
+            let parent = &mut self.nodes[parent_index];
+            let parent_s1_movenode = &mut parent.s1_options[s1_choice];
+            parent_s1_movenode.total_score += score;
+            parent_s1_movenode.visits += 1;
+
+            let parent_s2_movenode = &mut parent.s2_options[s2_choice];
+            parent_s2_movenode.total_score += 1.0 - score;
+            parent_s2_movenode.visits += 1;
+
+            node_index = parent_index;
         }
+    }
 
-        let parent_s1_movenode = &mut (*self.parent).s1_options[self.s1_choice];
-        parent_s1_movenode.total_score += score;
-        parent_s1_movenode.visits += 1;
-
-        let parent_s2_movenode = &mut (*self.parent).s2_options[self.s2_choice];
-        parent_s2_movenode.total_score += 1.0 - score;
-        parent_s2_movenode.visits += 1;
+    pub fn result(&self) -> MctsResult {
+        let root = self.root();
+        MctsResult {
+            s1: root
+                .s1_options
+                .iter()
+                .map(|v| MctsSideResult {
+                    move_choice: v.move_choice,
+                    total_score: v.total_score,
+                    visits: v.visits,
+                })
+                .collect(),
+            s2: root
+                .s2_options
+                .iter()
+                .map(|v| MctsSideResult {
+                    move_choice: v.move_choice,
+                    total_score: v.total_score,
+                    visits: v.visits,
+                })
+                .collect(),
+            iteration_count: root.times_visited,
+            max_depth: self.get_max_depth(),
+        }
+    }
 
-        state.reverse_instructions(&self.instructions.instruction_list);
-        (*self.parent).backpropagate(score, state);
+    /// Returns the subtree whose root is the state reached from `root_state` after one turn
+    ///
+    /// `root_state` must be the state this tree was searched from and `next_state` the state
+    /// after the turn was played. Returns `None` if no node of the tree leads to `next_state`
+    /// (e.g. the turn ended in an outcome that was never expanded). Nodes that were generated
+    /// one level below the root did not branch on damage rolls, so the reused tree is coarser
+    /// than a freshly built one near its root
+    pub fn reuse_subtree(self, root_state: &mut State, next_state: &mut State) -> Option<MctsTree> {
+        let target_hash = next_state.rehash();
+        root_state.rehash();
+
+        // children are visited in index order so that ties are broken the same way every time
+        let mut child_indices: Vec<usize> =
+            self.root().children.values().flatten().copied().collect();
+        child_indices.sort_unstable();
+
+        let mut new_root = None;
+        let mut new_root_visits = -1;
+        for child_index in child_indices {
+            let child = &self.nodes[child_index];
+            root_state.apply_instructions(&child.instructions.instruction_list);
+            let child_hash = root_state.get_hash();
+            root_state.reverse_instructions(&child.instructions.instruction_list);
+
+            // the same state can be reached through different move pairs,
+            // keep the one that was searched the most
+            if child_hash == target_hash && child.times_visited > new_root_visits {
+                new_root = Some(child_index);
+                new_root_visits = child.times_visited;
+            }
+        }
+        new_root.map(|new_root| self.into_subtree(new_root))
     }
 
-    pub fn rollout(&mut self, state: &mut State, root_eval: &f32) -> f32 {
-        let battle_is_over = state.battle_is_over();
-        if battle_is_over == 0.0 {
-            let eval = evaluate(state);
-            sigmoid(eval - root_eval)
-        } else {
-            if battle_is_over == -1.0 {
-                0.0
-            } else {
-                battle_is_over
+    fn into_subtree(self, new_root: usize) -> MctsTree {
+        // breadth first so that the new root ends up at index 0.
+        // children are walked in move pair order so that the layout, and the ties broken by
+        // index in a later `reuse_subtree`, do not depend on the iteration order of the map
+        let mut order = vec![new_root];
+        let mut i = 0;
+        while i < order.len() {
+            let node = &self.nodes[order[i]];
+            let mut move_pairs: Vec<&(usize, usize)> = node.children.keys().collect();
+            move_pairs.sort_unstable();
+            for move_pair in move_pairs {
+                order.extend(node.children[move_pair].iter().copied());
+            }
+            i += 1;
+        }
+        let new_indices: HashMap<usize, usize> = order
+            .iter()
+            .enumerate()
+            .map(|(new_index, old_index)| (*old_index, new_index))
+            .collect();
+
+        let mut old_nodes: Vec<Option<Node>> = self.nodes.into_iter().map(Some).collect();
+        let mut nodes = Vec::with_capacity(order.len());
+        for old_index in order.iter() {
+            let mut node = old_nodes[*old_index].take().unwrap();
+            node.parent = node.parent.and_then(|p| new_indices.get(&p).copied());
+            for children in node.children.values_mut() {
+                for child in children.iter_mut() {
+                    *child = new_indices[child];
+                }
             }
+            nodes.push(node);
         }
+
+        let root = &mut nodes[MctsTree::ROOT];
+        root.instructions = StateInstructions::default();
+        root.s1_choice = 0;
+        root.s2_choice = 0;
+        MctsTree { nodes }
     }
 }
 
@@ -202,12 +592,12 @@ pub struct MoveNode {
 }
 
 impl MoveNode {
-    pub fn ucb1(&self, parent_visits: i64) -> f32 {
+    pub fn ucb1(&self, parent_visits: i64, exploration_constant: f32) -> f32 {
         if self.visits == 0 {
             return f32::INFINITY;
         }
         let score = (self.total_score / self.visits as f32)
-            + (2.0 * (parent_visits as f32).ln() / self.visits as f32).sqrt();
+            + (exploration_constant * (parent_visits as f32).ln() / self.visits as f32).sqrt();
         score
     }
     pub fn average_score(&self) -> f32 {
@@ -240,68 +630,144 @@ pub struct MctsResult {
     pub max_depth: usize,
 }
 
-fn do_mcts(root_node: &mut Node, state: &mut State, root_eval: &f32) {
-    let (mut new_node, s1_move, s2_move) = unsafe { root_node.selection(state) };
-    new_node = unsafe { (*new_node).expand(state, s1_move, s2_move) };
-    let rollout_result = unsafe { (*new_node).rollout(state, root_eval) };
-    unsafe { (*new_node).backpropagate(rollout_result, state) }
+fn merge_side_results(merged: &mut Vec<MctsSideResult>, side_results: &[MctsSideResult]) {
+    for side_result in side_results {
+        match merged
+            .iter_mut()
+            .find(|m| m.move_choice == side_result.move_choice)
+        {
+            Some(m) => {
+                m.total_score += side_result.total_score;
+                m.visits += side_result.visits;
+            }
+            None => merged.push(side_result.clone()),
+        }
+    }
+}
+
+impl MctsResult {
+    /// Combines the results of independent searches started from the same state
+    ///
+    /// Scores and visits of the same move are summed, so the merged result can be read
+    /// exactly like the result of one search with the combined number of iterations
+    pub fn merge(results: Vec<MctsResult>) -> MctsResult {
+        let mut merged = MctsResult {
+            s1: vec![],
+            s2: vec![],
+            iteration_count: 0,
+            max_depth: 0,
+        };
+        for result in results.iter() {
+            merge_side_results(&mut merged.s1, &result.s1);
+            merge_side_results(&mut merged.s2, &result.s2);
+            merged.iteration_count += result.iteration_count;
+            merged.max_depth = merged.max_depth.max(result.max_depth);
+        }
+        merged
+    }
+}
+
+fn do_mcts<E: Evaluator + ?Sized>(
+    tree: &mut MctsTree,
+    state: &mut State,
+    root_eval: &f32,
+    config: &MctsConfig,
+    evaluator: &E,
+    rng: &mut BattleRng,
+) {
+    let (node_index, s1_move, s2_move) = tree.selection(state, config.exploration_constant, rng);
+    let new_node_index = tree.expand(
+        node_index,
+        state,
+        s1_move,
+        s2_move,
+        config.damage_bucket,
+        rng,
+    );
+    let rollout_result =
+        tree.nodes[new_node_index].rollout(state, root_eval, config, evaluator, rng);
+    tree.backpropagate(new_node_index, rollout_result, state)
 }
 
+/// Searches `state` with every random choice drawn from `rng`
+///
+/// The search only does the same thing from the same `rng` when it stops at
+/// `config.max_iterations` rather than at `config.max_time`
 pub fn perform_mcts(
     state: &mut State,
     side_one_options: Vec<MoveChoice>,
     side_two_options: Vec<MoveChoice>,
-    max_time: Duration,
+    config: &MctsConfig,
+    rng: &mut BattleRng,
 ) -> MctsResult {
-    let mut root_node = Node::new(side_one_options, side_two_options);
-    root_node.root = true;
+    let mut tree = MctsTree::new(side_one_options, side_two_options);
+    perform_mcts_with_tree(state, &mut tree, config, &DefaultEvaluator, rng)
+}
 
-    let root_eval = evaluate(state);
+/// Continues searching `tree`, which may already hold iterations from a previous search
+/// (see `MctsTree::reuse_subtree`). `state` must be the state at the root of the tree
+///
+/// Iterations already in the tree count towards `config.max_iterations`.
+/// Rollouts are scored with `evaluator`
+pub fn perform_mcts_with_tree<E: Evaluator + ?Sized>(
+    state: &mut State,
+    tree: &mut MctsTree,
+    config: &MctsConfig,
+    evaluator: &E,
+    rng: &mut BattleRng,
+) -> MctsResult {
+    let root_eval = evaluator.evaluate(state);
     let start_time = std::time::Instant::now();
-    while start_time.elapsed() < max_time {
-        for _ in 0..1000 {
-            do_mcts(&mut root_node, state, &root_eval);
-        }
-
-        /*
-        Cut off after 10 million iterations
-
-        Under normal circumstances the bot will only run for 2.5-3.5 million iterations
-        however towards the end of a battle the bot may perform tens of millions of iterations
-
-        Beyond about 30 million iterations some floating point nonsense happens where
-        MoveNode.total_score stops updating because f32 does not have enough precision
-
-        I can push the problem farther out by using f64 but if the bot is running for 10 million iterations
-        then it almost certainly sees a forced win
-        */
-        if root_node.times_visited == 10_000_000 {
+    while start_time.elapsed() < config.max_time {
+        let remaining_iterations = config.max_iterations - tree.root().times_visited;
+        if remaining_iterations <= 0 {
             break;
         }
+        for _ in 0..remaining_iterations.min(1000) {
+            do_mcts(tree, state, &root_eval, config, evaluator, rng);
+        }
+    }
+    tree.result()
+}
+
+/// Root-parallel MCTS: `config.threads` independent trees are searched from `state` at the same
+/// time and their root statistics are merged
+///
+/// Each tree gets its own generator seeded from `rng`
+pub fn perform_parallel_mcts<E: Evaluator + ?Sized>(
+    state: &State,
+    side_one_options: Vec<MoveChoice>,
+    side_two_options: Vec<MoveChoice>,
+    config: &MctsConfig,
+    evaluator: &E,
+    rng: &mut BattleRng,
+) -> MctsResult {
+    if config.threads <= 1 {
+        let mut tree = MctsTree::new(side_one_options, side_two_options);
+        return perform_mcts_with_tree(&mut state.clone(), &mut tree, config, evaluator, rng);
     }
-    let max_depth = root_node.get_max_depth();
-    let result = MctsResult {
-        s1: root_node
-            .s1_options
-            .iter()
-            .map(|v| MctsSideResult {
-                move_choice: v.move_choice.clone(),
-                total_score: v.total_score,
-                visits: v.visits,
-            })
-            .collect(),
-        s2: root_node
-            .s2_options
-            .iter()
-            .map(|v| MctsSideResult {
-                move_choice: v.move_choice.clone(),
-                total_score: v.total_score,
-                visits: v.visits,
-            })
-            .collect(),
-        iteration_count: root_node.times_visited,
-        max_depth,
-    };
 
-    result
+    let seeds: Vec<u64> = (0..config.threads).map(|_| rng.gen()).collect();
+    // a dedicated pool so that each tree gets its own thread even when called
+    // from inside another rayon pool, e.g. when running battles in parallel
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(config.threads)
+        .build()
+        .expect("Failed to build MCTS thread pool");
+    let results = pool.install(|| {
+        seeds
+            .into_par_iter()
+            .map(|seed| {
+                let mut tree = MctsTree::new(side_one_options.clone(), side_two_options.clone());
+                perform_mcts_with_tree(
+                    &mut state.clone(),
+                    &mut tree,
+                    config,
+                    evaluator,
+                    &mut seeded_rng(seed),
+                )
+            })
+            .collect()
+    });
+    MctsResult::merge(results)
 }
@@ -1,8 +1,9 @@
 use crate::define_enum_with_from_str;
+use serde::{Deserialize, Serialize};
 
 define_enum_with_from_str! {
     #[repr(i16)]
-    #[derive(Clone, Copy, Debug, PartialEq)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
     PokemonName {
         NONE,
         BULBASAUR,
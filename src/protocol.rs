@@ -0,0 +1,354 @@
+use crate::engine::generate_instructions::generate_instructions_from_move_pair;
+use crate::engine::state::MoveChoice;
+use crate::evaluator::DefaultEvaluator;
+use crate::instruction::{Instruction, StateInstructions};
+use crate::mcts::{perform_parallel_mcts, MctsConfig, MctsSideResult};
+use crate::rng::{seeded_rng, BattleRng};
+use crate::search::iterative_deepen_expectiminimax;
+use crate::state::{Side, State};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+use std::time::Duration;
+
+pub const DEFAULT_SEARCH_TIME_MS: u64 = 1000;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchAlgorithm {
+    #[default]
+    Mcts,
+    IterativeDeepening,
+}
+
+fn default_search_time_ms() -> u64 {
+    DEFAULT_SEARCH_TIME_MS
+}
+
+fn default_threads() -> usize {
+    1
+}
+
+// one of these per line on stdin
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProtocolRequest {
+    // `state` is either a serialized state string or a JSON state object
+    SetState {
+        state: serde_json::Value,
+    },
+    GetState,
+    // generates the instructions for the move pair and applies one of the possible outcomes,
+    // the most likely one unless `branch` says which
+    ApplyMoves {
+        side_one: String,
+        side_two: String,
+        #[serde(default)]
+        branch: Option<usize>,
+    },
+    ApplyInstructions {
+        instructions: Vec<Instruction>,
+    },
+    Search {
+        #[serde(default = "default_search_time_ms")]
+        time_ms: u64,
+        #[serde(default)]
+        algorithm: SearchAlgorithm,
+        #[serde(default = "default_threads")]
+        threads: usize,
+        // stops mcts after this many iterations instead of after `time_ms`
+        #[serde(default)]
+        max_iterations: Option<i64>,
+    },
+    Quit,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RankedMove {
+    #[serde(rename = "move")]
+    pub move_choice: String,
+    // from the point of view of the side making the move, higher is better
+    pub score: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visits: Option<i64>,
+}
+
+// one of these per line on stdout for every request except `quit`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProtocolResponse {
+    State {
+        state: String,
+    },
+    Applied {
+        branch: usize,
+        branch_count: usize,
+        instructions: StateInstructions,
+        state: String,
+    },
+    SearchResult {
+        side_one: Vec<RankedMove>,
+        side_two: Vec<RankedMove>,
+        // mcts iterations, or the depth iterative deepening finished
+        iterations: Option<i64>,
+        depth: Option<i64>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl ProtocolResponse {
+    fn error(message: String) -> ProtocolResponse {
+        ProtocolResponse::Error { message }
+    }
+}
+
+pub struct ProtocolSession {
+    pub state: Option<State>,
+    rng: BattleRng,
+}
+
+impl ProtocolSession {
+    pub fn new(seed: u64) -> ProtocolSession {
+        ProtocolSession {
+            state: None,
+            rng: seeded_rng(seed),
+        }
+    }
+
+    fn state(&mut self) -> Result<&mut State, String> {
+        self.state
+            .as_mut()
+            .ok_or_else(|| "No state has been set".to_string())
+    }
+
+    /// The response to one request, or None when the session should end
+    pub fn handle_request(&mut self, request: ProtocolRequest) -> Option<ProtocolResponse> {
+        let response = match request {
+            ProtocolRequest::Quit => return None,
+            ProtocolRequest::SetState { state } => self.set_state(state),
+            ProtocolRequest::GetState => self.state().map(|state| ProtocolResponse::State {
+                state: State::serialize(state),
+            }),
+            ProtocolRequest::ApplyMoves {
+                side_one,
+                side_two,
+                branch,
+            } => self.apply_moves(&side_one, &side_two, branch),
+            ProtocolRequest::ApplyInstructions { instructions } => self.state().map(|state| {
+                state.apply_instructions(&instructions);
+                ProtocolResponse::State {
+                    state: State::serialize(state),
+                }
+            }),
+            ProtocolRequest::Search {
+                time_ms,
+                algorithm,
+                threads,
+                max_iterations,
+            } => self.search(time_ms, algorithm, threads, max_iterations),
+        };
+        Some(response.unwrap_or_else(ProtocolResponse::error))
+    }
+
+    /// Parses one line of input and returns the line to write back, if any
+    pub fn handle_line(&mut self, line: &str) -> Option<String> {
+        let response = match serde_json::from_str::<ProtocolRequest>(line) {
+            Ok(request) => self.handle_request(request)?,
+            Err(e) => ProtocolResponse::error(format!("Invalid request: {}", e)),
+        };
+        Some(serde_json::to_string(&response).unwrap())
+    }
+
+    fn set_state(&mut self, state: serde_json::Value) -> Result<ProtocolResponse, String> {
+        let state = match state {
+            serde_json::Value::String(s) => {
+                State::try_deserialize(&s).map_err(|e| format!("Invalid state: {}", e))?
+            }
+            value => serde_json::from_value::<State>(value)
+                .map_err(|e| format!("Invalid state JSON: {}", e))?,
+        };
+        let serialized = state.serialize();
+        self.state = Some(state);
+        Ok(ProtocolResponse::State { state: serialized })
+    }
+
+    fn apply_moves(
+        &mut self,
+        side_one: &str,
+        side_two: &str,
+        branch: Option<usize>,
+    ) -> Result<ProtocolResponse, String> {
+        let state = self.state()?;
+        let side_one_move = parse_move(side_one, &state.side_one, "side one")?;
+        let side_two_move = parse_move(side_two, &state.side_two, "side two")?;
+        let mut branches =
+            generate_instructions_from_move_pair(state, &side_one_move, &side_two_move, true);
+
+        let branch_count = branches.len();
+        let branch = match branch {
+            Some(index) if index >= branch_count => {
+                return Err(format!(
+                    "Branch {} does not exist, there are {} branches",
+                    index, branch_count
+                ));
+            }
+            Some(index) => index,
+            None => most_likely_branch(&branches),
+        };
+        let instructions = branches.swap_remove(branch);
+        state.apply_instructions(&instructions.instruction_list);
+        Ok(ProtocolResponse::Applied {
+            branch,
+            branch_count,
+            instructions,
+            state: State::serialize(state),
+        })
+    }
+
+    fn search(
+        &mut self,
+        time_ms: u64,
+        algorithm: SearchAlgorithm,
+        threads: usize,
+        max_iterations: Option<i64>,
+    ) -> Result<ProtocolResponse, String> {
+        let mut state = self.state()?.clone();
+        let (side_one_options, side_two_options) = state.root_get_all_options();
+
+        match algorithm {
+            SearchAlgorithm::Mcts => {
+                let mut config = MctsConfig {
+                    max_time: Duration::from_millis(time_ms),
+                    threads: threads.max(1),
+                    ..Default::default()
+                };
+                if let Some(max_iterations) = max_iterations {
+                    config.max_time = Duration::MAX;
+                    config.max_iterations = max_iterations;
+                }
+                let result = perform_parallel_mcts(
+                    &state,
+                    side_one_options,
+                    side_two_options,
+                    &config,
+                    &DefaultEvaluator,
+                    &mut self.rng,
+                );
+                Ok(ProtocolResponse::SearchResult {
+                    side_one: rank_mcts_moves(&result.s1, &state.side_one),
+                    side_two: rank_mcts_moves(&result.s2, &state.side_two),
+                    iterations: Some(result.iteration_count),
+                    depth: Some(result.max_depth as i64),
+                })
+            }
+            SearchAlgorithm::IterativeDeepening => {
+                let (side_one_options, side_two_options, scores, depth) =
+                    iterative_deepen_expectiminimax(
+                        &mut state,
+                        side_one_options,
+                        side_two_options,
+                        Duration::from_millis(time_ms),
+                    );
+                let (side_one, side_two) =
+                    rank_worst_cases(&state, &side_one_options, &side_two_options, &scores);
+                Ok(ProtocolResponse::SearchResult {
+                    side_one,
+                    side_two,
+                    iterations: None,
+                    depth: Some(depth as i64),
+                })
+            }
+        }
+    }
+}
+
+fn parse_move(move_string: &str, side: &Side, side_name: &str) -> Result<MoveChoice, String> {
+    MoveChoice::from_string(move_string, side)
+        .ok_or_else(|| format!("Invalid move choice for {}: {}", side_name, move_string))
+}
+
+fn most_likely_branch(branches: &[StateInstructions]) -> usize {
+    let mut best_index = 0;
+    for (index, branch) in branches.iter().enumerate() {
+        if branch.percentage > branches[best_index].percentage {
+            best_index = index;
+        }
+    }
+    best_index
+}
+
+// most visited first, the same move the search would pick by default
+fn rank_mcts_moves(results: &[MctsSideResult], side: &Side) -> Vec<RankedMove> {
+    let mut ranked: Vec<&MctsSideResult> = results.iter().collect();
+    ranked.sort_by_key(|result| std::cmp::Reverse(result.visits));
+    ranked
+        .into_iter()
+        .map(|result| RankedMove {
+            move_choice: result.move_choice.to_string(side),
+            score: result.average_score(),
+            visits: Some(result.visits),
+        })
+        .collect()
+}
+
+// every move is scored by the worst outcome over the other side's replies
+// pruned entries in the score matrix are NaN and are skipped
+fn rank_worst_cases(
+    state: &State,
+    side_one_options: &[MoveChoice],
+    side_two_options: &[MoveChoice],
+    scores: &[f32],
+) -> (Vec<RankedMove>, Vec<RankedMove>) {
+    let num_s2_moves = side_two_options.len();
+    let mut side_one = vec![];
+    for (i, move_choice) in side_one_options.iter().enumerate() {
+        let worst = (0..num_s2_moves)
+            .map(|j| scores[i * num_s2_moves + j])
+            .filter(|score| !score.is_nan())
+            .fold(f32::MAX, f32::min);
+        side_one.push(RankedMove {
+            move_choice: move_choice.to_string(&state.side_one),
+            score: worst,
+            visits: None,
+        });
+    }
+    let mut side_two = vec![];
+    for (j, move_choice) in side_two_options.iter().enumerate() {
+        let worst = (0..side_one_options.len())
+            .map(|i| scores[i * num_s2_moves + j])
+            .filter(|score| !score.is_nan())
+            .fold(f32::MIN, f32::max);
+        side_two.push(RankedMove {
+            move_choice: move_choice.to_string(&state.side_two),
+            score: -worst,
+            visits: None,
+        });
+    }
+    side_one.sort_by(|a, b| b.score.total_cmp(&a.score));
+    side_two.sort_by(|a, b| b.score.total_cmp(&a.score));
+    (side_one, side_two)
+}
+
+/// Reads newline delimited JSON requests from `reader` and writes one JSON response per request
+/// to `writer`, until a `quit` request or the end of the input
+pub fn run_protocol<R: BufRead, W: Write>(
+    reader: R,
+    writer: &mut W,
+    session: &mut ProtocolSession,
+) -> std::io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match session.handle_line(&line) {
+            Some(response) => {
+                writeln!(writer, "{}", response)?;
+                writer.flush()?;
+            }
+            None => break,
+        }
+    }
+    Ok(())
+}
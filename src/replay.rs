@@ -0,0 +1,225 @@
+use crate::instruction::StateInstructions;
+use crate::state::{SideReference, State};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+// bumped whenever a change to the replay format would break reading older files
+pub const REPLAY_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayHeader {
+    pub version: u32,
+    pub seed: u64,
+    pub player_one: String,
+    pub player_two: String,
+    // the serialized state once leads are chosen, before any start of battle effects
+    pub initial_state: String,
+}
+
+// turn 0 holds the start of battle effects, e.g. weather or intimidate from the leads
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayTurn {
+    pub turn_number: usize,
+    pub side_one_choice: String,
+    pub side_two_choice: String,
+    pub state_before: String,
+    pub instructions: StateInstructions,
+    pub state_after: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEnd {
+    pub winner: Option<SideReference>,
+    pub turn_count: usize,
+}
+
+// a replay file is one of these per line, starting with the header
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReplayLine {
+    Header(ReplayHeader),
+    Turn(ReplayTurn),
+    End(ReplayEnd),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub header: ReplayHeader,
+    pub turns: Vec<ReplayTurn>,
+    // missing if the battle was not played to the end
+    pub end: Option<ReplayEnd>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, player_one: &str, player_two: &str, initial_state: &State) -> Replay {
+        Replay {
+            header: ReplayHeader {
+                version: REPLAY_FORMAT_VERSION,
+                seed,
+                player_one: player_one.to_string(),
+                player_two: player_two.to_string(),
+                initial_state: initial_state.serialize(),
+            },
+            turns: vec![],
+            end: None,
+        }
+    }
+
+    pub fn push_turn(
+        &mut self,
+        turn_number: usize,
+        side_one_choice: String,
+        side_two_choice: String,
+        state_before: &State,
+        instructions: StateInstructions,
+        state_after: &State,
+    ) {
+        self.turns.push(ReplayTurn {
+            turn_number,
+            side_one_choice,
+            side_two_choice,
+            state_before: state_before.serialize(),
+            instructions,
+            state_after: state_after.serialize(),
+        });
+    }
+
+    pub fn finish(&mut self, winner: Option<SideReference>, turn_count: usize) {
+        self.end = Some(ReplayEnd { winner, turn_count });
+    }
+
+    pub fn to_json_lines(&self) -> String {
+        let mut lines = vec![ReplayLine::Header(self.header.clone())];
+        lines.extend(self.turns.iter().cloned().map(ReplayLine::Turn));
+        lines.extend(self.end.clone().map(ReplayLine::End));
+
+        let mut json_lines = String::new();
+        for line in lines {
+            json_lines.push_str(&serde_json::to_string(&line).unwrap());
+            json_lines.push('\n');
+        }
+        json_lines
+    }
+
+    pub fn from_json_lines(json_lines: &str) -> Result<Replay, String> {
+        let mut lines = json_lines
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str::<ReplayLine>(line)
+                    .map_err(|e| format!("Invalid replay line {}: {}", index + 1, e))
+            });
+
+        let header = match lines.next() {
+            Some(Ok(ReplayLine::Header(header))) => header,
+            Some(Err(e)) => return Err(e),
+            _ => return Err("Replay does not start with a header".to_string()),
+        };
+        if header.version != REPLAY_FORMAT_VERSION {
+            return Err(format!(
+                "Unsupported replay version {}, expected {}",
+                header.version, REPLAY_FORMAT_VERSION
+            ));
+        }
+
+        let mut replay = Replay {
+            header,
+            turns: vec![],
+            end: None,
+        };
+        for line in lines {
+            if replay.end.is_some() {
+                return Err("Replay continues after its end".to_string());
+            }
+            match line? {
+                ReplayLine::Turn(turn) => replay.turns.push(turn),
+                ReplayLine::End(end) => replay.end = Some(end),
+                ReplayLine::Header(_) => return Err("Replay has more than one header".to_string()),
+            }
+        }
+        Ok(replay)
+    }
+
+    pub fn write_to_file(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_json_lines())
+            .map_err(|e| format!("Failed to write replay to {}: {}", path, e))
+    }
+
+    pub fn from_file(path: &str) -> Result<Replay, String> {
+        let json_lines = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read replay from {}: {}", path, e))?;
+        Replay::from_json_lines(&json_lines)
+    }
+}
+
+// Steps through a replay by applying and reversing each turn's instructions
+pub struct ReplayViewer {
+    pub replay: Replay,
+    pub state: State,
+    // the number of turns from `replay.turns` applied to `state`
+    pub position: usize,
+}
+
+impl ReplayViewer {
+    pub fn new(replay: Replay) -> ReplayViewer {
+        let state = State::deserialize(&replay.header.initial_state);
+        ReplayViewer {
+            replay,
+            state,
+            position: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.replay.turns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.replay.turns.is_empty()
+    }
+
+    // the last turn applied to `state`
+    pub fn current_turn(&self) -> Option<&ReplayTurn> {
+        match self.position {
+            0 => None,
+            position => Some(&self.replay.turns[position - 1]),
+        }
+    }
+
+    pub fn step_forward(&mut self) -> bool {
+        if self.position == self.len() {
+            return false;
+        }
+        self.state.apply_instructions(
+            &self.replay.turns[self.position]
+                .instructions
+                .instruction_list,
+        );
+        self.position += 1;
+        true
+    }
+
+    pub fn step_backward(&mut self) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+        self.position -= 1;
+        self.state.reverse_instructions(
+            &self.replay.turns[self.position]
+                .instructions
+                .instruction_list,
+        );
+        true
+    }
+
+    pub fn go_to(&mut self, position: usize) {
+        let position = position.min(self.len());
+        while self.position < position {
+            self.step_forward();
+        }
+        while self.position > position {
+            self.step_backward();
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+
+/// The random number generator that battles, players and searches draw from
+///
+/// A battle run from the same seed with the same players is replayed exactly, as long as
+/// every search it does is limited by iterations rather than by time
+pub type BattleRng = StdRng;
+
+pub fn seeded_rng(seed: u64) -> BattleRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// A seed for a battle that was not given one, so that it can still be printed and replayed
+pub fn random_seed() -> u64 {
+    thread_rng().gen()
+}
+
+/// The seed of battle number `battle_index` of a run of battles started from `seed`,
+/// so that any one battle of the run can be replayed on its own
+pub fn battle_seed(seed: u64, battle_index: usize) -> u64 {
+    seed.wrapping_add(battle_index as u64)
+}
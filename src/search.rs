@@ -1,6 +1,6 @@
-use crate::engine::evaluate::evaluate;
 use crate::engine::generate_instructions::generate_instructions_from_move_pair;
 use crate::engine::state::MoveChoice;
+use crate::evaluator::{DefaultEvaluator, Evaluator};
 use crate::state::State;
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::{Arc, Mutex};
@@ -11,13 +11,172 @@ enum IterativeDeependingThreadMessage {
     Stop((Vec<MoveChoice>, Vec<MoveChoice>, Vec<f32>, i8)),
 }
 
+// used by `expectiminimax_search` when the caller does not keep a table between searches
+pub const DEFAULT_TRANSPOSITION_TABLE_SIZE: usize = 1 << 16;
+// used by `iterative_deepen_expectiminimax`, which keeps one table across every iteration
+const ITERATIVE_DEEPENING_TRANSPOSITION_TABLE_SIZE: usize = 1 << 18;
+
+#[derive(Debug, Clone, Copy)]
+pub struct TranspositionTableEntry {
+    pub key: u64,
+    pub depth: i8,
+    pub score: f32,
+    pub best_side_one_move: MoveChoice,
+}
+
+/// Bounded cache of sub-game values keyed by `State::get_hash`
+///
+/// An entry stores the value of the sub-game searched to `depth` and side one's safest move in it.
+/// Each key maps to a single slot; when two states share a slot the one searched deeper is kept
+pub struct TranspositionTable {
+    entries: Vec<Option<TranspositionTableEntry>>,
+    mask: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl TranspositionTable {
+    /// `size` is rounded up to the next power of two
+    pub fn new(size: usize) -> TranspositionTable {
+        let size = size.max(1).next_power_of_two();
+        TranspositionTable {
+            entries: vec![None; size],
+            mask: size - 1,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.iter().filter(|e| e.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.iter_mut().for_each(|e| *e = None);
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    pub fn get(&self, key: u64) -> Option<TranspositionTableEntry> {
+        match self.entries[key as usize & self.mask] {
+            Some(entry) if entry.key == key => Some(entry),
+            _ => None,
+        }
+    }
+
+    pub fn insert(&mut self, entry: TranspositionTableEntry) {
+        let slot = &mut self.entries[entry.key as usize & self.mask];
+        match slot {
+            Some(existing) if existing.key != entry.key && existing.depth > entry.depth => {}
+            _ => *slot = Some(entry),
+        }
+    }
+}
+
 pub fn expectiminimax_search(
+    state: &mut State,
+    depth: i8,
+    side_one_options: Vec<MoveChoice>,
+    side_two_options: Vec<MoveChoice>,
+    ab_prune: bool,
+    mtx: &Arc<Mutex<bool>>,
+) -> Vec<f32> {
+    state.rehash();
+    expectiminimax_search_with_table(
+        state,
+        depth,
+        side_one_options,
+        side_two_options,
+        ab_prune,
+        mtx,
+        &mut TranspositionTable::new(DEFAULT_TRANSPOSITION_TABLE_SIZE),
+        &DefaultEvaluator,
+        None,
+    )
+}
+
+// Searches the sub-game reached after a turn, or takes its value from the table
+fn sub_game_value<E: Evaluator + ?Sized>(
+    state: &mut State,
+    depth: i8,
+    mtx: &Arc<Mutex<bool>>,
+    table: &mut TranspositionTable,
+    evaluator: &E,
+    damage_bucket: Option<i16>,
+) -> f32 {
+    let key = state.get_hash();
+    let mut best_move_hint = None;
+    if let Some(entry) = table.get(key) {
+        if entry.depth == depth {
+            table.hits += 1;
+            return entry.score;
+        }
+        best_move_hint = Some(entry.best_side_one_move);
+    }
+    table.misses += 1;
+
+    let (mut side_one_options, side_two_options) = state.get_all_options();
+    // searching the previously best move first gives alpha-beta pruning a better bound
+    if let Some(best_move) = best_move_hint {
+        if let Some(index) = side_one_options.iter().position(|m| *m == best_move) {
+            side_one_options[..=index].rotate_right(1);
+        }
+    }
+    let num_s1_moves = side_one_options.len();
+    let num_s2_moves = side_two_options.len();
+    let scores = expectiminimax_search_with_table(
+        state,
+        depth,
+        side_one_options.clone(),
+        side_two_options,
+        true, // until there is something better than `pick_safest` for evaluating a sub-game, there is no point in this being anything other than `true`
+        mtx,
+        table,
+        evaluator,
+        damage_bucket,
+    );
+    let (best_index, safest) = pick_safest(&scores, num_s1_moves, num_s2_moves);
+
+    // a search that was stopped part way through returns placeholder scores
+    if *mtx.lock().unwrap() {
+        table.insert(TranspositionTableEntry {
+            key,
+            depth,
+            score: safest,
+            best_side_one_move: side_one_options[best_index],
+        });
+    }
+    safest
+}
+
+/// `expectiminimax_search` that reads and fills `table` and scores leaves with `evaluator`
+///
+/// The table is keyed on `State::get_hash`, so `state.rehash()` must be called first if the
+/// state was changed without going through instructions. Entries do not record which evaluator
+/// or `damage_bucket` produced them, so a table must not be shared between searches using
+/// different ones
+///
+/// With a `damage_bucket` the outcomes of each move pair are merged by
+/// `State::merge_equivalent_instructions` before they are searched
+#[allow(clippy::too_many_arguments)]
+pub fn expectiminimax_search_with_table<E: Evaluator + ?Sized>(
     state: &mut State,
     mut depth: i8,
     side_one_options: Vec<MoveChoice>,
     side_two_options: Vec<MoveChoice>,
     ab_prune: bool,
     mtx: &Arc<Mutex<bool>>,
+    table: &mut TranspositionTable,
+    evaluator: &E,
+    damage_bucket: Option<i16>,
 ) -> Vec<f32> {
     depth -= 1;
     let num_s1_moves = side_one_options.len();
@@ -34,7 +193,8 @@ pub fn expectiminimax_search(
     let battle_is_over = state.battle_is_over();
     if battle_is_over != 0.0 {
         for _ in 0..(num_s1_moves * num_s2_moves) {
-            score_lookup.push(((100.0 * depth as f32) * battle_is_over) + evaluate(state));
+            score_lookup
+                .push(((100.0 * depth as f32) * battle_is_over) + evaluator.evaluate(state));
         }
         return score_lookup;
     }
@@ -52,34 +212,21 @@ pub fn expectiminimax_search(
             }
 
             let mut score = 0.0;
-            let instructions =
+            let mut instructions =
                 generate_instructions_from_move_pair(state, &side_one_move, &side_two_move, false);
+            if let Some(damage_bucket) = damage_bucket {
+                state.merge_equivalent_instructions(&mut instructions, damage_bucket);
+            }
             if depth == 0 {
                 for instruction in instructions.iter() {
                     state.apply_instructions(&instruction.instruction_list);
-                    score += instruction.percentage * evaluate(state) / 100.0;
+                    score += instruction.percentage * evaluator.evaluate(state) / 100.0;
                     state.reverse_instructions(&instruction.instruction_list);
                 }
             } else {
                 for instruction in instructions.iter() {
                     state.apply_instructions(&instruction.instruction_list);
-                    let (next_turn_side_one_options, next_turn_side_two_options) =
-                        state.get_all_options();
-
-                    let next_turn_side_one_options_len = next_turn_side_one_options.len();
-                    let next_turn_side_two_options_len = next_turn_side_two_options.len();
-                    let (_, safest) = pick_safest(
-                        &expectiminimax_search(
-                            state,
-                            depth,
-                            next_turn_side_one_options,
-                            next_turn_side_two_options,
-                            true, // until there is something better than `pick_safest` for evaluating a sub-game, there is no point in this being anything other than `true`
-                            &mtx,
-                        ),
-                        next_turn_side_one_options_len,
-                        next_turn_side_two_options_len,
-                    );
+                    let safest = sub_game_value(state, depth, mtx, table, evaluator, damage_bucket);
                     score += instruction.percentage * safest / 100.0;
 
                     state.reverse_instructions(&instruction.instruction_list);
@@ -130,6 +277,142 @@ pub fn pick_safest(
     (best_worst_case_s1_index, best_worst_case)
 }
 
+/// Equilibrium of the simultaneous-move game described by a `score_lookup` matrix
+///
+/// `s1` and `s2` are the probabilities each side plays its options with, in the order of the
+/// options the matrix was searched with. `value` is side one's expected score when both sides
+/// play these strategies
+#[derive(Debug, Clone)]
+pub struct NashEquilibrium {
+    pub s1: Vec<f32>,
+    pub s2: Vec<f32>,
+    pub value: f32,
+}
+
+impl NashEquilibrium {
+    pub fn best_s1_index(&self) -> usize {
+        most_likely_index(&self.s1)
+    }
+
+    pub fn best_s2_index(&self) -> usize {
+        most_likely_index(&self.s2)
+    }
+}
+
+fn most_likely_index(probabilities: &[f32]) -> usize {
+    let mut best_index = 0;
+    for (index, probability) in probabilities.iter().enumerate() {
+        if *probability > probabilities[best_index] {
+            best_index = index;
+        }
+    }
+    best_index
+}
+
+const SIMPLEX_EPSILON: f64 = 1e-9;
+
+/// Solves the zero-sum matrix game where side one picks a row to maximize the score and side
+/// two picks a column to minimize it
+///
+/// This is solved exactly as a linear program with the simplex method. Entries that were pruned
+/// by alpha-beta (`NaN`) are replaced with the worst case of their row, so the equilibrium of a
+/// pruned matrix is only an approximation
+pub fn solve_nash_equilibrium(
+    score_lookup: &[f32],
+    num_s1_moves: usize,
+    num_s2_moves: usize,
+) -> NashEquilibrium {
+    let mut payoffs: Vec<Vec<f64>> = score_lookup
+        .chunks(num_s2_moves)
+        .take(num_s1_moves)
+        .map(|row| row.iter().map(|score| *score as f64).collect())
+        .collect();
+    for row in payoffs.iter_mut() {
+        let worst_case = row
+            .iter()
+            .filter(|score| !score.is_nan())
+            .fold(f64::MAX, |a, b| a.min(*b));
+        for score in row.iter_mut().filter(|score| score.is_nan()) {
+            *score = worst_case;
+        }
+    }
+
+    // shifting every payoff to be at least 1 makes the value of the game positive
+    // which lets the game be written as the LP: maximize sum(y) subject to Ay <= 1, y >= 0
+    // side two's strategy is y / sum(y) and side one's comes from the dual
+    let min_payoff = payoffs.iter().flatten().fold(f64::MAX, |a, b| a.min(*b));
+    let shift = 1.0 - min_payoff;
+
+    // tableau rows are the constraints followed by the objective
+    // columns are side two's variables, one slack per constraint, then the right hand side
+    let rhs = num_s2_moves + num_s1_moves;
+    let mut tableau = vec![vec![0.0; rhs + 1]; num_s1_moves + 1];
+    for (i, row) in payoffs.iter().enumerate() {
+        for (j, payoff) in row.iter().enumerate() {
+            tableau[i][j] = payoff + shift;
+        }
+        tableau[i][num_s2_moves + i] = 1.0;
+        tableau[i][rhs] = 1.0;
+    }
+    for value in tableau[num_s1_moves][..num_s2_moves].iter_mut() {
+        *value = -1.0;
+    }
+    let mut basis: Vec<usize> = (num_s2_moves..rhs).collect();
+
+    // Bland's rule: the lowest index improving column and lowest index tied row
+    // so that degenerate games cannot cycle
+    while let Some(pivot_column) = (0..rhs).find(|j| tableau[num_s1_moves][*j] < -SIMPLEX_EPSILON) {
+        let mut pivot_row = None;
+        let mut best_ratio = f64::MAX;
+        for i in 0..num_s1_moves {
+            if tableau[i][pivot_column] > SIMPLEX_EPSILON {
+                let ratio = tableau[i][rhs] / tableau[i][pivot_column];
+                if ratio < best_ratio - SIMPLEX_EPSILON
+                    || (ratio < best_ratio + SIMPLEX_EPSILON
+                        && basis[i] < basis[pivot_row.unwrap()])
+                {
+                    best_ratio = ratio;
+                    pivot_row = Some(i);
+                }
+            }
+        }
+        // the LP is bounded because every payoff is positive, so there is always a pivot row
+        let pivot_row = pivot_row.unwrap();
+
+        let pivot = tableau[pivot_row][pivot_column];
+        for value in tableau[pivot_row].iter_mut() {
+            *value /= pivot;
+        }
+        let pivot_values = tableau[pivot_row].clone();
+        for (i, row) in tableau.iter_mut().enumerate() {
+            let factor = row[pivot_column];
+            if i != pivot_row && factor != 0.0 {
+                for (value, pivot_value) in row.iter_mut().zip(pivot_values.iter()) {
+                    *value -= factor * pivot_value;
+                }
+            }
+        }
+        basis[pivot_row] = pivot_column;
+    }
+
+    let mut s2 = vec![0.0; num_s2_moves];
+    for (i, variable) in basis.iter().enumerate() {
+        if *variable < num_s2_moves {
+            s2[*variable] = tableau[i][rhs];
+        }
+    }
+    let s1: Vec<f64> = (0..num_s1_moves)
+        .map(|i| tableau[num_s1_moves][num_s2_moves + i])
+        .collect();
+    let shifted_value = 1.0 / tableau[num_s1_moves][rhs];
+
+    NashEquilibrium {
+        s1: s1.iter().map(|p| (p * shifted_value) as f32).collect(),
+        s2: s2.iter().map(|p| (p * shifted_value) as f32).collect(),
+        value: (shifted_value - shift) as f32,
+    }
+}
+
 fn re_order_moves_for_iterative_deepening(
     last_search_result: &Vec<f32>,
     side_one_options: Vec<MoveChoice>,
@@ -164,15 +447,38 @@ pub fn iterative_deepen_expectiminimax(
     side_two_options: Vec<MoveChoice>,
     max_time: Duration,
 ) -> (Vec<MoveChoice>, Vec<MoveChoice>, Vec<f32>, i8) {
+    iterative_deepen_expectiminimax_with_evaluator(
+        state,
+        side_one_options,
+        side_two_options,
+        max_time,
+        Arc::new(DefaultEvaluator),
+        None,
+    )
+}
+
+pub fn iterative_deepen_expectiminimax_with_evaluator<E: Evaluator + ?Sized + 'static>(
+    state: &mut State,
+    side_one_options: Vec<MoveChoice>,
+    side_two_options: Vec<MoveChoice>,
+    max_time: Duration,
+    evaluator: Arc<E>,
+    damage_bucket: Option<i16>,
+) -> (Vec<MoveChoice>, Vec<MoveChoice>, Vec<f32>, i8) {
+    state.rehash();
     let mut state_clone = state.clone();
+    let mut table = TranspositionTable::new(ITERATIVE_DEEPENING_TRANSPOSITION_TABLE_SIZE);
 
-    let mut result = expectiminimax_search(
+    let mut result = expectiminimax_search_with_table(
         state,
         1,
         side_one_options.clone(),
         side_two_options.clone(),
         true,
         &Arc::new(Mutex::new(true)),
+        &mut table,
+        evaluator.as_ref(),
+        damage_bucket,
     );
     let (mut re_ordered_s1_options, mut re_ordered_s2_options) =
         re_order_moves_for_iterative_deepening(&result, side_one_options, side_two_options);
@@ -191,13 +497,16 @@ pub fn iterative_deepen_expectiminimax(
         loop {
             let previous_result = result;
             i += 1;
-            result = expectiminimax_search(
+            result = expectiminimax_search_with_table(
                 &mut state_clone,
                 i,
                 re_ordered_s1_options.clone(),
                 re_ordered_s2_options.clone(),
                 true,
                 &running_clone,
+                &mut table,
+                evaluator.as_ref(),
+                damage_bucket,
             );
 
             // when we are told to stop, return the *previous* result.
@@ -3,9 +3,10 @@ use crate::engine::abilities::Abilities;
 use crate::engine::items::Items;
 use crate::engine::state::{Terrain, Weather};
 use crate::pokemon::PokemonName;
-use crate::state::{LastUsedMove, VolatileStatusDurations};
+use crate::rng::BattleRng;
+use crate::state::{LastUsedMove, StateHash, VolatileStatusDurations};
 use crate::state::{
-    Move, Pokemon, PokemonIndex, PokemonMoves, PokemonNature, PokemonStatus, PokemonType, Side,
+    Move, Pokemon, PokemonGender, PokemonIndex, PokemonMoves, PokemonNature, PokemonStatus, PokemonType, Side,
     SideConditions, SidePokemon, State, StateTerrain, StateTrickRoom, StateWeather,
 };
 use deunicode::deunicode;
@@ -23,27 +24,32 @@ struct PackedTeamEntry {
 }
 
 #[derive(Deserialize)]
-struct PokemonDexEntry {
-    name: String,
-    types: Vec<String>,
+pub struct PokemonDexEntry {
+    pub name: String,
+    pub types: Vec<String>,
     #[serde(rename = "baseStats")]
-    base_stats: BaseStats,
-    weightkg: f32,
+    pub base_stats: BaseStats,
+    pub weightkg: f32,
+    #[serde(default)]
+    pub abilities: HashMap<String, String>,
+    // only present for species that are always the same gender, `N` for genderless
+    #[serde(default)]
+    pub gender: Option<String>,
 }
 
 #[derive(Deserialize)]
-struct BaseStats {
-    hp: u16,
-    attack: u16,
-    defense: u16,
+pub struct BaseStats {
+    pub hp: u16,
+    pub attack: u16,
+    pub defense: u16,
     #[serde(rename = "special-attack")]
-    special_attack: u16,
+    pub special_attack: u16,
     #[serde(rename = "special-defense")]
-    special_defense: u16,
-    speed: u16,
+    pub special_defense: u16,
+    pub speed: u16,
 }
 
-fn normalize_name(name: &str) -> String {
+pub fn normalize_name(name: &str) -> String {
     deunicode(name) // Handle unicode characters
         .replace(" ", "")
         .replace("-", "")
@@ -72,6 +78,7 @@ struct PackedPokemon {
     shiny: bool,
     level: u8,
     tera_type: String,
+    gigantamax: bool,
 }
 
 #[derive(Deserialize)]
@@ -136,6 +143,9 @@ fn parse_packed_team(packed_team: &str) -> Vec<PackedPokemon> {
                 "".to_string()
             };
 
+            // the gigantamax flag is the fourth comma-separated value in the last part
+            let gigantamax = parts.last().unwrap().split(',').nth(3) == Some("G");
+
             PackedPokemon {
                 nickname: normalize_name(parts[0]),
                 species: if parts[1].is_empty() {
@@ -157,6 +167,7 @@ fn parse_packed_team(packed_team: &str) -> Vec<PackedPokemon> {
                 shiny: parts.len() > 9 && parts[9] == "S",
                 level,
                 tera_type,
+                gigantamax,
             }
         })
         .collect()
@@ -216,6 +227,16 @@ fn create_pokemon(
         }
     }
 
+    // every pokemon is level 100
+    let stat_value = |base: u16, stat_index: usize| {
+        2 * base + packed.ivs[stat_index] as u16 + packed.evs[stat_index] as u16 / 4
+    };
+    let maxhp = (stat_value(dex_entry.base_stats.hp, 0) + 110) as i16;
+    let gender = match dex_entry.gender.as_deref() {
+        Some(gender) if packed.gender.is_empty() => gender,
+        _ => packed.gender.as_str(),
+    };
+
     // Rest of the Pokemon creation remains the same
     Pokemon {
         id: PokemonName::from_str(
@@ -229,10 +250,8 @@ fn create_pokemon(
         level: 100,
         types: (type1, type2),
         base_types: (type1, type2),
-        hp: ((2 * dex_entry.base_stats.hp + 31 + (packed.evs[0] as u16 / 4)) * 100 / 100 + 110)
-            as i16,
-        maxhp: ((2 * dex_entry.base_stats.hp + 31 + (packed.evs[0] as u16 / 4)) * 100 / 100 + 110)
-            as i16,
+        hp: maxhp,
+        maxhp,
         ability: Abilities::from_str(&normalize_name(&packed.ability)).unwrap_or(Abilities::NONE),
         base_ability: Abilities::from_str(&normalize_name(&packed.ability))
             .unwrap_or(Abilities::NONE),
@@ -247,24 +266,24 @@ fn create_pokemon(
             packed.evs[4],
             packed.evs[5],
         ),
-        attack: ((2 * dex_entry.base_stats.attack + 31 + (packed.evs[1] as u16 / 4)) * 100 / 100
-            + 5) as i16,
-        defense: ((2 * dex_entry.base_stats.defense + 31 + (packed.evs[2] as u16 / 4)) * 100 / 100
-            + 5) as i16,
-        special_attack: ((2 * dex_entry.base_stats.special_attack
-            + 31
-            + (packed.evs[3] as u16 / 4))
-            * 100
-            / 100
-            + 5) as i16,
-        special_defense: ((2 * dex_entry.base_stats.special_defense
-            + 31
-            + (packed.evs[4] as u16 / 4))
-            * 100
-            / 100
-            + 5) as i16,
-        speed: ((2 * dex_entry.base_stats.speed + 31 + (packed.evs[5] as u16 / 4)) * 100 / 100 + 5)
-            as i16,
+        ivs: (
+            packed.ivs[0],
+            packed.ivs[1],
+            packed.ivs[2],
+            packed.ivs[3],
+            packed.ivs[4],
+            packed.ivs[5],
+        ),
+        gender: match gender {
+            "M" => PokemonGender::MALE,
+            "F" => PokemonGender::FEMALE,
+            _ => PokemonGender::GENDERLESS,
+        },
+        attack: (stat_value(dex_entry.base_stats.attack, 1) + 5) as i16,
+        defense: (stat_value(dex_entry.base_stats.defense, 2) + 5) as i16,
+        special_attack: (stat_value(dex_entry.base_stats.special_attack, 3) + 5) as i16,
+        special_defense: (stat_value(dex_entry.base_stats.special_defense, 4) + 5) as i16,
+        speed: (stat_value(dex_entry.base_stats.speed, 5) + 5) as i16,
         status: PokemonStatus::NONE,
         rest_turns: 0,
         sleep_turns: 0,
@@ -272,6 +291,7 @@ fn create_pokemon(
         terastallized: false,
         tera_type: PokemonType::from_str(&normalize_name(&packed.tera_type))
             .unwrap_or(PokemonType::NORMAL),
+        gigantamax: packed.gigantamax,
         moves,
     }
 }
@@ -280,6 +300,7 @@ pub fn initialize_battle_state(
     random_teams_json: &str,
     pokedex_json: &str,
     movedex_json: &str,
+    rng: &mut BattleRng,
 ) -> State {
     let random_teams: Vec<PackedTeamEntry> =
         serde_json::from_str(random_teams_json).expect("Failed to parse random teams JSON");
@@ -292,8 +313,7 @@ pub fn initialize_battle_state(
 
     // Team selection logic remains the same
     use rand::seq::SliceRandom;
-    let mut rng = rand::thread_rng();
-    let selected_teams: Vec<_> = random_teams.choose_multiple(&mut rng, 2).collect();
+    let selected_teams: Vec<_> = random_teams.choose_multiple(rng, 2).collect();
 
     let team1 = parse_packed_team(&selected_teams[0].packed_team);
     let team2 = parse_packed_team(&selected_teams[1].packed_team);
@@ -332,6 +352,8 @@ pub fn initialize_battle_state(
         force_switch: false,
         force_trapped: false,
         slow_uturn_move: false,
+        dynamax_used: false,
+        z_move_used: false,
         volatile_statuses: HashSet::new(),
         substitute_health: 0,
         attack_boost: 0,
@@ -344,6 +366,7 @@ pub fn initialize_battle_state(
         last_used_move: LastUsedMove::None,
         damage_dealt: Default::default(),
         switch_out_move_second_saved_move: Choices::NONE,
+        ally_position: None,
         volatile_status_durations: VolatileStatusDurations::default(),
     };
 
@@ -358,6 +381,8 @@ pub fn initialize_battle_state(
         force_switch: false,
         force_trapped: false,
         slow_uturn_move: false,
+        dynamax_used: false,
+        z_move_used: false,
         volatile_statuses: HashSet::new(),
         substitute_health: 0,
         attack_boost: 0,
@@ -370,6 +395,7 @@ pub fn initialize_battle_state(
         last_used_move: LastUsedMove::None,
         damage_dealt: Default::default(),
         switch_out_move_second_saved_move: Choices::NONE,
+        ally_position: None,
         volatile_status_durations: VolatileStatusDurations::default(),
     };
 
@@ -391,5 +417,6 @@ pub fn initialize_battle_state(
         team_preview: false,
         use_last_used_move: false,
         use_damage_dealt: false,
+        hash: StateHash::default(),
     }
 }
@@ -0,0 +1,942 @@
+use crate::choices::{Choices, MOVES};
+use crate::engine::abilities::Abilities;
+use crate::engine::items::Items;
+use crate::engine::state::{PokemonVolatileStatus, Terrain, Weather};
+use crate::pokemon::PokemonName;
+use crate::selfplay::initialization::{normalize_name, PokemonDexEntry};
+use crate::state::{
+    pokemon_index_iter, LastUsedMove, Pokemon, PokemonBoostableStat, PokemonIndex,
+    PokemonMoveIndex, PokemonStatus, PokemonType, Side, SideReference, State,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+// Random battle sets use 85 EVs in every stat
+const UNKNOWN_POKEMON_EVS: u8 = 85;
+const UNKNOWN_POKEMON_IVS: u16 = 31;
+
+/// What needs to be decided at a `DecisionPoint`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecisionKind {
+    TeamPreview,
+    // start of a turn: both sides choose a move or a switch
+    Turn,
+    // mid-turn replacement after a faint or a pivoting move
+    Switch,
+}
+
+#[derive(Debug, Clone)]
+pub struct DecisionPoint {
+    pub turn: u16,
+    pub kind: DecisionKind,
+    pub state: State,
+}
+
+/// A line of a battle log that could not be parsed
+///
+/// `line` starts at 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl LogParseError {
+    fn new(line: usize, message: String) -> LogParseError {
+        LogParseError { line, message }
+    }
+}
+
+impl fmt::Display for LogParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Rebuilds the engine `State` from a Pokemon Showdown battle log, one protocol line at a time
+///
+/// The team of `known_side` is given up front. Everything about the other side is learned from
+/// the log as it is revealed, and whatever has not been revealed is left unknown:
+///   - pokemon that have not been seen are `PokemonName::NONE` with full hp, so they are not
+///     mistaken for fainted pokemon. Slots past the size given by a `teamsize` line are empty:
+///     `PokemonName::NONE` with 0 hp
+///   - moves that have not been used are `Choices::NONE`
+///   - an item that has not been revealed is `Items::UNKNOWNITEM`
+///   - an ability that has not been revealed is `Abilities::NONE`, unless the species only
+///     has one possible ability
+///
+/// Stats for the unknown side are calculated assuming random battle spreads.
+/// Only singles logs are supported
+pub struct ShowdownLogParser {
+    pub state: State,
+    pub decision_points: Vec<DecisionPoint>,
+    pub turn: u16,
+    known_side: SideReference,
+    pokedex: HashMap<String, PokemonDexEntry>,
+    nicknames: HashMap<(SideReference, String), PokemonIndex>,
+    pivoting: [bool; 2],
+    baton_passing: [bool; 2],
+    single_turn_volatile_statuses: Vec<(SideReference, PokemonVolatileStatus)>,
+    replacement_recorded: bool,
+}
+
+fn side_number(side_ref: &SideReference) -> usize {
+    match side_ref {
+        SideReference::SideOne => 0,
+        SideReference::SideTwo => 1,
+    }
+}
+
+fn parse_side_reference(ident: &str) -> Result<SideReference, String> {
+    if ident.starts_with("p1") {
+        Ok(SideReference::SideOne)
+    } else if ident.starts_with("p2") {
+        Ok(SideReference::SideTwo)
+    } else {
+        Err(format!("Invalid side in pokemon identifier: {}", ident))
+    }
+}
+
+// "p1a: Nickname" -> (SideOne, "Nickname")
+fn parse_pokemon_ident(ident: &str) -> Result<(SideReference, String), String> {
+    let side_ref = parse_side_reference(ident)?;
+    match ident.find(": ") {
+        Some(index) => Ok((side_ref, ident[index + 2..].to_string())),
+        None => Err(format!("Invalid pokemon identifier: {}", ident)),
+    }
+}
+
+// the argument at `index` of a line split on `|`, where the command is at index 1
+fn arg<'a>(args: &[&'a str], index: usize) -> Result<&'a str, String> {
+    args.get(index)
+        .copied()
+        .ok_or_else(|| format!("Missing argument {} of {}", index, args[1]))
+}
+
+// "Garchomp, L84, F, tera:Fire" -> ("Garchomp", 84)
+fn parse_details(details: &str) -> (String, i8) {
+    let mut split = details.split(", ");
+    let species = split.next().unwrap_or("").to_string();
+    let level = split
+        .find(|s| s.starts_with('L'))
+        .and_then(|s| s[1..].parse::<i8>().ok())
+        .unwrap_or(100);
+    (species, level)
+}
+
+// "55/100 par" -> (55, 100, Some(PARALYZE)), "0 fnt" -> (0, 0, None)
+fn parse_hp_status(hp_status: &str) -> (i16, i16, Option<PokemonStatus>) {
+    let mut split = hp_status.split(' ');
+    let hp_string = split.next().unwrap_or("0");
+    let (hp, denominator) = match hp_string.find('/') {
+        Some(index) => (
+            hp_string[..index].parse::<i16>().unwrap_or(0),
+            hp_string[index + 1..].parse::<i16>().unwrap_or(100),
+        ),
+        None => (hp_string.parse::<i16>().unwrap_or(0), 0),
+    };
+    let status = split.next().map(parse_status);
+    (hp, denominator, status)
+}
+
+fn parse_status(status: &str) -> PokemonStatus {
+    match status {
+        "brn" => PokemonStatus::BURN,
+        "par" => PokemonStatus::PARALYZE,
+        "slp" => PokemonStatus::SLEEP,
+        "frz" => PokemonStatus::FREEZE,
+        "psn" => PokemonStatus::POISON,
+        "tox" => PokemonStatus::TOXIC,
+        _ => PokemonStatus::NONE,
+    }
+}
+
+fn parse_boostable_stat(stat: &str) -> Option<PokemonBoostableStat> {
+    match stat {
+        "atk" => Some(PokemonBoostableStat::Attack),
+        "def" => Some(PokemonBoostableStat::Defense),
+        "spa" => Some(PokemonBoostableStat::SpecialAttack),
+        "spd" => Some(PokemonBoostableStat::SpecialDefense),
+        "spe" => Some(PokemonBoostableStat::Speed),
+        "accuracy" => Some(PokemonBoostableStat::Accuracy),
+        "evasion" => Some(PokemonBoostableStat::Evasion),
+        _ => None,
+    }
+}
+
+fn parse_weather(weather: &str) -> Weather {
+    match weather {
+        "SunnyDay" => Weather::SUN,
+        "RainDance" => Weather::RAIN,
+        "Sandstorm" => Weather::SAND,
+        "Hail" => Weather::HAIL,
+        "Snow" | "Snowscape" => Weather::SNOW,
+        "DesolateLand" => Weather::HARSHSUN,
+        "PrimordialSea" => Weather::HEAVYRAIN,
+        _ => Weather::NONE,
+    }
+}
+
+fn parse_type(pokemon_type: &str) -> PokemonType {
+    PokemonType::from_str(&normalize_name(pokemon_type)).unwrap_or(PokemonType::TYPELESS)
+}
+
+fn parse_ability(ability: &str) -> Abilities {
+    Abilities::from_str(&normalize_name(ability)).unwrap_or(Abilities::NONE)
+}
+
+// the second type is `PokemonType::TYPELESS` for species with one type
+fn dex_entry_types(
+    species: &str,
+    dex_entry: &PokemonDexEntry,
+) -> Result<(PokemonType, PokemonType), String> {
+    match dex_entry.types.first() {
+        Some(first_type) => Ok((
+            parse_type(first_type),
+            match dex_entry.types.get(1) {
+                Some(second_type) => parse_type(second_type),
+                None => PokemonType::TYPELESS,
+            },
+        )),
+        None => Err(format!("Pokedex entry has no types: {}", species)),
+    }
+}
+
+// "move: Stealth Rock" -> "stealthrock"
+fn effect_name(effect: &str) -> String {
+    let effect = match effect.find(": ") {
+        Some(index) => &effect[index + 2..],
+        None => effect,
+    };
+    normalize_name(effect)
+}
+
+// finds the value of a tag such as `[from] item: Leftovers` among the remaining arguments
+fn find_tag<'a>(args: &[&'a str], tag: &str) -> Option<&'a str> {
+    args.iter()
+        .find(|a| a.starts_with(tag))
+        .map(|a| a[tag.len()..].trim())
+}
+
+fn boost_mut<'a>(side: &'a mut Side, stat: &PokemonBoostableStat) -> &'a mut i8 {
+    match stat {
+        PokemonBoostableStat::Attack => &mut side.attack_boost,
+        PokemonBoostableStat::Defense => &mut side.defense_boost,
+        PokemonBoostableStat::SpecialAttack => &mut side.special_attack_boost,
+        PokemonBoostableStat::SpecialDefense => &mut side.special_defense_boost,
+        PokemonBoostableStat::Speed => &mut side.speed_boost,
+        PokemonBoostableStat::Evasion => &mut side.evasion_boost,
+        PokemonBoostableStat::Accuracy => &mut side.accuracy_boost,
+    }
+}
+
+fn clear_boosts(side: &mut Side) {
+    side.attack_boost = 0;
+    side.defense_boost = 0;
+    side.special_attack_boost = 0;
+    side.special_defense_boost = 0;
+    side.speed_boost = 0;
+    side.accuracy_boost = 0;
+    side.evasion_boost = 0;
+}
+
+fn unknown_side(pokemon: Pokemon) -> Side {
+    let mut side = Side::default();
+    for pkmn_index in pokemon_index_iter() {
+        side.pokemon[pkmn_index] = pokemon.clone();
+    }
+    side
+}
+
+impl ShowdownLogParser {
+    pub fn new(
+        known_side: SideReference,
+        known_team: &Side,
+        pokedex_json: &str,
+    ) -> Result<ShowdownLogParser, String> {
+        let pokedex: HashMap<String, PokemonDexEntry> = serde_json::from_str(pokedex_json)
+            .map_err(|e| format!("Invalid pokedex JSON: {}", e))?;
+
+        let unknown_pokemon = Pokemon {
+            id: PokemonName::NONE,
+            item: Items::UNKNOWNITEM,
+            ..Pokemon::default()
+        };
+        let mut state = State::default();
+        match known_side {
+            SideReference::SideOne => {
+                state.side_one = known_team.clone();
+                state.side_two = unknown_side(unknown_pokemon);
+            }
+            SideReference::SideTwo => {
+                state.side_one = unknown_side(unknown_pokemon);
+                state.side_two = known_team.clone();
+            }
+        }
+
+        Ok(ShowdownLogParser {
+            state,
+            decision_points: vec![],
+            turn: 0,
+            known_side,
+            pokedex,
+            nicknames: HashMap::new(),
+            pivoting: [false, false],
+            baton_passing: [false, false],
+            single_turn_volatile_statuses: vec![],
+            replacement_recorded: false,
+        })
+    }
+
+    /// Parses every line of `log`, stopping at the first line that cannot be parsed
+    pub fn parse_log(&mut self, log: &str) -> Result<(), LogParseError> {
+        for (index, line) in log.lines().enumerate() {
+            self.parse_line(line)
+                .map_err(|message| LogParseError::new(index + 1, message))?;
+        }
+        Ok(())
+    }
+
+    /// A line that cannot be parsed, such as one with missing arguments or an invalid pokemon
+    /// identifier, is an error. Lines with unknown commands are ignored
+    pub fn parse_line(&mut self, line: &str) -> Result<(), String> {
+        let args: Vec<&str> = line.split('|').collect();
+        if args.len() < 2 {
+            return Ok(());
+        }
+        let command = args[1];
+        if command != "switch" && command != "drag" && command != "replace" {
+            self.replacement_recorded = false;
+        }
+        match command {
+            "poke" => self.poke(&args)?,
+            "teamsize" => self.team_size(&args)?,
+            "teampreview" => {
+                let mut state = self.state.clone();
+                state.team_preview = true;
+                self.record_decision_point(DecisionKind::TeamPreview, state);
+            }
+            "switch" | "drag" | "replace" => self.switch(&args, command == "drag")?,
+            "move" => self.use_move(&args)?,
+            "cant" => {
+                if args.get(3) == Some(&"recharge") {
+                    let (side_ref, _) = parse_pokemon_ident(arg(&args, 2)?)?;
+                    self.state
+                        .get_side(&side_ref)
+                        .volatile_statuses
+                        .remove(&PokemonVolatileStatus::MUSTRECHARGE);
+                }
+            }
+            "faint" => {
+                let pkmn_index = self.pokemon_index(arg(&args, 2)?)?;
+                let (side_ref, _) = parse_pokemon_ident(arg(&args, 2)?)?;
+                self.state.get_side(&side_ref).pokemon[pkmn_index].hp = 0;
+            }
+            "-damage" | "-heal" | "-sethp" => {
+                self.set_hp(arg(&args, 2)?, arg(&args, 3)?)?;
+                self.reveal_from_tags(&args)?;
+            }
+            "-status" => {
+                let pkmn = self.pokemon_mut(arg(&args, 2)?)?;
+                pkmn.status = parse_status(arg(&args, 3)?);
+                pkmn.sleep_turns = 0;
+                self.reveal_from_tags(&args)?;
+            }
+            "-curestatus" => {
+                self.pokemon_mut(arg(&args, 2)?)?.status = PokemonStatus::NONE;
+            }
+            "-cureteam" => {
+                let side_ref = parse_side_reference(arg(&args, 2)?)?;
+                let side = self.state.get_side(&side_ref);
+                for pkmn_index in pokemon_index_iter() {
+                    side.pokemon[pkmn_index].status = PokemonStatus::NONE;
+                }
+            }
+            "-boost" | "-unboost" | "-setboost" => self.boost(&args)?,
+            "-clearboost" => {
+                let (side_ref, _) = parse_pokemon_ident(arg(&args, 2)?)?;
+                clear_boosts(self.state.get_side(&side_ref));
+            }
+            "-clearallboost" => {
+                clear_boosts(&mut self.state.side_one);
+                clear_boosts(&mut self.state.side_two);
+            }
+            "-clearnegativeboost" => {
+                let (side_ref, _) = parse_pokemon_ident(arg(&args, 2)?)?;
+                let side = self.state.get_side(&side_ref);
+                for stat in BOOSTABLE_STATS.iter() {
+                    let boost = boost_mut(side, stat);
+                    *boost = (*boost).max(0);
+                }
+            }
+            "-invertboost" => {
+                let (side_ref, _) = parse_pokemon_ident(arg(&args, 2)?)?;
+                let side = self.state.get_side(&side_ref);
+                for stat in BOOSTABLE_STATS.iter() {
+                    let boost = boost_mut(side, stat);
+                    *boost = -*boost;
+                }
+            }
+            "-weather" => self.weather(&args)?,
+            "-fieldstart" => self.field_start(arg(&args, 2)?),
+            "-fieldend" => self.field_end(arg(&args, 2)?),
+            "-sidestart" => self.side_start(arg(&args, 2)?, arg(&args, 3)?)?,
+            "-sideend" => self.side_end(arg(&args, 2)?, arg(&args, 3)?)?,
+            "-start" => self.start_volatile_status(&args)?,
+            "-end" => self.end_volatile_status(&args)?,
+            "-singleturn" => {
+                let (side_ref, _) = parse_pokemon_ident(arg(&args, 2)?)?;
+                if let Ok(volatile_status) =
+                    PokemonVolatileStatus::from_str(&effect_name(arg(&args, 3)?))
+                {
+                    if volatile_status != PokemonVolatileStatus::NONE {
+                        self.state
+                            .get_side(&side_ref)
+                            .volatile_statuses
+                            .insert(volatile_status);
+                        self.single_turn_volatile_statuses
+                            .push((side_ref, volatile_status));
+                    }
+                }
+            }
+            "-mustrecharge" => {
+                let (side_ref, _) = parse_pokemon_ident(arg(&args, 2)?)?;
+                self.state
+                    .get_side(&side_ref)
+                    .volatile_statuses
+                    .insert(PokemonVolatileStatus::MUSTRECHARGE);
+            }
+            "-item" => {
+                self.pokemon_mut(arg(&args, 2)?)?.item =
+                    Items::from_str(&normalize_name(arg(&args, 3)?)).unwrap_or(Items::UNKNOWNITEM);
+            }
+            "-enditem" => {
+                self.pokemon_mut(arg(&args, 2)?)?.item = Items::NONE;
+            }
+            "-ability" => {
+                let ability = parse_ability(arg(&args, 3)?);
+                self.pokemon_mut(arg(&args, 2)?)?.ability = ability;
+            }
+            "-terastallize" => {
+                let tera_type = parse_type(arg(&args, 3)?);
+                let pkmn = self.pokemon_mut(arg(&args, 2)?)?;
+                pkmn.terastallized = true;
+                pkmn.tera_type = tera_type;
+            }
+            "detailschange" | "-formechange" => {
+                self.forme_change(arg(&args, 2)?, arg(&args, 3)?)?
+            }
+            "turn" => {
+                self.end_of_turn();
+                self.turn = arg(&args, 2)?.parse::<u16>().unwrap_or(self.turn + 1);
+                let state = self.state.clone();
+                self.record_decision_point(DecisionKind::Turn, state);
+            }
+            _ => self.reveal_from_tags(&args)?,
+        }
+        Ok(())
+    }
+
+    fn record_decision_point(&mut self, kind: DecisionKind, state: State) {
+        self.decision_points.push(DecisionPoint {
+            turn: self.turn,
+            kind,
+            state,
+        });
+    }
+
+    fn poke(&mut self, args: &[&str]) -> Result<(), String> {
+        let side_ref = parse_side_reference(arg(args, 2)?)?;
+        if side_ref == self.known_side {
+            return Ok(());
+        }
+        let (species, level) = parse_details(arg(args, 3)?);
+        let pkmn_index = self.next_unknown_slot(&side_ref)?;
+        let pkmn = self.create_pokemon(&species, level)?;
+        self.state.get_side(&side_ref).pokemon[pkmn_index] = pkmn;
+        Ok(())
+    }
+
+    fn team_size(&mut self, args: &[&str]) -> Result<(), String> {
+        let side_ref = parse_side_reference(arg(args, 2)?)?;
+        if side_ref == self.known_side {
+            return Ok(());
+        }
+        let team_size = arg(args, 3)?
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid team size: {}", args[3]))?;
+        let side = self.state.get_side(&side_ref);
+        for pkmn_index in pokemon_index_iter().skip(team_size) {
+            if side.pokemon[pkmn_index].id == PokemonName::NONE {
+                side.pokemon[pkmn_index].hp = 0;
+            }
+        }
+        Ok(())
+    }
+
+    fn switch(&mut self, args: &[&str], dragged: bool) -> Result<(), String> {
+        let (side_ref, nickname) = parse_pokemon_ident(arg(args, 2)?)?;
+        let (species, level) = parse_details(arg(args, 3)?);
+        let side_number = side_number(&side_ref);
+
+        // a switch after a faint or a pivoting move is a decision made in the middle of the turn
+        // both sides replacing fainted pokemon at the same time is one decision
+        if self.turn > 0 && !dragged {
+            let active_fainted = self
+                .state
+                .get_side_immutable(&side_ref)
+                .get_active_immutable()
+                .hp
+                <= 0;
+            if (active_fainted || self.pivoting[side_number]) && !self.replacement_recorded {
+                let mut state = self.state.clone();
+                if !active_fainted {
+                    state.get_side(&side_ref).force_switch = true;
+                }
+                self.record_decision_point(DecisionKind::Switch, state);
+                self.replacement_recorded = true;
+            }
+        }
+        self.pivoting[side_number] = false;
+
+        let pkmn_index = match self.nicknames.get(&(side_ref, nickname.clone())) {
+            Some(pkmn_index) => *pkmn_index,
+            None => {
+                let pkmn_index = self.find_slot_for_species(&side_ref, &species, level)?;
+                self.nicknames.insert((side_ref, nickname), pkmn_index);
+                pkmn_index
+            }
+        };
+
+        let baton_passing = self.baton_passing[side_number];
+        self.baton_passing[side_number] = false;
+        let side = self.state.get_side(&side_ref);
+        if !baton_passing {
+            clear_boosts(side);
+            side.volatile_statuses.clear();
+            side.substitute_health = 0;
+        }
+        side.volatile_status_durations = Default::default();
+        side.active_index = pkmn_index;
+        side.last_used_move = LastUsedMove::Switch(pkmn_index);
+        self.single_turn_volatile_statuses
+            .retain(|(s, _)| *s != side_ref);
+
+        self.set_hp(arg(args, 2)?, arg(args, 4)?)
+    }
+
+    fn use_move(&mut self, args: &[&str]) -> Result<(), String> {
+        let (side_ref, _) = parse_pokemon_ident(arg(args, 2)?)?;
+        let move_id = Choices::from_str(&normalize_name(arg(args, 3)?)).unwrap_or(Choices::NONE);
+        let choice = match MOVES.get(&move_id) {
+            Some(choice) if move_id != Choices::NONE => choice,
+            _ => return Ok(()),
+        };
+        let side_number = side_number(&side_ref);
+        self.pivoting[side_number] = choice.flags.pivot;
+        self.baton_passing[side_number] = move_id == Choices::BATONPASS;
+
+        // moves called by another move (Sleep Talk, Metronome, etc.) are not part of the moveset
+        if find_tag(args, "[from]").is_some() {
+            return Ok(());
+        }
+        let side = self.state.get_side(&side_ref);
+        let pkmn = side.get_active();
+        let mut move_index = None;
+        for (index, mv) in [
+            PokemonMoveIndex::M0,
+            PokemonMoveIndex::M1,
+            PokemonMoveIndex::M2,
+            PokemonMoveIndex::M3,
+        ]
+        .iter()
+        .zip(
+            [
+                pkmn.moves.m0.id,
+                pkmn.moves.m1.id,
+                pkmn.moves.m2.id,
+                pkmn.moves.m3.id,
+            ]
+            .iter(),
+        ) {
+            if *mv == move_id {
+                move_index = Some(*index);
+                break;
+            }
+            if *mv == Choices::NONE && move_index.is_none() {
+                move_index = Some(*index);
+            }
+        }
+        if let Some(move_index) = move_index {
+            if pkmn.moves[&move_index].id != move_id {
+                pkmn.replace_move(move_index, move_id);
+            }
+            pkmn.moves[&move_index].pp -= 1;
+            side.last_used_move = LastUsedMove::Move(move_index);
+        }
+        Ok(())
+    }
+
+    fn set_hp(&mut self, ident: &str, hp_status: &str) -> Result<(), String> {
+        let (hp, denominator, status) = parse_hp_status(hp_status);
+        let pkmn = self.pokemon_mut(ident)?;
+        if denominator == 0 || denominator == pkmn.maxhp {
+            pkmn.hp = hp;
+        } else {
+            pkmn.hp = (pkmn.maxhp as f32 * hp as f32 / denominator as f32).round() as i16;
+            if hp > 0 && pkmn.hp == 0 {
+                pkmn.hp = 1;
+            }
+        }
+        if let Some(status) = status {
+            pkmn.status = status;
+        }
+        Ok(())
+    }
+
+    fn boost(&mut self, args: &[&str]) -> Result<(), String> {
+        let (side_ref, _) = parse_pokemon_ident(arg(args, 2)?)?;
+        let stat = match parse_boostable_stat(arg(args, 3)?) {
+            Some(stat) => stat,
+            None => return Ok(()),
+        };
+        let amount = arg(args, 4)?.parse::<i8>().unwrap_or(0);
+        let boost = boost_mut(self.state.get_side(&side_ref), &stat);
+        *boost = match args[1] {
+            "-boost" => *boost + amount,
+            "-unboost" => *boost - amount,
+            _ => amount,
+        }
+        .clamp(-6, 6);
+        self.reveal_from_tags(args)
+    }
+
+    fn weather(&mut self, args: &[&str]) -> Result<(), String> {
+        let weather_type = parse_weather(arg(args, 2)?);
+        if find_tag(args, "[upkeep]").is_some() {
+            if self.state.weather.turns_remaining > 0 {
+                self.state.weather.turns_remaining -= 1;
+            }
+            return Ok(());
+        }
+        self.state.weather.weather_type = weather_type;
+        self.state.weather.turns_remaining = match weather_type {
+            Weather::NONE => 0,
+            // primal weather does not end on its own
+            Weather::HARSHSUN | Weather::HEAVYRAIN => -1,
+            _ => 5,
+        };
+        self.reveal_from_tags(args)
+    }
+
+    fn field_start(&mut self, field: &str) {
+        match effect_name(field).as_str() {
+            "trickroom" => {
+                self.state.trick_room.active = true;
+                self.state.trick_room.turns_remaining = 5;
+            }
+            terrain => {
+                let terrain_type = Terrain::from_str(terrain).unwrap_or(Terrain::NONE);
+                if terrain_type != Terrain::NONE {
+                    self.state.terrain.terrain_type = terrain_type;
+                    self.state.terrain.turns_remaining = 5;
+                }
+            }
+        }
+    }
+
+    fn field_end(&mut self, field: &str) {
+        if effect_name(field) == "trickroom" {
+            self.state.trick_room.active = false;
+            self.state.trick_room.turns_remaining = 0;
+        } else {
+            self.state.terrain.terrain_type = Terrain::NONE;
+            self.state.terrain.turns_remaining = 0;
+        }
+    }
+
+    fn side_start(&mut self, side: &str, condition: &str) -> Result<(), String> {
+        let side_ref = parse_side_reference(side)?;
+        let side_conditions = &mut self.state.get_side(&side_ref).side_conditions;
+        match effect_name(condition).as_str() {
+            "stealthrock" => side_conditions.stealth_rock = 1,
+            "stickyweb" => side_conditions.sticky_web = 1,
+            "spikes" => side_conditions.spikes = (side_conditions.spikes + 1).min(3),
+            "toxicspikes" => {
+                side_conditions.toxic_spikes = (side_conditions.toxic_spikes + 1).min(2)
+            }
+            "reflect" => side_conditions.reflect = 5,
+            "lightscreen" => side_conditions.light_screen = 5,
+            "auroraveil" => side_conditions.aurora_veil = 5,
+            "safeguard" => side_conditions.safeguard = 5,
+            "mist" => side_conditions.mist = 5,
+            "tailwind" => side_conditions.tailwind = 4,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn side_end(&mut self, side: &str, condition: &str) -> Result<(), String> {
+        let side_ref = parse_side_reference(side)?;
+        let side_conditions = &mut self.state.get_side(&side_ref).side_conditions;
+        match effect_name(condition).as_str() {
+            "stealthrock" => side_conditions.stealth_rock = 0,
+            "stickyweb" => side_conditions.sticky_web = 0,
+            "spikes" => side_conditions.spikes = 0,
+            "toxicspikes" => side_conditions.toxic_spikes = 0,
+            "reflect" => side_conditions.reflect = 0,
+            "lightscreen" => side_conditions.light_screen = 0,
+            "auroraveil" => side_conditions.aurora_veil = 0,
+            "safeguard" => side_conditions.safeguard = 0,
+            "mist" => side_conditions.mist = 0,
+            "tailwind" => side_conditions.tailwind = 0,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn start_volatile_status(&mut self, args: &[&str]) -> Result<(), String> {
+        let (side_ref, _) = parse_pokemon_ident(arg(args, 2)?)?;
+        let effect = effect_name(arg(args, 3)?);
+        if effect == "typechange" {
+            if let Some(new_type) = args.get(4) {
+                let new_type = parse_type(new_type);
+                self.state.get_side(&side_ref).get_active().types =
+                    (new_type, PokemonType::TYPELESS);
+            }
+            return Ok(());
+        }
+        let volatile_status =
+            PokemonVolatileStatus::from_str(&effect).unwrap_or(PokemonVolatileStatus::NONE);
+        if volatile_status == PokemonVolatileStatus::NONE {
+            return Ok(());
+        }
+        let side = self.state.get_side(&side_ref);
+        side.volatile_statuses.insert(volatile_status);
+        if volatile_status == PokemonVolatileStatus::SUBSTITUTE {
+            side.substitute_health = side.get_active_immutable().maxhp / 4;
+        }
+        self.reveal_from_tags(args)
+    }
+
+    fn end_volatile_status(&mut self, args: &[&str]) -> Result<(), String> {
+        let (side_ref, _) = parse_pokemon_ident(arg(args, 2)?)?;
+        let volatile_status = PokemonVolatileStatus::from_str(&effect_name(arg(args, 3)?))
+            .unwrap_or(PokemonVolatileStatus::NONE);
+        let side = self.state.get_side(&side_ref);
+        side.volatile_statuses.remove(&volatile_status);
+        if volatile_status == PokemonVolatileStatus::SUBSTITUTE {
+            side.substitute_health = 0;
+        }
+        Ok(())
+    }
+
+    fn forme_change(&mut self, ident: &str, details: &str) -> Result<(), String> {
+        let (species, _) = parse_details(details);
+        let dex_types = match self.pokedex.get(&normalize_name(&species)) {
+            Some(entry) => Some(dex_entry_types(&species, entry)?),
+            None => None,
+        };
+        let pkmn = self.pokemon_mut(ident)?;
+        pkmn.id = PokemonName::from_str(&normalize_name(&species)).unwrap_or(PokemonName::NONE);
+        if let Some(types) = dex_types {
+            pkmn.types = types;
+            pkmn.base_types = types;
+        }
+        Ok(())
+    }
+
+    // `[from] item: X` and `[from] ability: X` reveal information about the pokemon
+    // in the `[of]` tag if there is one, otherwise about the pokemon the line is about
+    fn reveal_from_tags(&mut self, args: &[&str]) -> Result<(), String> {
+        let from = match find_tag(args, "[from]") {
+            Some(from) => from,
+            None => return Ok(()),
+        };
+        let ident = match find_tag(args, "[of]") {
+            Some(of) => of,
+            None => match args.get(2) {
+                Some(ident) if ident.contains(": ") => ident,
+                _ => return Ok(()),
+            },
+        };
+        if let Some(item) = from.strip_prefix("item: ") {
+            let item = Items::from_str(&normalize_name(item)).unwrap_or(Items::UNKNOWNITEM);
+            self.pokemon_mut(ident)?.item = item;
+        } else if let Some(ability) = from.strip_prefix("ability: ") {
+            let ability = parse_ability(ability);
+            self.pokemon_mut(ident)?.ability = ability;
+        }
+        Ok(())
+    }
+
+    // turn-based effects are decremented here because Showdown does not announce them
+    // until they end
+    fn end_of_turn(&mut self) {
+        for (side_ref, volatile_status) in self.single_turn_volatile_statuses.drain(..) {
+            self.state
+                .get_side(&side_ref)
+                .volatile_statuses
+                .remove(&volatile_status);
+        }
+        if self.turn == 0 {
+            return;
+        }
+        if self.state.terrain.turns_remaining > 0 {
+            self.state.terrain.turns_remaining -= 1;
+        }
+        if self.state.trick_room.turns_remaining > 0 {
+            self.state.trick_room.turns_remaining -= 1;
+        }
+        for side_ref in [SideReference::SideOne, SideReference::SideTwo].iter() {
+            let side_conditions = &mut self.state.get_side(side_ref).side_conditions;
+            for turns_remaining in [
+                &mut side_conditions.reflect,
+                &mut side_conditions.light_screen,
+                &mut side_conditions.aurora_veil,
+                &mut side_conditions.safeguard,
+                &mut side_conditions.mist,
+                &mut side_conditions.tailwind,
+            ] {
+                if *turns_remaining > 0 {
+                    *turns_remaining -= 1;
+                }
+            }
+        }
+        self.pivoting = [false, false];
+    }
+
+    fn pokemon_index(&self, ident: &str) -> Result<PokemonIndex, String> {
+        let (side_ref, nickname) = parse_pokemon_ident(ident)?;
+        match self.nicknames.get(&(side_ref, nickname)) {
+            Some(pkmn_index) => Ok(*pkmn_index),
+            // a pokemon that has not switched in yet can only be the active one
+            None => Ok(self.state.get_side_immutable(&side_ref).active_index),
+        }
+    }
+
+    fn pokemon_mut(&mut self, ident: &str) -> Result<&mut Pokemon, String> {
+        let (side_ref, _) = parse_pokemon_ident(ident)?;
+        let pkmn_index = self.pokemon_index(ident)?;
+        Ok(&mut self.state.get_side(&side_ref).pokemon[pkmn_index])
+    }
+
+    fn next_unknown_slot(&self, side_ref: &SideReference) -> Result<PokemonIndex, String> {
+        let side = self.state.get_side_immutable(side_ref);
+        pokemon_index_iter()
+            .find(|i| side.pokemon[*i].id == PokemonName::NONE && side.pokemon[*i].hp > 0)
+            .ok_or_else(|| {
+                format!(
+                    "More pokemon revealed than fit on the team for {:?}",
+                    side_ref
+                )
+            })
+    }
+
+    fn find_slot_for_species(
+        &mut self,
+        side_ref: &SideReference,
+        species: &str,
+        level: i8,
+    ) -> Result<PokemonIndex, String> {
+        let pokemon_name =
+            PokemonName::from_str(&normalize_name(species)).unwrap_or(PokemonName::NONE);
+        let side = self.state.get_side_immutable(side_ref);
+        let already_seen: Vec<PokemonIndex> = self
+            .nicknames
+            .iter()
+            .filter(|((s, _), _)| s == side_ref)
+            .map(|(_, i)| *i)
+            .collect();
+        if let Some(pkmn_index) = pokemon_index_iter()
+            .find(|i| side.pokemon[*i].id == pokemon_name && !already_seen.contains(i))
+        {
+            return Ok(pkmn_index);
+        }
+        if *side_ref == self.known_side {
+            return Err(format!("{} is not on the known team", species));
+        }
+        let pkmn_index = self.next_unknown_slot(side_ref)?;
+        let pkmn = self.create_pokemon(species, level)?;
+        self.state.get_side(side_ref).pokemon[pkmn_index] = pkmn;
+        Ok(pkmn_index)
+    }
+
+    fn create_pokemon(&self, species: &str, level: i8) -> Result<Pokemon, String> {
+        let id = PokemonName::from_str(&normalize_name(species)).unwrap_or(PokemonName::NONE);
+        let dex_entry = match self.pokedex.get(&normalize_name(species)) {
+            Some(dex_entry) => dex_entry,
+            None => return Err(format!("Pokemon not found in pokedex: {}", species)),
+        };
+        let types = dex_entry_types(species, dex_entry)?;
+        let ability = match dex_entry.abilities.len() {
+            1 => match dex_entry.abilities.get("0") {
+                Some(ability) => parse_ability(ability),
+                None => return Err(format!("Pokedex entry has no first ability: {}", species)),
+            },
+            _ => Abilities::NONE,
+        };
+
+        let level_multiplier = level as u16;
+        let ev_bonus = UNKNOWN_POKEMON_EVS as u16 / 4;
+        let stat = |base: u16| -> i16 {
+            ((2 * base + UNKNOWN_POKEMON_IVS + ev_bonus) * level_multiplier / 100 + 5) as i16
+        };
+        let maxhp = ((2 * dex_entry.base_stats.hp + UNKNOWN_POKEMON_IVS + ev_bonus)
+            * level_multiplier
+            / 100
+            + level_multiplier
+            + 10) as i16;
+
+        Ok(Pokemon {
+            id,
+            level,
+            types,
+            base_types: types,
+            hp: maxhp,
+            maxhp,
+            ability,
+            base_ability: ability,
+            item: Items::UNKNOWNITEM,
+            evs: (
+                UNKNOWN_POKEMON_EVS,
+                UNKNOWN_POKEMON_EVS,
+                UNKNOWN_POKEMON_EVS,
+                UNKNOWN_POKEMON_EVS,
+                UNKNOWN_POKEMON_EVS,
+                UNKNOWN_POKEMON_EVS,
+            ),
+            attack: stat(dex_entry.base_stats.attack),
+            defense: stat(dex_entry.base_stats.defense),
+            special_attack: stat(dex_entry.base_stats.special_attack),
+            special_defense: stat(dex_entry.base_stats.special_defense),
+            speed: stat(dex_entry.base_stats.speed),
+            weight_kg: dex_entry.weightkg,
+            tera_type: types.0,
+            ..Pokemon::default()
+        })
+    }
+}
+
+const BOOSTABLE_STATS: [PokemonBoostableStat; 7] = [
+    PokemonBoostableStat::Attack,
+    PokemonBoostableStat::Defense,
+    PokemonBoostableStat::SpecialAttack,
+    PokemonBoostableStat::SpecialDefense,
+    PokemonBoostableStat::Speed,
+    PokemonBoostableStat::Accuracy,
+    PokemonBoostableStat::Evasion,
+];
+
+/// Parses a full battle log and returns the `State` at every decision point in the battle
+pub fn parse_battle_log(
+    log: &str,
+    known_side: SideReference,
+    known_team: &Side,
+    pokedex_json: &str,
+) -> Result<Vec<DecisionPoint>, String> {
+    let mut parser = ShowdownLogParser::new(known_side, known_team, pokedex_json)?;
+    parser.parse_log(log).map_err(|e| e.to_string())?;
+    Ok(parser.decision_points)
+}
@@ -0,0 +1,446 @@
+use crate::choices::Choices;
+use crate::engine::abilities::Abilities;
+use crate::engine::battle_environment::{
+    create_pokemon, MoveDexEntry, PackedPokemon, PokemonDexEntry,
+};
+use crate::engine::items::Items;
+use crate::pokemon::PokemonName;
+use crate::selfplay::initialization::normalize_name;
+use crate::state::{
+    pokemon_index_iter, Pokemon, PokemonGender, PokemonNature, PokemonType, Side, SidePokemon,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+const EV_STATS: [&str; 6] = ["HP", "Atk", "Def", "SpA", "SpD", "Spe"];
+const MAX_TEAM_SIZE: usize = 6;
+
+// competitive hidden power sets always have the highest base power
+#[cfg(any(
+    feature = "gen1",
+    feature = "gen2",
+    feature = "gen3",
+    feature = "gen4",
+    feature = "gen5"
+))]
+const HIDDEN_POWER_BASE_POWER: &str = "70";
+#[cfg(not(any(
+    feature = "gen1",
+    feature = "gen2",
+    feature = "gen3",
+    feature = "gen4",
+    feature = "gen5"
+)))]
+const HIDDEN_POWER_BASE_POWER: &str = "60";
+
+/// A problem with one line of a team in Showdown's export format
+///
+/// `line` starts at 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeamParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl TeamParseError {
+    fn new(line: usize, message: String) -> TeamParseError {
+        TeamParseError { line, message }
+    }
+}
+
+impl fmt::Display for TeamParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+// the line of the `Species @ Item` header each pokemon was parsed from
+struct ParsedSet {
+    line: usize,
+    pokemon: PackedPokemon,
+}
+
+fn is_known_nature(nature: &str) -> bool {
+    (0..25u8).any(|n| format!("{:?}", PokemonNature::from(n)).to_lowercase() == nature)
+}
+
+// `Nickname (Species) (M) @ Item`, where everything but the species is optional
+fn parse_header(line: &str) -> Result<PackedPokemon, String> {
+    let (name, item) = match line.rsplit_once(" @ ") {
+        Some((name, item)) => (name.trim(), item.trim()),
+        None => (line.trim(), ""),
+    };
+    let item_id = normalize_name(item);
+    if !item_id.is_empty() && Items::from_str(&item_id).unwrap() == Items::UNKNOWNITEM {
+        return Err(format!("Unknown item: {}", item));
+    }
+
+    let (name, gender) = match name.strip_suffix(" (M)") {
+        Some(name) => (name, "M"),
+        None => match name.strip_suffix(" (F)") {
+            Some(name) => (name, "F"),
+            None => (name, ""),
+        },
+    };
+    let (nickname, species) = match name.strip_suffix(')').and_then(|n| n.rsplit_once(" (")) {
+        Some((nickname, species)) => (normalize_name(nickname), Some(normalize_name(species))),
+        None => (normalize_name(name), None),
+    };
+    let species_name = species.as_ref().unwrap_or(&nickname);
+    if species_name.is_empty() {
+        return Err("Missing species".to_string());
+    }
+    if PokemonName::from_str(species_name).unwrap() == PokemonName::NONE {
+        return Err(format!("Unknown species: {}", species_name));
+    }
+
+    Ok(PackedPokemon {
+        nickname,
+        species,
+        item: item_id,
+        ability: "".to_string(),
+        moves: vec![],
+        nature: "serious".to_string(),
+        evs: vec![0; 6],
+        ivs: vec![31; 6],
+        gender: gender.to_string(),
+        tera_type: "".to_string(),
+        gigantamax: false,
+    })
+}
+
+// `252 Atk / 4 SpD / 252 Spe`, stats that are not listed are `unlisted`
+fn parse_stat_spread(spread: &str, name: &str, unlisted: u8, max: u8) -> Result<Vec<u8>, String> {
+    let mut parsed = vec![unlisted; 6];
+    for value in spread.split('/') {
+        let (amount, stat) = value
+            .trim()
+            .split_once(' ')
+            .ok_or_else(|| format!("Expected `<amount> <stat>`, got `{}`", value.trim()))?;
+        let stat_index = EV_STATS
+            .iter()
+            .position(|s| s.eq_ignore_ascii_case(stat.trim()))
+            .ok_or_else(|| format!("Unknown stat: {}", stat.trim()))?;
+        parsed[stat_index] = match amount.parse::<u8>() {
+            Ok(amount) if amount <= max => amount,
+            _ => return Err(format!("Invalid {} amount: {}", name, amount)),
+        };
+    }
+    Ok(parsed)
+}
+
+fn parse_evs(evs: &str) -> Result<Vec<u8>, String> {
+    let parsed = parse_stat_spread(evs, "EV", 0, 255)?;
+    if parsed.iter().map(|ev| *ev as u16).sum::<u16>() > 510 {
+        return Err("EVs add up to more than 510".to_string());
+    }
+    Ok(parsed)
+}
+
+fn parse_attribute(pokemon: &mut PackedPokemon, line: &str) -> Result<(), String> {
+    if let Some(move_name) = line.strip_prefix('-') {
+        // `Hidden Power [Fire]` is the move `hiddenpowerfire60`
+        let mut move_id = normalize_name(&move_name.replace(['[', ']'], ""));
+        if move_id.starts_with("hiddenpower") && move_id != "hiddenpower" {
+            move_id.push_str(HIDDEN_POWER_BASE_POWER);
+        }
+        if Choices::from_str(&move_id).unwrap() == Choices::NONE {
+            return Err(format!("Unknown move: {}", move_name.trim()));
+        }
+        if pokemon.moves.len() == 4 {
+            return Err("A pokemon cannot have more than 4 moves".to_string());
+        }
+        pokemon.moves.push(move_id);
+        return Ok(());
+    }
+    if let Some(nature) = line
+        .strip_suffix(" Nature")
+        .or_else(|| line.strip_suffix(" nature"))
+    {
+        let nature_id = normalize_name(nature);
+        if !is_known_nature(&nature_id) {
+            return Err(format!("Unknown nature: {}", nature));
+        }
+        pokemon.nature = nature_id;
+        return Ok(());
+    }
+
+    let (key, value) = line
+        .split_once(':')
+        .ok_or_else(|| format!("Unrecognized line: {}", line))?;
+    let value = value.trim();
+    match key.trim().to_lowercase().as_str() {
+        "ability" => {
+            let ability = normalize_name(value);
+            if Abilities::from_str(&ability).unwrap() == Abilities::NONE {
+                return Err(format!("Unknown ability: {}", value));
+            }
+            pokemon.ability = ability;
+        }
+        "tera type" => {
+            let tera_type = normalize_name(value);
+            if PokemonType::from_str(&tera_type).unwrap() == PokemonType::TYPELESS {
+                return Err(format!("Unknown type: {}", value));
+            }
+            pokemon.tera_type = tera_type;
+        }
+        "gigantamax" => match value.to_lowercase().as_str() {
+            "yes" => pokemon.gigantamax = true,
+            "no" => pokemon.gigantamax = false,
+            _ => return Err(format!("Invalid gigantamax: {}", value)),
+        },
+        "evs" => pokemon.evs = parse_evs(value)?,
+        "ivs" => pokemon.ivs = parse_stat_spread(value, "IV", 31, 31)?,
+        // the engine builds every pokemon at level 100,
+        // this is only checked so that a typo is still reported
+        "level" => match value.parse::<u8>() {
+            Ok(1..=100) => {}
+            _ => return Err(format!("Invalid level: {}", value)),
+        },
+        // cosmetic or unused by the engine
+        "shiny" | "happiness" | "dynamax level" | "pokeball" | "hidden power" => {}
+        _ => return Err(format!("Unrecognized line: {}", line)),
+    }
+    Ok(())
+}
+
+fn parse_sets(team: &str) -> Result<Vec<ParsedSet>, Vec<TeamParseError>> {
+    let mut sets: Vec<ParsedSet> = vec![];
+    let mut errors = vec![];
+    // whether the next non-empty line starts a new pokemon
+    let mut expecting_header = true;
+    // a pokemon whose header could not be parsed, its other lines are skipped
+    let mut skipping_set = false;
+
+    for (index, line) in team.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            expecting_header = true;
+            continue;
+        }
+        // `=== [gen9ou] Team Name ===` separates teams in a teambuilder backup
+        if line.starts_with("===") {
+            expecting_header = true;
+            continue;
+        }
+
+        if expecting_header {
+            expecting_header = false;
+            if sets.len() == MAX_TEAM_SIZE {
+                errors.push(TeamParseError::new(
+                    line_number,
+                    format!("A team cannot have more than {} pokemon", MAX_TEAM_SIZE),
+                ));
+                skipping_set = true;
+                continue;
+            }
+            match parse_header(line) {
+                Ok(pokemon) => {
+                    skipping_set = false;
+                    sets.push(ParsedSet {
+                        line: line_number,
+                        pokemon,
+                    });
+                }
+                Err(e) => {
+                    errors.push(TeamParseError::new(line_number, e));
+                    skipping_set = true;
+                }
+            }
+        } else if !skipping_set {
+            let pokemon = &mut sets.last_mut().unwrap().pokemon;
+            if let Err(e) = parse_attribute(pokemon, line) {
+                errors.push(TeamParseError::new(line_number, e));
+            }
+        }
+    }
+
+    if sets.is_empty() && errors.is_empty() {
+        errors.push(TeamParseError::new(1, "The team is empty".to_string()));
+    }
+    if errors.is_empty() {
+        Ok(sets)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parses a team written in Showdown's export format
+///
+/// ```text
+/// Garchomp @ Choice Scarf
+/// Ability: Rough Skin
+/// EVs: 252 Atk / 4 SpD / 252 Spe
+/// Jolly Nature
+/// - Earthquake
+/// - Outrage
+/// ```
+///
+/// Every line that could not be parsed is reported, not only the first one
+pub fn parse_showdown_team(team: &str) -> Result<Vec<PackedPokemon>, Vec<TeamParseError>> {
+    Ok(parse_sets(team)?.into_iter().map(|s| s.pokemon).collect())
+}
+
+/// Builds a `Side` from a team in Showdown's export format
+///
+/// A team with fewer than 6 pokemon is padded with fainted placeholder pokemon
+pub fn side_from_showdown_team(
+    team: &str,
+    pokedex: &HashMap<String, PokemonDexEntry>,
+    movedex: &HashMap<String, MoveDexEntry>,
+) -> Result<Side, Vec<TeamParseError>> {
+    let sets = parse_sets(team)?;
+    let errors: Vec<TeamParseError> = sets
+        .iter()
+        .filter_map(|set| {
+            let species = set
+                .pokemon
+                .species
+                .as_ref()
+                .unwrap_or(&set.pokemon.nickname);
+            if pokedex.contains_key(species) {
+                None
+            } else {
+                Some(TeamParseError::new(
+                    set.line,
+                    format!("Pokemon not found in pokedex: {}", species),
+                ))
+            }
+        })
+        .collect();
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut pokemon = SidePokemon {
+        p0: Pokemon::default(),
+        p1: Pokemon::default(),
+        p2: Pokemon::default(),
+        p3: Pokemon::default(),
+        p4: Pokemon::default(),
+        p5: Pokemon::default(),
+    };
+    for (pkmn_index, set) in
+        pokemon_index_iter().zip(sets.iter().map(Some).chain(std::iter::repeat(None)))
+    {
+        pokemon[pkmn_index] = match set {
+            Some(set) => create_pokemon(&set.pokemon, pokedex, movedex),
+            None => Pokemon {
+                hp: 0,
+                ..Pokemon::default()
+            },
+        };
+    }
+    Ok(Side {
+        pokemon,
+        ..Side::default()
+    })
+}
+
+fn capitalize(name: &str) -> String {
+    let lowercase = name.to_lowercase();
+    let mut chars = lowercase.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => lowercase,
+    }
+}
+
+/// Writes one pokemon in Showdown's export format
+///
+/// Names are written as lowercase ids (e.g. `choicescarf`), which Showdown's importer accepts
+pub fn write_showdown_pokemon(pokemon: &Pokemon) -> String {
+    let mut lines = vec![];
+    let mut species = format!("{:?}", pokemon.id).to_lowercase();
+    match pokemon.gender {
+        PokemonGender::MALE => species.push_str(" (M)"),
+        PokemonGender::FEMALE => species.push_str(" (F)"),
+        PokemonGender::GENDERLESS => {}
+    }
+    if pokemon.item == Items::NONE || pokemon.item == Items::UNKNOWNITEM {
+        lines.push(species);
+    } else {
+        lines.push(format!(
+            "{} @ {}",
+            species,
+            format!("{:?}", pokemon.item).to_lowercase()
+        ));
+    }
+    if pokemon.ability != Abilities::NONE {
+        lines.push(format!(
+            "Ability: {}",
+            format!("{:?}", pokemon.ability).to_lowercase()
+        ));
+    }
+    if pokemon.level != 100 {
+        lines.push(format!("Level: {}", pokemon.level));
+    }
+    if pokemon.tera_type != PokemonType::TYPELESS {
+        lines.push(format!(
+            "Tera Type: {}",
+            capitalize(&format!("{:?}", pokemon.tera_type))
+        ));
+    }
+    if pokemon.gigantamax {
+        lines.push("Gigantamax: Yes".to_string());
+    }
+
+    let evs = [
+        pokemon.evs.0,
+        pokemon.evs.1,
+        pokemon.evs.2,
+        pokemon.evs.3,
+        pokemon.evs.4,
+        pokemon.evs.5,
+    ];
+    let evs: Vec<String> = evs
+        .iter()
+        .zip(EV_STATS.iter())
+        .filter(|(ev, _)| **ev > 0)
+        .map(|(ev, stat)| format!("{} {}", ev, stat))
+        .collect();
+    if !evs.is_empty() {
+        lines.push(format!("EVs: {}", evs.join(" / ")));
+    }
+    lines.push(format!(
+        "{} Nature",
+        capitalize(&format!("{:?}", pokemon.nature))
+    ));
+    let ivs = [
+        pokemon.ivs.0,
+        pokemon.ivs.1,
+        pokemon.ivs.2,
+        pokemon.ivs.3,
+        pokemon.ivs.4,
+        pokemon.ivs.5,
+    ];
+    let ivs: Vec<String> = ivs
+        .iter()
+        .zip(EV_STATS.iter())
+        .filter(|(iv, _)| **iv < 31)
+        .map(|(iv, stat)| format!("{} {}", iv, stat))
+        .collect();
+    if !ivs.is_empty() {
+        lines.push(format!("IVs: {}", ivs.join(" / ")));
+    }
+    for mv in pokemon.moves.into_iter() {
+        if mv.id != Choices::NONE {
+            lines.push(format!("- {}", format!("{:?}", mv.id).to_lowercase()));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Writes every pokemon of `side` in Showdown's export format
+///
+/// Placeholder pokemon (`PokemonName::NONE`) are skipped
+pub fn write_showdown_team(side: &Side) -> String {
+    side.pokemon
+        .into_iter()
+        .filter(|pokemon| pokemon.id != PokemonName::NONE)
+        .map(write_showdown_pokemon)
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
@@ -1,15 +1,19 @@
+use crate::battle_format::BattleFormat;
 use crate::choices::{Choice, Choices, MoveCategory, MOVES};
 use crate::define_enum_with_from_str;
 use crate::engine::abilities::Abilities;
 use crate::engine::items::Items;
 use crate::engine::state::{PokemonVolatileStatus, Terrain, Weather};
-use crate::instruction::{BoostInstruction, EnableMoveInstruction, Instruction};
+use crate::instruction::{BoostInstruction, EnableMoveInstruction, Instruction, StateInstructions};
 use crate::pokemon::PokemonName;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::fmt;
+use std::mem;
 use std::ops::{Index, IndexMut};
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, Serialize, Deserialize)]
 pub enum SideReference {
     SideOne,
     SideTwo,
@@ -23,7 +27,101 @@ impl SideReference {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+/// Why a serialized state could not be parsed, see `State::try_deserialize`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateParseError {
+    pub side: Option<SideReference>,
+    pub pokemon_index: Option<PokemonIndex>,
+    // nested fields are joined with a `.`, e.g. `m1.pp` for the pp of a pokemon's second move
+    pub field: String,
+    // empty if the field is missing
+    pub token: String,
+}
+
+impl StateParseError {
+    pub fn new(field: &str, token: &str) -> StateParseError {
+        StateParseError {
+            side: None,
+            pokemon_index: None,
+            field: field.to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    pub fn in_field(mut self, field: &str) -> StateParseError {
+        self.field = if self.field.is_empty() {
+            field.to_string()
+        } else {
+            format!("{}.{}", field, self.field)
+        };
+        self
+    }
+
+    pub fn in_pokemon(mut self, pokemon_index: PokemonIndex) -> StateParseError {
+        self.pokemon_index = Some(pokemon_index);
+        self
+    }
+
+    pub fn on_side(mut self, side_ref: SideReference) -> StateParseError {
+        self.side = Some(side_ref);
+        self
+    }
+}
+
+impl fmt::Display for StateParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.side {
+            Some(SideReference::SideOne) => write!(f, "side one, ")?,
+            Some(SideReference::SideTwo) => write!(f, "side two, ")?,
+            None => {}
+        }
+        if let Some(pokemon_index) = self.pokemon_index {
+            write!(f, "pokemon {}, ", pokemon_index.serialize())?;
+        }
+        if self.token.is_empty() {
+            write!(f, "missing {}", self.field)
+        } else {
+            write!(f, "invalid {}: {}", self.field, self.token)
+        }
+    }
+}
+
+fn state_field<'a>(
+    split: &[&'a str],
+    index: usize,
+    field: &str,
+) -> Result<&'a str, StateParseError> {
+    split
+        .get(index)
+        .copied()
+        .ok_or_else(|| StateParseError::new(field, ""))
+}
+
+fn parse_state_field<T: FromStr>(
+    split: &[&str],
+    index: usize,
+    field: &str,
+) -> Result<T, StateParseError> {
+    let token = state_field(split, index, field)?;
+    token
+        .parse::<T>()
+        .map_err(|_| StateParseError::new(field, token))
+}
+
+fn try_deserialize_volatile_statuses(
+    serialized: &str,
+) -> Result<HashSet<PokemonVolatileStatus>, StateParseError> {
+    let mut volatile_statuses = HashSet::new();
+    for item in serialized.split(":").filter(|item| !item.is_empty()) {
+        match PokemonVolatileStatus::from_str(item) {
+            Ok(volatile_status) => volatile_statuses.insert(volatile_status),
+            Err(_) => return Err(StateParseError::new("volatile_statuses", item)),
+        };
+    }
+    Ok(volatile_statuses)
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone, Serialize, Deserialize)]
 pub enum PokemonSideCondition {
     AuroraVeil,
     CraftyShield,
@@ -46,7 +144,7 @@ pub enum PokemonSideCondition {
     WideGuard,
 }
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
 pub enum LastUsedMove {
     Move(PokemonMoveIndex),
     Switch(PokemonIndex),
@@ -61,22 +159,24 @@ impl LastUsedMove {
         }
     }
     pub fn deserialize(serialized: &str) -> LastUsedMove {
-        let split: Vec<&str> = serialized.split(":").collect();
-        match split[0] {
-            "move" => {
-                if split[1] == "none" {
-                    LastUsedMove::None
-                } else {
-                    LastUsedMove::Move(PokemonMoveIndex::deserialize(split[1]))
-                }
-            }
-            "switch" => LastUsedMove::Switch(PokemonIndex::deserialize(split[1])),
-            _ => panic!("Invalid LastUsedMove: {}", serialized),
+        LastUsedMove::try_deserialize(serialized).unwrap_or_else(|e| panic!("{}", e))
+    }
+    pub fn try_deserialize(serialized: &str) -> Result<LastUsedMove, StateParseError> {
+        let invalid = || StateParseError::new("", serialized);
+        match serialized.split_once(":") {
+            Some(("move", "none")) => Ok(LastUsedMove::None),
+            Some(("move", move_index)) => PokemonMoveIndex::try_deserialize(move_index)
+                .map(LastUsedMove::Move)
+                .map_err(|_| invalid()),
+            Some(("switch", pkmn_index)) => PokemonIndex::try_deserialize(pkmn_index)
+                .map(LastUsedMove::Switch)
+                .map_err(|_| invalid()),
+            _ => Err(invalid()),
         }
     }
 }
 
-#[derive(Debug, Copy, PartialEq, Clone, Eq, Hash)]
+#[derive(Debug, Copy, PartialEq, Clone, Eq, Hash, Serialize, Deserialize)]
 pub enum PokemonMoveIndex {
     M0,
     M1,
@@ -93,17 +193,20 @@ impl PokemonMoveIndex {
         }
     }
     pub fn deserialize(serialized: &str) -> PokemonMoveIndex {
+        PokemonMoveIndex::try_deserialize(serialized).unwrap_or_else(|e| panic!("{}", e))
+    }
+    pub fn try_deserialize(serialized: &str) -> Result<PokemonMoveIndex, StateParseError> {
         match serialized {
-            "0" => PokemonMoveIndex::M0,
-            "1" => PokemonMoveIndex::M1,
-            "2" => PokemonMoveIndex::M2,
-            "3" => PokemonMoveIndex::M3,
-            _ => panic!("Invalid PokemonMoveIndex: {}", serialized),
+            "0" => Ok(PokemonMoveIndex::M0),
+            "1" => Ok(PokemonMoveIndex::M1),
+            "2" => Ok(PokemonMoveIndex::M2),
+            "3" => Ok(PokemonMoveIndex::M3),
+            _ => Err(StateParseError::new("move_index", serialized)),
         }
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum PokemonBoostableStat {
     Attack,
     Defense,
@@ -116,7 +219,7 @@ pub enum PokemonBoostableStat {
 
 define_enum_with_from_str! {
     #[repr(u8)]
-    #[derive(Debug, PartialEq, Copy, Clone)]
+    #[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
     PokemonStatus {
         NONE,
         BURN,
@@ -140,7 +243,34 @@ define_enum_with_from_str! {
 
 define_enum_with_from_str! {
     #[repr(u8)]
-    #[derive(Debug, PartialEq, Clone)]
+    #[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+    PokemonGender {
+        MALE,
+        FEMALE,
+        GENDERLESS,
+    }
+}
+
+impl Default for PokemonGender {
+    fn default() -> PokemonGender {
+        PokemonGender::GENDERLESS
+    }
+}
+
+impl PokemonGender {
+    // genderless pokemon are never the opposite gender of anything
+    pub fn is_opposite_of(&self, other: &PokemonGender) -> bool {
+        matches!(
+            (self, other),
+            (PokemonGender::MALE, PokemonGender::FEMALE)
+                | (PokemonGender::FEMALE, PokemonGender::MALE)
+        )
+    }
+}
+
+define_enum_with_from_str! {
+    #[repr(u8)]
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
     PokemonNature {
         HARDY,
         LONELY,
@@ -172,7 +302,7 @@ define_enum_with_from_str! {
 
 define_enum_with_from_str! {
     #[repr(u8)]
-    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
     PokemonType {
         NORMAL,
         FIRE,
@@ -271,7 +401,7 @@ impl<'a> IntoIterator for &'a PokemonMoves {
     }
 }
 
-#[derive(Debug, Copy, PartialEq, Clone, Eq, Hash)]
+#[derive(Debug, Copy, PartialEq, Clone, Eq, Hash, Serialize, Deserialize)]
 pub enum PokemonIndex {
     P0,
     P1,
@@ -292,14 +422,17 @@ impl PokemonIndex {
         }
     }
     pub fn deserialize(serialized: &str) -> PokemonIndex {
+        PokemonIndex::try_deserialize(serialized).unwrap_or_else(|e| panic!("{}", e))
+    }
+    pub fn try_deserialize(serialized: &str) -> Result<PokemonIndex, StateParseError> {
         match serialized {
-            "0" => PokemonIndex::P0,
-            "1" => PokemonIndex::P1,
-            "2" => PokemonIndex::P2,
-            "3" => PokemonIndex::P3,
-            "4" => PokemonIndex::P4,
-            "5" => PokemonIndex::P5,
-            _ => panic!("Invalid PokemonIndex: {}", serialized),
+            "0" => Ok(PokemonIndex::P0),
+            "1" => Ok(PokemonIndex::P1),
+            "2" => Ok(PokemonIndex::P2),
+            "3" => Ok(PokemonIndex::P3),
+            "4" => Ok(PokemonIndex::P4),
+            "5" => Ok(PokemonIndex::P5),
+            _ => Err(StateParseError::new("pokemon_index", serialized)),
         }
     }
 }
@@ -346,7 +479,7 @@ impl Iterator for PokemonIndexIterator {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SidePokemon {
     pub p0: Pokemon,
     pub p1: Pokemon,
@@ -505,32 +638,76 @@ impl Default for Side {
             damage_dealt: DamageDealt::default(),
             switch_out_move_second_saved_move: Choices::NONE,
             evasion_boost: 0,
+            dynamax_used: false,
+            z_move_used: false,
+            ally_position: None,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "SerializedMove", from = "SerializedMove")]
 pub struct Move {
     pub id: Choices,
     pub disabled: bool,
     pub pp: i8,
     pub choice: Choice,
 }
+
+// the `Choice` of a move always comes from `MOVES` for its id, so it is not compared
+impl PartialEq for Move {
+    fn eq(&self, other: &Move) -> bool {
+        self.id == other.id && self.disabled == other.disabled && self.pp == other.pp
+    }
+}
+
+// the `Choice` of a move is looked up from `MOVES` rather than written out, as in `Move::serialize`
+#[derive(Serialize, Deserialize)]
+struct SerializedMove {
+    id: Choices,
+    disabled: bool,
+    pp: i8,
+}
+
+impl From<Move> for SerializedMove {
+    fn from(m: Move) -> SerializedMove {
+        SerializedMove {
+            id: m.id,
+            disabled: m.disabled,
+            pp: m.pp,
+        }
+    }
+}
+
+impl From<SerializedMove> for Move {
+    fn from(m: SerializedMove) -> Move {
+        Move {
+            id: m.id,
+            disabled: m.disabled,
+            pp: m.pp,
+            choice: MOVES.get(&m.id).unwrap().to_owned(),
+        }
+    }
+}
 impl Move {
     pub fn serialize(&self) -> String {
         format!("{:?};{};{}", self.id, self.disabled, self.pp)
     }
     pub fn deserialize(serialized: &str) -> Move {
+        Move::try_deserialize(serialized).unwrap_or_else(|e| panic!("{}", e))
+    }
+    pub fn try_deserialize(serialized: &str) -> Result<Move, StateParseError> {
         let split: Vec<&str> = serialized.split(";").collect();
-        Move {
-            id: Choices::from_str(split[0]).unwrap(),
-            disabled: split[1].parse::<bool>().unwrap(),
-            pp: split[2].parse::<i8>().unwrap(),
+        let id: Choices = parse_state_field(&split, 0, "id")?;
+        Ok(Move {
+            id,
+            disabled: parse_state_field(&split, 1, "disabled")?,
+            pp: parse_state_field(&split, 2, "pp")?,
             choice: MOVES
-                .get(&Choices::from_str(split[0]).unwrap())
-                .unwrap()
+                .get(&id)
+                .ok_or_else(|| StateParseError::new("id", split[0]))?
                 .to_owned(),
-        }
+        })
     }
 }
 impl Default for Move {
@@ -544,7 +721,7 @@ impl Default for Move {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DamageDealt {
     pub damage: i16,
     pub move_category: MoveCategory,
@@ -561,7 +738,7 @@ impl Default for DamageDealt {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PokemonMoves {
     pub m0: Move,
     pub m1: Move,
@@ -569,7 +746,7 @@ pub struct PokemonMoves {
     pub m3: Move,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct SideConditions {
     pub aurora_veil: i8,
     pub crafty_shield: i8,
@@ -651,28 +828,31 @@ impl SideConditions {
         )
     }
     pub fn deserialize(serialized: &str) -> SideConditions {
+        SideConditions::try_deserialize(serialized).unwrap_or_else(|e| panic!("{}", e))
+    }
+    pub fn try_deserialize(serialized: &str) -> Result<SideConditions, StateParseError> {
         let split: Vec<&str> = serialized.split(";").collect();
-        SideConditions {
-            aurora_veil: split[0].parse::<i8>().unwrap(),
-            crafty_shield: split[1].parse::<i8>().unwrap(),
-            healing_wish: split[2].parse::<i8>().unwrap(),
-            light_screen: split[3].parse::<i8>().unwrap(),
-            lucky_chant: split[4].parse::<i8>().unwrap(),
-            lunar_dance: split[5].parse::<i8>().unwrap(),
-            mat_block: split[6].parse::<i8>().unwrap(),
-            mist: split[7].parse::<i8>().unwrap(),
-            protect: split[8].parse::<i8>().unwrap(),
-            quick_guard: split[9].parse::<i8>().unwrap(),
-            reflect: split[10].parse::<i8>().unwrap(),
-            safeguard: split[11].parse::<i8>().unwrap(),
-            spikes: split[12].parse::<i8>().unwrap(),
-            stealth_rock: split[13].parse::<i8>().unwrap(),
-            sticky_web: split[14].parse::<i8>().unwrap(),
-            tailwind: split[15].parse::<i8>().unwrap(),
-            toxic_count: split[16].parse::<i8>().unwrap(),
-            toxic_spikes: split[17].parse::<i8>().unwrap(),
-            wide_guard: split[18].parse::<i8>().unwrap(),
-        }
+        Ok(SideConditions {
+            aurora_veil: parse_state_field(&split, 0, "aurora_veil")?,
+            crafty_shield: parse_state_field(&split, 1, "crafty_shield")?,
+            healing_wish: parse_state_field(&split, 2, "healing_wish")?,
+            light_screen: parse_state_field(&split, 3, "light_screen")?,
+            lucky_chant: parse_state_field(&split, 4, "lucky_chant")?,
+            lunar_dance: parse_state_field(&split, 5, "lunar_dance")?,
+            mat_block: parse_state_field(&split, 6, "mat_block")?,
+            mist: parse_state_field(&split, 7, "mist")?,
+            protect: parse_state_field(&split, 8, "protect")?,
+            quick_guard: parse_state_field(&split, 9, "quick_guard")?,
+            reflect: parse_state_field(&split, 10, "reflect")?,
+            safeguard: parse_state_field(&split, 11, "safeguard")?,
+            spikes: parse_state_field(&split, 12, "spikes")?,
+            stealth_rock: parse_state_field(&split, 13, "stealth_rock")?,
+            sticky_web: parse_state_field(&split, 14, "sticky_web")?,
+            tailwind: parse_state_field(&split, 15, "tailwind")?,
+            toxic_count: parse_state_field(&split, 16, "toxic_count")?,
+            toxic_spikes: parse_state_field(&split, 17, "toxic_spikes")?,
+            wide_guard: parse_state_field(&split, 18, "wide_guard")?,
+        })
     }
 }
 impl Default for SideConditions {
@@ -701,7 +881,7 @@ impl Default for SideConditions {
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct StateWeather {
     pub weather_type: Weather,
     pub turns_remaining: i8,
@@ -711,15 +891,18 @@ impl StateWeather {
         format!("{:?};{}", self.weather_type, self.turns_remaining)
     }
     pub fn deserialize(serialized: &str) -> StateWeather {
+        StateWeather::try_deserialize(serialized).unwrap_or_else(|e| panic!("{}", e))
+    }
+    pub fn try_deserialize(serialized: &str) -> Result<StateWeather, StateParseError> {
         let split: Vec<&str> = serialized.split(";").collect();
-        StateWeather {
-            weather_type: Weather::from_str(split[0]).unwrap(),
-            turns_remaining: split[1].parse::<i8>().unwrap(),
-        }
+        Ok(StateWeather {
+            weather_type: parse_state_field(&split, 0, "weather_type")?,
+            turns_remaining: parse_state_field(&split, 1, "turns_remaining")?,
+        })
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct StateTerrain {
     pub terrain_type: Terrain,
     pub turns_remaining: i8,
@@ -729,15 +912,18 @@ impl StateTerrain {
         format!("{:?};{}", self.terrain_type, self.turns_remaining)
     }
     pub fn deserialize(serialized: &str) -> StateTerrain {
+        StateTerrain::try_deserialize(serialized).unwrap_or_else(|e| panic!("{}", e))
+    }
+    pub fn try_deserialize(serialized: &str) -> Result<StateTerrain, StateParseError> {
         let split: Vec<&str> = serialized.split(";").collect();
-        StateTerrain {
-            terrain_type: Terrain::from_str(split[0]).unwrap(),
-            turns_remaining: split[1].parse::<i8>().unwrap(),
-        }
+        Ok(StateTerrain {
+            terrain_type: parse_state_field(&split, 0, "terrain_type")?,
+            turns_remaining: parse_state_field(&split, 1, "turns_remaining")?,
+        })
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct StateTrickRoom {
     pub active: bool,
     pub turns_remaining: i8,
@@ -747,15 +933,18 @@ impl StateTrickRoom {
         format!("{};{}", self.active, self.turns_remaining)
     }
     pub fn deserialize(serialized: &str) -> StateTrickRoom {
+        StateTrickRoom::try_deserialize(serialized).unwrap_or_else(|e| panic!("{}", e))
+    }
+    pub fn try_deserialize(serialized: &str) -> Result<StateTrickRoom, StateParseError> {
         let split: Vec<&str> = serialized.split(";").collect();
-        StateTrickRoom {
-            active: split[0].parse::<bool>().unwrap(),
-            turns_remaining: split[1].parse::<i8>().unwrap(),
-        }
+        Ok(StateTrickRoom {
+            active: parse_state_field(&split, 0, "active")?,
+            turns_remaining: parse_state_field(&split, 1, "turns_remaining")?,
+        })
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VolatileStatusDurations {
     pub confusion: i8,
     pub encore: i8,
@@ -763,6 +952,8 @@ pub struct VolatileStatusDurations {
     pub slowstart: i8,
     pub taunt: i8,
     pub yawn: i8,
+    #[serde(default)]
+    pub dynamax: i8,
 }
 
 impl Default for VolatileStatusDurations {
@@ -774,6 +965,7 @@ impl Default for VolatileStatusDurations {
             slowstart: 0,
             taunt: 0,
             yawn: 0,
+            dynamax: 0,
         }
     }
 }
@@ -787,6 +979,7 @@ impl VolatileStatusDurations {
             ("slowstart", self.slowstart),
             ("taunt", self.taunt),
             ("yawn", self.yawn),
+            ("dynamax", self.dynamax),
         ];
 
         let mut output = String::new();
@@ -803,24 +996,58 @@ impl VolatileStatusDurations {
 
     pub fn serialize(&self) -> String {
         format!(
-            "{};{};{};{};{};{}",
-            self.confusion, self.encore, self.lockedmove, self.slowstart, self.taunt, self.yawn
+            "{};{};{};{};{};{};{}",
+            self.confusion,
+            self.encore,
+            self.lockedmove,
+            self.slowstart,
+            self.taunt,
+            self.yawn,
+            self.dynamax
         )
     }
     pub fn deserialize(serialized: &str) -> VolatileStatusDurations {
+        VolatileStatusDurations::try_deserialize(serialized).unwrap_or_else(|e| panic!("{}", e))
+    }
+    pub fn try_deserialize(serialized: &str) -> Result<VolatileStatusDurations, StateParseError> {
         let split: Vec<&str> = serialized.split(";").collect();
-        VolatileStatusDurations {
-            confusion: split[0].parse::<i8>().unwrap(),
-            encore: split[1].parse::<i8>().unwrap(),
-            lockedmove: split[2].parse::<i8>().unwrap(),
-            slowstart: split[3].parse::<i8>().unwrap(),
-            taunt: split[4].parse::<i8>().unwrap(),
-            yawn: split[5].parse::<i8>().unwrap(),
-        }
+        Ok(VolatileStatusDurations {
+            confusion: parse_state_field(&split, 0, "confusion")?,
+            encore: parse_state_field(&split, 1, "encore")?,
+            lockedmove: parse_state_field(&split, 2, "lockedmove")?,
+            slowstart: parse_state_field(&split, 3, "slowstart")?,
+            taunt: parse_state_field(&split, 4, "taunt")?,
+            yawn: parse_state_field(&split, 5, "yawn")?,
+            // dynamax was added after the other durations and may be missing
+            dynamax: match split.get(6) {
+                Some(_) => parse_state_field(&split, 6, "dynamax")?,
+                None => 0,
+            },
+        })
+    }
+}
+
+fn default_ivs() -> (u8, u8, u8, u8, u8, u8) {
+    (31, 31, 31, 31, 31, 31)
+}
+
+// `;` separated hp, attack, defense, special attack, special defense and speed
+fn try_deserialize_stat_spread(
+    serialized: &str,
+    field: &str,
+) -> Result<(u8, u8, u8, u8, u8, u8), StateParseError> {
+    let spread: Vec<u8> = serialized
+        .split(";")
+        .map(|value| value.parse::<u8>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| StateParseError::new(field, serialized))?;
+    match spread.as_slice() {
+        [hp, atk, def, spa, spd, spe] => Ok((*hp, *atk, *def, *spa, *spd, *spe)),
+        _ => Err(StateParseError::new(field, serialized)),
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Pokemon {
     pub id: PokemonName,
     pub level: i8,
@@ -833,6 +1060,10 @@ pub struct Pokemon {
     pub item: Items,
     pub nature: PokemonNature,
     pub evs: (u8, u8, u8, u8, u8, u8),
+    #[serde(default = "default_ivs")]
+    pub ivs: (u8, u8, u8, u8, u8, u8),
+    #[serde(default)]
+    pub gender: PokemonGender,
     pub attack: i16,
     pub defense: i16,
     pub special_attack: i16,
@@ -844,6 +1075,8 @@ pub struct Pokemon {
     pub weight_kg: f32,
     pub terastallized: bool,
     pub tera_type: PokemonType,
+    #[serde(default)]
+    pub gigantamax: bool,
     pub moves: PokemonMoves,
 }
 
@@ -854,13 +1087,17 @@ impl Default for Pokemon {
             level: 100,
             types: (PokemonType::NORMAL, PokemonType::TYPELESS),
             base_types: (PokemonType::NORMAL, PokemonType::TYPELESS),
-            hp: 0,
+            // a default pokemon is alive: fainted pokemon don't act and a side with
+            // no living pokemon has lost the battle
+            hp: 100,
             maxhp: 100,
             ability: Abilities::NONE,
             base_ability: Abilities::NONE,
             item: Items::NONE,
             nature: PokemonNature::SERIOUS,
             evs: (85, 85, 85, 85, 85, 85),
+            ivs: default_ivs(),
+            gender: PokemonGender::default(),
             attack: 100,
             defense: 100,
             special_attack: 100,
@@ -872,6 +1109,7 @@ impl Default for Pokemon {
             weight_kg: 1.0,
             terastallized: false,
             tera_type: PokemonType::NORMAL,
+            gigantamax: false,
             moves: PokemonMoves {
                 m0: Default::default(),
                 m1: Default::default(),
@@ -933,8 +1171,12 @@ impl Pokemon {
             "{};{};{};{};{};{}",
             self.evs.0, self.evs.1, self.evs.2, self.evs.3, self.evs.4, self.evs.5
         );
+        let ivs_str = format!(
+            "{};{};{};{};{};{}",
+            self.ivs.0, self.ivs.1, self.ivs.2, self.ivs.3, self.ivs.4, self.ivs.5
+        );
         format!(
-            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
             self.id,
             self.level,
             self.types.0.to_string(),
@@ -963,64 +1205,196 @@ impl Pokemon {
             self.moves.m3.serialize(),
             self.terastallized,
             self.tera_type.to_string(),
+            ivs_str,
+            self.gender,
+            self.gigantamax,
         )
     }
 
     pub fn deserialize(serialized: &str) -> Pokemon {
+        Pokemon::try_deserialize(serialized).unwrap_or_else(|e| panic!("{}", e))
+    }
+    pub fn try_deserialize(serialized: &str) -> Result<Pokemon, StateParseError> {
         let split: Vec<&str> = serialized.split(",").collect();
-        let evs = if split[12] != "" {
-            let mut ev_iter = split[12].split(";");
-            (
-                ev_iter.next().unwrap().parse::<u8>().unwrap(),
-                ev_iter.next().unwrap().parse::<u8>().unwrap(),
-                ev_iter.next().unwrap().parse::<u8>().unwrap(),
-                ev_iter.next().unwrap().parse::<u8>().unwrap(),
-                ev_iter.next().unwrap().parse::<u8>().unwrap(),
-                ev_iter.next().unwrap().parse::<u8>().unwrap(),
-            )
+        let serialized_evs = state_field(&split, 12, "evs")?;
+        let evs = if serialized_evs != "" {
+            try_deserialize_stat_spread(serialized_evs, "evs")?
         } else {
             (85, 85, 85, 85, 85, 85)
         };
-        Pokemon {
-            id: PokemonName::from_str(split[0]).unwrap(),
-            level: split[1].parse::<i8>().unwrap(),
+        // ivs, gender and gigantamax were added after the other fields and may be missing
+        let ivs = match split.get(28) {
+            Some(serialized_ivs) if !serialized_ivs.is_empty() => {
+                try_deserialize_stat_spread(serialized_ivs, "ivs")?
+            }
+            _ => default_ivs(),
+        };
+        let gender = match split.get(29) {
+            Some(_) => parse_state_field(&split, 29, "gender")?,
+            None => PokemonGender::default(),
+        };
+        let gigantamax = match split.get(30) {
+            Some(_) => parse_state_field(&split, 30, "gigantamax")?,
+            None => false,
+        };
+        let parse_move = |index: usize, field: &str| {
+            Move::try_deserialize(state_field(&split, index, field)?).map_err(|e| e.in_field(field))
+        };
+        Ok(Pokemon {
+            id: parse_state_field(&split, 0, "id")?,
+            level: parse_state_field(&split, 1, "level")?,
             types: (
-                PokemonType::from_str(split[2]).unwrap(),
-                PokemonType::from_str(split[3]).unwrap(),
+                parse_state_field(&split, 2, "type_1")?,
+                parse_state_field(&split, 3, "type_2")?,
             ),
             base_types: (
-                PokemonType::from_str(split[4]).unwrap(),
-                PokemonType::from_str(split[5]).unwrap(),
+                parse_state_field(&split, 4, "base_type_1")?,
+                parse_state_field(&split, 5, "base_type_2")?,
             ),
-            hp: split[6].parse::<i16>().unwrap(),
-            maxhp: split[7].parse::<i16>().unwrap(),
-            ability: Abilities::from_str(split[8]).unwrap(),
-            base_ability: Abilities::from_str(split[9]).unwrap(),
-            item: Items::from_str(split[10]).unwrap(),
-            nature: PokemonNature::from_str(split[11]).unwrap(),
+            hp: parse_state_field(&split, 6, "hp")?,
+            maxhp: parse_state_field(&split, 7, "maxhp")?,
+            ability: parse_state_field(&split, 8, "ability")?,
+            base_ability: parse_state_field(&split, 9, "base_ability")?,
+            item: parse_state_field(&split, 10, "item")?,
+            nature: parse_state_field(&split, 11, "nature")?,
             evs,
-            attack: split[13].parse::<i16>().unwrap(),
-            defense: split[14].parse::<i16>().unwrap(),
-            special_attack: split[15].parse::<i16>().unwrap(),
-            special_defense: split[16].parse::<i16>().unwrap(),
-            speed: split[17].parse::<i16>().unwrap(),
-            status: PokemonStatus::from_str(split[18]).unwrap(),
-            rest_turns: split[19].parse::<i8>().unwrap(),
-            sleep_turns: split[20].parse::<i8>().unwrap(),
-            weight_kg: split[21].parse::<f32>().unwrap(),
+            ivs,
+            gender,
+            attack: parse_state_field(&split, 13, "attack")?,
+            defense: parse_state_field(&split, 14, "defense")?,
+            special_attack: parse_state_field(&split, 15, "special_attack")?,
+            special_defense: parse_state_field(&split, 16, "special_defense")?,
+            speed: parse_state_field(&split, 17, "speed")?,
+            status: parse_state_field(&split, 18, "status")?,
+            rest_turns: parse_state_field(&split, 19, "rest_turns")?,
+            sleep_turns: parse_state_field(&split, 20, "sleep_turns")?,
+            weight_kg: parse_state_field(&split, 21, "weight_kg")?,
             moves: PokemonMoves {
-                m0: Move::deserialize(split[22]),
-                m1: Move::deserialize(split[23]),
-                m2: Move::deserialize(split[24]),
-                m3: Move::deserialize(split[25]),
+                m0: parse_move(22, "m0")?,
+                m1: parse_move(23, "m1")?,
+                m2: parse_move(24, "m2")?,
+                m3: parse_move(25, "m3")?,
             },
-            terastallized: split[26].parse::<bool>().unwrap(),
-            tera_type: PokemonType::from_str(split[27]).unwrap(),
+            terastallized: parse_state_field(&split, 26, "terastallized")?,
+            tera_type: parse_state_field(&split, 27, "tera_type")?,
+            gigantamax,
+        })
+    }
+}
+
+/// The state belonging to one active position rather than to the whole side
+///
+/// Only used in doubles. A `Side` always holds the position that is "in front" in its own fields
+/// and the other active position here, in `Side::ally_position`.
+/// `Instruction::SwapActivePosition` exchanges the two so that every existing instruction,
+/// which operates on the active pokemon, can be applied to either position.
+/// Outside of instruction generation the first position is always the one in front.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionState {
+    pub active_index: PokemonIndex,
+    pub force_switch: bool,
+    pub volatile_statuses: HashSet<PokemonVolatileStatus>,
+    pub volatile_status_durations: VolatileStatusDurations,
+    pub substitute_health: i16,
+    pub attack_boost: i8,
+    pub defense_boost: i8,
+    pub special_attack_boost: i8,
+    pub special_defense_boost: i8,
+    pub speed_boost: i8,
+    pub accuracy_boost: i8,
+    pub evasion_boost: i8,
+    pub last_used_move: LastUsedMove,
+    pub damage_dealt: DamageDealt,
+}
+impl PositionState {
+    pub fn new(active_index: PokemonIndex) -> PositionState {
+        PositionState {
+            active_index,
+            force_switch: false,
+            volatile_statuses: HashSet::<PokemonVolatileStatus>::new(),
+            volatile_status_durations: VolatileStatusDurations::default(),
+            substitute_health: 0,
+            attack_boost: 0,
+            defense_boost: 0,
+            special_attack_boost: 0,
+            special_defense_boost: 0,
+            speed_boost: 0,
+            accuracy_boost: 0,
+            evasion_boost: 0,
+            last_used_move: LastUsedMove::None,
+            damage_dealt: DamageDealt::default(),
         }
     }
+    pub fn serialize(&self) -> String {
+        // sorted so that the same volatile statuses always serialize the same way
+        let mut vs_names: Vec<String> = self
+            .volatile_statuses
+            .iter()
+            .map(|vs| vs.to_string())
+            .collect();
+        vs_names.sort_unstable();
+        let mut vs_string = String::new();
+        for vs in vs_names {
+            vs_string.push_str(&vs);
+            vs_string.push(':');
+        }
+        format!(
+            "{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}",
+            self.active_index.serialize(),
+            self.force_switch,
+            vs_string,
+            self.volatile_status_durations.serialize(),
+            self.substitute_health,
+            self.attack_boost,
+            self.defense_boost,
+            self.special_attack_boost,
+            self.special_defense_boost,
+            self.speed_boost,
+            self.accuracy_boost,
+            self.evasion_boost,
+            self.last_used_move.serialize(),
+        )
+    }
+    pub fn deserialize(serialized: &str) -> PositionState {
+        PositionState::try_deserialize(serialized).unwrap_or_else(|e| panic!("{}", e))
+    }
+    pub fn try_deserialize(serialized: &str) -> Result<PositionState, StateParseError> {
+        let split: Vec<&str> = serialized.split("|").collect();
+        Ok(PositionState {
+            active_index: PokemonIndex::try_deserialize(state_field(&split, 0, "active_index")?)
+                .map_err(|e| e.in_field("active_index"))?,
+            force_switch: parse_state_field(&split, 1, "force_switch")?,
+            volatile_statuses: try_deserialize_volatile_statuses(state_field(
+                &split,
+                2,
+                "volatile_statuses",
+            )?)?,
+            volatile_status_durations: VolatileStatusDurations::try_deserialize(state_field(
+                &split,
+                3,
+                "volatile_status_durations",
+            )?)
+            .map_err(|e| e.in_field("volatile_status_durations"))?,
+            substitute_health: parse_state_field(&split, 4, "substitute_health")?,
+            attack_boost: parse_state_field(&split, 5, "attack_boost")?,
+            defense_boost: parse_state_field(&split, 6, "defense_boost")?,
+            special_attack_boost: parse_state_field(&split, 7, "special_attack_boost")?,
+            special_defense_boost: parse_state_field(&split, 8, "special_defense_boost")?,
+            speed_boost: parse_state_field(&split, 9, "speed_boost")?,
+            accuracy_boost: parse_state_field(&split, 10, "accuracy_boost")?,
+            evasion_boost: parse_state_field(&split, 11, "evasion_boost")?,
+            last_used_move: LastUsedMove::try_deserialize(state_field(
+                &split,
+                12,
+                "last_used_move",
+            )?)
+            .map_err(|e| e.in_field("last_used_move"))?,
+            damage_dealt: DamageDealt::default(),
+        })
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Side {
     pub active_index: PokemonIndex,
     pub baton_passing: bool,
@@ -1045,6 +1419,11 @@ pub struct Side {
     pub last_used_move: LastUsedMove,
     pub damage_dealt: DamageDealt,
     pub switch_out_move_second_saved_move: Choices,
+    #[serde(default)]
+    pub dynamax_used: bool,
+    #[serde(default)]
+    pub z_move_used: bool,
+    pub ally_position: Option<PositionState>,
 }
 impl Side {
     fn io_conditional_print(&self) -> String {
@@ -1052,6 +1431,12 @@ impl Side {
         if self.baton_passing {
             output.push_str("\n  baton_passing: true");
         }
+        if self.dynamax_used {
+            output.push_str("\n  dynamax_used: true");
+        }
+        if self.z_move_used {
+            output.push_str("\n  z_move_used: true");
+        }
         if self.wish.0 != 0 {
             output.push_str(&format!("\n  wish: ({}, {})", self.wish.0, self.wish.1));
         }
@@ -1113,13 +1498,20 @@ impl Side {
         )
     }
     pub fn serialize(&self) -> String {
+        // sorted so that the same volatile statuses always serialize the same way
+        let mut vs_names: Vec<String> = self
+            .volatile_statuses
+            .iter()
+            .map(|vs| vs.to_string())
+            .collect();
+        vs_names.sort_unstable();
         let mut vs_string = String::new();
-        for vs in &self.volatile_statuses {
-            vs_string.push_str(&vs.to_string());
+        for vs in vs_names {
+            vs_string.push_str(&vs);
             vs_string.push_str(":");
         }
-        format!(
-            "{}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}",
+        let mut serialized = format!(
+            "{}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}={}",
             self.pokemon.p0.serialize(),
             self.pokemon.p1.serialize(),
             self.pokemon.p2.serialize(),
@@ -1149,55 +1541,104 @@ impl Side {
             self.force_trapped,
             self.last_used_move.serialize(),
             self.slow_uturn_move,
-        )
+            self.dynamax_used,
+            self.z_move_used,
+        );
+        if let Some(ally_position) = &self.ally_position {
+            serialized.push('=');
+            serialized.push_str(&ally_position.serialize());
+        }
+        serialized
     }
     pub fn deserialize(serialized: &str) -> Side {
+        Side::try_deserialize(serialized).unwrap_or_else(|e| panic!("{}", e))
+    }
+    pub fn try_deserialize(serialized: &str) -> Result<Side, StateParseError> {
         let split: Vec<&str> = serialized.split("=").collect();
-
-        let mut vs_hashset = HashSet::new();
-        if split[8] != "" {
-            for item in split[8].split(":") {
-                vs_hashset.insert(PokemonVolatileStatus::from_str(item).unwrap());
+        let parse_pokemon = |pkmn_index: PokemonIndex| {
+            let index = pkmn_index as usize;
+            Pokemon::try_deserialize(state_field(&split, index, "pokemon")?)
+                .map_err(|e| e.in_pokemon(pkmn_index))
+        };
+        let ally_position = match split.get(31) {
+            Some(p) => {
+                Some(PositionState::try_deserialize(p).map_err(|e| e.in_field("ally_position"))?)
             }
-        }
-        Side {
+            None => None,
+        };
+        Ok(Side {
             pokemon: SidePokemon {
-                p0: Pokemon::deserialize(split[0]),
-                p1: Pokemon::deserialize(split[1]),
-                p2: Pokemon::deserialize(split[2]),
-                p3: Pokemon::deserialize(split[3]),
-                p4: Pokemon::deserialize(split[4]),
-                p5: Pokemon::deserialize(split[5]),
+                p0: parse_pokemon(PokemonIndex::P0)?,
+                p1: parse_pokemon(PokemonIndex::P1)?,
+                p2: parse_pokemon(PokemonIndex::P2)?,
+                p3: parse_pokemon(PokemonIndex::P3)?,
+                p4: parse_pokemon(PokemonIndex::P4)?,
+                p5: parse_pokemon(PokemonIndex::P5)?,
             },
-            active_index: PokemonIndex::deserialize(split[6]),
-            side_conditions: SideConditions::deserialize(split[7]),
-            volatile_statuses: vs_hashset,
-            volatile_status_durations: VolatileStatusDurations::deserialize(split[9]),
-            substitute_health: split[10].parse::<i16>().unwrap(),
-            attack_boost: split[11].parse::<i8>().unwrap(),
-            defense_boost: split[12].parse::<i8>().unwrap(),
-            special_attack_boost: split[13].parse::<i8>().unwrap(),
-            special_defense_boost: split[14].parse::<i8>().unwrap(),
-            speed_boost: split[15].parse::<i8>().unwrap(),
-            accuracy_boost: split[16].parse::<i8>().unwrap(),
-            evasion_boost: split[17].parse::<i8>().unwrap(),
+            active_index: PokemonIndex::try_deserialize(state_field(&split, 6, "active_index")?)
+                .map_err(|e| e.in_field("active_index"))?,
+            side_conditions: SideConditions::try_deserialize(state_field(
+                &split,
+                7,
+                "side_conditions",
+            )?)
+            .map_err(|e| e.in_field("side_conditions"))?,
+            volatile_statuses: try_deserialize_volatile_statuses(state_field(
+                &split,
+                8,
+                "volatile_statuses",
+            )?)?,
+            volatile_status_durations: VolatileStatusDurations::try_deserialize(state_field(
+                &split,
+                9,
+                "volatile_status_durations",
+            )?)
+            .map_err(|e| e.in_field("volatile_status_durations"))?,
+            substitute_health: parse_state_field(&split, 10, "substitute_health")?,
+            attack_boost: parse_state_field(&split, 11, "attack_boost")?,
+            defense_boost: parse_state_field(&split, 12, "defense_boost")?,
+            special_attack_boost: parse_state_field(&split, 13, "special_attack_boost")?,
+            special_defense_boost: parse_state_field(&split, 14, "special_defense_boost")?,
+            speed_boost: parse_state_field(&split, 15, "speed_boost")?,
+            accuracy_boost: parse_state_field(&split, 16, "accuracy_boost")?,
+            evasion_boost: parse_state_field(&split, 17, "evasion_boost")?,
             wish: (
-                split[18].parse::<i8>().unwrap(),
-                split[19].parse::<i16>().unwrap(),
+                parse_state_field(&split, 18, "wish_turns")?,
+                parse_state_field(&split, 19, "wish_amount")?,
             ),
             future_sight: (
-                split[20].parse::<i8>().unwrap(),
-                PokemonIndex::deserialize(split[21]),
+                parse_state_field(&split, 20, "future_sight_turns")?,
+                PokemonIndex::try_deserialize(state_field(&split, 21, "future_sight_index")?)
+                    .map_err(|e| e.in_field("future_sight_index"))?,
             ),
-            force_switch: split[22].parse::<bool>().unwrap(),
-            switch_out_move_second_saved_move: Choices::from_str(split[23]).unwrap(),
-            baton_passing: split[24].parse::<bool>().unwrap(),
-            shed_tailing: split[25].parse::<bool>().unwrap(),
-            force_trapped: split[26].parse::<bool>().unwrap(),
-            last_used_move: LastUsedMove::deserialize(split[27]),
+            force_switch: parse_state_field(&split, 22, "force_switch")?,
+            switch_out_move_second_saved_move: parse_state_field(
+                &split,
+                23,
+                "switch_out_move_second_saved_move",
+            )?,
+            baton_passing: parse_state_field(&split, 24, "baton_passing")?,
+            shed_tailing: parse_state_field(&split, 25, "shed_tailing")?,
+            force_trapped: parse_state_field(&split, 26, "force_trapped")?,
+            last_used_move: LastUsedMove::try_deserialize(state_field(
+                &split,
+                27,
+                "last_used_move",
+            )?)
+            .map_err(|e| e.in_field("last_used_move"))?,
             damage_dealt: DamageDealt::default(),
-            slow_uturn_move: split[28].parse::<bool>().unwrap(),
-        }
+            slow_uturn_move: parse_state_field(&split, 28, "slow_uturn_move")?,
+            // dynamax_used and z_move_used were added after the other fields and may be missing
+            dynamax_used: match split.get(29) {
+                Some(_) => parse_state_field(&split, 29, "dynamax_used")?,
+                None => false,
+            },
+            z_move_used: match split.get(30) {
+                Some(_) => parse_state_field(&split, 30, "z_move_used")?,
+                None => false,
+            },
+            ally_position,
+        })
     }
 }
 impl Side {
@@ -1216,6 +1657,53 @@ impl Side {
     pub fn get_active_immutable(&self) -> &Pokemon {
         &self.pokemon[self.active_index]
     }
+    pub fn swap_active_position(&mut self) {
+        let position = match &mut self.ally_position {
+            Some(position) => position,
+            None => panic!("Cannot swap active positions on a side without an ally position"),
+        };
+        mem::swap(&mut self.active_index, &mut position.active_index);
+        mem::swap(&mut self.force_switch, &mut position.force_switch);
+        mem::swap(&mut self.volatile_statuses, &mut position.volatile_statuses);
+        mem::swap(
+            &mut self.volatile_status_durations,
+            &mut position.volatile_status_durations,
+        );
+        mem::swap(&mut self.substitute_health, &mut position.substitute_health);
+        mem::swap(&mut self.attack_boost, &mut position.attack_boost);
+        mem::swap(&mut self.defense_boost, &mut position.defense_boost);
+        mem::swap(
+            &mut self.special_attack_boost,
+            &mut position.special_attack_boost,
+        );
+        mem::swap(
+            &mut self.special_defense_boost,
+            &mut position.special_defense_boost,
+        );
+        mem::swap(&mut self.speed_boost, &mut position.speed_boost);
+        mem::swap(&mut self.accuracy_boost, &mut position.accuracy_boost);
+        mem::swap(&mut self.evasion_boost, &mut position.evasion_boost);
+        mem::swap(&mut self.last_used_move, &mut position.last_used_move);
+        mem::swap(&mut self.damage_dealt, &mut position.damage_dealt);
+    }
+    pub fn ally_active_index(&self) -> Option<PokemonIndex> {
+        self.ally_position.as_ref().map(|p| p.active_index)
+    }
+    // the position in front is always position 0 outside of instruction generation
+    pub fn get_position_active_immutable(&self, position: u8) -> &Pokemon {
+        match (position, &self.ally_position) {
+            (0, _) => self.get_active_immutable(),
+            (_, Some(ally_position)) => &self.pokemon[ally_position.active_index],
+            (_, None) => panic!("Side has no active position {}", position),
+        }
+    }
+    pub fn get_position_volatile_statuses(&self, position: u8) -> &HashSet<PokemonVolatileStatus> {
+        match (position, &self.ally_position) {
+            (0, _) => &self.volatile_statuses,
+            (_, Some(ally_position)) => &ally_position.volatile_statuses,
+            (_, None) => panic!("Side has no active position {}", position),
+        }
+    }
     fn toggle_force_switch(&mut self) {
         self.force_switch = !self.force_switch;
     }
@@ -1269,8 +1757,12 @@ impl Side {
         let mut vec = Vec::with_capacity(6);
         let mut iter = self.pokemon.into_iter();
 
+        let ally_active_index = self.ally_active_index();
         while let Some(p) = iter.next() {
-            if p.hp > 0 && iter.pokemon_index != self.active_index {
+            if p.hp > 0
+                && iter.pokemon_index != self.active_index
+                && Some(iter.pokemon_index) != ally_active_index
+            {
                 vec.push(iter.pokemon_index.clone());
             }
         }
@@ -1279,7 +1771,264 @@ impl Side {
     }
 }
 
-#[derive(Debug, Clone)]
+// The state hash is split into regions so that applying an instruction only has to re-hash
+// the part of the state it touched: the field, each side (without its pokemon) and each pokemon
+const STATE_HASH_REGIONS: usize = 15;
+const FIELD_HASH_REGION: usize = 0;
+const ALL_HASH_REGIONS_DIRTY: u16 = (1 << STATE_HASH_REGIONS) - 1;
+
+fn side_hash_region(side_ref: &SideReference) -> usize {
+    match side_ref {
+        SideReference::SideOne => 1,
+        SideReference::SideTwo => 8,
+    }
+}
+
+fn pokemon_hash_region(side_ref: &SideReference, pokemon_index: PokemonIndex) -> usize {
+    side_hash_region(side_ref) + 1 + pokemon_index as usize
+}
+
+// splitmix64 finalizer, used instead of `DefaultHasher` so that hashes are the same
+// across runs, platforms and compiler versions
+fn hash_mix(hash: u64, value: u64) -> u64 {
+    let mut z = (hash ^ value).wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+fn hash_all(seed: u64, values: &[u64]) -> u64 {
+    values
+        .iter()
+        .fold(seed, |hash, value| hash_mix(hash, *value))
+}
+
+// whether two instruction lists are the same apart from damage amounts in the same bucket
+fn same_damage_bucket(first: &[Instruction], second: &[Instruction], bucket: i16) -> bool {
+    first.len() == second.len()
+        && first.iter().zip(second.iter()).all(|pair| match pair {
+            (Instruction::Damage(a), Instruction::Damage(b)) => {
+                a.side_ref == b.side_ref && a.damage_amount / bucket == b.damage_amount / bucket
+            }
+            (Instruction::ChangeDamageDealtDamage(a), Instruction::ChangeDamageDealtDamage(b)) => {
+                a.side_ref == b.side_ref && a.damage_change / bucket == b.damage_change / bucket
+            }
+            (a, b) => a == b,
+        })
+}
+
+fn hash_volatile_statuses(hash: u64, volatile_statuses: &HashSet<PokemonVolatileStatus>) -> u64 {
+    // XOR so that the result does not depend on the iteration order of the set
+    let set_hash = volatile_statuses
+        .iter()
+        .fold(0, |acc, vs| acc ^ hash_mix(0, *vs as u64));
+    hash_mix(hash, set_hash)
+}
+
+fn hash_last_used_move(hash: u64, last_used_move: &LastUsedMove) -> u64 {
+    match last_used_move {
+        LastUsedMove::Move(move_index) => hash_all(hash, &[1, *move_index as u64]),
+        LastUsedMove::Switch(pokemon_index) => hash_all(hash, &[2, *pokemon_index as u64]),
+        LastUsedMove::None => hash_mix(hash, 3),
+    }
+}
+
+fn hash_durations(hash: u64, durations: &VolatileStatusDurations) -> u64 {
+    hash_all(
+        hash,
+        &[
+            durations.confusion as u64,
+            durations.encore as u64,
+            durations.lockedmove as u64,
+            durations.slowstart as u64,
+            durations.taunt as u64,
+            durations.yawn as u64,
+            durations.dynamax as u64,
+        ],
+    )
+}
+
+fn hash_damage_dealt(hash: u64, damage_dealt: &DamageDealt) -> u64 {
+    hash_all(
+        hash,
+        &[
+            damage_dealt.damage as u64,
+            damage_dealt.move_category as u64,
+            damage_dealt.hit_substitute as u64,
+        ],
+    )
+}
+
+impl Pokemon {
+    fn hash_with_seed(&self, seed: u64) -> u64 {
+        let mut hash = hash_all(
+            seed,
+            &[
+                self.id as u64,
+                self.level as u64,
+                self.types.0 as u64,
+                self.types.1 as u64,
+                self.base_types.0 as u64,
+                self.base_types.1 as u64,
+                self.hp as u64,
+                self.maxhp as u64,
+                self.ability as u64,
+                self.base_ability as u64,
+                self.item as u64,
+                self.nature.clone() as u64,
+                self.evs.0 as u64,
+                self.evs.1 as u64,
+                self.evs.2 as u64,
+                self.evs.3 as u64,
+                self.evs.4 as u64,
+                self.evs.5 as u64,
+                self.ivs.0 as u64,
+                self.ivs.1 as u64,
+                self.ivs.2 as u64,
+                self.ivs.3 as u64,
+                self.ivs.4 as u64,
+                self.ivs.5 as u64,
+                self.gender as u64,
+                self.attack as u64,
+                self.defense as u64,
+                self.special_attack as u64,
+                self.special_defense as u64,
+                self.speed as u64,
+                self.status as u64,
+                self.rest_turns as u64,
+                self.sleep_turns as u64,
+                self.weight_kg.to_bits() as u64,
+                self.terastallized as u64,
+                self.tera_type as u64,
+                self.gigantamax as u64,
+            ],
+        );
+        for m in self.moves.into_iter() {
+            hash = hash_all(hash, &[m.id as u64, m.disabled as u64, m.pp as u64]);
+        }
+        hash
+    }
+}
+
+impl Side {
+    // everything about the side except its pokemon, which are hashed separately
+    fn hash_with_seed(&self, seed: u64) -> u64 {
+        let sc = &self.side_conditions;
+        let mut hash = hash_all(
+            seed,
+            &[
+                self.active_index as u64,
+                self.baton_passing as u64,
+                self.shed_tailing as u64,
+                sc.aurora_veil as u64,
+                sc.crafty_shield as u64,
+                sc.healing_wish as u64,
+                sc.light_screen as u64,
+                sc.lucky_chant as u64,
+                sc.lunar_dance as u64,
+                sc.mat_block as u64,
+                sc.mist as u64,
+                sc.protect as u64,
+                sc.quick_guard as u64,
+                sc.reflect as u64,
+                sc.safeguard as u64,
+                sc.spikes as u64,
+                sc.stealth_rock as u64,
+                sc.sticky_web as u64,
+                sc.tailwind as u64,
+                sc.toxic_count as u64,
+                sc.toxic_spikes as u64,
+                sc.wide_guard as u64,
+                self.wish.0 as u64,
+                self.wish.1 as u64,
+                self.future_sight.0 as u64,
+                self.future_sight.1 as u64,
+                self.force_switch as u64,
+                self.force_trapped as u64,
+                self.slow_uturn_move as u64,
+                self.dynamax_used as u64,
+                self.z_move_used as u64,
+                self.substitute_health as u64,
+                self.attack_boost as u64,
+                self.defense_boost as u64,
+                self.special_attack_boost as u64,
+                self.special_defense_boost as u64,
+                self.speed_boost as u64,
+                self.accuracy_boost as u64,
+                self.evasion_boost as u64,
+                self.switch_out_move_second_saved_move as u64,
+            ],
+        );
+        hash = hash_durations(hash, &self.volatile_status_durations);
+        hash = hash_volatile_statuses(hash, &self.volatile_statuses);
+        hash = hash_last_used_move(hash, &self.last_used_move);
+        hash = hash_damage_dealt(hash, &self.damage_dealt);
+        if let Some(position) = &self.ally_position {
+            hash = hash_all(
+                hash,
+                &[
+                    position.active_index as u64,
+                    position.force_switch as u64,
+                    position.substitute_health as u64,
+                    position.attack_boost as u64,
+                    position.defense_boost as u64,
+                    position.special_attack_boost as u64,
+                    position.special_defense_boost as u64,
+                    position.speed_boost as u64,
+                    position.accuracy_boost as u64,
+                    position.evasion_boost as u64,
+                ],
+            );
+            hash = hash_durations(hash, &position.volatile_status_durations);
+            hash = hash_volatile_statuses(hash, &position.volatile_statuses);
+            hash = hash_last_used_move(hash, &position.last_used_move);
+            hash = hash_damage_dealt(hash, &position.damage_dealt);
+        }
+        hash
+    }
+}
+
+/// Cached hash of a `State`
+///
+/// Instructions applied or reversed through `State` only mark the regions of the hash they touch
+/// as stale, and `State::get_hash` only recomputes those. Two states with the same contents have
+/// the same hash regardless of the instructions used to reach them
+#[derive(Clone)]
+pub struct StateHash {
+    regions: [u64; STATE_HASH_REGIONS],
+    dirty: u16,
+}
+
+impl Default for StateHash {
+    fn default() -> StateHash {
+        StateHash {
+            regions: [0; STATE_HASH_REGIONS],
+            dirty: ALL_HASH_REGIONS_DIRTY,
+        }
+    }
+}
+
+// the cache is not part of the state's contents, so it is left out of the debug output
+impl fmt::Debug for StateHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "StateHash")
+    }
+}
+
+// the cache is not part of the state's contents, so it does not affect equality either
+impl PartialEq for StateHash {
+    fn eq(&self, _other: &StateHash) -> bool {
+        true
+    }
+}
+
+impl StateHash {
+    fn mark_dirty(&mut self, region: usize) {
+        self.dirty |= 1 << region;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct State {
     pub side_one: Side,
     pub side_two: Side,
@@ -1289,6 +2038,8 @@ pub struct State {
     pub team_preview: bool,
     pub use_last_used_move: bool,
     pub use_damage_dealt: bool,
+    #[serde(skip)]
+    pub hash: StateHash,
 }
 impl Default for State {
     fn default() -> State {
@@ -1310,6 +2061,7 @@ impl Default for State {
             team_preview: false,
             use_damage_dealt: false,
             use_last_used_move: false,
+            hash: StateHash::default(),
         };
 
         // many tests rely on the speed of side 2's active pokemon being greater than side_one's
@@ -1318,6 +2070,14 @@ impl Default for State {
     }
 }
 impl State {
+    pub fn battle_format(&self) -> BattleFormat {
+        if self.side_one.ally_position.is_some() {
+            BattleFormat::Doubles
+        } else {
+            BattleFormat::Singles
+        }
+    }
+
     pub fn battle_is_over(&self) -> f32 {
         //  0 if battle is not over
         //  1 if side one has won
@@ -1594,6 +2354,9 @@ impl State {
             PokemonVolatileStatus::YAWN => {
                 side.volatile_status_durations.yawn += amount;
             }
+            PokemonVolatileStatus::DYNAMAX => {
+                side.volatile_status_durations.dynamax += amount;
+            }
             _ => panic!(
                 "Invalid volatile status for increment_volatile_status_duration: {:?}",
                 volatile_status
@@ -1716,6 +2479,34 @@ impl State {
         self.trick_room.turns_remaining = new_turns_remaining;
     }
 
+    fn dynamax(&mut self, side_reference: &SideReference, hp_change: i16) {
+        let side = self.get_side(side_reference);
+        side.dynamax_used = true;
+        let active = side.get_active();
+        active.maxhp *= 2;
+        active.hp += hp_change;
+    }
+
+    fn reverse_dynamax(&mut self, side_reference: &SideReference, hp_change: i16) {
+        let side = self.get_side(side_reference);
+        side.dynamax_used = false;
+        let active = side.get_active();
+        active.hp -= hp_change;
+        active.maxhp /= 2;
+    }
+
+    fn end_dynamax(&mut self, side_reference: &SideReference, hp_change: i16) {
+        let active = self.get_side(side_reference).get_active();
+        active.maxhp /= 2;
+        active.hp += hp_change;
+    }
+
+    fn reverse_end_dynamax(&mut self, side_reference: &SideReference, hp_change: i16) {
+        let active = self.get_side(side_reference).get_active();
+        active.hp -= hp_change;
+        active.maxhp *= 2;
+    }
+
     fn set_last_used_move(&mut self, side_reference: &SideReference, last_used_move: LastUsedMove) {
         match side_reference {
             SideReference::SideOne => self.side_one.last_used_move = last_used_move,
@@ -1747,6 +2538,216 @@ impl State {
         }
     }
 
+    /// Returns a hash of the state's contents
+    ///
+    /// Only the parts of the state changed by instructions since the last call are re-hashed.
+    /// Changes made by mutating fields directly are not tracked, so `rehash` must be used after them
+    pub fn get_hash(&mut self) -> u64 {
+        if self.hash.dirty != 0 {
+            for region in 0..STATE_HASH_REGIONS {
+                if self.hash.dirty & (1 << region) != 0 {
+                    self.hash.regions[region] = self.hash_region(region);
+                }
+            }
+            self.hash.dirty = 0;
+        }
+        self.hash
+            .regions
+            .iter()
+            .fold(0, |acc, region_hash| acc ^ region_hash)
+    }
+
+    pub fn rehash(&mut self) -> u64 {
+        self.hash.dirty = ALL_HASH_REGIONS_DIRTY;
+        self.get_hash()
+    }
+
+    fn hash_region(&self, region: usize) -> u64 {
+        // the region is used as the seed so that identical pokemon in different slots,
+        // or identical sides, do not cancel each other out
+        let seed = region as u64;
+        if region == FIELD_HASH_REGION {
+            return hash_all(
+                seed,
+                &[
+                    self.weather.weather_type as u64,
+                    self.weather.turns_remaining as u64,
+                    self.terrain.terrain_type as u64,
+                    self.terrain.turns_remaining as u64,
+                    self.trick_room.active as u64,
+                    self.trick_room.turns_remaining as u64,
+                    self.team_preview as u64,
+                    self.use_last_used_move as u64,
+                    self.use_damage_dealt as u64,
+                ],
+            );
+        }
+        let (side, side_region) = if region < side_hash_region(&SideReference::SideTwo) {
+            (&self.side_one, side_hash_region(&SideReference::SideOne))
+        } else {
+            (&self.side_two, side_hash_region(&SideReference::SideTwo))
+        };
+        if region == side_region {
+            side.hash_with_seed(seed)
+        } else {
+            let pokemon_index = pokemon_index_iter().nth(region - side_region - 1).unwrap();
+            side.pokemon[pokemon_index].hash_with_seed(seed)
+        }
+    }
+
+    fn mark_active_hash_dirty(&mut self, side_ref: &SideReference) {
+        let active_index = self.get_side_immutable(side_ref).active_index;
+        self.hash
+            .mark_dirty(pokemon_hash_region(side_ref, active_index));
+    }
+
+    fn mark_hash_dirty(&mut self, instruction: &Instruction) {
+        match instruction {
+            Instruction::Switch(instruction) => {
+                self.hash
+                    .mark_dirty(side_hash_region(&instruction.side_ref));
+                self.hash.mark_dirty(pokemon_hash_region(
+                    &instruction.side_ref,
+                    instruction.previous_index,
+                ));
+                self.hash.mark_dirty(pokemon_hash_region(
+                    &instruction.side_ref,
+                    instruction.next_index,
+                ));
+            }
+            Instruction::ChangeStatus(instruction) => self.hash.mark_dirty(pokemon_hash_region(
+                &instruction.side_ref,
+                instruction.pokemon_index,
+            )),
+            Instruction::SetRestTurns(instruction) | Instruction::SetSleepTurns(instruction) => {
+                self.hash.mark_dirty(pokemon_hash_region(
+                    &instruction.side_ref,
+                    instruction.pokemon_index,
+                ))
+            }
+            Instruction::Heal(instruction) => self.mark_active_hash_dirty(&instruction.side_ref),
+            Instruction::Damage(instruction) => self.mark_active_hash_dirty(&instruction.side_ref),
+            Instruction::ChangeType(instruction) => {
+                self.mark_active_hash_dirty(&instruction.side_ref)
+            }
+            Instruction::ChangeAbility(instruction) => {
+                self.mark_active_hash_dirty(&instruction.side_ref)
+            }
+            Instruction::ChangeItem(instruction) => {
+                self.mark_active_hash_dirty(&instruction.side_ref)
+            }
+            Instruction::ChangeAttack(instruction)
+            | Instruction::ChangeDefense(instruction)
+            | Instruction::ChangeSpecialAttack(instruction)
+            | Instruction::ChangeSpecialDefense(instruction)
+            | Instruction::ChangeSpeed(instruction) => {
+                self.mark_active_hash_dirty(&instruction.side_ref)
+            }
+            Instruction::DisableMove(instruction) => {
+                self.mark_active_hash_dirty(&instruction.side_ref)
+            }
+            Instruction::EnableMove(instruction) => {
+                self.mark_active_hash_dirty(&instruction.side_ref)
+            }
+            Instruction::DecrementRestTurns(instruction) => {
+                self.mark_active_hash_dirty(&instruction.side_ref)
+            }
+            Instruction::FormeChange(instruction) => {
+                self.mark_active_hash_dirty(&instruction.side_ref)
+            }
+            Instruction::DecrementPP(instruction) => {
+                self.mark_active_hash_dirty(&instruction.side_ref)
+            }
+            Instruction::ToggleTerastallized(instruction) => {
+                self.mark_active_hash_dirty(&instruction.side_ref)
+            }
+            Instruction::MegaEvolve(instruction) => {
+                self.mark_active_hash_dirty(&instruction.side_ref)
+            }
+            Instruction::Dynamax(instruction) => {
+                self.hash
+                    .mark_dirty(side_hash_region(&instruction.side_ref));
+                self.mark_active_hash_dirty(&instruction.side_ref)
+            }
+            Instruction::EndDynamax(instruction) => {
+                self.mark_active_hash_dirty(&instruction.side_ref)
+            }
+            Instruction::ToggleZMoveUsed(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::ApplyVolatileStatus(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::RemoveVolatileStatus(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::Boost(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::ChangeSideCondition(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::ChangeVolatileStatusDuration(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::ChangeWish(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::DecrementWish(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::SetFutureSight(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::DecrementFutureSight(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::DamageSubstitute(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::ChangeSubstituteHealth(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::ToggleBatonPassing(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::ToggleShedTailing(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::SetLastUsedMove(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::ChangeDamageDealtDamage(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::ChangeDamageDealtMoveCatagory(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::ToggleDamageDealtHitSubstitute(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::SwapActivePosition(instruction) => self
+                .hash
+                .mark_dirty(side_hash_region(&instruction.side_ref)),
+            Instruction::SetSideOneMoveSecondSwitchOutMove(_)
+            | Instruction::ToggleSideOneForceSwitch => self
+                .hash
+                .mark_dirty(side_hash_region(&SideReference::SideOne)),
+            Instruction::SetSideTwoMoveSecondSwitchOutMove(_)
+            | Instruction::ToggleSideTwoForceSwitch => self
+                .hash
+                .mark_dirty(side_hash_region(&SideReference::SideTwo)),
+            Instruction::ChangeWeather(_)
+            | Instruction::DecrementWeatherTurnsRemaining
+            | Instruction::ChangeTerrain(_)
+            | Instruction::DecrementTerrainTurnsRemaining
+            | Instruction::ToggleTrickRoom(_)
+            | Instruction::DecrementTrickRoomTurnsRemaining => {
+                self.hash.mark_dirty(FIELD_HASH_REGION)
+            }
+        }
+    }
+
     pub fn apply_instructions(&mut self, instructions: &Vec<Instruction>) {
         for i in instructions {
             self.apply_one_instruction(i)
@@ -1754,6 +2755,7 @@ impl State {
     }
 
     pub fn apply_one_instruction(&mut self, instruction: &Instruction) {
+        self.mark_hash_dirty(instruction);
         match instruction {
             Instruction::Damage(instruction) => {
                 self.damage(&instruction.side_ref, instruction.damage_amount)
@@ -1906,6 +2908,22 @@ impl State {
                 SideReference::SideOne => self.side_one.get_active().terastallized ^= true,
                 SideReference::SideTwo => self.side_two.get_active().terastallized ^= true,
             },
+            Instruction::MegaEvolve(instruction) => self
+                .get_side(&instruction.side_ref)
+                .get_active()
+                .toggle_mega_evolution(instruction.ability_change),
+            Instruction::Dynamax(instruction) => {
+                self.dynamax(&instruction.side_ref, instruction.hp_change)
+            }
+            Instruction::EndDynamax(instruction) => {
+                self.end_dynamax(&instruction.side_ref, instruction.hp_change)
+            }
+            Instruction::ToggleZMoveUsed(instruction) => {
+                self.get_side(&instruction.side_ref).z_move_used ^= true
+            }
+            Instruction::SwapActivePosition(instruction) => {
+                self.get_side(&instruction.side_ref).swap_active_position()
+            }
             Instruction::SetLastUsedMove(instruction) => {
                 self.set_last_used_move(&instruction.side_ref, instruction.last_used_move)
             }
@@ -1940,7 +2958,71 @@ impl State {
         }
     }
 
+    /// Merges the branches of `instructions` that lead to the same state, adding up their
+    /// percentages
+    ///
+    /// Branches whose instructions only differ in damage amounts that fall in the same bucket of
+    /// `damage_bucket` hp are merged as well, keeping the instructions of the first of them.
+    /// A bucket of 1 only merges branches that lead to the same state, a larger one makes the
+    /// result an approximation
+    pub fn merge_equivalent_instructions(
+        &mut self,
+        instructions: &mut Vec<StateInstructions>,
+        damage_bucket: i16,
+    ) {
+        let mut merged: Vec<StateInstructions> = Vec::with_capacity(instructions.len());
+        let mut end_state_hashes: Vec<u64> = Vec::with_capacity(instructions.len());
+        for state_instructions in instructions.drain(..) {
+            self.apply_instructions(&state_instructions.instruction_list);
+            let end_state_hash = self.get_hash();
+            // the hash only finds candidates, they are confirmed by comparing the whole state
+            let end_state = if end_state_hashes.contains(&end_state_hash) {
+                Some(self.clone())
+            } else {
+                None
+            };
+            self.reverse_instructions(&state_instructions.instruction_list);
+
+            let mut equivalent = None;
+            for (index, other) in merged.iter().enumerate() {
+                let same_state = match &end_state {
+                    Some(end_state) if end_state_hashes[index] == end_state_hash => {
+                        self.instructions_lead_to(&other.instruction_list, end_state)
+                    }
+                    _ => false,
+                };
+                if same_state
+                    || (damage_bucket > 1
+                        && same_damage_bucket(
+                            &other.instruction_list,
+                            &state_instructions.instruction_list,
+                            damage_bucket,
+                        ))
+                {
+                    equivalent = Some(index);
+                    break;
+                }
+            }
+            match equivalent {
+                Some(index) => merged[index].percentage += state_instructions.percentage,
+                None => {
+                    merged.push(state_instructions);
+                    end_state_hashes.push(end_state_hash);
+                }
+            }
+        }
+        *instructions = merged;
+    }
+
+    fn instructions_lead_to(&mut self, instructions: &Vec<Instruction>, end_state: &State) -> bool {
+        self.apply_instructions(instructions);
+        let same_state = self == end_state;
+        self.reverse_instructions(instructions);
+        same_state
+    }
+
     pub fn reverse_one_instruction(&mut self, instruction: &Instruction) {
+        self.mark_hash_dirty(instruction);
         match instruction {
             Instruction::Damage(instruction) => {
                 self.heal(&instruction.side_ref, instruction.damage_amount)
@@ -2093,6 +3175,22 @@ impl State {
                 SideReference::SideOne => self.side_one.get_active().terastallized ^= true,
                 SideReference::SideTwo => self.side_two.get_active().terastallized ^= true,
             },
+            Instruction::MegaEvolve(instruction) => self
+                .get_side(&instruction.side_ref)
+                .get_active()
+                .toggle_mega_evolution(instruction.ability_change),
+            Instruction::Dynamax(instruction) => {
+                self.reverse_dynamax(&instruction.side_ref, instruction.hp_change)
+            }
+            Instruction::EndDynamax(instruction) => {
+                self.reverse_end_dynamax(&instruction.side_ref, instruction.hp_change)
+            }
+            Instruction::ToggleZMoveUsed(instruction) => {
+                self.get_side(&instruction.side_ref).z_move_used ^= true
+            }
+            Instruction::SwapActivePosition(instruction) => {
+                self.get_side(&instruction.side_ref).swap_active_position()
+            }
             Instruction::SetLastUsedMove(instruction) => {
                 self.set_last_used_move(&instruction.side_ref, instruction.previous_last_used_move)
             }
@@ -2253,7 +3351,17 @@ impl State {
     /// "false,",
     ///
     /// // tera_type
-    /// "Normal=",
+    /// "Normal,",
+    ///
+    /// // IVs split by `;`. These, the gender and gigantamax can be left off,
+    /// // for 31 IVs, no gender and no gigantamax factor
+    /// "31;0;31;31;31;31,",
+    ///
+    /// // gender: MALE, FEMALE or GENDERLESS
+    /// "MALE,",
+    ///
+    /// // gigantamax: whether the pokemon becomes its G-Max forme when it dynamaxes
+    /// "false=",
     ///
     /// // all remaining Pokémon shown in 1 line for brevity
     /// "skarmory,100,Steel,Flying,Steel,Flying,271,271,STURDY,STURDY,CUSTAPBERRY,SERIOUS,,259,316,104,177,262,None,0,0,25.5,STEALTHROCK;false;32,SPIKES;false;32,BRAVEBIRD;false;24,THIEF;false;40,false,Normal=",
@@ -2272,7 +3380,8 @@ impl State {
     /// "=",
     ///
     /// // some volatile statuses have durations associated with them, delimited by ;
-    /// "0;0;0;0;0;0=",
+    /// // the last one is the dynamax duration, which can be left off
+    /// "0;0;0;0;0;0;0=",
     ///
     /// // substitute_health
     /// "0=",
@@ -2309,6 +3418,9 @@ impl State {
     ///
     /// // a boolean representing if the side is slow uturning.
     /// // This is only ever set externally. It is used to know if the opposing side has a stored move to use after uturn.
+    /// "false=",
+    ///
+    /// // a boolean representing if the side has used its dynamax. This can be left off
     /// "false/",
     ///
     /// // SIDE 2, all in one line for brevity
@@ -2332,6 +3444,7 @@ impl State {
     ///
     /// assert_eq!(state.side_one.get_active_immutable().id, PokemonName::ALAKAZAM);
     /// assert_eq!(state.side_one.get_active_immutable().weight_kg, 25.5);
+    /// assert_eq!(state.side_one.get_active_immutable().ivs.1, 0);
     /// assert_eq!(state.side_one.substitute_health, 0);
     /// assert_eq!(state.side_two.get_active_immutable().id, PokemonName::TERRAKION);
     /// assert_eq!(state.trick_room.active, false);
@@ -2339,24 +3452,45 @@ impl State {
     ///
     ///
     /// // the same state, but all in one line
-    /// let serialized_state = "alakazam,100,Psychic,Typeless,Psychic,Typeless,251,251,NONE,NONE,LIFEORB,SERIOUS,252;0;252;0;4;0,121,148,353,206,365,None,0,0,25.5,PSYCHIC;false;16,GRASSKNOT;false;32,SHADOWBALL;false;24,HIDDENPOWERFIRE70;false;24,false,Normal=skarmory,100,Steel,Flying,Steel,Flying,271,271,STURDY,STURDY,CUSTAPBERRY,SERIOUS,,259,316,104,177,262,None,0,0,25.5,STEALTHROCK;false;32,SPIKES;false;32,BRAVEBIRD;false;24,THIEF;false;40,false,Normal=tyranitar,100,Rock,Dark,Rock,Dark,404,404,SANDSTREAM,SANDSTREAM,CHOPLEBERRY,SERIOUS,,305,256,203,327,159,None,0,0,25.5,CRUNCH;false;24,SUPERPOWER;false;8,THUNDERWAVE;false;32,PURSUIT;false;32,false,Normal=mamoswine,100,Ice,Ground,Ice,Ground,362,362,THICKFAT,THICKFAT,NEVERMELTICE,SERIOUS,,392,196,158,176,241,None,0,0,25.5,ICESHARD;false;48,EARTHQUAKE;false;16,SUPERPOWER;false;8,ICICLECRASH;false;16,false,Normal=jellicent,100,Water,Ghost,Water,Ghost,404,404,WATERABSORB,WATERABSORB,AIRBALLOON,SERIOUS,,140,237,206,246,180,None,0,0,25.5,TAUNT;false;32,NIGHTSHADE;false;24,WILLOWISP;false;24,RECOVER;false;16,false,Normal=excadrill,100,Ground,Steel,Ground,Steel,362,362,SANDFORCE,SANDFORCE,CHOICESCARF,SERIOUS,,367,156,122,168,302,None,0,0,25.5,EARTHQUAKE;false;16,IRONHEAD;false;24,ROCKSLIDE;false;16,RAPIDSPIN;false;64,false,Normal=0=0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;==0;0;0;0;0;0=0=0=0=0=0=0=0=0=0=0=0=0=false=NONE=false=false=false=switch:0=false/terrakion,100,Rock,Fighting,Rock,Fighting,323,323,NONE,NONE,FOCUSSASH,SERIOUS,,357,216,163,217,346,None,0,0,25.5,CLOSECOMBAT;false;8,STONEEDGE;false;8,STEALTHROCK;false;32,TAUNT;false;32,false,Normal=lucario,100,Fighting,Steel,Fighting,Steel,281,281,NONE,NONE,LIFEORB,SERIOUS,,350,176,241,177,279,None,0,0,25.5,CLOSECOMBAT;false;8,EXTREMESPEED;false;8,SWORDSDANCE;false;32,CRUNCH;false;24,false,Normal=breloom,100,Grass,Fighting,Grass,Fighting,262,262,TECHNICIAN,TECHNICIAN,LIFEORB,SERIOUS,,394,196,141,156,239,None,0,0,25.5,MACHPUNCH;false;48,BULLETSEED;false;48,SWORDSDANCE;false;32,LOWSWEEP;false;32,false,Normal=keldeo,100,Water,Fighting,Water,Fighting,323,323,NONE,NONE,LEFTOVERS,SERIOUS,,163,216,357,217,346,None,0,0,25.5,SECRETSWORD;false;16,HYDROPUMP;false;8,SCALD;false;24,SURF;false;24,false,Normal=conkeldurr,100,Fighting,Typeless,Fighting,Typeless,414,414,GUTS,GUTS,LEFTOVERS,SERIOUS,,416,226,132,167,126,None,0,0,25.5,MACHPUNCH;false;48,DRAINPUNCH;false;16,ICEPUNCH;false;24,THUNDERPUNCH;false;24,false,Normal=toxicroak,100,Poison,Fighting,Poison,Fighting,307,307,DRYSKIN,DRYSKIN,LIFEORB,SERIOUS,,311,166,189,167,295,None,0,0,25.5,DRAINPUNCH;false;16,SUCKERPUNCH;false;8,SWORDSDANCE;false;32,ICEPUNCH;false;24,false,Normal=0=0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;==0;0;0;0;0;0=0=0=0=0=0=0=0=0=0=0=0=0=false=NONE=false=false=false=switch:0=false/none;5/none;5/false;5/false";
+    /// let serialized_state = "alakazam,100,Psychic,Typeless,Psychic,Typeless,251,251,NONE,NONE,LIFEORB,SERIOUS,252;0;252;0;4;0,121,148,353,206,365,None,0,0,25.5,PSYCHIC;false;16,GRASSKNOT;false;32,SHADOWBALL;false;24,HIDDENPOWERFIRE70;false;24,false,Normal,31;0;31;31;31;31,MALE=skarmory,100,Steel,Flying,Steel,Flying,271,271,STURDY,STURDY,CUSTAPBERRY,SERIOUS,,259,316,104,177,262,None,0,0,25.5,STEALTHROCK;false;32,SPIKES;false;32,BRAVEBIRD;false;24,THIEF;false;40,false,Normal=tyranitar,100,Rock,Dark,Rock,Dark,404,404,SANDSTREAM,SANDSTREAM,CHOPLEBERRY,SERIOUS,,305,256,203,327,159,None,0,0,25.5,CRUNCH;false;24,SUPERPOWER;false;8,THUNDERWAVE;false;32,PURSUIT;false;32,false,Normal=mamoswine,100,Ice,Ground,Ice,Ground,362,362,THICKFAT,THICKFAT,NEVERMELTICE,SERIOUS,,392,196,158,176,241,None,0,0,25.5,ICESHARD;false;48,EARTHQUAKE;false;16,SUPERPOWER;false;8,ICICLECRASH;false;16,false,Normal=jellicent,100,Water,Ghost,Water,Ghost,404,404,WATERABSORB,WATERABSORB,AIRBALLOON,SERIOUS,,140,237,206,246,180,None,0,0,25.5,TAUNT;false;32,NIGHTSHADE;false;24,WILLOWISP;false;24,RECOVER;false;16,false,Normal=excadrill,100,Ground,Steel,Ground,Steel,362,362,SANDFORCE,SANDFORCE,CHOICESCARF,SERIOUS,,367,156,122,168,302,None,0,0,25.5,EARTHQUAKE;false;16,IRONHEAD;false;24,ROCKSLIDE;false;16,RAPIDSPIN;false;64,false,Normal=0=0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;==0;0;0;0;0;0=0=0=0=0=0=0=0=0=0=0=0=0=false=NONE=false=false=false=switch:0=false/terrakion,100,Rock,Fighting,Rock,Fighting,323,323,NONE,NONE,FOCUSSASH,SERIOUS,,357,216,163,217,346,None,0,0,25.5,CLOSECOMBAT;false;8,STONEEDGE;false;8,STEALTHROCK;false;32,TAUNT;false;32,false,Normal=lucario,100,Fighting,Steel,Fighting,Steel,281,281,NONE,NONE,LIFEORB,SERIOUS,,350,176,241,177,279,None,0,0,25.5,CLOSECOMBAT;false;8,EXTREMESPEED;false;8,SWORDSDANCE;false;32,CRUNCH;false;24,false,Normal=breloom,100,Grass,Fighting,Grass,Fighting,262,262,TECHNICIAN,TECHNICIAN,LIFEORB,SERIOUS,,394,196,141,156,239,None,0,0,25.5,MACHPUNCH;false;48,BULLETSEED;false;48,SWORDSDANCE;false;32,LOWSWEEP;false;32,false,Normal=keldeo,100,Water,Fighting,Water,Fighting,323,323,NONE,NONE,LEFTOVERS,SERIOUS,,163,216,357,217,346,None,0,0,25.5,SECRETSWORD;false;16,HYDROPUMP;false;8,SCALD;false;24,SURF;false;24,false,Normal=conkeldurr,100,Fighting,Typeless,Fighting,Typeless,414,414,GUTS,GUTS,LEFTOVERS,SERIOUS,,416,226,132,167,126,None,0,0,25.5,MACHPUNCH;false;48,DRAINPUNCH;false;16,ICEPUNCH;false;24,THUNDERPUNCH;false;24,false,Normal=toxicroak,100,Poison,Fighting,Poison,Fighting,307,307,DRYSKIN,DRYSKIN,LIFEORB,SERIOUS,,311,166,189,167,295,None,0,0,25.5,DRAINPUNCH;false;16,SUCKERPUNCH;false;8,SWORDSDANCE;false;32,ICEPUNCH;false;24,false,Normal=0=0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;0;==0;0;0;0;0;0=0=0=0=0=0=0=0=0=0=0=0=0=false=NONE=false=false=false=switch:0=false/none;5/none;5/false;5/false";
     /// let state2 = State::deserialize(serialized_state);
     /// assert_eq!(state.serialize(), state2.serialize());
     ///
     /// ```
     pub fn deserialize(serialized: &str) -> State {
+        State::try_deserialize(serialized).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like `State::deserialize` but returns an error naming the side, pokemon, field and token
+    /// that could not be parsed rather than panicking
+    pub fn try_deserialize(serialized: &str) -> Result<State, StateParseError> {
         let split: Vec<&str> = serialized.split("/").collect();
         let mut state = State {
-            side_one: Side::deserialize(split[0]),
-            side_two: Side::deserialize(split[1]),
-            weather: StateWeather::deserialize(split[2]),
-            terrain: StateTerrain::deserialize(split[3]),
-            trick_room: StateTrickRoom::deserialize(split[4]),
-            team_preview: split[5].parse::<bool>().unwrap(),
+            side_one: Side::try_deserialize(state_field(&split, 0, "side_one")?)
+                .map_err(|e| e.on_side(SideReference::SideOne))?,
+            side_two: Side::try_deserialize(state_field(&split, 1, "side_two")?)
+                .map_err(|e| e.on_side(SideReference::SideTwo))?,
+            weather: StateWeather::try_deserialize(state_field(&split, 2, "weather")?)
+                .map_err(|e| e.in_field("weather"))?,
+            terrain: StateTerrain::try_deserialize(state_field(&split, 3, "terrain")?)
+                .map_err(|e| e.in_field("terrain"))?,
+            trick_room: StateTrickRoom::try_deserialize(state_field(&split, 4, "trick_room")?)
+                .map_err(|e| e.in_field("trick_room"))?,
+            team_preview: parse_state_field(&split, 5, "team_preview")?,
             use_damage_dealt: false,
             use_last_used_move: false,
+            hash: StateHash::default(),
         };
         state.set_conditional_mechanics();
-        state
+        Ok(state)
+    }
+
+    /// JSON with every field of the state, unlike `State::serialize` other tools can parse it
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<State, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid state JSON: {}", e))
     }
 }
@@ -0,0 +1,406 @@
+use crate::engine::battle_environment::{initialize_battle_state, BattleEnvironment, Player};
+use crate::rng::{battle_seed, seeded_rng};
+use crate::state::{SideReference, State};
+use rayon::prelude::*;
+use std::f64::consts::PI;
+use std::fmt::Write;
+use std::sync::Arc;
+
+pub const INITIAL_RATING: f64 = 1500.0;
+pub const INITIAL_GLICKO_DEVIATION: f64 = 350.0;
+
+// the z-score of the 95% confidence intervals given for ratings
+const CONFIDENCE_Z: f64 = 1.96;
+const ELO_FIT_ITERATIONS: usize = 1000;
+
+pub type PlayerFactory = Arc<dyn Fn() -> Box<dyn Player> + Send + Sync>;
+
+pub struct TournamentPlayer {
+    pub name: String,
+    // called once per battle so that players never share state between battles
+    pub factory: PlayerFactory,
+}
+
+impl TournamentPlayer {
+    pub fn new<F>(name: &str, factory: F) -> TournamentPlayer
+    where
+        F: Fn() -> Box<dyn Player> + Send + Sync + 'static,
+    {
+        TournamentPlayer {
+            name: name.to_string(),
+            factory: Arc::new(factory),
+        }
+    }
+}
+
+pub struct TournamentConfig {
+    // every pairing of players battles from each of these states twice, once from each side
+    pub initial_states: Vec<State>,
+    pub max_turns: usize,
+    pub threads: usize,
+    // game `i` is run with `battle_seed(seed, i)`
+    pub seed: u64,
+}
+
+impl TournamentConfig {
+    // `state_count` random battle states, state `i` is generated from `battle_seed(seed, i)`
+    pub fn with_random_states(
+        state_count: usize,
+        random_teams_json: &str,
+        pokedex_json: &str,
+        movedex_json: &str,
+        seed: u64,
+    ) -> TournamentConfig {
+        let initial_states = (0..state_count)
+            .map(|state_index| {
+                initialize_battle_state(
+                    random_teams_json,
+                    pokedex_json,
+                    movedex_json,
+                    &mut seeded_rng(battle_seed(seed, state_index)),
+                )
+            })
+            .collect();
+        TournamentConfig {
+            initial_states,
+            max_turns: 100,
+            threads: 1,
+            seed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TournamentGame {
+    // indices into `TournamentResults::names`
+    pub side_one: usize,
+    pub side_two: usize,
+    pub state_index: usize,
+    pub seed: u64,
+    pub winner: Option<SideReference>,
+    pub turn_count: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HeadToHead {
+    pub wins: usize,
+    pub losses: usize,
+    pub draws: usize,
+}
+
+impl HeadToHead {
+    pub fn games(&self) -> usize {
+        self.wins + self.losses + self.draws
+    }
+
+    // a draw counts as half a win
+    pub fn score(&self) -> f64 {
+        self.wins as f64 + 0.5 * self.draws as f64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerRating {
+    pub name: String,
+    pub record: HeadToHead,
+    pub elo: f64,
+    pub elo_interval: (f64, f64),
+    pub glicko: f64,
+    pub glicko_deviation: f64,
+}
+
+impl PlayerRating {
+    pub fn glicko_interval(&self) -> (f64, f64) {
+        (
+            self.glicko - CONFIDENCE_Z * self.glicko_deviation,
+            self.glicko + CONFIDENCE_Z * self.glicko_deviation,
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TournamentResults {
+    pub names: Vec<String>,
+    pub games: Vec<TournamentGame>,
+    // `head_to_head[i][j]` is the record of player `i` against player `j`
+    pub head_to_head: Vec<Vec<HeadToHead>>,
+    // sorted from the highest to the lowest elo
+    pub ratings: Vec<PlayerRating>,
+}
+
+impl TournamentResults {
+    pub fn from_games(names: Vec<String>, games: Vec<TournamentGame>) -> TournamentResults {
+        let player_count = names.len();
+        let mut head_to_head = vec![vec![HeadToHead::default(); player_count]; player_count];
+        for game in games.iter() {
+            let (one, two) = (game.side_one, game.side_two);
+            match game.winner {
+                Some(SideReference::SideOne) => {
+                    head_to_head[one][two].wins += 1;
+                    head_to_head[two][one].losses += 1;
+                }
+                Some(SideReference::SideTwo) => {
+                    head_to_head[one][two].losses += 1;
+                    head_to_head[two][one].wins += 1;
+                }
+                None => {
+                    head_to_head[one][two].draws += 1;
+                    head_to_head[two][one].draws += 1;
+                }
+            }
+        }
+
+        let (elos, elo_deviations) = fit_elo(&head_to_head);
+        let glickos = glicko_ratings(&head_to_head);
+        let mut ratings: Vec<PlayerRating> = (0..player_count)
+            .map(|player| {
+                let mut record = HeadToHead::default();
+                for opponent_record in head_to_head[player].iter() {
+                    record.wins += opponent_record.wins;
+                    record.losses += opponent_record.losses;
+                    record.draws += opponent_record.draws;
+                }
+                PlayerRating {
+                    name: names[player].clone(),
+                    record,
+                    elo: elos[player],
+                    elo_interval: (
+                        elos[player] - CONFIDENCE_Z * elo_deviations[player],
+                        elos[player] + CONFIDENCE_Z * elo_deviations[player],
+                    ),
+                    glicko: glickos[player].0,
+                    glicko_deviation: glickos[player].1,
+                }
+            })
+            .collect();
+        ratings.sort_by(|a, b| b.elo.total_cmp(&a.elo));
+
+        TournamentResults {
+            names,
+            games,
+            head_to_head,
+            ratings,
+        }
+    }
+
+    pub fn rating(&self, name: &str) -> Option<&PlayerRating> {
+        self.ratings.iter().find(|rating| rating.name == name)
+    }
+
+    pub fn report(&self) -> String {
+        let name_width = self.names.iter().map(|n| n.len()).max().unwrap_or(0).max(6);
+        let mut report = String::new();
+
+        writeln!(
+            report,
+            "{:<name_width$} {:>6} {:>6} {:>6} {:>6} {:>8} {:>19} {:>8} {:>19}",
+            "Player",
+            "Games",
+            "Wins",
+            "Losses",
+            "Draws",
+            "Elo",
+            "Elo 95% CI",
+            "Glicko",
+            "Glicko 95% CI",
+            name_width = name_width
+        )
+        .unwrap();
+        for rating in self.ratings.iter() {
+            let glicko_interval = rating.glicko_interval();
+            writeln!(
+                report,
+                "{:<name_width$} {:>6} {:>6} {:>6} {:>6} {:>8.1} {:>19} {:>8.1} {:>19}",
+                rating.name,
+                rating.record.games(),
+                rating.record.wins,
+                rating.record.losses,
+                rating.record.draws,
+                rating.elo,
+                format!(
+                    "{:.1} - {:.1}",
+                    rating.elo_interval.0, rating.elo_interval.1
+                ),
+                rating.glicko,
+                format!("{:.1} - {:.1}", glicko_interval.0, glicko_interval.1),
+                name_width = name_width
+            )
+            .unwrap();
+        }
+
+        // each cell is the row player's wins-losses-draws against the column player
+        writeln!(report, "\nHead to head (wins-losses-draws):").unwrap();
+        let cell_width = self
+            .head_to_head
+            .iter()
+            .flatten()
+            .map(|record| format!("{}-{}-{}", record.wins, record.losses, record.draws).len())
+            .chain(self.names.iter().map(|name| name.len()))
+            .max()
+            .unwrap_or(0);
+        write!(report, "{:<name_width$}", "", name_width = name_width).unwrap();
+        for name in self.names.iter() {
+            write!(report, " {:>cell_width$}", name, cell_width = cell_width).unwrap();
+        }
+        writeln!(report).unwrap();
+        for (player, name) in self.names.iter().enumerate() {
+            write!(report, "{:<name_width$}", name, name_width = name_width).unwrap();
+            for (opponent, record) in self.head_to_head[player].iter().enumerate() {
+                let cell = if player == opponent {
+                    "-".to_string()
+                } else {
+                    format!("{}-{}-{}", record.wins, record.losses, record.draws)
+                };
+                write!(report, " {:>cell_width$}", cell, cell_width = cell_width).unwrap();
+            }
+            writeln!(report).unwrap();
+        }
+        report
+    }
+}
+
+// the chance that a player rated `rating` scores against one rated `opponent_rating`
+fn expected_score(rating: f64, opponent_rating: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent_rating - rating) / 400.0))
+}
+
+// Maximum likelihood Bradley-Terry ratings on the Elo scale, with their standard errors
+//
+// Every pair of players that met is given one extra virtual draw so that a player who won
+// or lost every game still gets a finite rating. Ratings are centered on `INITIAL_RATING`
+fn fit_elo(head_to_head: &[Vec<HeadToHead>]) -> (Vec<f64>, Vec<f64>) {
+    let player_count = head_to_head.len();
+    let games = |i: usize, j: usize| match head_to_head[i][j].games() {
+        0 => 0.0,
+        n => n as f64 + 1.0,
+    };
+    let scores: Vec<f64> = (0..player_count)
+        .map(|i| {
+            (0..player_count)
+                .filter(|&j| head_to_head[i][j].games() > 0)
+                .map(|j| head_to_head[i][j].score() + 0.5)
+                .sum()
+        })
+        .collect();
+
+    // minorization-maximization updates of each player's strength
+    let mut strengths = vec![1.0; player_count];
+    for _ in 0..ELO_FIT_ITERATIONS {
+        let mut next: Vec<f64> = (0..player_count)
+            .map(|i| {
+                let denominator: f64 = (0..player_count)
+                    .filter(|&j| j != i)
+                    .map(|j| games(i, j) / (strengths[i] + strengths[j]))
+                    .sum();
+                if denominator == 0.0 {
+                    strengths[i]
+                } else {
+                    scores[i] / denominator
+                }
+            })
+            .collect();
+        let log_mean = next.iter().map(|s| s.ln()).sum::<f64>() / player_count as f64;
+        for strength in next.iter_mut() {
+            *strength /= log_mean.exp();
+        }
+        strengths = next;
+    }
+
+    let elos: Vec<f64> = strengths
+        .iter()
+        .map(|s| INITIAL_RATING + 400.0 * s.log10())
+        .collect();
+    let deviations = (0..player_count)
+        .map(|i| {
+            let information: f64 = (0..player_count)
+                .filter(|&j| j != i)
+                .map(|j| {
+                    let expected = expected_score(elos[i], elos[j]);
+                    games(i, j) * expected * (1.0 - expected)
+                })
+                .sum();
+            if information == 0.0 {
+                INITIAL_GLICKO_DEVIATION
+            } else {
+                400.0 / 10f64.ln() / information.sqrt()
+            }
+        })
+        .collect();
+    (elos, deviations)
+}
+
+// Glicko ratings and deviations after a single rating period holding every game,
+// with every player starting at `INITIAL_RATING` and `INITIAL_GLICKO_DEVIATION`
+fn glicko_ratings(head_to_head: &[Vec<HeadToHead>]) -> Vec<(f64, f64)> {
+    let q = 10f64.ln() / 400.0;
+    let g = |deviation: f64| 1.0 / (1.0 + 3.0 * q * q * deviation * deviation / (PI * PI)).sqrt();
+    let g_opponent = g(INITIAL_GLICKO_DEVIATION);
+    // every player starts with the same rating
+    let expected = 0.5;
+
+    head_to_head
+        .iter()
+        .map(|records| {
+            let games: f64 = records.iter().map(|r| r.games() as f64).sum();
+            if games == 0.0 {
+                return (INITIAL_RATING, INITIAL_GLICKO_DEVIATION);
+            }
+            let score: f64 = records.iter().map(|r| r.score()).sum();
+            let d_squared =
+                1.0 / (q * q * games * g_opponent * g_opponent * expected * (1.0 - expected));
+            let precision =
+                1.0 / (INITIAL_GLICKO_DEVIATION * INITIAL_GLICKO_DEVIATION) + 1.0 / d_squared;
+            let rating = INITIAL_RATING + q / precision * g_opponent * (score - games * expected);
+            (rating, (1.0 / precision).sqrt())
+        })
+        .collect()
+}
+
+// Every pair of players battles from every initial state twice, once from each side,
+// over `config.threads` threads
+pub fn run_tournament(
+    players: &[TournamentPlayer],
+    config: &TournamentConfig,
+) -> TournamentResults {
+    let mut pairings = vec![];
+    for first in 0..players.len() {
+        for second in first + 1..players.len() {
+            for state_index in 0..config.initial_states.len() {
+                pairings.push((first, second, state_index));
+                pairings.push((second, first, state_index));
+            }
+        }
+    }
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(config.threads.max(1))
+        .build()
+        .expect("Failed to build tournament thread pool");
+    let games = pool.install(|| {
+        pairings
+            .into_par_iter()
+            .enumerate()
+            .map(|(game_index, (side_one, side_two, state_index))| {
+                let seed = battle_seed(config.seed, game_index);
+                let env = BattleEnvironment::new(
+                    (players[side_one].factory)(),
+                    (players[side_two].factory)(),
+                    config.max_turns,
+                    false,
+                )
+                .with_seed(seed);
+                let result = env.run_battle(config.initial_states[state_index].clone());
+                TournamentGame {
+                    side_one,
+                    side_two,
+                    state_index,
+                    seed,
+                    winner: result.winner,
+                    turn_count: result.turn_count,
+                }
+            })
+            .collect()
+    });
+
+    TournamentResults::from_games(players.iter().map(|p| p.name.clone()).collect(), games)
+}
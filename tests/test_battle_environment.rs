@@ -0,0 +1,127 @@
+#![cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+
+use poke_engine::engine::battle_environment::{
+    initialize_battle_state, run_random_battle, BattleEnvironment, BattleResult, MctsPlayer,
+    RandomPlayer,
+};
+use poke_engine::engine::state::PokemonVolatileStatus;
+use poke_engine::mcts::{MctsConfig, RolloutPolicy};
+use poke_engine::rng::seeded_rng;
+use poke_engine::state::State;
+use std::time::Duration;
+
+const RANDOM_TEAMS_JSON: &str = include_str!("../data/random_teams.json");
+const POKEDEX_JSON: &str = include_str!("../data/pokedex.json");
+const MOVEDEX_JSON: &str = include_str!("../data/moves.json");
+
+fn mcts_player() -> Box<MctsPlayer> {
+    Box::new(MctsPlayer::new(
+        "mcts".to_string(),
+        MctsConfig {
+            max_time: Duration::MAX,
+            max_iterations: 100,
+            rollout_policy: RolloutPolicy::Random,
+            rollout_turns: 2,
+            ..Default::default()
+        },
+    ))
+}
+
+fn battle(seed: u64) -> BattleResult {
+    run_random_battle(
+        RANDOM_TEAMS_JSON,
+        POKEDEX_JSON,
+        MOVEDEX_JSON,
+        mcts_player(),
+        Box::new(RandomPlayer::new("random".to_string())),
+        30,
+        false,
+        seed,
+    )
+}
+
+fn battle_log(result: &BattleResult) -> Vec<String> {
+    result
+        .turn_history
+        .iter()
+        .map(|turn| {
+            format!(
+                "{:?} {:?} {}",
+                turn.side_one_choice,
+                turn.side_two_choice,
+                turn.state_after.serialize()
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_the_same_seed_picks_the_same_teams() {
+    let state = initialize_battle_state(
+        RANDOM_TEAMS_JSON,
+        POKEDEX_JSON,
+        MOVEDEX_JSON,
+        &mut seeded_rng(3),
+    );
+    let same_state = initialize_battle_state(
+        RANDOM_TEAMS_JSON,
+        POKEDEX_JSON,
+        MOVEDEX_JSON,
+        &mut seeded_rng(3),
+    );
+
+    assert_eq!(state.serialize(), same_state.serialize());
+}
+
+#[test]
+fn test_the_same_seed_replays_the_same_battle() {
+    let result = battle(11);
+    let replayed = battle(11);
+
+    assert_eq!(11, result.seed);
+    assert_eq!(result.turn_count, replayed.turn_count);
+    assert_eq!(battle_log(&result), battle_log(&replayed));
+    assert_ne!(battle_log(&result), battle_log(&battle(12)));
+}
+
+#[test]
+fn test_a_battle_without_a_seed_reports_the_one_it_used() {
+    let state = initialize_battle_state(
+        RANDOM_TEAMS_JSON,
+        POKEDEX_JSON,
+        MOVEDEX_JSON,
+        &mut seeded_rng(5),
+    );
+    let random_player = || Box::new(RandomPlayer::new("random".to_string()));
+
+    let result = BattleEnvironment::new(random_player(), random_player(), 30, false)
+        .run_battle(state.clone());
+    let replayed = BattleEnvironment::new(random_player(), random_player(), 30, false)
+        .with_seed(result.seed)
+        .run_battle(state);
+
+    assert_eq!(battle_log(&result), battle_log(&replayed));
+}
+
+#[test]
+fn test_the_same_volatile_statuses_always_serialize_the_same_way() {
+    let volatile_statuses = [
+        PokemonVolatileStatus::CONFUSION,
+        PokemonVolatileStatus::LOCKEDMOVE,
+        PokemonVolatileStatus::LEECHSEED,
+        PokemonVolatileStatus::SUBSTITUTE,
+        PokemonVolatileStatus::TAUNT,
+        PokemonVolatileStatus::ENCORE,
+        PokemonVolatileStatus::FLINCH,
+        PokemonVolatileStatus::PROTECT,
+    ];
+    let mut state = State::default();
+    let mut other_state = State::default();
+    state.side_one.volatile_statuses.extend(volatile_statuses);
+    other_state
+        .side_one
+        .volatile_statuses
+        .extend(volatile_statuses.iter().rev());
+
+    assert_eq!(state.serialize(), other_state.serialize());
+}
@@ -24,7 +24,7 @@ use poke_engine::instruction::{
 };
 use poke_engine::pokemon::PokemonName;
 use poke_engine::state::{
-    pokemon_index_iter, Move, PokemonBoostableStat, PokemonIndex, PokemonMoveIndex,
+    pokemon_index_iter, Move, PokemonBoostableStat, PokemonGender, PokemonIndex, PokemonMoveIndex,
     PokemonSideCondition, PokemonStatus, PokemonType, SideReference, State, StateWeather,
 };
 
@@ -7723,6 +7723,7 @@ fn test_meteorbeam_volatile_only_allows_meteor_beam_to_be_used() {
 #[cfg(not(feature = "terastallization"))]
 fn test_assaultvest_prevents_status_move() {
     let mut state = State::default();
+    state.side_one.dynamax_used = true;
     state.side_one.pokemon[PokemonIndex::P1].hp = 0;
     state.side_one.pokemon[PokemonIndex::P2].hp = 0;
     state.side_one.pokemon[PokemonIndex::P3].hp = 0;
@@ -7772,6 +7773,7 @@ fn test_assaultvest_prevents_status_move() {
 #[test]
 fn test_taunt_prevents_status_move() {
     let mut state = State::default();
+    state.side_one.dynamax_used = true;
     state.side_one.get_active().terastallized = true;
     state.side_one.pokemon[PokemonIndex::P1].hp = 0;
     state.side_one.pokemon[PokemonIndex::P2].hp = 0;
@@ -7826,6 +7828,8 @@ fn test_taunt_prevents_status_move() {
 #[cfg(not(feature = "terastallization"))]
 fn test_cannot_use_bloodmoon_after_using_bloodmoon() {
     let mut state = State::default();
+    state.side_one.dynamax_used = true;
+    state.side_two.dynamax_used = true;
 
     state.side_one.last_used_move = LastUsedMove::Move(PokemonMoveIndex::M0);
     state.side_one.get_active().moves[&PokemonMoveIndex::M0] = Move {
@@ -7961,6 +7965,8 @@ fn test_terastallization_side_two() {
 #[cfg(not(feature = "terastallization"))]
 fn test_cannot_use_gigatonhammer_after_using_gigatonhammer() {
     let mut state = State::default();
+    state.side_one.dynamax_used = true;
+    state.side_two.dynamax_used = true;
 
     state.side_one.last_used_move = LastUsedMove::Move(PokemonMoveIndex::M0);
     state.side_one.get_active().moves[&PokemonMoveIndex::M0] = Move {
@@ -8006,6 +8012,8 @@ fn test_cannot_use_gigatonhammer_after_using_gigatonhammer() {
 #[cfg(not(feature = "terastallization"))]
 fn test_can_use_gigatonhammer_after_using_switch() {
     let mut state = State::default();
+    state.side_one.dynamax_used = true;
+    state.side_two.dynamax_used = true;
 
     state.side_one.last_used_move = LastUsedMove::Switch(PokemonIndex::P0);
     state.side_one.get_active().moves[&PokemonMoveIndex::M0] = Move {
@@ -8051,6 +8059,8 @@ fn test_can_use_gigatonhammer_after_using_switch() {
 #[cfg(not(feature = "terastallization"))]
 fn test_can_use_bloodmoon_after_using_switch() {
     let mut state = State::default();
+    state.side_one.dynamax_used = true;
+    state.side_two.dynamax_used = true;
 
     state.side_one.last_used_move = LastUsedMove::Switch(PokemonIndex::P0);
     state.side_one.get_active().moves[&PokemonMoveIndex::M0] = Move {
@@ -8096,6 +8106,8 @@ fn test_can_use_bloodmoon_after_using_switch() {
 #[cfg(not(feature = "terastallization"))]
 fn test_arenatrap_traps_opponent() {
     let mut state = State::default();
+    state.side_one.dynamax_used = true;
+    state.side_two.dynamax_used = true;
     state.side_one.get_active().ability = Abilities::ARENATRAP;
 
     let (side_one_moves, side_two_moves) = state.get_all_options();
@@ -8131,6 +8143,8 @@ fn test_arenatrap_traps_opponent() {
 #[cfg(not(feature = "terastallization"))]
 fn test_arenatrap_does_not_trap_flying() {
     let mut state = State::default();
+    state.side_one.dynamax_used = true;
+    state.side_two.dynamax_used = true;
     state.side_one.get_active().ability = Abilities::ARENATRAP;
     state.side_two.get_active().types.1 = PokemonType::FLYING;
 
@@ -8171,6 +8185,8 @@ fn test_arenatrap_does_not_trap_flying() {
 #[cfg(not(feature = "terastallization"))]
 fn test_arenatrap_does_not_trap_ghost() {
     let mut state = State::default();
+    state.side_one.dynamax_used = true;
+    state.side_two.dynamax_used = true;
     state.side_one.get_active().ability = Abilities::ARENATRAP;
     state.side_two.get_active().types.1 = PokemonType::GHOST;
 
@@ -8211,6 +8227,8 @@ fn test_arenatrap_does_not_trap_ghost() {
 #[cfg(not(feature = "terastallization"))]
 fn test_arenatrap_does_not_trap_shedshell() {
     let mut state = State::default();
+    state.side_one.dynamax_used = true;
+    state.side_two.dynamax_used = true;
     state.side_one.get_active().ability = Abilities::ARENATRAP;
     state.side_two.get_active().item = Items::SHEDSHELL;
 
@@ -8273,6 +8291,7 @@ fn test_turn_after_switch_out_move_other_side_does_nothing() {
 #[cfg(not(feature = "terastallization"))]
 fn test_lockedmove_prevents_switches() {
     let mut state = State::default();
+    state.side_one.dynamax_used = true;
     state
         .side_one
         .volatile_statuses
@@ -8294,6 +8313,7 @@ fn test_lockedmove_prevents_switches() {
 #[cfg(not(feature = "terastallization"))]
 fn test_zero_pp_move_cannot_be_used() {
     let mut state = State::default();
+    state.side_one.dynamax_used = true;
     state.side_one.get_active().moves[&PokemonMoveIndex::M0].pp = 0;
 
     let (side_one_moves, _) = state.get_all_options();
@@ -13738,6 +13758,51 @@ fn test_sunnyday() {
     assert_eq!(expected_instructions, vec_of_instructions);
 }
 
+#[test]
+fn test_sunnyday_with_heatrock_lasts_8_turns() {
+    let mut state = State::default();
+    state.side_one.get_active().item = Items::HEATROCK;
+
+    let vec_of_instructions = set_moves_on_pkmn_and_call_generate_instructions(
+        &mut state,
+        Choices::SUNNYDAY,
+        Choices::SPLASH,
+    );
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![
+            Instruction::ChangeWeather(ChangeWeather {
+                new_weather: Weather::SUN,
+                new_weather_turns_remaining: 8,
+                previous_weather: Weather::NONE,
+                previous_weather_turns_remaining: -1,
+            }),
+            Instruction::DecrementWeatherTurnsRemaining,
+        ],
+    }];
+    assert_eq!(expected_instructions, vec_of_instructions);
+}
+
+#[test]
+fn test_raindance_does_not_replace_harshsun() {
+    let mut state = State::default();
+    state.weather.weather_type = Weather::HARSHSUN;
+    state.weather.turns_remaining = -1;
+
+    let vec_of_instructions = set_moves_on_pkmn_and_call_generate_instructions(
+        &mut state,
+        Choices::RAINDANCE,
+        Choices::SPLASH,
+    );
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![],
+    }];
+    assert_eq!(expected_instructions, vec_of_instructions);
+}
+
 #[test]
 fn test_snowscape() {
     let mut state = State::default();
@@ -15981,6 +16046,158 @@ fn test_overcoat_vs_weather_damage() {
     assert_eq!(expected_instructions, vec_of_instructions);
 }
 
+#[test]
+fn test_cutecharm_infatuates_the_attacker_on_contact() {
+    let mut state = State::default();
+    state.side_one.get_active().gender = PokemonGender::MALE;
+    state.side_two.get_active().gender = PokemonGender::FEMALE;
+    state.side_two.get_active().ability = Abilities::CUTECHARM;
+
+    let vec_of_instructions = set_moves_on_pkmn_and_call_generate_instructions(
+        &mut state,
+        Choices::TACKLE,
+        Choices::SPLASH,
+    );
+
+    let expected_instructions = vec![
+        StateInstructions {
+            percentage: 70.0,
+            instruction_list: vec![Instruction::Damage(DamageInstruction {
+                side_ref: SideReference::SideTwo,
+                damage_amount: 48,
+            })],
+        },
+        StateInstructions {
+            percentage: 30.000002,
+            instruction_list: vec![
+                Instruction::Damage(DamageInstruction {
+                    side_ref: SideReference::SideTwo,
+                    damage_amount: 48,
+                }),
+                Instruction::ApplyVolatileStatus(ApplyVolatileStatusInstruction {
+                    side_ref: SideReference::SideOne,
+                    volatile_status: PokemonVolatileStatus::ATTRACT,
+                }),
+            ],
+        },
+    ];
+    assert_eq!(expected_instructions, vec_of_instructions);
+}
+
+#[test]
+fn test_cutecharm_does_nothing_against_the_same_gender() {
+    let mut state = State::default();
+    state.side_one.get_active().gender = PokemonGender::FEMALE;
+    state.side_two.get_active().gender = PokemonGender::FEMALE;
+    state.side_two.get_active().ability = Abilities::CUTECHARM;
+
+    let vec_of_instructions = set_moves_on_pkmn_and_call_generate_instructions(
+        &mut state,
+        Choices::TACKLE,
+        Choices::SPLASH,
+    );
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![Instruction::Damage(DamageInstruction {
+            side_ref: SideReference::SideTwo,
+            damage_amount: 48,
+        })],
+    }];
+    assert_eq!(expected_instructions, vec_of_instructions);
+}
+
+#[test]
+fn test_attract_against_the_opposite_gender() {
+    let mut state = State::default();
+    state.side_one.get_active().gender = PokemonGender::FEMALE;
+    state.side_two.get_active().gender = PokemonGender::MALE;
+
+    let vec_of_instructions = set_moves_on_pkmn_and_call_generate_instructions(
+        &mut state,
+        Choices::ATTRACT,
+        Choices::SPLASH,
+    );
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![Instruction::ApplyVolatileStatus(
+            ApplyVolatileStatusInstruction {
+                side_ref: SideReference::SideTwo,
+                volatile_status: PokemonVolatileStatus::ATTRACT,
+            },
+        )],
+    }];
+    assert_eq!(expected_instructions, vec_of_instructions);
+}
+
+#[test]
+fn test_attract_fails_against_the_same_gender_or_genderless() {
+    for (attacker_gender, defender_gender) in [
+        (PokemonGender::MALE, PokemonGender::MALE),
+        (PokemonGender::FEMALE, PokemonGender::GENDERLESS),
+        (PokemonGender::GENDERLESS, PokemonGender::GENDERLESS),
+    ] {
+        let mut state = State::default();
+        state.side_one.get_active().gender = attacker_gender;
+        state.side_two.get_active().gender = defender_gender;
+
+        let vec_of_instructions = set_moves_on_pkmn_and_call_generate_instructions(
+            &mut state,
+            Choices::ATTRACT,
+            Choices::SPLASH,
+        );
+
+        let expected_instructions = vec![StateInstructions {
+            percentage: 100.0,
+            instruction_list: vec![],
+        }];
+        assert_eq!(expected_instructions, vec_of_instructions);
+    }
+}
+
+#[test]
+fn test_captivate_fails_against_the_same_gender() {
+    let mut state = State::default();
+    state.side_one.get_active().gender = PokemonGender::MALE;
+    state.side_two.get_active().gender = PokemonGender::MALE;
+
+    let vec_of_instructions = set_moves_on_pkmn_and_call_generate_instructions(
+        &mut state,
+        Choices::CAPTIVATE,
+        Choices::SPLASH,
+    );
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![],
+    }];
+    assert_eq!(expected_instructions, vec_of_instructions);
+}
+
+#[test]
+fn test_captivate_against_the_opposite_gender() {
+    let mut state = State::default();
+    state.side_one.get_active().gender = PokemonGender::MALE;
+    state.side_two.get_active().gender = PokemonGender::FEMALE;
+
+    let vec_of_instructions = set_moves_on_pkmn_and_call_generate_instructions(
+        &mut state,
+        Choices::CAPTIVATE,
+        Choices::SPLASH,
+    );
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![Instruction::Boost(BoostInstruction {
+            side_ref: SideReference::SideTwo,
+            stat: PokemonBoostableStat::SpecialAttack,
+            amount: -2,
+        })],
+    }];
+    assert_eq!(expected_instructions, vec_of_instructions);
+}
+
 #[test]
 fn test_poisonpoint() {
     let mut state = State::default();
@@ -18598,6 +18815,74 @@ fn test_technician() {
     assert_eq!(expected_instructions, vec_of_instructions);
 }
 
+#[test]
+fn test_rivalry_boosts_against_the_same_gender() {
+    let mut state = State::default();
+    state.side_one.get_active().ability = Abilities::RIVALRY;
+    state.side_one.get_active().gender = PokemonGender::MALE;
+    state.side_two.get_active().gender = PokemonGender::MALE;
+
+    let vec_of_instructions = set_moves_on_pkmn_and_call_generate_instructions(
+        &mut state,
+        Choices::TACKLE,
+        Choices::SPLASH,
+    );
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![Instruction::Damage(DamageInstruction {
+            side_ref: SideReference::SideTwo,
+            damage_amount: 61,
+        })],
+    }];
+    assert_eq!(expected_instructions, vec_of_instructions);
+}
+
+#[test]
+fn test_rivalry_weakens_against_the_opposite_gender() {
+    let mut state = State::default();
+    state.side_one.get_active().ability = Abilities::RIVALRY;
+    state.side_one.get_active().gender = PokemonGender::MALE;
+    state.side_two.get_active().gender = PokemonGender::FEMALE;
+
+    let vec_of_instructions = set_moves_on_pkmn_and_call_generate_instructions(
+        &mut state,
+        Choices::TACKLE,
+        Choices::SPLASH,
+    );
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![Instruction::Damage(DamageInstruction {
+            side_ref: SideReference::SideTwo,
+            damage_amount: 37,
+        })],
+    }];
+    assert_eq!(expected_instructions, vec_of_instructions);
+}
+
+#[test]
+fn test_rivalry_does_nothing_against_a_genderless_pokemon() {
+    let mut state = State::default();
+    state.side_one.get_active().ability = Abilities::RIVALRY;
+    state.side_one.get_active().gender = PokemonGender::MALE;
+
+    let vec_of_instructions = set_moves_on_pkmn_and_call_generate_instructions(
+        &mut state,
+        Choices::TACKLE,
+        Choices::SPLASH,
+    );
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![Instruction::Damage(DamageInstruction {
+            side_ref: SideReference::SideTwo,
+            damage_amount: 48,
+        })],
+    }];
+    assert_eq!(expected_instructions, vec_of_instructions);
+}
+
 #[test]
 fn test_hadronengine_boost() {
     let mut state = State::default();
@@ -20704,6 +20989,8 @@ fn test_using_none_with_mustrecharge_removes_volatile() {
 #[cfg(not(feature = "terastallization"))]
 fn test_mustrecharge_move_only_allows_none() {
     let mut state = State::default();
+    state.side_one.dynamax_used = true;
+    state.side_two.dynamax_used = true;
     state
         .side_one
         .volatile_statuses
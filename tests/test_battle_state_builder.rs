@@ -0,0 +1,211 @@
+#![cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+
+use poke_engine::battle_format::BattleFormat;
+use poke_engine::engine::battle_environment::{
+    BattleEnvironment, BattleStateBuilder, LeadSelection, Player, GENERATION,
+};
+use poke_engine::engine::state::MoveChoice;
+use poke_engine::pokemon::PokemonName;
+use poke_engine::rng::BattleRng;
+use poke_engine::state::{Pokemon, PokemonIndex, SideReference, State};
+
+fn team(species: &[PokemonName]) -> Vec<Pokemon> {
+    species
+        .iter()
+        .map(|id| Pokemon {
+            id: *id,
+            ..Pokemon::default()
+        })
+        .collect()
+}
+
+fn team_one() -> Vec<Pokemon> {
+    team(&[
+        PokemonName::GARCHOMP,
+        PokemonName::TOXAPEX,
+        PokemonName::CORVIKNIGHT,
+    ])
+}
+
+fn team_two() -> Vec<Pokemon> {
+    team(&[
+        PokemonName::PIKACHU,
+        PokemonName::SNORLAX,
+        PokemonName::GENGAR,
+        PokemonName::BLISSEY,
+        PokemonName::SKARMORY,
+        PokemonName::TYRANITAR,
+    ])
+}
+
+// always leads with the last pokemon it is offered
+struct LastOptionPlayer;
+
+impl Player for LastOptionPlayer {
+    fn choose_move(
+        &self,
+        _state: &State,
+        _side_ref: SideReference,
+        options: &[MoveChoice],
+        _rng: &mut BattleRng,
+    ) -> MoveChoice {
+        *options.last().unwrap()
+    }
+
+    fn name(&self) -> &str {
+        "last option"
+    }
+}
+
+#[test]
+fn test_small_teams_are_padded_with_fainted_pokemon() {
+    let state = BattleStateBuilder::new(team_one(), team_two())
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        PokemonName::GARCHOMP,
+        state.side_one.get_active_immutable().id
+    );
+    assert_eq!(
+        PokemonName::PIKACHU,
+        state.side_two.get_active_immutable().id
+    );
+    assert_eq!(
+        PokemonName::CORVIKNIGHT,
+        state.side_one.pokemon[PokemonIndex::P2].id
+    );
+    for pkmn_index in [PokemonIndex::P3, PokemonIndex::P4, PokemonIndex::P5] {
+        assert_eq!(0, state.side_one.pokemon[pkmn_index].hp);
+        assert!(state.side_two.pokemon[pkmn_index].hp > 0);
+    }
+    assert!(!state.team_preview);
+    assert_eq!(BattleFormat::Singles, state.battle_format());
+}
+
+#[test]
+fn test_fixed_leads_are_moved_to_the_front() {
+    let state = BattleStateBuilder::new(team_one(), team_two())
+        .with_lead_selection(LeadSelection::Fixed(vec![2], vec![4]))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        PokemonName::CORVIKNIGHT,
+        state.side_one.get_active_immutable().id
+    );
+    assert_eq!(
+        PokemonName::GARCHOMP,
+        state.side_one.pokemon[PokemonIndex::P1].id
+    );
+    assert_eq!(
+        PokemonName::TOXAPEX,
+        state.side_one.pokemon[PokemonIndex::P2].id
+    );
+    assert_eq!(
+        PokemonName::SKARMORY,
+        state.side_two.get_active_immutable().id
+    );
+    assert_eq!(
+        PokemonName::TYRANITAR,
+        state.side_two.pokemon[PokemonIndex::P5].id
+    );
+}
+
+#[test]
+fn test_random_leads_are_reproducible_with_a_seed() {
+    let build = |seed| {
+        BattleStateBuilder::new(team_one(), team_two())
+            .with_seed(seed)
+            .with_lead_selection(LeadSelection::Random)
+            .build()
+            .unwrap()
+            .serialize()
+    };
+
+    assert_eq!(build(7), build(7));
+    assert!((0..20).any(|seed| build(seed) != build(7)));
+}
+
+#[test]
+fn test_doubles_has_two_leads_per_side() {
+    let state = BattleStateBuilder::new(team_one(), team_two())
+        .with_format(BattleFormat::Doubles)
+        .with_lead_selection(LeadSelection::Fixed(vec![1, 0], vec![3, 5]))
+        .build()
+        .unwrap();
+
+    assert_eq!(BattleFormat::Doubles, state.battle_format());
+    assert_eq!(
+        PokemonName::TOXAPEX,
+        state.side_one.get_position_active_immutable(0).id
+    );
+    assert_eq!(
+        PokemonName::GARCHOMP,
+        state.side_one.get_position_active_immutable(1).id
+    );
+    assert_eq!(
+        PokemonName::BLISSEY,
+        state.side_two.get_position_active_immutable(0).id
+    );
+    assert_eq!(
+        PokemonName::TYRANITAR,
+        state.side_two.get_position_active_immutable(1).id
+    );
+}
+
+#[test]
+fn test_players_choose_their_leads_at_team_preview() {
+    let state = BattleStateBuilder::new(team_one(), team_two())
+        .with_lead_selection(LeadSelection::TeamPreview)
+        .build()
+        .unwrap();
+    assert!(state.team_preview);
+    let (side_one_options, side_two_options) = state.root_get_all_options();
+    assert_eq!(3, side_one_options.len());
+    assert_eq!(6, side_two_options.len());
+
+    let env = BattleEnvironment::new(
+        Box::new(LastOptionPlayer),
+        Box::new(LastOptionPlayer),
+        0,
+        false,
+    );
+    let result = env.run_battle(state);
+
+    assert!(!result.final_state.team_preview);
+    assert_eq!(
+        PokemonName::CORVIKNIGHT,
+        result.final_state.side_one.get_active_immutable().id
+    );
+    assert_eq!(
+        PokemonName::TYRANITAR,
+        result.final_state.side_two.get_active_immutable().id
+    );
+}
+
+#[test]
+fn test_invalid_battles_are_rejected() {
+    let invalid = [
+        BattleStateBuilder::new(vec![], team_two()),
+        BattleStateBuilder::new(
+            team_one().into_iter().chain(team_two()).collect(),
+            team_two(),
+        ),
+        BattleStateBuilder::new(team_one(), team_two()).with_generation(GENERATION - 1),
+        BattleStateBuilder::new(team_one(), team_two())
+            .with_format(BattleFormat::Doubles)
+            .with_lead_selection(LeadSelection::TeamPreview),
+        BattleStateBuilder::new(team_one(), team_two())
+            .with_lead_selection(LeadSelection::Fixed(vec![1], vec![1, 2])),
+        BattleStateBuilder::new(team_one(), team_two())
+            .with_lead_selection(LeadSelection::Fixed(vec![3], vec![0])),
+        BattleStateBuilder::new(team_one(), team_two())
+            .with_format(BattleFormat::Doubles)
+            .with_lead_selection(LeadSelection::Fixed(vec![0, 0], vec![0, 1])),
+    ];
+
+    for builder in invalid.iter() {
+        assert!(builder.build().is_err(), "{:?}", builder);
+    }
+}
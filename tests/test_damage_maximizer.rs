@@ -0,0 +1,115 @@
+#![cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+
+use poke_engine::choices::Choices;
+use poke_engine::engine::battle_environment::{DamageMaximizer, Player};
+use poke_engine::engine::state::MoveChoice;
+use poke_engine::rng::seeded_rng;
+use poke_engine::state::{PokemonIndex, PokemonMoveIndex, PokemonType, SideReference, State};
+
+fn state_with_moves(side_one_moves: &[Choices], side_two_moves: &[Choices]) -> State {
+    let mut state = State::default();
+    // Max Moves never miss, which would hide what these tests are checking
+    state.side_one.dynamax_used = true;
+    let move_indices = [
+        PokemonMoveIndex::M0,
+        PokemonMoveIndex::M1,
+        PokemonMoveIndex::M2,
+        PokemonMoveIndex::M3,
+    ];
+    for (move_index, choice) in move_indices.iter().zip(side_one_moves) {
+        state
+            .side_one
+            .get_active()
+            .replace_move(*move_index, *choice);
+    }
+    for (move_index, choice) in move_indices.iter().zip(side_two_moves) {
+        state
+            .side_two
+            .get_active()
+            .replace_move(*move_index, *choice);
+    }
+    state
+}
+
+fn side_one_choice(state: &State) -> MoveChoice {
+    let (side_one_options, _) = state.get_all_options();
+    DamageMaximizer::new("damage".to_string()).choose_move(
+        state,
+        SideReference::SideOne,
+        &side_one_options,
+        &mut seeded_rng(0),
+    )
+}
+
+#[test]
+fn test_type_effectiveness_beats_base_power() {
+    let mut state = state_with_moves(&[Choices::BODYSLAM, Choices::MACHPUNCH], &[]);
+    state.side_two.get_active().types = (PokemonType::STEEL, PokemonType::TYPELESS);
+    // neither move gets STAB, with or without terastallizing
+    state.side_one.get_active().types = (PokemonType::PSYCHIC, PokemonType::TYPELESS);
+    state.side_one.get_active().tera_type = PokemonType::PSYCHIC;
+
+    assert_eq!(
+        MoveChoice::Move(PokemonMoveIndex::M1),
+        side_one_choice(&state)
+    );
+}
+
+#[test]
+fn test_accuracy_lowers_the_expected_damage() {
+    let state = state_with_moves(&[Choices::ZAPCANNON, Choices::THUNDERBOLT], &[]);
+
+    assert_eq!(
+        MoveChoice::Move(PokemonMoveIndex::M1),
+        side_one_choice(&state)
+    );
+}
+
+#[test]
+fn test_a_certain_knockout_is_preferred() {
+    let mut state = state_with_moves(&[Choices::HYDROPUMP, Choices::TACKLE], &[]);
+    state.side_two.get_active().hp = 10;
+
+    assert_eq!(
+        MoveChoice::Move(PokemonMoveIndex::M1),
+        side_one_choice(&state)
+    );
+}
+
+#[test]
+fn test_damage_scores_include_knockouts() {
+    let mut state = state_with_moves(&[Choices::TACKLE], &[]);
+    let tackle = state.side_one.get_active().moves[&PokemonMoveIndex::M0]
+        .choice
+        .clone();
+    let full_hp_score = DamageMaximizer::score_damage(&state, SideReference::SideOne, &tackle);
+    state.side_two.get_active().hp = 1;
+
+    let knockout_score = DamageMaximizer::score_damage(&state, SideReference::SideOne, &tackle);
+
+    assert!(full_hp_score > 0.0 && full_hp_score < 100.0);
+    // 1 hp out of the defender's max hp, plus the bonus for a certain knockout
+    let max_hp = state.side_two.get_active().maxhp as f32;
+    assert!((knockout_score - (100.0 / max_hp + 100.0)).abs() < 0.01);
+}
+
+#[test]
+fn test_switches_to_a_resisting_pokemon_when_nothing_does_damage() {
+    let mut state = state_with_moves(&[Choices::SPLASH], &[Choices::THUNDERBOLT]);
+    state.side_one.pokemon[PokemonIndex::P2].types = (PokemonType::GROUND, PokemonType::TYPELESS);
+
+    assert_eq!(
+        MoveChoice::Switch(PokemonIndex::P2),
+        side_one_choice(&state)
+    );
+}
+
+#[test]
+fn test_stays_in_when_no_switch_takes_less_damage() {
+    let state = state_with_moves(&[Choices::SPLASH], &[Choices::SPLASH]);
+
+    assert_eq!(
+        MoveChoice::Move(PokemonMoveIndex::M0),
+        side_one_choice(&state)
+    );
+}
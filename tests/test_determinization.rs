@@ -0,0 +1,170 @@
+#![cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+
+use poke_engine::choices::Choices;
+use poke_engine::determinization::{determinized_expectiminimax, SetDistribution};
+use poke_engine::engine::abilities::Abilities;
+use poke_engine::engine::items::Items;
+use poke_engine::pokemon::PokemonName;
+use poke_engine::state::{pokemon_index_iter, PokemonIndex, PokemonMoveIndex, Side, State};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+const POKEDEX_JSON: &str = include_str!("../data/pokedex.json");
+const MOVEDEX_JSON: &str = include_str!("../data/moves.json");
+
+// Cresselia has two sets, one of which appears twice
+const RANDOM_TEAMS_JSON: &str = r#"[
+  {"packedTeam": "Cresselia||Leftovers|Levitate|psychic,moonblast,calmmind,moonlight||85,85,85,85,85,85||||80|,,,,,Fairy]Slaking||ChoiceScarf|Truant|gigaimpact,bodyslam,throatchop,earthquake||85,85,85,85,85,85||||85|,,,,,Normal"},
+  {"packedTeam": "Cresselia||Leftovers|Levitate|psychic,moonblast,calmmind,moonlight||85,85,85,85,85,85||||80|,,,,,Fairy]Lokix||LifeOrb|TintedLens|leechlife,firstimpression,suckerpunch,axekick||85,85,85,85,85,85||||82|,,,,,Bug"},
+  {"packedTeam": "Cresselia||ChoiceSpecs|Levitate|lunardance,reflect,lightscreen,psychic||85,85,85,85,85,85||||80|,,,,,Fairy]Abomasnow||LightClay|SnowWarning|iceshard,auroraveil,blizzard,earthquake||85,85,85,85,85,85||||86|,,,,,Water"}
+]"#;
+
+fn set_distribution() -> SetDistribution {
+    SetDistribution::from_random_teams(RANDOM_TEAMS_JSON, POKEDEX_JSON, MOVEDEX_JSON).unwrap()
+}
+
+fn unknown_side() -> Side {
+    let mut side = Side::default();
+    for pkmn_index in pokemon_index_iter() {
+        side.pokemon[pkmn_index].id = PokemonName::NONE;
+        side.pokemon[pkmn_index].item = Items::UNKNOWNITEM;
+        side.pokemon[pkmn_index].ability = Abilities::NONE;
+    }
+    side
+}
+
+#[test]
+fn test_distinct_species_are_sampled_for_unrevealed_pokemon() {
+    let set_distribution = set_distribution();
+    assert_eq!(4, set_distribution.number_of_species());
+
+    let mut side = unknown_side();
+    side.pokemon[PokemonIndex::P0].id = PokemonName::CRESSELIA;
+    let sampled = set_distribution.sample_side(&side, &mut StdRng::seed_from_u64(1));
+
+    let mut species = vec![];
+    for pkmn_index in pokemon_index_iter() {
+        let pkmn = &sampled.pokemon[pkmn_index];
+        if pkmn.id != PokemonName::NONE {
+            assert!(!species.contains(&pkmn.id));
+            species.push(pkmn.id);
+        }
+    }
+    // there are only 4 species to sample from, the other slots stay unknown
+    assert_eq!(4, species.len());
+    let new_pokemon = &sampled.pokemon[PokemonIndex::P1];
+    assert_eq!(new_pokemon.maxhp, new_pokemon.hp);
+    assert_ne!(Choices::NONE, new_pokemon.moves.m0.id);
+}
+
+#[test]
+fn test_sampled_set_is_consistent_with_revealed_move() {
+    let set_distribution = set_distribution();
+    let mut side = unknown_side();
+    side.pokemon[PokemonIndex::P0].id = PokemonName::CRESSELIA;
+    side.pokemon[PokemonIndex::P0].hp = 50;
+    side.pokemon[PokemonIndex::P0].replace_move(PokemonMoveIndex::M0, Choices::LUNARDANCE);
+
+    let mut rng = StdRng::seed_from_u64(1);
+    for _ in 0..10 {
+        let sampled = set_distribution.sample_side(&side, &mut rng);
+        let cresselia = &sampled.pokemon[PokemonIndex::P0];
+        assert_eq!(Items::CHOICESPECS, cresselia.item);
+        assert_eq!(Abilities::LEVITATE, cresselia.ability);
+        assert_eq!(Choices::LUNARDANCE, cresselia.moves.m0.id);
+        assert_eq!(cresselia.maxhp / 2, cresselia.hp);
+        let moves: Vec<Choices> = cresselia.moves.into_iter().map(|m| m.id).collect();
+        assert!(moves.contains(&Choices::REFLECT));
+        assert!(moves.contains(&Choices::LIGHTSCREEN));
+    }
+}
+
+#[test]
+fn test_sampled_set_is_consistent_with_revealed_item() {
+    let set_distribution = set_distribution();
+    let mut side = unknown_side();
+    side.pokemon[PokemonIndex::P0].id = PokemonName::CRESSELIA;
+    side.pokemon[PokemonIndex::P0].item = Items::LEFTOVERS;
+
+    let mut rng = StdRng::seed_from_u64(1);
+    for _ in 0..10 {
+        let sampled = set_distribution.sample_side(&side, &mut rng);
+        let moves: Vec<Choices> = sampled.pokemon[PokemonIndex::P0]
+            .moves
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        assert!(moves.contains(&Choices::CALMMIND));
+    }
+}
+
+#[test]
+fn test_determinized_expectiminimax_scores_every_side_one_option() {
+    let set_distribution = set_distribution();
+    let mut state = State::default();
+    state.side_two = unknown_side();
+    state.side_two.pokemon[PokemonIndex::P0].id = PokemonName::SLAKING;
+    state.side_two.pokemon[PokemonIndex::P0].hp = 100;
+    state
+        .side_one
+        .get_active()
+        .replace_move(PokemonMoveIndex::M0, Choices::TACKLE);
+
+    let (s1_options, _) = state.root_get_all_options();
+    let result = determinized_expectiminimax(
+        &state,
+        &set_distribution,
+        3,
+        1,
+        &mut StdRng::seed_from_u64(1),
+    );
+
+    assert_eq!(3, result.samples);
+    assert_eq!(s1_options.len(), result.s1.len());
+    assert!(result.s1.iter().all(|s| s.samples == 3));
+    assert!(result.best_move().is_some());
+}
+
+#[test]
+fn test_invalid_json_is_an_error() {
+    assert!(SetDistribution::from_random_teams("[", POKEDEX_JSON, MOVEDEX_JSON).is_err());
+    assert!(SetDistribution::from_random_teams(RANDOM_TEAMS_JSON, "{", MOVEDEX_JSON).is_err());
+    assert!(SetDistribution::from_random_teams(RANDOM_TEAMS_JSON, POKEDEX_JSON, "{").is_err());
+}
+
+#[test]
+fn test_a_species_missing_from_the_pokedex_is_an_error() {
+    let random_teams_json = r#"[{"packedTeam": "Missingno||Leftovers|Levitate|psychic||85,85,85,85,85,85||||80|,,,,,Fairy"}]"#;
+    assert_eq!(
+        Some("Pokemon not found in pokedex: missingno".to_string()),
+        SetDistribution::from_random_teams(random_teams_json, POKEDEX_JSON, MOVEDEX_JSON).err()
+    );
+}
+
+#[test]
+fn test_empty_slots_are_not_sampled() {
+    let set_distribution = set_distribution();
+    let mut side = unknown_side();
+    side.pokemon[PokemonIndex::P1].hp = 0;
+    let sampled = set_distribution.sample_side(&side, &mut StdRng::seed_from_u64(1));
+    assert_ne!(PokemonName::NONE, sampled.pokemon[PokemonIndex::P0].id);
+    assert_eq!(PokemonName::NONE, sampled.pokemon[PokemonIndex::P1].id);
+    assert_eq!(0, sampled.pokemon[PokemonIndex::P1].hp);
+}
+
+#[test]
+fn test_stats_are_recalculated_from_the_sampled_set() {
+    let set_distribution = set_distribution();
+    let mut side = unknown_side();
+    side.pokemon[PokemonIndex::P0].id = PokemonName::CRESSELIA;
+    side.pokemon[PokemonIndex::P0].level = 80;
+    side.pokemon[PokemonIndex::P0].evs = (0, 0, 0, 0, 0, 0);
+    let sampled = set_distribution.sample_side(&side, &mut StdRng::seed_from_u64(1));
+    let cresselia = &sampled.pokemon[PokemonIndex::P0];
+    assert_eq!((85, 85, 85, 85, 85, 85), cresselia.evs);
+    // (2 * 120 + 31 + 85 / 4) * 80 / 100 + 80 + 10
+    assert_eq!(323, cresselia.maxhp);
+    assert_eq!(cresselia.maxhp, cresselia.hp);
+    // (2 * 85 + 31 + 85 / 4) * 80 / 100 + 5
+    assert_eq!(182, cresselia.speed);
+}
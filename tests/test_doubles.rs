@@ -0,0 +1,480 @@
+#![cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+
+use poke_engine::battle_format::{BattleFormat, BattlePosition};
+use poke_engine::choices::Choices;
+use poke_engine::engine::generate_instructions::generate_instructions_from_doubles_move_quad;
+use poke_engine::engine::state::{DoublesMoveChoice, MoveChoice, PokemonVolatileStatus};
+use poke_engine::instruction::{
+    DamageInstruction, Instruction, StateInstructions, SwapActivePositionInstruction,
+};
+use poke_engine::state::{
+    PokemonIndex, PokemonMoveIndex, PokemonType, PositionState, SideReference, State,
+};
+
+fn doubles_state() -> State {
+    let mut state = State::default();
+    state.side_one.ally_position = Some(PositionState::new(PokemonIndex::P1));
+    state.side_two.ally_position = Some(PositionState::new(PokemonIndex::P1));
+    state
+}
+
+fn set_move(
+    state: &mut State,
+    side_ref: SideReference,
+    pkmn_index: PokemonIndex,
+    move_name: Choices,
+) {
+    state.get_side(&side_ref).pokemon[pkmn_index].replace_move(PokemonMoveIndex::M0, move_name);
+}
+
+fn use_move(target: Option<BattlePosition>) -> DoublesMoveChoice {
+    DoublesMoveChoice::new(MoveChoice::Move(PokemonMoveIndex::M0), target)
+}
+
+fn do_nothing() -> DoublesMoveChoice {
+    DoublesMoveChoice::new(MoveChoice::None, None)
+}
+
+fn generate_doubles_instructions_with_state_assertion(
+    state: &mut State,
+    side_one_choices: &[DoublesMoveChoice; 2],
+    side_two_choices: &[DoublesMoveChoice; 2],
+) -> Vec<StateInstructions> {
+    let before_state_string = format!("{:?}", state);
+    let instructions = generate_instructions_from_doubles_move_quad(
+        state,
+        side_one_choices,
+        side_two_choices,
+        false,
+    );
+    let after_state_string = format!("{:?}", state);
+    assert_eq!(before_state_string, after_state_string);
+    instructions
+}
+
+// applies each branch and returns the hp of the four active pokemon
+fn active_hp_after(state: &mut State, instructions: &StateInstructions) -> [i16; 4] {
+    state.apply_instructions(&instructions.instruction_list);
+    let hp = [
+        state.side_one.get_position_active_immutable(0).hp,
+        state.side_one.get_position_active_immutable(1).hp,
+        state.side_two.get_position_active_immutable(0).hp,
+        state.side_two.get_position_active_immutable(1).hp,
+    ];
+    state.reverse_instructions(&instructions.instruction_list);
+    hp
+}
+
+fn swap(side_ref: SideReference) -> Instruction {
+    Instruction::SwapActivePosition(SwapActivePositionInstruction { side_ref })
+}
+
+fn target(side_ref: SideReference, position: u8) -> Option<BattlePosition> {
+    Some(BattlePosition::new(side_ref, position))
+}
+
+#[test]
+fn test_battle_format_is_determined_by_ally_position() {
+    assert_eq!(BattleFormat::Singles, State::default().battle_format());
+    assert_eq!(BattleFormat::Doubles, doubles_state().battle_format());
+}
+
+#[test]
+fn test_swap_active_position_instruction_is_reversible() {
+    let mut state = doubles_state();
+    state.side_one.attack_boost = 2;
+    state
+        .side_one
+        .volatile_statuses
+        .insert(PokemonVolatileStatus::FOLLOWME);
+    let before_state_string = format!("{:?}", state);
+
+    let instructions = vec![swap(SideReference::SideOne)];
+    state.apply_instructions(&instructions);
+    assert_eq!(PokemonIndex::P1, state.side_one.active_index);
+    assert_eq!(0, state.side_one.attack_boost);
+    assert!(state.side_one.volatile_statuses.is_empty());
+    assert_eq!(
+        PokemonIndex::P0,
+        state.side_one.ally_active_index().unwrap()
+    );
+
+    state.reverse_instructions(&instructions);
+    assert_eq!(before_state_string, format!("{:?}", state));
+}
+
+#[test]
+fn test_doubles_state_serialization_round_trip() {
+    let mut state = doubles_state();
+    state.side_two.ally_position.as_mut().unwrap().speed_boost = -1;
+    state
+        .side_two
+        .ally_position
+        .as_mut()
+        .unwrap()
+        .volatile_statuses
+        .insert(PokemonVolatileStatus::HELPINGHAND);
+
+    let serialized = state.serialize();
+    let deserialized = State::deserialize(&serialized);
+    assert_eq!(serialized, deserialized.serialize());
+    assert_eq!(BattleFormat::Doubles, deserialized.battle_format());
+}
+
+#[test]
+fn test_doubles_options_target_each_opposing_position() {
+    let mut state = doubles_state();
+    set_move(
+        &mut state,
+        SideReference::SideOne,
+        PokemonIndex::P0,
+        Choices::TACKLE,
+    );
+
+    let (side_one_options, _) = state.get_all_doubles_options();
+    let tackle_targets: Vec<Option<BattlePosition>> = side_one_options
+        .iter()
+        .filter(|o| o[0].move_choice == MoveChoice::Move(PokemonMoveIndex::M0))
+        .map(|o| o[0].target)
+        .collect();
+
+    assert!(tackle_targets.contains(&target(SideReference::SideTwo, 0)));
+    assert!(tackle_targets.contains(&target(SideReference::SideTwo, 1)));
+    assert!(!tackle_targets.contains(&None));
+
+    // both positions can never switch to the same pokemon
+    assert!(!side_one_options
+        .iter()
+        .any(|o| matches!(o[0].move_choice, MoveChoice::Switch(_))
+            && o[0].move_choice == o[1].move_choice));
+}
+
+#[test]
+fn test_doubles_options_do_not_target_fainted_foe() {
+    let mut state = doubles_state();
+    set_move(
+        &mut state,
+        SideReference::SideOne,
+        PokemonIndex::P0,
+        Choices::TACKLE,
+    );
+    state.side_two.pokemon[PokemonIndex::P1].hp = 0;
+
+    let (side_one_options, _) = state.get_all_doubles_options();
+    assert!(!side_one_options
+        .iter()
+        .any(|o| o[0].target == target(SideReference::SideTwo, 1)));
+}
+
+#[test]
+fn test_spread_move_hits_both_foes_with_reduced_power() {
+    let mut state = doubles_state();
+    set_move(
+        &mut state,
+        SideReference::SideOne,
+        PokemonIndex::P0,
+        Choices::DAZZLINGGLEAM,
+    );
+
+    let vec_of_instructions = generate_doubles_instructions_with_state_assertion(
+        &mut state,
+        &[use_move(None), do_nothing()],
+        &[do_nothing(), do_nothing()],
+    );
+
+    // 63 damage when used in singles
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![
+            Instruction::Damage(DamageInstruction {
+                side_ref: SideReference::SideTwo,
+                damage_amount: 48,
+            }),
+            swap(SideReference::SideTwo),
+            Instruction::Damage(DamageInstruction {
+                side_ref: SideReference::SideTwo,
+                damage_amount: 48,
+            }),
+            swap(SideReference::SideTwo),
+        ],
+    }];
+    assert_eq!(expected_instructions, vec_of_instructions);
+}
+
+#[test]
+fn test_spread_move_into_one_remaining_foe_is_not_reduced() {
+    let mut state = doubles_state();
+    set_move(
+        &mut state,
+        SideReference::SideOne,
+        PokemonIndex::P0,
+        Choices::DAZZLINGGLEAM,
+    );
+    state.side_two.pokemon[PokemonIndex::P1].hp = 0;
+
+    let vec_of_instructions = generate_doubles_instructions_with_state_assertion(
+        &mut state,
+        &[use_move(None), do_nothing()],
+        &[do_nothing(), do_nothing()],
+    );
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![Instruction::Damage(DamageInstruction {
+            side_ref: SideReference::SideTwo,
+            damage_amount: 63,
+        })],
+    }];
+    assert_eq!(expected_instructions, vec_of_instructions);
+}
+
+#[test]
+fn test_single_target_move_hits_chosen_target() {
+    let mut state = doubles_state();
+    set_move(
+        &mut state,
+        SideReference::SideOne,
+        PokemonIndex::P0,
+        Choices::TACKLE,
+    );
+
+    let vec_of_instructions = generate_doubles_instructions_with_state_assertion(
+        &mut state,
+        &[use_move(target(SideReference::SideTwo, 1)), do_nothing()],
+        &[do_nothing(), do_nothing()],
+    );
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![
+            swap(SideReference::SideTwo),
+            Instruction::Damage(DamageInstruction {
+                side_ref: SideReference::SideTwo,
+                damage_amount: 48,
+            }),
+            swap(SideReference::SideTwo),
+        ],
+    }];
+    assert_eq!(expected_instructions, vec_of_instructions);
+}
+
+#[test]
+fn test_followme_redirects_single_target_move() {
+    let mut state = doubles_state();
+    set_move(
+        &mut state,
+        SideReference::SideOne,
+        PokemonIndex::P0,
+        Choices::TACKLE,
+    );
+    set_move(
+        &mut state,
+        SideReference::SideTwo,
+        PokemonIndex::P0,
+        Choices::FOLLOWME,
+    );
+
+    let vec_of_instructions = generate_doubles_instructions_with_state_assertion(
+        &mut state,
+        &[use_move(target(SideReference::SideTwo, 1)), do_nothing()],
+        &[use_move(None), do_nothing()],
+    );
+
+    assert_eq!(1, vec_of_instructions.len());
+    assert_eq!(
+        [100, 100, 52, 100],
+        active_hp_after(&mut state, &vec_of_instructions[0])
+    );
+    // follow me only lasts for the turn
+    state.apply_instructions(&vec_of_instructions[0].instruction_list);
+    assert!(!state
+        .side_two
+        .volatile_statuses
+        .contains(&PokemonVolatileStatus::FOLLOWME));
+}
+
+#[test]
+fn test_grass_type_ignores_ragepowder() {
+    let mut state = doubles_state();
+    set_move(
+        &mut state,
+        SideReference::SideOne,
+        PokemonIndex::P0,
+        Choices::TACKLE,
+    );
+    set_move(
+        &mut state,
+        SideReference::SideTwo,
+        PokemonIndex::P0,
+        Choices::RAGEPOWDER,
+    );
+    state.side_one.get_active().types.0 = PokemonType::GRASS;
+
+    let vec_of_instructions = generate_doubles_instructions_with_state_assertion(
+        &mut state,
+        &[use_move(target(SideReference::SideTwo, 1)), do_nothing()],
+        &[use_move(None), do_nothing()],
+    );
+
+    // no STAB on tackle anymore, so 32 damage
+    assert_eq!(1, vec_of_instructions.len());
+    assert_eq!(
+        [100, 100, 100, 68],
+        active_hp_after(&mut state, &vec_of_instructions[0])
+    );
+}
+
+#[test]
+fn test_helping_hand_boosts_ally_move() {
+    let mut state = doubles_state();
+    set_move(
+        &mut state,
+        SideReference::SideOne,
+        PokemonIndex::P0,
+        Choices::TACKLE,
+    );
+    set_move(
+        &mut state,
+        SideReference::SideOne,
+        PokemonIndex::P1,
+        Choices::HELPINGHAND,
+    );
+
+    let vec_of_instructions = generate_doubles_instructions_with_state_assertion(
+        &mut state,
+        &[use_move(target(SideReference::SideTwo, 0)), use_move(None)],
+        &[do_nothing(), do_nothing()],
+    );
+
+    // tackle does 48 damage without helping hand
+    assert_eq!(1, vec_of_instructions.len());
+    assert_eq!(
+        [100, 100, 28, 100],
+        active_hp_after(&mut state, &vec_of_instructions[0])
+    );
+}
+
+#[test]
+fn test_earthquake_hits_ally() {
+    let mut state = doubles_state();
+    set_move(
+        &mut state,
+        SideReference::SideOne,
+        PokemonIndex::P0,
+        Choices::EARTHQUAKE,
+    );
+
+    let vec_of_instructions = generate_doubles_instructions_with_state_assertion(
+        &mut state,
+        &[use_move(None), do_nothing()],
+        &[do_nothing(), do_nothing()],
+    );
+
+    assert_eq!(1, vec_of_instructions.len());
+    let hp = active_hp_after(&mut state, &vec_of_instructions[0]);
+    assert_eq!(100, hp[0]);
+    assert!(hp[1] < 100);
+    assert!(hp[2] < 100);
+    assert_eq!(hp[2], hp[3]);
+}
+
+#[test]
+fn test_earthquake_does_not_hit_protected_ally() {
+    let mut state = doubles_state();
+    set_move(
+        &mut state,
+        SideReference::SideOne,
+        PokemonIndex::P0,
+        Choices::EARTHQUAKE,
+    );
+    set_move(
+        &mut state,
+        SideReference::SideOne,
+        PokemonIndex::P1,
+        Choices::PROTECT,
+    );
+
+    let vec_of_instructions = generate_doubles_instructions_with_state_assertion(
+        &mut state,
+        &[use_move(None), use_move(None)],
+        &[do_nothing(), do_nothing()],
+    );
+
+    assert_eq!(1, vec_of_instructions.len());
+    let hp = active_hp_after(&mut state, &vec_of_instructions[0]);
+    assert_eq!([100, 100], [hp[0], hp[1]]);
+    assert!(hp[2] < 100);
+}
+
+#[test]
+fn test_fainted_pokemon_does_not_move() {
+    let mut state = doubles_state();
+    set_move(
+        &mut state,
+        SideReference::SideOne,
+        PokemonIndex::P1,
+        Choices::TACKLE,
+    );
+    state.side_one.pokemon[PokemonIndex::P1].speed = 50;
+    set_move(
+        &mut state,
+        SideReference::SideTwo,
+        PokemonIndex::P0,
+        Choices::TACKLE,
+    );
+    state.side_one.pokemon[PokemonIndex::P1].hp = 40;
+
+    let vec_of_instructions = generate_doubles_instructions_with_state_assertion(
+        &mut state,
+        &[do_nothing(), use_move(target(SideReference::SideTwo, 0))],
+        &[use_move(target(SideReference::SideOne, 1)), do_nothing()],
+    );
+
+    assert_eq!(1, vec_of_instructions.len());
+    assert_eq!(
+        [100, 0, 100, 100],
+        active_hp_after(&mut state, &vec_of_instructions[0])
+    );
+}
+
+#[test]
+fn test_speed_tie_branches_on_which_position_moves_first() {
+    let mut state = doubles_state();
+    set_move(
+        &mut state,
+        SideReference::SideOne,
+        PokemonIndex::P0,
+        Choices::TACKLE,
+    );
+    set_move(
+        &mut state,
+        SideReference::SideTwo,
+        PokemonIndex::P0,
+        Choices::TACKLE,
+    );
+    state.side_two.pokemon[PokemonIndex::P0].speed = state.side_one.pokemon[PokemonIndex::P0].speed;
+
+    let vec_of_instructions = generate_doubles_instructions_with_state_assertion(
+        &mut state,
+        &[use_move(target(SideReference::SideTwo, 0)), do_nothing()],
+        &[use_move(target(SideReference::SideOne, 0)), do_nothing()],
+    );
+
+    let first_damaged_sides: Vec<(f32, SideReference)> = vec_of_instructions
+        .iter()
+        .map(|i| {
+            let first_damage = i.instruction_list.iter().find_map(|i| match i {
+                Instruction::Damage(d) => Some(d.side_ref),
+                _ => None,
+            });
+            (i.percentage, first_damage.unwrap())
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            (50.0, SideReference::SideTwo),
+            (50.0, SideReference::SideOne)
+        ],
+        first_damaged_sides
+    );
+}
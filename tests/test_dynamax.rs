@@ -0,0 +1,458 @@
+#![cfg(feature = "gen8")]
+
+use poke_engine::choices::Choices;
+use poke_engine::engine::generate_instructions::generate_instructions_from_move_pair;
+use poke_engine::engine::items::Items;
+use poke_engine::engine::state::{MoveChoice, PokemonVolatileStatus, Terrain, Weather};
+use poke_engine::instruction::{
+    ApplyVolatileStatusInstruction, BoostInstruction, ChangeStatusInstruction, DynamaxInstruction,
+    Instruction, StateInstructions,
+};
+use poke_engine::pokemon::PokemonName;
+use poke_engine::state::{
+    PokemonBoostableStat, PokemonIndex, PokemonMoveIndex, PokemonStatus, SideReference, State,
+};
+
+fn splash_state() -> State {
+    let mut state = State::default();
+    let active = state.side_one.get_active();
+    active.id = PokemonName::CHARIZARD;
+    active.speed = 150;
+    active.replace_move(PokemonMoveIndex::M0, Choices::SPLASH);
+    active.replace_move(PokemonMoveIndex::M1, Choices::TACKLE);
+    let opponent = state.side_two.get_active();
+    opponent.replace_move(PokemonMoveIndex::M0, Choices::SPLASH);
+    opponent.replace_move(PokemonMoveIndex::M1, Choices::TACKLE);
+    state
+}
+
+fn generate_instructions(
+    state: &mut State,
+    side_one_move: MoveChoice,
+    side_two_move: MoveChoice,
+) -> Vec<StateInstructions> {
+    let before = format!("{:?}", state);
+    let instructions =
+        generate_instructions_from_move_pair(state, &side_one_move, &side_two_move, false);
+    assert_eq!(before, format!("{:?}", state));
+    instructions
+}
+
+fn dynamaxed_state() -> State {
+    let mut state = splash_state();
+    state.side_one.dynamax_used = true;
+    state
+        .side_one
+        .volatile_statuses
+        .insert(PokemonVolatileStatus::DYNAMAX);
+    let active = state.side_one.get_active();
+    active.maxhp = 200;
+    active.hp = 151;
+    state
+}
+
+#[test]
+fn test_dynamaxing_is_offered_once_per_side() {
+    let mut state = splash_state();
+    let (side_one_options, _) = state.get_all_options();
+    assert!(side_one_options.contains(&MoveChoice::MoveDynamax(PokemonMoveIndex::M0)));
+    assert_eq!(
+        "splash-dynamax",
+        MoveChoice::MoveDynamax(PokemonMoveIndex::M0).to_string(&state.side_one)
+    );
+    assert_eq!(
+        Some(MoveChoice::MoveDynamax(PokemonMoveIndex::M0)),
+        MoveChoice::from_string("splash-dynamax", &state.side_one)
+    );
+
+    state.side_one.dynamax_used = true;
+    let (side_one_options, _) = state.get_all_options();
+    assert!(!side_one_options
+        .iter()
+        .any(|m| matches!(m, MoveChoice::MoveDynamax(_))));
+}
+
+#[test]
+fn test_zacian_cannot_dynamax() {
+    let mut state = splash_state();
+    state.side_one.get_active().id = PokemonName::ZACIAN;
+    let (side_one_options, _) = state.get_all_options();
+    assert!(!side_one_options
+        .iter()
+        .any(|m| matches!(m, MoveChoice::MoveDynamax(_))));
+}
+
+#[test]
+fn test_dynamaxing_doubles_hp_and_is_reversible() {
+    let mut state = splash_state();
+    state.side_one.get_active().hp = 60;
+    let before = format!("{:?}", state);
+
+    let instructions = generate_instructions(
+        &mut state,
+        MoveChoice::MoveDynamax(PokemonMoveIndex::M0),
+        MoveChoice::Move(PokemonMoveIndex::M0),
+    );
+    assert_eq!(1, instructions.len());
+    assert_eq!(
+        vec![
+            Instruction::Dynamax(DynamaxInstruction {
+                side_ref: SideReference::SideOne,
+                hp_change: 60,
+            }),
+            Instruction::ApplyVolatileStatus(ApplyVolatileStatusInstruction {
+                side_ref: SideReference::SideOne,
+                volatile_status: PokemonVolatileStatus::DYNAMAX,
+            }),
+        ],
+        instructions[0].instruction_list[..2]
+    );
+
+    state.apply_instructions(&instructions[0].instruction_list);
+    assert!(state.side_one.dynamax_used);
+    assert_eq!(1, state.side_one.volatile_status_durations.dynamax);
+    assert!(state
+        .side_one
+        .volatile_statuses
+        .contains(&PokemonVolatileStatus::DYNAMAX));
+    let active = state.side_one.get_active_immutable();
+    assert_eq!(200, active.maxhp);
+    assert_eq!(120, active.hp);
+
+    state.reverse_instructions(&instructions[0].instruction_list);
+    assert_eq!(before, format!("{:?}", state));
+}
+
+#[test]
+fn test_status_moves_become_max_guard() {
+    let mut state = splash_state();
+    let instructions = generate_instructions(
+        &mut state,
+        MoveChoice::MoveDynamax(PokemonMoveIndex::M0),
+        MoveChoice::Move(PokemonMoveIndex::M1),
+    );
+    for state_instructions in instructions.iter() {
+        assert!(state_instructions
+            .instruction_list
+            .contains(&Instruction::ApplyVolatileStatus(
+                ApplyVolatileStatusInstruction {
+                    side_ref: SideReference::SideOne,
+                    volatile_status: PokemonVolatileStatus::MAXGUARD,
+                }
+            )));
+        assert!(
+            !state_instructions.instruction_list.iter().any(|i| matches!(
+                i,
+                Instruction::Damage(d) if d.side_ref == SideReference::SideOne
+            ))
+        );
+    }
+}
+
+#[test]
+fn test_max_moves_set_weather() {
+    let mut state = dynamaxed_state();
+    state
+        .side_one
+        .get_active()
+        .replace_move(PokemonMoveIndex::M0, Choices::FLAMETHROWER);
+    let instructions = generate_instructions(
+        &mut state,
+        MoveChoice::Move(PokemonMoveIndex::M0),
+        MoveChoice::Move(PokemonMoveIndex::M0),
+    );
+    for state_instructions in instructions.iter() {
+        state.apply_instructions(&state_instructions.instruction_list);
+        assert_eq!(Weather::SUN, state.weather.weather_type);
+        // the end of turn already used up one of the 5 turns
+        assert_eq!(4, state.weather.turns_remaining);
+        state.reverse_instructions(&state_instructions.instruction_list);
+    }
+}
+
+#[test]
+fn test_max_move_weather_is_extended_by_a_weather_rock() {
+    let mut state = dynamaxed_state();
+    let active = state.side_one.get_active();
+    active.item = Items::HEATROCK;
+    active.replace_move(PokemonMoveIndex::M0, Choices::FLAMETHROWER);
+    let instructions = generate_instructions(
+        &mut state,
+        MoveChoice::Move(PokemonMoveIndex::M0),
+        MoveChoice::Move(PokemonMoveIndex::M0),
+    );
+    for state_instructions in instructions.iter() {
+        state.apply_instructions(&state_instructions.instruction_list);
+        assert_eq!(Weather::SUN, state.weather.weather_type);
+        assert_eq!(7, state.weather.turns_remaining);
+        state.reverse_instructions(&state_instructions.instruction_list);
+    }
+}
+
+#[test]
+fn test_max_moves_do_not_replace_a_strong_weather() {
+    let mut state = dynamaxed_state();
+    state.weather.weather_type = Weather::HARSHSUN;
+    state.weather.turns_remaining = -1;
+    state
+        .side_one
+        .get_active()
+        .replace_move(PokemonMoveIndex::M0, Choices::SURF);
+    let instructions = generate_instructions(
+        &mut state,
+        MoveChoice::Move(PokemonMoveIndex::M0),
+        MoveChoice::Move(PokemonMoveIndex::M0),
+    );
+    for state_instructions in instructions.iter() {
+        state.apply_instructions(&state_instructions.instruction_list);
+        assert_eq!(Weather::HARSHSUN, state.weather.weather_type);
+        state.reverse_instructions(&state_instructions.instruction_list);
+    }
+}
+
+#[test]
+fn test_max_move_terrain_is_extended_by_a_terrain_extender() {
+    let mut state = dynamaxed_state();
+    let active = state.side_one.get_active();
+    active.item = Items::TERRAINEXTENDER;
+    active.replace_move(PokemonMoveIndex::M0, Choices::THUNDERBOLT);
+    let instructions = generate_instructions(
+        &mut state,
+        MoveChoice::Move(PokemonMoveIndex::M0),
+        MoveChoice::Move(PokemonMoveIndex::M0),
+    );
+    for state_instructions in instructions.iter() {
+        state.apply_instructions(&state_instructions.instruction_list);
+        assert_eq!(Terrain::ELECTRICTERRAIN, state.terrain.terrain_type);
+        assert_eq!(7, state.terrain.turns_remaining);
+        state.reverse_instructions(&state_instructions.instruction_list);
+    }
+}
+
+fn damage_dealt_to_side_two(instructions: &[StateInstructions]) -> Vec<i16> {
+    instructions
+        .iter()
+        .map(|state_instructions| {
+            state_instructions
+                .instruction_list
+                .iter()
+                .filter_map(|i| match i {
+                    Instruction::Damage(d) if d.side_ref == SideReference::SideTwo => {
+                        Some(d.damage_amount)
+                    }
+                    _ => None,
+                })
+                .sum()
+        })
+        .collect()
+}
+
+#[test]
+fn test_max_moves_break_through_protect_for_a_quarter_of_their_damage() {
+    let mut state = dynamaxed_state();
+    state.side_two.get_active().maxhp = 500;
+    state.side_two.get_active().hp = 500;
+    state
+        .side_two
+        .get_active()
+        .replace_move(PokemonMoveIndex::M0, Choices::PROTECT);
+    let unprotected = generate_instructions(
+        &mut state,
+        MoveChoice::Move(PokemonMoveIndex::M1),
+        MoveChoice::Move(PokemonMoveIndex::M1),
+    );
+    let protected = generate_instructions(
+        &mut state,
+        MoveChoice::Move(PokemonMoveIndex::M1),
+        MoveChoice::Move(PokemonMoveIndex::M0),
+    );
+    assert_eq!(vec![106], damage_dealt_to_side_two(&unprotected));
+    assert_eq!(vec![27], damage_dealt_to_side_two(&protected));
+}
+
+#[test]
+fn test_max_guard_stops_max_moves() {
+    let mut state = dynamaxed_state();
+    state.side_two.dynamax_used = true;
+    state
+        .side_two
+        .volatile_statuses
+        .insert(PokemonVolatileStatus::DYNAMAX);
+    let instructions = generate_instructions(
+        &mut state,
+        MoveChoice::Move(PokemonMoveIndex::M1),
+        MoveChoice::Move(PokemonMoveIndex::M0),
+    );
+    assert_eq!(vec![0], damage_dealt_to_side_two(&instructions));
+}
+
+#[test]
+fn test_max_knuckle_boosts_the_user() {
+    let mut state = dynamaxed_state();
+    state
+        .side_one
+        .get_active()
+        .replace_move(PokemonMoveIndex::M0, Choices::CLOSECOMBAT);
+    let instructions = generate_instructions(
+        &mut state,
+        MoveChoice::Move(PokemonMoveIndex::M0),
+        MoveChoice::Move(PokemonMoveIndex::M0),
+    );
+    for state_instructions in instructions.iter() {
+        assert!(state_instructions
+            .instruction_list
+            .contains(&Instruction::Boost(BoostInstruction {
+                side_ref: SideReference::SideOne,
+                stat: PokemonBoostableStat::Attack,
+                amount: 1,
+            })));
+        // close combat's own defense drops are not kept by the Max Move
+        assert!(
+            !state_instructions.instruction_list.iter().any(|i| matches!(
+                i,
+                Instruction::Boost(b) if b.side_ref == SideReference::SideOne && b.amount < 0
+            ))
+        );
+    }
+}
+
+#[test]
+fn test_dynamax_ends_after_three_turns() {
+    let mut state = dynamaxed_state();
+    state.side_one.volatile_status_durations.dynamax = 2;
+    let before = format!("{:?}", state);
+
+    let instructions = generate_instructions(
+        &mut state,
+        MoveChoice::Move(PokemonMoveIndex::M0),
+        MoveChoice::Move(PokemonMoveIndex::M0),
+    );
+    assert_eq!(1, instructions.len());
+
+    state.apply_instructions(&instructions[0].instruction_list);
+    assert!(!state
+        .side_one
+        .volatile_statuses
+        .contains(&PokemonVolatileStatus::DYNAMAX));
+    assert_eq!(0, state.side_one.volatile_status_durations.dynamax);
+    assert!(state.side_one.dynamax_used);
+    let active = state.side_one.get_active_immutable();
+    assert_eq!(100, active.maxhp);
+    assert_eq!(76, active.hp);
+
+    state.reverse_instructions(&instructions[0].instruction_list);
+    assert_eq!(before, format!("{:?}", state));
+}
+
+#[test]
+fn test_switching_out_ends_dynamax() {
+    let mut state = dynamaxed_state();
+    state.side_one.volatile_status_durations.dynamax = 1;
+    let before = format!("{:?}", state);
+
+    let instructions = generate_instructions(
+        &mut state,
+        MoveChoice::Switch(PokemonIndex::P1),
+        MoveChoice::Move(PokemonMoveIndex::M0),
+    );
+    assert_eq!(1, instructions.len());
+
+    state.apply_instructions(&instructions[0].instruction_list);
+    assert!(!state
+        .side_one
+        .volatile_statuses
+        .contains(&PokemonVolatileStatus::DYNAMAX));
+    assert_eq!(0, state.side_one.volatile_status_durations.dynamax);
+    let charizard = &state.side_one.pokemon[PokemonIndex::P0];
+    assert_eq!(100, charizard.maxhp);
+    assert_eq!(76, charizard.hp);
+
+    state.reverse_instructions(&instructions[0].instruction_list);
+    assert_eq!(before, format!("{:?}", state));
+}
+
+#[test]
+fn test_gigantamax_pokemon_changes_forme_and_uses_its_gmax_move() {
+    let mut state = splash_state();
+    let pikachu = state.side_one.get_active();
+    pikachu.id = PokemonName::PIKACHU;
+    pikachu.gigantamax = true;
+    pikachu.replace_move(PokemonMoveIndex::M0, Choices::THUNDERBOLT);
+    let opponent = state.side_two.get_active();
+    opponent.maxhp = 500;
+    opponent.hp = 500;
+    let before = format!("{:?}", state);
+
+    let instructions = generate_instructions(
+        &mut state,
+        MoveChoice::MoveDynamax(PokemonMoveIndex::M0),
+        MoveChoice::Move(PokemonMoveIndex::M0),
+    );
+    for state_instructions in instructions.iter() {
+        state.apply_instructions(&state_instructions.instruction_list);
+        assert_eq!(
+            PokemonName::PIKACHUGMAX,
+            state.side_one.get_active_immutable().id
+        );
+        state.reverse_instructions(&state_instructions.instruction_list);
+    }
+    // G-Max Volt Crash always paralyzes
+    assert!(instructions
+        .iter()
+        .all(|s| s.instruction_list.contains(&Instruction::ChangeStatus(
+            ChangeStatusInstruction {
+                side_ref: SideReference::SideTwo,
+                pokemon_index: PokemonIndex::P0,
+                old_status: PokemonStatus::NONE,
+                new_status: PokemonStatus::PARALYZE,
+            }
+        ))));
+    assert_eq!(before, format!("{:?}", state));
+}
+
+#[test]
+fn test_dynamaxed_pokemon_cannot_flinch() {
+    let mut state = dynamaxed_state();
+    let opponent = state.side_two.get_active();
+    opponent.speed = 200;
+    opponent.replace_move(PokemonMoveIndex::M0, Choices::FAKEOUT);
+    let instructions = generate_instructions(
+        &mut state,
+        MoveChoice::Move(PokemonMoveIndex::M0),
+        MoveChoice::Move(PokemonMoveIndex::M0),
+    );
+    assert!(!instructions.iter().any(|s| s.instruction_list.contains(
+        &Instruction::ApplyVolatileStatus(ApplyVolatileStatusInstruction {
+            side_ref: SideReference::SideOne,
+            volatile_status: PokemonVolatileStatus::FLINCH,
+        })
+    )));
+}
+
+#[test]
+fn test_a_max_move_saved_behind_a_uturn_is_saved_as_the_original_move() {
+    let mut state = dynamaxed_state();
+    state.side_one.get_active().speed = 50;
+    state
+        .side_two
+        .get_active()
+        .replace_move(PokemonMoveIndex::M0, Choices::UTURN);
+    let instructions = generate_instructions(
+        &mut state,
+        MoveChoice::Move(PokemonMoveIndex::M1),
+        MoveChoice::Move(PokemonMoveIndex::M0),
+    );
+    for state_instructions in instructions.iter() {
+        state.apply_instructions(&state_instructions.instruction_list);
+        assert_eq!(
+            Choices::TACKLE,
+            state.side_one.switch_out_move_second_saved_move
+        );
+        let (side_one_options, _) = state.get_all_options();
+        assert_eq!(
+            vec![MoveChoice::Move(PokemonMoveIndex::M1)],
+            side_one_options
+        );
+        state.reverse_instructions(&state_instructions.instruction_list);
+    }
+}
@@ -0,0 +1,135 @@
+#![cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+
+use poke_engine::choices::Choices;
+use poke_engine::engine::evaluate::{evaluate, EvaluationWeights};
+use poke_engine::evaluator::{DefaultEvaluator, Evaluator};
+use poke_engine::mcts::{perform_mcts_with_tree, MctsConfig, MctsTree};
+use poke_engine::rng::seeded_rng;
+use poke_engine::search::{expectiminimax_search_with_table, TranspositionTable};
+use poke_engine::state::{PokemonMoveIndex, PokemonStatus, State};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+struct ConstantEvaluator(f32);
+
+impl Evaluator for ConstantEvaluator {
+    fn evaluate(&self, _state: &State) -> f32 {
+        self.0
+    }
+}
+
+fn state_with_everything_evaluated() -> State {
+    let mut state = State::default();
+    state.side_one.attack_boost = 2;
+    state.side_one.speed_boost = -1;
+    state.side_two.special_defense_boost = 6;
+    state.side_one.get_active().status = PokemonStatus::PARALYZE;
+    state.side_two.get_active().status = PokemonStatus::TOXIC;
+    state.side_two.get_active().hp -= 40;
+    state.side_one.side_conditions.stealth_rock = 1;
+    state.side_two.side_conditions.spikes = 2;
+    state.side_two.side_conditions.reflect = 3;
+    state
+}
+
+#[test]
+fn test_default_weights_evaluate_the_same_as_evaluate() {
+    let state = state_with_everything_evaluated();
+
+    assert_eq!(evaluate(&state), DefaultEvaluator.evaluate(&state));
+    assert_eq!(
+        evaluate(&state),
+        EvaluationWeights::default().evaluate(&state)
+    );
+}
+
+#[test]
+fn test_weights_missing_from_json_keep_their_default() {
+    let weights =
+        EvaluationWeights::from_json(r#"{"stealth_rock": -20.0, "paralyzed": 0.0}"#).unwrap();
+
+    assert_eq!(-20.0, weights.stealth_rock);
+    assert_eq!(0.0, weights.paralyzed);
+    assert_eq!(EvaluationWeights::default().spikes, weights.spikes);
+    assert_eq!(
+        EvaluationWeights::default().boost_multipliers,
+        weights.boost_multipliers
+    );
+}
+
+#[test]
+fn test_loaded_weights_change_the_evaluation() {
+    let state = state_with_everything_evaluated();
+    let path = std::env::temp_dir().join("poke_engine_test_evaluation_weights.json");
+    std::fs::write(
+        &path,
+        r#"{
+            "paralyzed": 0.0,
+            "boost_multipliers": [-6, -5, -4, -3, -2, -1, 0, 1, 2, 3, 4, 5, 6]
+        }"#,
+    )
+    .unwrap();
+
+    let weights = EvaluationWeights::from_file(path.to_str().unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // side one is no longer penalized for paralysis and +2 attack is worth 2 * 30 as before,
+    // -1 speed is still worth -30, and +6 special defense on side two drops from 3.3 to 6 * 15
+    let expected = evaluate(&state) + 25.0 - (6.0 - 3.3) * 15.0;
+    assert!((expected - weights.evaluate(&state)).abs() < 0.001);
+}
+
+#[test]
+fn test_unknown_weights_are_rejected() {
+    assert!(EvaluationWeights::from_json(r#"{"stealth_rocks": -20.0}"#).is_err());
+    assert!(EvaluationWeights::from_file("/this/file/does/not/exist.json").is_err());
+}
+
+#[test]
+fn test_search_scores_leaves_with_the_given_evaluator() {
+    let mut state = State::default();
+    state
+        .side_one
+        .get_active()
+        .replace_move(PokemonMoveIndex::M0, Choices::TACKLE);
+    let (side_one_options, side_two_options) = state.get_all_options();
+    state.rehash();
+
+    let scores = expectiminimax_search_with_table(
+        &mut state,
+        2,
+        side_one_options,
+        side_two_options,
+        false,
+        &Arc::new(Mutex::new(true)),
+        &mut TranspositionTable::new(1 << 10),
+        &ConstantEvaluator(12.5),
+        None,
+    );
+
+    assert!(scores.iter().all(|score| (score - 12.5).abs() < 0.001));
+}
+
+#[test]
+fn test_mcts_rollouts_use_the_given_evaluator() {
+    let mut state = State::default();
+    let (side_one_options, side_two_options) = state.get_all_options();
+    let mut tree = MctsTree::new(side_one_options, side_two_options);
+
+    // every rollout is scored relative to the root's evaluation, which is the same constant
+    let result = perform_mcts_with_tree(
+        &mut state,
+        &mut tree,
+        &MctsConfig {
+            max_time: Duration::from_secs(60),
+            max_iterations: 200,
+            ..Default::default()
+        },
+        &ConstantEvaluator(-500.0),
+        &mut seeded_rng(0),
+    );
+
+    for side_result in result.s1.iter().filter(|r| r.visits > 0) {
+        assert_eq!(0.5, side_result.average_score());
+    }
+}
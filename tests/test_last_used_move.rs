@@ -162,6 +162,7 @@ fn test_last_used_move_overwritten_when_dragged_out() {
 #[cfg(not(feature = "terastallization"))]
 fn test_encore_causes_get_all_options_to_only_allow_last_used_move() {
     let mut state = State::default();
+    state.side_one.dynamax_used = true;
     state.use_last_used_move = true;
     state
         .side_one
@@ -192,6 +193,7 @@ fn test_encore_causes_get_all_options_to_only_allow_last_used_move() {
 #[cfg(not(feature = "terastallization"))]
 fn test_encore_and_arenatrapped_together() {
     let mut state = State::default();
+    state.side_one.dynamax_used = true;
     state.use_last_used_move = true;
     state.side_two.get_active().ability = Abilities::ARENATRAP;
     state
@@ -216,6 +218,7 @@ fn test_encore_and_arenatrapped_together() {
 #[cfg(not(feature = "terastallization"))]
 fn test_encore_slow() {
     let mut state = State::default();
+    state.side_two.dynamax_used = true;
     state.use_last_used_move = true;
 
     state
@@ -284,6 +287,7 @@ fn test_encore_slow() {
 #[cfg(not(feature = "terastallization"))]
 fn test_encore_slow_into_substitute() {
     let mut state = State::default();
+    state.side_two.dynamax_used = true;
     state.use_last_used_move = true;
 
     state
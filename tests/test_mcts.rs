@@ -0,0 +1,418 @@
+#![cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+
+use poke_engine::choices::Choices;
+use poke_engine::engine::battle_environment::RandomPlayer;
+use poke_engine::engine::evaluate::evaluate;
+use poke_engine::engine::state::MoveChoice;
+use poke_engine::evaluator::DefaultEvaluator;
+use poke_engine::mcts::{
+    perform_mcts, perform_mcts_with_tree, perform_parallel_mcts, MctsConfig, MctsResult,
+    MctsSideResult, MctsTree, MoveSelectionPolicy, Node, RolloutPolicy,
+};
+use poke_engine::rng::seeded_rng;
+use poke_engine::state::{pokemon_index_iter, PokemonIndex, PokemonMoveIndex, State};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn side_result(move_choice: MoveChoice, total_score: f32, visits: i64) -> MctsSideResult {
+    MctsSideResult {
+        move_choice,
+        total_score,
+        visits,
+    }
+}
+
+#[test]
+fn test_merging_mcts_results_sums_scores_and_visits_per_move() {
+    let tackle = MoveChoice::Move(PokemonMoveIndex::M0);
+    let switch = MoveChoice::Switch(PokemonIndex::P1);
+    let first = MctsResult {
+        s1: vec![side_result(tackle, 6.0, 10), side_result(switch, 1.0, 2)],
+        s2: vec![side_result(tackle, 5.0, 12)],
+        iteration_count: 12,
+        max_depth: 3,
+    };
+    let second = MctsResult {
+        s1: vec![side_result(switch, 2.0, 4), side_result(tackle, 3.0, 4)],
+        s2: vec![side_result(tackle, 4.0, 8)],
+        iteration_count: 8,
+        max_depth: 5,
+    };
+
+    let merged = MctsResult::merge(vec![first, second]);
+
+    assert_eq!(20, merged.iteration_count);
+    assert_eq!(5, merged.max_depth);
+    assert_eq!(2, merged.s1.len());
+    assert_eq!(tackle, merged.s1[0].move_choice);
+    assert_eq!(9.0, merged.s1[0].total_score);
+    assert_eq!(14, merged.s1[0].visits);
+    assert_eq!(switch, merged.s1[1].move_choice);
+    assert_eq!(3.0, merged.s1[1].total_score);
+    assert_eq!(6, merged.s1[1].visits);
+    assert_eq!(1, merged.s2.len());
+    assert_eq!(20, merged.s2[0].visits);
+}
+
+fn config(max_time_ms: u64) -> MctsConfig {
+    MctsConfig::with_max_time(Duration::from_millis(max_time_ms))
+}
+
+fn state_with_tackles() -> State {
+    let mut state = State::default();
+    state
+        .side_one
+        .get_active()
+        .replace_move(PokemonMoveIndex::M0, Choices::TACKLE);
+    state
+        .side_two
+        .get_active()
+        .replace_move(PokemonMoveIndex::M0, Choices::TACKLE);
+    state
+}
+
+#[test]
+fn test_parallel_mcts_visits_add_up_to_the_iteration_count() {
+    let state = state_with_tackles();
+    let (side_one_options, side_two_options) = state.root_get_all_options();
+
+    let result = perform_parallel_mcts(
+        &state,
+        side_one_options.clone(),
+        side_two_options.clone(),
+        &MctsConfig {
+            max_time: Duration::from_millis(20),
+            threads: 3,
+            ..Default::default()
+        },
+        &DefaultEvaluator,
+        &mut seeded_rng(0),
+    );
+
+    assert_eq!(side_one_options.len(), result.s1.len());
+    assert_eq!(side_two_options.len(), result.s2.len());
+    assert!(result.iteration_count > 0);
+    assert_eq!(
+        result.iteration_count,
+        result.s1.iter().map(|s| s.visits).sum::<i64>()
+    );
+    assert_eq!(
+        result.iteration_count,
+        result.s2.iter().map(|s| s.visits).sum::<i64>()
+    );
+}
+
+#[test]
+fn test_tree_nodes_point_back_to_their_parent() {
+    let mut state = state_with_tackles();
+    let (side_one_options, side_two_options) = state.get_all_options();
+    let mut tree = MctsTree::new(side_one_options, side_two_options);
+    let state_before = format!("{:?}", state);
+
+    perform_mcts_with_tree(
+        &mut state,
+        &mut tree,
+        &config(20),
+        &DefaultEvaluator,
+        &mut seeded_rng(0),
+    );
+
+    assert_eq!(state_before, format!("{:?}", state));
+    assert!(tree.nodes.len() > 1);
+    for (node_index, node) in tree.nodes.iter().enumerate() {
+        for children in node.children.values() {
+            for child in children.iter() {
+                assert_eq!(Some(node_index), tree.nodes[*child].parent);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_expanding_with_a_damage_bucket_merges_damage_rolls() {
+    let mut state = state_with_tackles();
+    let (side_one_options, side_two_options) = state.get_all_options();
+    let state_before = format!("{:?}", state);
+
+    let mut children_per_bucket = vec![];
+    for damage_bucket in [None, Some(100)] {
+        let mut tree = MctsTree::new(side_one_options.clone(), side_two_options.clone());
+        let child = tree.expand(
+            MctsTree::ROOT,
+            &mut state,
+            0,
+            0,
+            damage_bucket,
+            &mut seeded_rng(0),
+        );
+        state.reverse_instructions(&tree.nodes[child].instructions.instruction_list);
+        assert_eq!(state_before, format!("{:?}", state));
+
+        let children = &tree.root().children[&(0, 0)];
+        let total: f32 = children
+            .iter()
+            .map(|c| tree.nodes[*c].instructions.percentage)
+            .sum();
+        assert!((total - 100.0).abs() < 0.001);
+        children_per_bucket.push(children.len());
+    }
+
+    assert!(children_per_bucket[0] > 1);
+    assert_eq!(1, children_per_bucket[1]);
+}
+
+#[test]
+fn test_reused_subtrees_have_the_same_layout_every_time() {
+    let config = MctsConfig {
+        max_time: Duration::MAX,
+        max_iterations: 300,
+        ..Default::default()
+    };
+    let mut layouts = vec![];
+    for _ in 0..2 {
+        let mut state = state_with_tackles();
+        let (side_one_options, side_two_options) = state.get_all_options();
+        let mut tree = MctsTree::new(side_one_options, side_two_options);
+        perform_mcts_with_tree(
+            &mut state,
+            &mut tree,
+            &config,
+            &DefaultEvaluator,
+            &mut seeded_rng(0),
+        );
+        let mut next_state = state.clone();
+        next_state.apply_instructions(&tree.nodes[1].instructions.instruction_list);
+
+        let reused = tree.reuse_subtree(&mut state, &mut next_state).unwrap();
+        let layout: Vec<_> = reused
+            .nodes
+            .iter()
+            .map(|n| (n.parent, n.s1_choice, n.s2_choice, n.instructions.clone()))
+            .collect();
+        layouts.push(layout);
+    }
+    assert_eq!(layouts[0], layouts[1]);
+}
+
+#[test]
+fn test_reusing_the_subtree_below_the_state_that_was_reached() {
+    let mut state = state_with_tackles();
+    let (side_one_options, side_two_options) = state.get_all_options();
+    let mut tree = MctsTree::new(side_one_options, side_two_options);
+    perform_mcts_with_tree(
+        &mut state,
+        &mut tree,
+        &config(20),
+        &DefaultEvaluator,
+        &mut seeded_rng(0),
+    );
+
+    let (_, children) = tree
+        .root()
+        .children
+        .iter()
+        .max_by_key(|(_, children)| children.len())
+        .unwrap();
+    let child = &tree.nodes[children[0]];
+    let child_visits = child.times_visited;
+    let child_s1_options: Vec<MoveChoice> =
+        child.s1_options.iter().map(|m| m.move_choice).collect();
+    let mut next_state = state.clone();
+    next_state.apply_instructions(&child.instructions.instruction_list);
+
+    let mut reused = tree.reuse_subtree(&mut state, &mut next_state).unwrap();
+
+    assert!(reused.root().is_root());
+    assert!(reused.root().times_visited >= child_visits);
+    let (next_s1_options, next_s2_options) = next_state.get_all_options();
+    assert_eq!(child_s1_options, next_s1_options);
+    assert!(reused.root_options_match(&next_s1_options, &next_s2_options));
+
+    let visits_before = reused.root().times_visited;
+    let result = perform_mcts_with_tree(
+        &mut next_state,
+        &mut reused,
+        &config(5),
+        &DefaultEvaluator,
+        &mut seeded_rng(0),
+    );
+    assert!(result.iteration_count > visits_before);
+}
+
+#[test]
+fn test_no_subtree_is_reused_for_an_unreachable_state() {
+    let mut state = state_with_tackles();
+    let (side_one_options, side_two_options) = state.get_all_options();
+    let mut tree = MctsTree::new(side_one_options, side_two_options);
+    perform_mcts_with_tree(
+        &mut state,
+        &mut tree,
+        &config(20),
+        &DefaultEvaluator,
+        &mut seeded_rng(0),
+    );
+
+    let mut next_state = state.clone();
+    next_state.side_one.get_active().speed += 50;
+
+    assert!(tree.reuse_subtree(&mut state, &mut next_state).is_none());
+}
+
+#[test]
+fn test_mcts_stops_at_the_iteration_limit() {
+    let mut state = state_with_tackles();
+    let (side_one_options, side_two_options) = state.get_all_options();
+    let config = MctsConfig {
+        max_time: Duration::from_secs(60),
+        max_iterations: 1500,
+        ..Default::default()
+    };
+
+    let result = perform_mcts(
+        &mut state,
+        side_one_options,
+        side_two_options,
+        &config,
+        &mut seeded_rng(0),
+    );
+
+    assert_eq!(1500, result.iteration_count);
+}
+
+#[test]
+fn test_move_selection_policies() {
+    let most_visited = MoveChoice::Move(PokemonMoveIndex::M0);
+    let best_average = MoveChoice::Move(PokemonMoveIndex::M1);
+    let never_visited = MoveChoice::Move(PokemonMoveIndex::M2);
+    let results = vec![
+        side_result(most_visited, 50.0, 100),
+        side_result(best_average, 9.0, 10),
+        side_result(never_visited, 0.0, 0),
+    ];
+
+    assert_eq!(
+        Some(most_visited),
+        MoveSelectionPolicy::MostVisits.select(&results)
+    );
+    assert_eq!(
+        Some(best_average),
+        MoveSelectionPolicy::BestAverage.select(&results)
+    );
+    assert_eq!(
+        Ok(MoveSelectionPolicy::BestAverage),
+        "best-average".parse::<MoveSelectionPolicy>()
+    );
+    assert!("unknown".parse::<MoveSelectionPolicy>().is_err());
+}
+
+#[test]
+fn test_playout_rollouts_leave_the_state_unchanged() {
+    for rollout_policy in [
+        RolloutPolicy::Random,
+        RolloutPolicy::GreedyDamage,
+        RolloutPolicy::Player(Arc::new(RandomPlayer::new("random".to_string()))),
+    ] {
+        let mut state = state_with_tackles();
+        let (side_one_options, side_two_options) = state.get_all_options();
+        let state_before = format!("{:?}", state);
+        let config = MctsConfig {
+            max_time: Duration::from_secs(60),
+            max_iterations: 200,
+            rollout_policy,
+            rollout_turns: 5,
+            ..Default::default()
+        };
+
+        let result = perform_mcts(
+            &mut state,
+            side_one_options,
+            side_two_options,
+            &config,
+            &mut seeded_rng(0),
+        );
+
+        assert_eq!(200, result.iteration_count);
+        assert_eq!(state_before, format!("{:?}", state));
+    }
+}
+
+#[test]
+fn test_playout_rollout_scores_the_end_of_the_battle() {
+    let mut state = state_with_tackles();
+    for pkmn_index in pokemon_index_iter() {
+        state.side_two.pokemon[pkmn_index].hp = 0;
+    }
+    state.side_two.get_active().hp = 1;
+    // dynamaxing would double side two's hp and let it survive the tackle
+    state.side_two.dynamax_used = true;
+    let (side_one_options, side_two_options) = state.get_all_options();
+    let node = Node::new(side_one_options, side_two_options);
+    let root_eval = evaluate(&state);
+
+    let static_score = node.rollout(
+        &mut state,
+        &root_eval,
+        &MctsConfig::default(),
+        &DefaultEvaluator,
+        &mut seeded_rng(0),
+    );
+    let greedy_score = node.rollout(
+        &mut state,
+        &root_eval,
+        &MctsConfig {
+            rollout_policy: RolloutPolicy::GreedyDamage,
+            rollout_turns: 1,
+            ..Default::default()
+        },
+        &DefaultEvaluator,
+        &mut seeded_rng(0),
+    );
+
+    assert_eq!(0.5, static_score);
+    assert_eq!(1.0, greedy_score);
+    assert_eq!(1, state.side_two.get_active().hp);
+}
+
+#[test]
+fn test_parsing_rollout_policies() {
+    assert!(matches!(
+        "greedy-damage".parse::<RolloutPolicy>(),
+        Ok(RolloutPolicy::GreedyDamage)
+    ));
+    assert!(matches!(
+        "random".parse::<RolloutPolicy>(),
+        Ok(RolloutPolicy::Random)
+    ));
+    assert!("player".parse::<RolloutPolicy>().is_err());
+}
+
+#[test]
+fn test_the_same_seed_gives_the_same_search() {
+    let search = |seed| {
+        let state = state_with_tackles();
+        let (side_one_options, side_two_options) = state.get_all_options();
+        let config = MctsConfig {
+            max_time: Duration::MAX,
+            max_iterations: 300,
+            rollout_policy: RolloutPolicy::Random,
+            threads: 2,
+            ..Default::default()
+        };
+        let result = perform_parallel_mcts(
+            &state,
+            side_one_options,
+            side_two_options,
+            &config,
+            &DefaultEvaluator,
+            &mut seeded_rng(seed),
+        );
+        result
+            .s1
+            .iter()
+            .chain(result.s2.iter())
+            .map(|r| (r.move_choice, r.visits, r.total_score))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(search(1), search(1));
+}
@@ -0,0 +1,268 @@
+#![cfg(any(feature = "gen6", feature = "gen7"))]
+
+use poke_engine::choices::Choices;
+use poke_engine::engine::abilities::{Abilities, WEATHER_ABILITY_TURNS};
+use poke_engine::engine::generate_instructions::generate_instructions_from_move_pair;
+use poke_engine::engine::items::Items;
+use poke_engine::engine::state::{MoveChoice, Weather};
+use poke_engine::instruction::{
+    BoostInstruction, ChangeType, ChangeWeather, DamageInstruction, Instruction,
+    MegaEvolveInstruction, StateInstructions,
+};
+use poke_engine::pokemon::PokemonName;
+use poke_engine::state::{
+    PokemonBoostableStat, PokemonIndex, PokemonMoveIndex, PokemonType, SideReference, State,
+};
+
+fn charizard_state(item: Items) -> State {
+    let mut state = State::default();
+    let charizard = state.side_one.get_active();
+    charizard.id = PokemonName::CHARIZARD;
+    charizard.item = item;
+    charizard.types = (PokemonType::FIRE, PokemonType::FLYING);
+    charizard.base_types = charizard.types;
+    charizard.ability = Abilities::BLAZE;
+    charizard.base_ability = Abilities::BLAZE;
+    charizard.replace_move(PokemonMoveIndex::M0, Choices::SPLASH);
+    state
+        .side_two
+        .get_active()
+        .replace_move(PokemonMoveIndex::M0, Choices::SPLASH);
+    state
+}
+
+fn generate_instructions(state: &mut State, side_one_move: MoveChoice) -> Vec<StateInstructions> {
+    let before = format!("{:?}", state);
+    let instructions = generate_instructions_from_move_pair(
+        state,
+        &side_one_move,
+        &MoveChoice::Move(PokemonMoveIndex::M0),
+        false,
+    );
+    assert_eq!(before, format!("{:?}", state));
+    instructions
+}
+
+#[test]
+fn test_mega_evolving_is_offered_with_the_matching_mega_stone() {
+    let state = charizard_state(Items::CHARIZARDITEX);
+    let (side_one_options, side_two_options) = state.get_all_options();
+
+    assert!(side_one_options.contains(&MoveChoice::MoveMega(PokemonMoveIndex::M0)));
+    assert!(!side_two_options
+        .iter()
+        .any(|m| matches!(m, MoveChoice::MoveMega(_))));
+    assert_eq!(
+        "splash-mega",
+        MoveChoice::MoveMega(PokemonMoveIndex::M0).to_string(&state.side_one)
+    );
+    assert_eq!(
+        Some(MoveChoice::MoveMega(PokemonMoveIndex::M0)),
+        MoveChoice::from_string("splash-mega", &state.side_one)
+    );
+}
+
+#[test]
+fn test_mega_evolving_is_not_offered_without_a_matching_stone_or_twice_per_side() {
+    let state = charizard_state(Items::VENUSAURITE);
+    let (side_one_options, _) = state.get_all_options();
+    assert!(!side_one_options
+        .iter()
+        .any(|m| matches!(m, MoveChoice::MoveMega(_))));
+
+    let mut state = charizard_state(Items::CHARIZARDITEY);
+    state.side_one.pokemon[PokemonIndex::P1].id = PokemonName::GENGARMEGA;
+    let (side_one_options, _) = state.get_all_options();
+    assert!(!side_one_options
+        .iter()
+        .any(|m| matches!(m, MoveChoice::MoveMega(_))));
+}
+
+#[test]
+fn test_mega_evolving_changes_forme_stats_types_and_ability() {
+    let mut state = charizard_state(Items::CHARIZARDITEX);
+    let before = state.side_one.get_active_immutable().clone();
+
+    let instructions =
+        generate_instructions(&mut state, MoveChoice::MoveMega(PokemonMoveIndex::M0));
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![Instruction::MegaEvolve(MegaEvolveInstruction {
+            side_ref: SideReference::SideOne,
+            ability_change: Abilities::TOUGHCLAWS as i16 - Abilities::BLAZE as i16,
+        })],
+    }];
+    assert_eq!(expected_instructions, instructions);
+
+    state.apply_instructions(&instructions[0].instruction_list);
+    let charizard = state.side_one.get_active_immutable();
+    assert_eq!(PokemonName::CHARIZARDMEGAX, charizard.id);
+    assert_eq!((PokemonType::FIRE, PokemonType::DRAGON), charizard.types);
+    assert_eq!(charizard.types, charizard.base_types);
+    assert_eq!(Abilities::TOUGHCLAWS, charizard.ability);
+    assert_eq!(Abilities::TOUGHCLAWS, charizard.base_ability);
+    assert!(charizard.attack > before.attack);
+    assert!(charizard.defense > before.defense);
+    assert_eq!(before.special_defense, charizard.special_defense);
+    assert_eq!(before.maxhp, charizard.maxhp);
+    assert!(charizard.item_is_permanent());
+
+    let (side_one_options, _) = state.get_all_options();
+    assert!(!side_one_options
+        .iter()
+        .any(|m| matches!(m, MoveChoice::MoveMega(_))));
+
+    state.reverse_instructions(&instructions[0].instruction_list);
+    assert_eq!(
+        format!("{:?}", before),
+        format!("{:?}", state.side_one.get_active_immutable())
+    );
+}
+
+#[test]
+fn test_changed_types_are_reset_before_mega_evolving() {
+    let mut state = charizard_state(Items::CHARIZARDITEY);
+    state.side_one.get_active().types = (PokemonType::WATER, PokemonType::TYPELESS);
+    let before = format!("{:?}", state);
+
+    let instructions =
+        generate_instructions(&mut state, MoveChoice::MoveMega(PokemonMoveIndex::M0));
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![
+            Instruction::ChangeType(ChangeType {
+                side_ref: SideReference::SideOne,
+                new_types: (PokemonType::FIRE, PokemonType::FLYING),
+                old_types: (PokemonType::WATER, PokemonType::TYPELESS),
+            }),
+            Instruction::MegaEvolve(MegaEvolveInstruction {
+                side_ref: SideReference::SideOne,
+                ability_change: Abilities::DROUGHT as i16 - Abilities::BLAZE as i16,
+            }),
+            Instruction::ChangeWeather(ChangeWeather {
+                new_weather: Weather::SUN,
+                new_weather_turns_remaining: WEATHER_ABILITY_TURNS,
+                previous_weather: Weather::NONE,
+                previous_weather_turns_remaining: -1,
+            }),
+            Instruction::DecrementWeatherTurnsRemaining,
+        ],
+    }];
+    assert_eq!(expected_instructions, instructions);
+
+    state.apply_instructions(&instructions[0].instruction_list);
+    assert_eq!(
+        PokemonName::CHARIZARDMEGAY,
+        state.side_one.get_active_immutable().id
+    );
+    state.reverse_instructions(&instructions[0].instruction_list);
+    assert_eq!(before, format!("{:?}", state));
+}
+
+#[test]
+fn test_drought_sets_sun_when_mega_evolving() {
+    let mut state = charizard_state(Items::CHARIZARDITEY);
+    let instructions =
+        generate_instructions(&mut state, MoveChoice::MoveMega(PokemonMoveIndex::M0));
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![
+            Instruction::MegaEvolve(MegaEvolveInstruction {
+                side_ref: SideReference::SideOne,
+                ability_change: Abilities::DROUGHT as i16 - Abilities::BLAZE as i16,
+            }),
+            Instruction::ChangeWeather(ChangeWeather {
+                new_weather: Weather::SUN,
+                new_weather_turns_remaining: WEATHER_ABILITY_TURNS,
+                previous_weather: Weather::NONE,
+                previous_weather_turns_remaining: -1,
+            }),
+            Instruction::DecrementWeatherTurnsRemaining,
+        ],
+    }];
+    assert_eq!(expected_instructions, instructions);
+}
+
+#[test]
+fn test_intimidate_activates_when_mega_evolving() {
+    let mut state = State::default();
+    let manectric = state.side_one.get_active();
+    manectric.id = PokemonName::MANECTRIC;
+    manectric.item = Items::MANECTITE;
+    manectric.types = (PokemonType::ELECTRIC, PokemonType::TYPELESS);
+    manectric.base_types = manectric.types;
+    manectric.ability = Abilities::STATIC;
+    manectric.base_ability = Abilities::STATIC;
+    manectric.replace_move(PokemonMoveIndex::M0, Choices::SPLASH);
+    state
+        .side_two
+        .get_active()
+        .replace_move(PokemonMoveIndex::M0, Choices::SPLASH);
+
+    let instructions =
+        generate_instructions(&mut state, MoveChoice::MoveMega(PokemonMoveIndex::M0));
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![
+            Instruction::MegaEvolve(MegaEvolveInstruction {
+                side_ref: SideReference::SideOne,
+                ability_change: Abilities::INTIMIDATE as i16 - Abilities::STATIC as i16,
+            }),
+            Instruction::Boost(BoostInstruction {
+                side_ref: SideReference::SideTwo,
+                stat: PokemonBoostableStat::Attack,
+                amount: -1,
+            }),
+        ],
+    }];
+    assert_eq!(expected_instructions, instructions);
+}
+
+// Beedrill's speed goes from 75 to 145 when it mega evolves
+fn beedrill_speed_state() -> State {
+    let mut state = State::default();
+    let beedrill = state.side_one.get_active();
+    beedrill.id = PokemonName::BEEDRILL;
+    beedrill.item = Items::BEEDRILLITE;
+    beedrill.types = (PokemonType::BUG, PokemonType::POISON);
+    beedrill.base_types = beedrill.types;
+    beedrill.speed = 150;
+    beedrill.replace_move(PokemonMoveIndex::M0, Choices::TACKLE);
+    let opponent = state.side_two.get_active();
+    opponent.speed = 200;
+    opponent.replace_move(PokemonMoveIndex::M0, Choices::TACKLE);
+    state
+}
+
+fn first_damaged_side(instructions: &[StateInstructions]) -> SideReference {
+    instructions[0]
+        .instruction_list
+        .iter()
+        .find_map(|i| match i {
+            Instruction::Damage(DamageInstruction { side_ref, .. }) => Some(*side_ref),
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[cfg(feature = "gen7")]
+#[test]
+fn test_mega_forme_speed_decides_turn_order_from_gen7() {
+    let mut state = beedrill_speed_state();
+    let instructions =
+        generate_instructions(&mut state, MoveChoice::MoveMega(PokemonMoveIndex::M0));
+    assert_eq!(SideReference::SideTwo, first_damaged_side(&instructions));
+}
+
+#[cfg(feature = "gen6")]
+#[test]
+fn test_speed_before_mega_evolving_decides_turn_order_in_gen6() {
+    let mut state = beedrill_speed_state();
+    let instructions =
+        generate_instructions(&mut state, MoveChoice::MoveMega(PokemonMoveIndex::M0));
+    assert_eq!(SideReference::SideOne, first_damaged_side(&instructions));
+}
@@ -0,0 +1,177 @@
+#![cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+
+use poke_engine::choices::Choices;
+use poke_engine::protocol::{run_protocol, ProtocolResponse, ProtocolSession};
+use poke_engine::state::{PokemonMoveIndex, State};
+
+// both actives know splash and tackle, side two's active has 1 hp left
+fn knockout_state() -> State {
+    let mut state = State::default();
+    for side in [&mut state.side_one, &mut state.side_two] {
+        let active = side.get_active();
+        active.replace_move(PokemonMoveIndex::M0, Choices::SPLASH);
+        active.replace_move(PokemonMoveIndex::M1, Choices::TACKLE);
+        // dynamaxing would let side two survive the tackle
+        side.dynamax_used = true;
+    }
+    state.side_two.get_active().hp = 1;
+    state
+}
+
+fn set_state_line(state: &State) -> String {
+    serde_json::json!({"type": "set_state", "state": state.serialize()}).to_string()
+}
+
+fn responses(input: &str) -> Vec<ProtocolResponse> {
+    let mut output = vec![];
+    run_protocol(input.as_bytes(), &mut output, &mut ProtocolSession::new(0)).unwrap();
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+fn error_message(response: &ProtocolResponse) -> &str {
+    match response {
+        ProtocolResponse::Error { message } => message,
+        r => panic!("expected an error, got {:?}", r),
+    }
+}
+
+#[test]
+fn test_every_request_gets_one_response_until_quit() {
+    let state = knockout_state();
+    let input = format!(
+        "{}\n\n{{\"type\": \"get_state\"}}\n{{\"type\": \"quit\"}}\n{{\"type\": \"get_state\"}}\n",
+        set_state_line(&state)
+    );
+
+    let responses = responses(&input);
+
+    let expected = ProtocolResponse::State {
+        state: state.serialize(),
+    };
+    assert_eq!(vec![expected.clone(), expected], responses);
+}
+
+#[test]
+fn test_json_states_are_accepted() {
+    let state = knockout_state();
+    let input = format!(
+        "{{\"type\": \"set_state\", \"state\": {}}}\n",
+        state.to_json()
+    );
+
+    assert_eq!(
+        vec![ProtocolResponse::State {
+            state: state.serialize(),
+        }],
+        responses(&input)
+    );
+}
+
+#[test]
+fn test_bad_requests_are_errors_and_the_session_continues() {
+    let input = format!(
+        "not json\n{{\"type\": \"get_state\"}}\n{{\"type\": \"set_state\", \"state\": \"abc\"}}\n{}\n{{\"type\": \"apply_moves\", \"side_one\": \"thunderbolt\", \"side_two\": \"splash\"}}\n",
+        set_state_line(&knockout_state())
+    );
+
+    let responses = responses(&input);
+
+    assert_eq!(5, responses.len());
+    assert!(error_message(&responses[0]).starts_with("Invalid request"));
+    assert_eq!("No state has been set", error_message(&responses[1]));
+    assert!(error_message(&responses[2]).starts_with("Invalid state"));
+    assert_eq!(
+        "Invalid move choice for side one: thunderbolt",
+        error_message(&responses[4])
+    );
+}
+
+#[test]
+fn test_apply_moves_applies_the_most_likely_branch() {
+    let state = knockout_state();
+    let mut session = ProtocolSession::new(0);
+    session.handle_line(&set_state_line(&state));
+
+    let response = session
+        .handle_line(r#"{"type": "apply_moves", "side_one": "tackle", "side_two": "splash"}"#)
+        .unwrap();
+
+    let (instructions, serialized) = match serde_json::from_str(&response).unwrap() {
+        ProtocolResponse::Applied {
+            branch: 0,
+            instructions,
+            state,
+            ..
+        } => (instructions, state),
+        r => panic!("expected the first branch to be applied, got {:?}", r),
+    };
+    let mut expected = state.clone();
+    expected.apply_instructions(&instructions.instruction_list);
+    assert_eq!(expected.serialize(), serialized);
+    assert_eq!(
+        0,
+        session
+            .state
+            .as_ref()
+            .unwrap()
+            .side_two
+            .get_active_immutable()
+            .hp
+    );
+}
+
+#[test]
+fn test_apply_instructions_and_out_of_range_branches() {
+    let state = knockout_state();
+    let mut session = ProtocolSession::new(0);
+    session.handle_line(&set_state_line(&state));
+
+    let response = session
+        .handle_line(
+            r#"{"type": "apply_moves", "side_one": "splash", "side_two": "splash", "branch": 5}"#,
+        )
+        .unwrap();
+    assert!(response.contains("Branch 5 does not exist"));
+
+    let response = session.handle_line(
+        r#"{"type": "apply_instructions", "instructions": [{"Damage": {"side_ref": "SideOne", "damage_amount": 10}}]}"#,
+    );
+    let mut expected = state.clone();
+    expected.side_one.get_active().hp -= 10;
+    assert_eq!(
+        Some(
+            serde_json::to_string(&ProtocolResponse::State {
+                state: expected.serialize(),
+            })
+            .unwrap()
+        ),
+        response
+    );
+}
+
+#[test]
+fn test_search_ranks_the_knockout_first() {
+    let mut session = ProtocolSession::new(0);
+    session.handle_line(&set_state_line(&knockout_state()));
+
+    for request in [
+        r#"{"type": "search", "max_iterations": 500}"#,
+        r#"{"type": "search", "algorithm": "iterative_deepening", "time_ms": 20}"#,
+    ] {
+        let response = session.handle_line(request).unwrap();
+        match serde_json::from_str(&response).unwrap() {
+            ProtocolResponse::SearchResult {
+                side_one, side_two, ..
+            } => {
+                assert_eq!("tackle", side_one[0].move_choice);
+                assert!(side_one[0].score >= side_one[side_one.len() - 1].score);
+                assert!(!side_two.is_empty());
+            }
+            r => panic!("expected a search result, got {:?}", r),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+#![cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+
+use poke_engine::choices::Choices;
+use poke_engine::engine::battle_environment::{BattleEnvironment, BattleResult, RandomPlayer};
+use poke_engine::instruction::{
+    DamageInstruction, Instruction, StateInstructions, SwitchInstruction,
+};
+use poke_engine::replay::{Replay, ReplayLine, ReplayViewer, REPLAY_FORMAT_VERSION};
+use poke_engine::state::{PokemonIndex, PokemonMoveIndex, SideReference, State};
+
+fn state_with_tackles() -> State {
+    let mut state = State::default();
+    state
+        .side_one
+        .get_active()
+        .replace_move(PokemonMoveIndex::M0, Choices::TACKLE);
+    state
+        .side_two
+        .get_active()
+        .replace_move(PokemonMoveIndex::M0, Choices::TACKLE);
+    state
+}
+
+fn replay_of_a_battle(name: &str) -> (BattleResult, Replay) {
+    let path = std::env::temp_dir().join(name);
+    let env = BattleEnvironment::new(
+        Box::new(RandomPlayer::new("one".to_string())),
+        Box::new(RandomPlayer::new("two".to_string())),
+        100,
+        false,
+    )
+    .with_seed(3)
+    .with_replay_file(path.to_str().unwrap().to_string());
+
+    let result = env.run_battle(state_with_tackles());
+    let replay = Replay::from_file(path.to_str().unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    (result, replay)
+}
+
+#[test]
+fn test_instructions_round_trip_through_json() {
+    let instructions = StateInstructions {
+        percentage: 12.5,
+        instruction_list: vec![
+            Instruction::Damage(DamageInstruction {
+                side_ref: SideReference::SideTwo,
+                damage_amount: 40,
+            }),
+            Instruction::Switch(SwitchInstruction {
+                side_ref: SideReference::SideOne,
+                previous_index: PokemonIndex::P0,
+                next_index: PokemonIndex::P3,
+            }),
+            Instruction::DecrementWeatherTurnsRemaining,
+        ],
+    };
+
+    let json = serde_json::to_string(&instructions).unwrap();
+
+    assert_eq!(
+        instructions,
+        serde_json::from_str::<StateInstructions>(&json).unwrap()
+    );
+}
+
+#[test]
+fn test_battle_writes_a_replay_of_every_turn() {
+    let (result, replay) = replay_of_a_battle("poke_engine_test_replay_turns.jsonl");
+
+    assert_eq!(REPLAY_FORMAT_VERSION, replay.header.version);
+    assert_eq!(3, replay.header.seed);
+    assert_eq!("one", replay.header.player_one);
+    assert_eq!(
+        state_with_tackles().serialize(),
+        replay.header.initial_state
+    );
+    // turn 0 holds the start of battle effects
+    assert_eq!(result.turn_count + 1, replay.turns.len());
+    for (turn_number, turn) in replay.turns.iter().enumerate() {
+        assert_eq!(turn_number, turn.turn_number);
+    }
+    let first_turn = &result.turn_history[0];
+    assert_eq!(
+        first_turn
+            .side_one_choice
+            .to_string(&first_turn.state_before.side_one),
+        replay.turns[1].side_one_choice
+    );
+    let end = replay.end.unwrap();
+    assert_eq!(result.winner, end.winner);
+    assert_eq!(result.turn_count, end.turn_count);
+    assert_eq!(
+        result.final_state.serialize(),
+        replay.turns.last().unwrap().state_after
+    );
+}
+
+#[test]
+fn test_viewer_steps_forward_and_backward_through_a_replay() {
+    let (_, replay) = replay_of_a_battle("poke_engine_test_replay_viewer.jsonl");
+    let mut viewer = ReplayViewer::new(replay.clone());
+
+    assert!(!viewer.step_backward());
+    for turn in replay.turns.iter() {
+        assert_eq!(turn.state_before, viewer.state.serialize());
+        assert!(viewer.step_forward());
+        assert_eq!(turn.state_after, viewer.state.serialize());
+        assert_eq!(Some(turn), viewer.current_turn());
+    }
+    assert!(!viewer.step_forward());
+
+    for turn in replay.turns.iter().rev() {
+        assert!(viewer.step_backward());
+        assert_eq!(turn.state_before, viewer.state.serialize());
+    }
+    assert_eq!(replay.header.initial_state, viewer.state.serialize());
+
+    viewer.go_to(2);
+    assert_eq!(replay.turns[1].state_after, viewer.state.serialize());
+    viewer.go_to(usize::MAX);
+    assert_eq!(replay.turns.len(), viewer.position);
+}
+
+#[test]
+fn test_reading_a_replay_back_from_json_lines() {
+    let (_, replay) = replay_of_a_battle("poke_engine_test_replay_lines.jsonl");
+    let json_lines = replay.to_json_lines();
+
+    assert_eq!(replay.turns.len() + 2, json_lines.lines().count());
+    assert_eq!(replay, Replay::from_json_lines(&json_lines).unwrap());
+}
+
+#[test]
+fn test_invalid_replays_are_rejected() {
+    let (_, replay) = replay_of_a_battle("poke_engine_test_replay_invalid.jsonl");
+    let json_lines = replay.to_json_lines();
+    let mut lines: Vec<&str> = json_lines.lines().collect();
+
+    let without_header = lines[1..].join("\n");
+    assert!(Replay::from_json_lines(&without_header).is_err());
+
+    let mut newer = replay.clone();
+    newer.header.version = REPLAY_FORMAT_VERSION + 1;
+    assert_eq!(
+        Err(format!(
+            "Unsupported replay version {}, expected {}",
+            REPLAY_FORMAT_VERSION + 1,
+            REPLAY_FORMAT_VERSION
+        )),
+        Replay::from_json_lines(&newer.to_json_lines())
+    );
+
+    let turn = serde_json::to_string(&ReplayLine::Turn(replay.turns[0].clone())).unwrap();
+    lines.push(&turn);
+    assert!(Replay::from_json_lines(&lines.join("\n")).is_err());
+
+    assert!(Replay::from_json_lines("{\"type\": \"header\"}").is_err());
+    assert!(Replay::from_file("/this/file/does/not/exist.jsonl").is_err());
+}
@@ -0,0 +1,407 @@
+#![cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+
+use poke_engine::choices::Choices;
+use poke_engine::engine::generate_instructions::generate_instructions_from_move_pair;
+use poke_engine::engine::state::MoveChoice;
+use poke_engine::evaluator::DefaultEvaluator;
+use poke_engine::instruction::{
+    DamageInstruction, HealInstruction, Instruction, StateInstructions,
+};
+use poke_engine::search::{
+    expectiminimax_search, expectiminimax_search_with_table, pick_safest, solve_nash_equilibrium,
+    TranspositionTable, TranspositionTableEntry,
+};
+use poke_engine::state::{PokemonIndex, PokemonMoveIndex, SideReference, State};
+use std::sync::{Arc, Mutex};
+
+fn state_with_moves() -> State {
+    let mut state = State::default();
+    for (index, choice) in [
+        (PokemonMoveIndex::M0, Choices::TACKLE),
+        (PokemonMoveIndex::M1, Choices::THUNDERWAVE),
+        (PokemonMoveIndex::M2, Choices::SWORDSDANCE),
+    ] {
+        state.side_one.get_active().replace_move(index, choice);
+        state.side_two.get_active().replace_move(index, choice);
+    }
+    state
+        .side_one
+        .get_active()
+        .replace_move(PokemonMoveIndex::M3, Choices::SPIKES);
+    state.side_one.pokemon[PokemonIndex::P1].replace_move(PokemonMoveIndex::M0, Choices::TACKLE);
+    state
+}
+
+#[test]
+fn test_hash_after_instructions_matches_a_full_rehash() {
+    let mut state = state_with_moves();
+    let initial_hash = state.rehash();
+    let (side_one_options, side_two_options) = state.get_all_options();
+
+    for side_one_move in side_one_options.iter() {
+        for side_two_move in side_two_options.iter() {
+            let instructions = generate_instructions_from_move_pair(
+                &mut state,
+                side_one_move,
+                side_two_move,
+                false,
+            );
+            for state_instructions in instructions.iter() {
+                state.apply_instructions(&state_instructions.instruction_list);
+                let incremental_hash = state.get_hash();
+                assert_eq!(state.clone().rehash(), incremental_hash);
+                state.reverse_instructions(&state_instructions.instruction_list);
+                assert_eq!(initial_hash, state.get_hash());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_hash_does_not_depend_on_the_instructions_used_to_reach_a_state() {
+    let mut state = State::default();
+    let mut other_state = State::default();
+    state.apply_instructions(&vec![
+        Instruction::Damage(DamageInstruction {
+            side_ref: SideReference::SideOne,
+            damage_amount: 30,
+        }),
+        Instruction::Heal(HealInstruction {
+            side_ref: SideReference::SideOne,
+            heal_amount: 10,
+        }),
+    ]);
+    other_state.apply_instructions(&vec![Instruction::Damage(DamageInstruction {
+        side_ref: SideReference::SideOne,
+        damage_amount: 20,
+    })]);
+
+    assert_eq!(state.get_hash(), other_state.get_hash());
+    assert_ne!(state.get_hash(), State::default().get_hash());
+}
+
+#[test]
+fn test_hash_differs_between_sides() {
+    let mut state = State::default();
+    state.side_two.get_active().speed -= 1;
+    let hash = state.rehash();
+
+    state.side_one.get_active().hp -= 1;
+    let side_one_damaged = state.rehash();
+    state.side_one.get_active().hp += 1;
+    state.side_two.get_active().hp -= 1;
+    let side_two_damaged = state.rehash();
+
+    assert_ne!(hash, side_one_damaged);
+    assert_ne!(side_one_damaged, side_two_damaged);
+}
+
+#[test]
+fn test_hash_includes_the_dynamax_and_z_move_flags() {
+    let mut state = State::default();
+    let hash = state.rehash();
+
+    state.side_one.dynamax_used = true;
+    let dynamax_used = state.rehash();
+    state.side_one.dynamax_used = false;
+    state.side_one.z_move_used = true;
+    let z_move_used = state.rehash();
+
+    assert_ne!(hash, dynamax_used);
+    assert_ne!(hash, z_move_used);
+    assert_ne!(dynamax_used, z_move_used);
+}
+
+#[test]
+fn test_search_with_table_gives_the_same_scores_as_without() {
+    let mut state = state_with_moves();
+    let (side_one_options, side_two_options) = state.get_all_options();
+    let running = Arc::new(Mutex::new(true));
+
+    let scores = expectiminimax_search(
+        &mut state,
+        2,
+        side_one_options.clone(),
+        side_two_options.clone(),
+        false,
+        &running,
+    );
+
+    // large enough that no sub-game is replaced by another one in the same slot
+    let mut table = TranspositionTable::new(1 << 20);
+    state.rehash();
+    let table_scores = expectiminimax_search_with_table(
+        &mut state,
+        2,
+        side_one_options.clone(),
+        side_two_options.clone(),
+        false,
+        &running,
+        &mut table,
+        &DefaultEvaluator,
+        None,
+    );
+    assert_eq!(scores, table_scores);
+    assert!(!table.is_empty());
+
+    // every sub-game of a second identical search is already in the table
+    let misses = table.misses;
+    let repeated_scores = expectiminimax_search_with_table(
+        &mut state,
+        2,
+        side_one_options,
+        side_two_options,
+        false,
+        &running,
+        &mut table,
+        &DefaultEvaluator,
+        None,
+    );
+    assert_eq!(scores, repeated_scores);
+    assert_eq!(misses, table.misses);
+    assert!(table.hits > 0);
+}
+
+#[test]
+fn test_transposition_table_keeps_the_deeper_entry_on_collision() {
+    let mut table = TranspositionTable::new(4);
+    let entry = |key, depth| TranspositionTableEntry {
+        key,
+        depth,
+        score: depth as f32,
+        best_side_one_move: MoveChoice::None,
+    };
+    table.insert(entry(1, 3));
+    table.insert(entry(5, 1));
+    assert_eq!(3, table.get(1).unwrap().depth);
+    assert!(table.get(5).is_none());
+
+    table.insert(entry(1, 2));
+    assert_eq!(2, table.get(1).unwrap().depth);
+    table.insert(entry(5, 4));
+    assert_eq!(4, table.get(5).unwrap().depth);
+    assert!(table.get(1).is_none());
+}
+
+fn assert_probabilities(expected: &[f32], actual: &[f32]) {
+    assert_eq!(expected.len(), actual.len());
+    for (e, a) in expected.iter().zip(actual.iter()) {
+        assert!((e - a).abs() < 0.0001, "{:?} != {:?}", expected, actual);
+    }
+}
+
+#[test]
+fn test_nash_equilibrium_of_rock_paper_scissors_is_uniform() {
+    let matrix = vec![0.0, -1.0, 1.0, 1.0, 0.0, -1.0, -1.0, 1.0, 0.0];
+
+    let equilibrium = solve_nash_equilibrium(&matrix, 3, 3);
+
+    assert_probabilities(&[1.0 / 3.0; 3], &equilibrium.s1);
+    assert_probabilities(&[1.0 / 3.0; 3], &equilibrium.s2);
+    assert!(equilibrium.value.abs() < 0.0001);
+}
+
+#[test]
+fn test_nash_equilibrium_of_a_game_without_a_saddle_point() {
+    let matrix = vec![3.0, -1.0, -2.0, 1.0];
+
+    let equilibrium = solve_nash_equilibrium(&matrix, 2, 2);
+
+    assert_probabilities(&[3.0 / 7.0, 4.0 / 7.0], &equilibrium.s1);
+    assert_probabilities(&[2.0 / 7.0, 5.0 / 7.0], &equilibrium.s2);
+    assert!((equilibrium.value - 1.0 / 7.0).abs() < 0.0001);
+}
+
+#[test]
+fn test_nash_equilibrium_with_a_saddle_point_is_the_safest_move() {
+    let matrix = vec![10.0, 20.0, 30.0, 40.0, 25.0, 50.0, 5.0, 20.0, 0.0];
+
+    let equilibrium = solve_nash_equilibrium(&matrix, 3, 3);
+
+    let (safest_index, safest_score) = pick_safest(&matrix, 3, 3);
+    assert_eq!(safest_index, equilibrium.best_s1_index());
+    assert_probabilities(&[0.0, 1.0, 0.0], &equilibrium.s1);
+    assert_probabilities(&[0.0, 1.0, 0.0], &equilibrium.s2);
+    assert!((equilibrium.value - safest_score).abs() < 0.0001);
+}
+
+#[test]
+fn test_nash_equilibrium_fills_pruned_entries_with_the_row_worst_case() {
+    let matrix = vec![5.0, 4.0, 1.0, f32::NAN];
+
+    let equilibrium = solve_nash_equilibrium(&matrix, 2, 2);
+
+    assert_probabilities(&[1.0, 0.0], &equilibrium.s1);
+    assert_probabilities(&[0.0, 1.0], &equilibrium.s2);
+    assert!((equilibrium.value - 4.0).abs() < 0.0001);
+}
+
+#[test]
+fn test_nash_equilibrium_of_a_searched_state() {
+    let mut state = state_with_moves();
+    let (side_one_options, side_two_options) = state.get_all_options();
+
+    let scores = expectiminimax_search(
+        &mut state,
+        2,
+        side_one_options.clone(),
+        side_two_options.clone(),
+        false,
+        &Arc::new(Mutex::new(true)),
+    );
+    let equilibrium =
+        solve_nash_equilibrium(&scores, side_one_options.len(), side_two_options.len());
+
+    assert!((equilibrium.s1.iter().sum::<f32>() - 1.0).abs() < 0.0001);
+    assert!((equilibrium.s2.iter().sum::<f32>() - 1.0).abs() < 0.0001);
+    // side one can always do at least as well as its pure maximin move
+    let (_, safest_score) = pick_safest(&scores, side_one_options.len(), side_two_options.len());
+    assert!(equilibrium.value >= safest_score - 0.001);
+}
+
+fn damage(side_ref: SideReference, damage_amount: i16) -> Instruction {
+    Instruction::Damage(DamageInstruction {
+        side_ref,
+        damage_amount,
+    })
+}
+
+fn branch(percentage: f32, instruction_list: Vec<Instruction>) -> StateInstructions {
+    StateInstructions {
+        percentage,
+        instruction_list,
+    }
+}
+
+#[test]
+fn test_branches_leading_to_the_same_state_are_merged() {
+    let mut state = State::default();
+    let state_before = format!("{:?}", state);
+    let mut instructions = vec![
+        branch(25.0, vec![damage(SideReference::SideOne, 20)]),
+        branch(50.0, vec![damage(SideReference::SideTwo, 20)]),
+        branch(
+            25.0,
+            vec![
+                damage(SideReference::SideOne, 30),
+                Instruction::Heal(HealInstruction {
+                    side_ref: SideReference::SideOne,
+                    heal_amount: 10,
+                }),
+            ],
+        ),
+    ];
+
+    state.merge_equivalent_instructions(&mut instructions, 1);
+
+    assert_eq!(
+        vec![
+            branch(50.0, vec![damage(SideReference::SideOne, 20)]),
+            branch(50.0, vec![damage(SideReference::SideTwo, 20)]),
+        ],
+        instructions
+    );
+    assert_eq!(state_before, format!("{:?}", state));
+}
+
+#[test]
+fn test_branches_with_the_same_hash_are_only_merged_if_they_lead_to_the_same_state() {
+    let mut state = State::default();
+    state.get_hash();
+    // changed without going through instructions, so the cached hash is for 100 hp
+    state.side_one.get_active().hp = 50;
+    let heal = Instruction::Heal(HealInstruction {
+        side_ref: SideReference::SideOne,
+        heal_amount: 50,
+    });
+    let mut instructions = vec![branch(50.0, vec![]), branch(50.0, vec![heal.clone()])];
+
+    state.merge_equivalent_instructions(&mut instructions, 1);
+
+    assert_eq!(
+        vec![branch(50.0, vec![]), branch(50.0, vec![heal])],
+        instructions
+    );
+}
+
+#[test]
+fn test_damage_rolls_that_all_ko_are_merged() {
+    let mut state = state_with_moves();
+    state.side_two.get_active().hp = 5;
+    let mut instructions = generate_instructions_from_move_pair(
+        &mut state,
+        &MoveChoice::Move(PokemonMoveIndex::M0),
+        &MoveChoice::Move(PokemonMoveIndex::M2),
+        true,
+    );
+    let branch_count = instructions.len();
+
+    state.merge_equivalent_instructions(&mut instructions, 1);
+
+    assert!(instructions.len() <= branch_count);
+    assert_eq!(
+        100.0,
+        instructions.iter().map(|i| i.percentage).sum::<f32>()
+    );
+    let mut end_state_hashes = vec![];
+    for state_instructions in instructions.iter() {
+        state.apply_instructions(&state_instructions.instruction_list);
+        end_state_hashes.push(state.get_hash());
+        state.reverse_instructions(&state_instructions.instruction_list);
+    }
+    end_state_hashes.sort();
+    end_state_hashes.dedup();
+    assert_eq!(instructions.len(), end_state_hashes.len());
+}
+
+#[test]
+fn test_damage_amounts_in_the_same_bucket_are_merged() {
+    let mut state = State::default();
+    let instructions = vec![
+        branch(30.0, vec![damage(SideReference::SideTwo, 40)]),
+        branch(30.0, vec![damage(SideReference::SideTwo, 44)]),
+        branch(40.0, vec![damage(SideReference::SideTwo, 45)]),
+    ];
+
+    let mut unbucketed = instructions.clone();
+    state.merge_equivalent_instructions(&mut unbucketed, 1);
+    assert_eq!(instructions, unbucketed);
+
+    let mut bucketed = instructions;
+    state.merge_equivalent_instructions(&mut bucketed, 5);
+    assert_eq!(
+        vec![
+            branch(60.0, vec![damage(SideReference::SideTwo, 40)]),
+            branch(40.0, vec![damage(SideReference::SideTwo, 45)]),
+        ],
+        bucketed
+    );
+}
+
+#[test]
+fn test_merging_branches_that_lead_to_the_same_state_does_not_change_search_scores() {
+    let mut state = state_with_moves();
+    state.side_two.get_active().hp = 5;
+    let (side_one_options, side_two_options) = state.get_all_options();
+    let running = Arc::new(Mutex::new(true));
+    state.rehash();
+
+    let mut scores = vec![];
+    for damage_bucket in [None, Some(1)] {
+        scores.push(expectiminimax_search_with_table(
+            &mut state,
+            2,
+            side_one_options.clone(),
+            side_two_options.clone(),
+            false,
+            &running,
+            &mut TranspositionTable::new(1 << 16),
+            &DefaultEvaluator,
+            damage_bucket,
+        ));
+    }
+
+    for (score, merged_score) in scores[0].iter().zip(scores[1].iter()) {
+        assert!((score - merged_score).abs() < 0.001);
+    }
+}
@@ -0,0 +1,96 @@
+#![cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+
+use poke_engine::choices::Choices;
+use poke_engine::engine::battle_environment::{
+    run_random_battle, ExpectiminimaxPlayer, IterativeDeepeningPlayer, Player, RandomPlayer,
+};
+use poke_engine::engine::state::MoveChoice;
+use poke_engine::rng::seeded_rng;
+use poke_engine::state::{PokemonMoveIndex, SideReference, State};
+use std::time::Duration;
+
+const RANDOM_TEAMS_JSON: &str = include_str!("../data/random_teams.json");
+const POKEDEX_JSON: &str = include_str!("../data/pokedex.json");
+const MOVEDEX_JSON: &str = include_str!("../data/moves.json");
+
+// the side given can knock out the other side's active pokemon with its second move
+fn state_with_a_knockout_for(side_ref: SideReference) -> State {
+    let mut state = State::default();
+    for side in [&mut state.side_one, &mut state.side_two] {
+        let active = side.get_active();
+        active.replace_move(PokemonMoveIndex::M0, Choices::SPLASH);
+        active.replace_move(PokemonMoveIndex::M1, Choices::TACKLE);
+        // dynamaxing would let the other side survive the tackle
+        side.dynamax_used = true;
+    }
+    match side_ref {
+        SideReference::SideOne => state.side_two.get_active().hp = 1,
+        SideReference::SideTwo => state.side_one.get_active().hp = 1,
+    }
+    state
+}
+
+fn choose_move(player: &dyn Player, side_ref: SideReference) -> MoveChoice {
+    let state = state_with_a_knockout_for(side_ref);
+    let (side_one_options, side_two_options) = state.get_all_options();
+    let options = match side_ref {
+        SideReference::SideOne => side_one_options,
+        SideReference::SideTwo => side_two_options,
+    };
+    player.choose_move(&state, side_ref, &options, &mut seeded_rng(0))
+}
+
+#[test]
+fn test_expectiminimax_player_takes_the_knockout() {
+    let player = ExpectiminimaxPlayer::new("expectiminimax".to_string(), 2);
+
+    for side_ref in [SideReference::SideOne, SideReference::SideTwo] {
+        assert_eq!(
+            MoveChoice::Move(PokemonMoveIndex::M1),
+            choose_move(&player, side_ref)
+        );
+    }
+}
+
+#[test]
+fn test_expectiminimax_player_without_pruning_takes_the_knockout() {
+    let player = ExpectiminimaxPlayer::new("expectiminimax".to_string(), 1).with_ab_prune(false);
+
+    assert_eq!(
+        MoveChoice::Move(PokemonMoveIndex::M1),
+        choose_move(&player, SideReference::SideTwo)
+    );
+}
+
+#[test]
+fn test_iterative_deepening_player_takes_the_knockout() {
+    let player =
+        IterativeDeepeningPlayer::new("iterative-deepening".to_string(), Duration::from_millis(20));
+
+    for side_ref in [SideReference::SideOne, SideReference::SideTwo] {
+        assert_eq!(
+            MoveChoice::Move(PokemonMoveIndex::M1),
+            choose_move(&player, side_ref)
+        );
+    }
+}
+
+#[test]
+fn test_expectiminimax_player_plays_a_battle_from_team_preview() {
+    let result = run_random_battle(
+        RANDOM_TEAMS_JSON,
+        POKEDEX_JSON,
+        MOVEDEX_JSON,
+        Box::new(ExpectiminimaxPlayer::new("expectiminimax".to_string(), 1)),
+        Box::new(RandomPlayer::new("random".to_string())),
+        5,
+        false,
+        4,
+    );
+
+    assert!(!result.turn_history.is_empty());
+    for turn in result.turn_history.iter() {
+        let (side_one_options, _) = turn.state_before.get_all_options();
+        assert!(side_one_options.contains(&turn.side_one_choice));
+    }
+}
@@ -0,0 +1,270 @@
+#![cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+
+use poke_engine::choices::Choices;
+use poke_engine::engine::items::Items;
+use poke_engine::engine::state::{PokemonVolatileStatus, Weather};
+use poke_engine::pokemon::PokemonName;
+use poke_engine::showdown_log::{parse_battle_log, DecisionKind, LogParseError, ShowdownLogParser};
+use poke_engine::state::{
+    LastUsedMove, PokemonIndex, PokemonMoveIndex, PokemonStatus, Side, SideReference,
+};
+
+const POKEDEX_JSON: &str = include_str!("../data/pokedex.json");
+
+fn known_team() -> Side {
+    let mut side = Side::default();
+    side.pokemon[PokemonIndex::P0].id = PokemonName::GARCHOMP;
+    side.pokemon[PokemonIndex::P1].id = PokemonName::PIKACHU;
+    side.pokemon[PokemonIndex::P0].replace_move(PokemonMoveIndex::M0, Choices::EARTHQUAKE);
+    side.pokemon[PokemonIndex::P1].replace_move(PokemonMoveIndex::M0, Choices::VOLTSWITCH);
+    side
+}
+
+const LOG: &str = "|player|p1|Alice|1
+|player|p2|Bob|2
+|gen|9
+|start
+|switch|p1a: Chompy|Garchomp, L100, M|100/100
+|switch|p2a: Tyranitar|Tyranitar, L80, M|100/100
+|-weather|Sandstorm|[from] ability: Sand Stream|[of] p2a: Tyranitar
+|turn|1
+|move|p1a: Chompy|Earthquake|p2a: Tyranitar
+|-supereffective|p2a: Tyranitar
+|-damage|p2a: Tyranitar|40/100
+|move|p2a: Tyranitar|Dragon Dance|p2a: Tyranitar
+|-boost|p2a: Tyranitar|atk|1
+|-boost|p2a: Tyranitar|spe|1
+|-weather|Sandstorm|[upkeep]
+|-heal|p2a: Tyranitar|46/100|[from] item: Leftovers
+|turn|2
+|move|p2a: Tyranitar|Stone Edge|p1a: Chompy
+|-damage|p1a: Chompy|0 fnt
+|faint|p1a: Chompy
+|upkeep
+|switch|p1a: Pikachu|Pikachu, L100, F|100/100
+|turn|3
+|move|p1a: Pikachu|Volt Switch|p2a: Tyranitar
+|-damage|p2a: Tyranitar|30/100 par
+|switch|p1a: Chompy|Garchomp, L100, M|0/100
+";
+
+#[test]
+fn test_decision_points_are_recorded_for_turns_and_replacements() {
+    let decision_points =
+        parse_battle_log(LOG, SideReference::SideOne, &known_team(), POKEDEX_JSON).unwrap();
+
+    let kinds: Vec<(u16, DecisionKind)> =
+        decision_points.iter().map(|d| (d.turn, d.kind)).collect();
+    assert_eq!(
+        vec![
+            (1, DecisionKind::Turn),
+            (2, DecisionKind::Turn),
+            (2, DecisionKind::Switch),
+            (3, DecisionKind::Turn),
+            (3, DecisionKind::Switch),
+        ],
+        kinds
+    );
+}
+
+#[test]
+fn test_opponent_is_revealed_as_the_battle_goes_on() {
+    let decision_points =
+        parse_battle_log(LOG, SideReference::SideOne, &known_team(), POKEDEX_JSON).unwrap();
+
+    let turn_one = &decision_points[0].state;
+    let tyranitar = turn_one.side_two.get_active_immutable();
+    assert_eq!(PokemonName::TYRANITAR, tyranitar.id);
+    assert_eq!(80, tyranitar.level);
+    assert_eq!(tyranitar.maxhp, tyranitar.hp);
+    assert_eq!(Items::UNKNOWNITEM, tyranitar.item);
+    assert_eq!(Choices::NONE, tyranitar.moves.m0.id);
+    assert_eq!(Weather::SAND, turn_one.weather.weather_type);
+    assert_eq!(5, turn_one.weather.turns_remaining);
+
+    // pokemon that have not been seen are unknown
+    assert_eq!(
+        PokemonName::NONE,
+        turn_one.side_two.pokemon[PokemonIndex::P1].id
+    );
+    // and are not mistaken for fainted pokemon
+    assert!(turn_one.side_two.pokemon[PokemonIndex::P1].hp > 0);
+
+    let turn_two = &decision_points[1].state;
+    let tyranitar = turn_two.side_two.get_active_immutable();
+    assert_eq!(Items::LEFTOVERS, tyranitar.item);
+    assert_eq!(Choices::DRAGONDANCE, tyranitar.moves.m0.id);
+    assert_eq!(31, tyranitar.moves.m0.pp);
+    assert_eq!((tyranitar.maxhp as f32 * 0.46).round() as i16, tyranitar.hp);
+    assert_eq!(1, turn_two.side_two.attack_boost);
+    assert_eq!(1, turn_two.side_two.speed_boost);
+    assert_eq!(4, turn_two.weather.turns_remaining);
+    assert_eq!(
+        LastUsedMove::Move(PokemonMoveIndex::M0),
+        turn_two.side_two.last_used_move
+    );
+}
+
+#[test]
+fn test_replacement_decision_state() {
+    let decision_points =
+        parse_battle_log(LOG, SideReference::SideOne, &known_team(), POKEDEX_JSON).unwrap();
+
+    // after a faint the fainted pokemon is still active
+    let faint_replacement = &decision_points[2].state;
+    assert_eq!(PokemonIndex::P0, faint_replacement.side_one.active_index);
+    assert_eq!(0, faint_replacement.side_one.get_active_immutable().hp);
+    assert!(!faint_replacement.side_one.force_switch);
+
+    // after a pivoting move the side is forced to switch
+    let pivot_replacement = &decision_points[4].state;
+    assert_eq!(PokemonIndex::P1, pivot_replacement.side_one.active_index);
+    assert!(pivot_replacement.side_one.force_switch);
+    assert_eq!(
+        PokemonStatus::PARALYZE,
+        pivot_replacement.side_two.get_active_immutable().status
+    );
+    assert_eq!(
+        Choices::STONEEDGE,
+        pivot_replacement
+            .side_two
+            .get_active_immutable()
+            .moves
+            .m1
+            .id
+    );
+}
+
+#[test]
+fn test_switching_clears_volatile_statuses_and_boosts() {
+    let log = "|switch|p1a: Chompy|Garchomp, L100, M|100/100
+|switch|p2a: Tyranitar|Tyranitar, L80, M|100/100
+|turn|1
+|move|p1a: Chompy|Swords Dance|p1a: Chompy
+|-boost|p1a: Chompy|atk|2
+|move|p2a: Tyranitar|Substitute|p2a: Tyranitar
+|-start|p2a: Tyranitar|Substitute
+|-damage|p2a: Tyranitar|75/100
+|turn|2
+|switch|p1a: Pikachu|Pikachu, L100, F|100/100
+|switch|p2a: Pupitar|Pupitar, L90, M|100/100
+|turn|3
+";
+    let decision_points =
+        parse_battle_log(log, SideReference::SideOne, &known_team(), POKEDEX_JSON).unwrap();
+
+    let turn_two = &decision_points[1].state;
+    assert_eq!(2, turn_two.side_one.attack_boost);
+    assert!(turn_two
+        .side_two
+        .volatile_statuses
+        .contains(&PokemonVolatileStatus::SUBSTITUTE));
+    assert_eq!(
+        turn_two.side_two.get_active_immutable().maxhp / 4,
+        turn_two.side_two.substitute_health
+    );
+
+    let turn_three = &decision_points[2].state;
+    assert_eq!(0, turn_three.side_one.attack_boost);
+    assert!(turn_three.side_two.volatile_statuses.is_empty());
+    assert_eq!(0, turn_three.side_two.substitute_health);
+    assert_eq!(PokemonIndex::P1, turn_three.side_two.active_index);
+    assert_eq!(
+        PokemonName::PUPITAR,
+        turn_three.side_two.get_active_immutable().id
+    );
+}
+
+#[test]
+fn test_malformed_lines_are_errors_with_their_line_number() {
+    for (log, message) in [
+        (
+            "|switch|p1a: Chompy|Garchomp, L100, M|100/100\n|faint|p3a: Chompy",
+            "Invalid side in pokemon identifier: p3a: Chompy",
+        ),
+        (
+            "|switch|p1a: Chompy|Garchomp, L100, M|100/100\n|-damage|p1a Chompy|50/100",
+            "Invalid pokemon identifier: p1a Chompy",
+        ),
+        (
+            "|switch|p1a: Chompy|Garchomp, L100, M|100/100\n|-damage|p1a: Chompy",
+            "Missing argument 3 of -damage",
+        ),
+    ] {
+        let mut parser =
+            ShowdownLogParser::new(SideReference::SideOne, &known_team(), POKEDEX_JSON).unwrap();
+        assert_eq!(
+            Err(LogParseError {
+                line: 2,
+                message: message.to_string()
+            }),
+            parser.parse_log(log)
+        );
+    }
+}
+
+#[test]
+fn test_unknown_commands_are_ignored() {
+    let mut parser =
+        ShowdownLogParser::new(SideReference::SideOne, &known_team(), POKEDEX_JSON).unwrap();
+    assert_eq!(Ok(()), parser.parse_line("|j|Charlie"));
+    assert_eq!(Ok(()), parser.parse_line("|-anim|p1a: Chompy|Earthquake"));
+    assert_eq!(Ok(()), parser.parse_line("not a protocol line"));
+}
+
+#[test]
+fn test_a_pokemon_missing_from_the_known_team_is_an_error() {
+    let log = "|switch|p1a: Chompy|Garchomp, L100, M|100/100
+|switch|p1a: Tyranitar|Tyranitar, L100, M|100/100";
+    assert_eq!(
+        Err("line 2: Tyranitar is not on the known team".to_string()),
+        parse_battle_log(log, SideReference::SideOne, &known_team(), POKEDEX_JSON)
+            .map(|decision_points| decision_points.len())
+    );
+}
+
+#[test]
+fn test_invalid_pokedex_json_is_an_error() {
+    assert!(ShowdownLogParser::new(SideReference::SideOne, &known_team(), "{").is_err());
+}
+
+#[test]
+fn test_a_pokedex_entry_without_types_is_an_error() {
+    let pokedex_json = r#"{"tyranitar": {"name": "Tyranitar", "types": [], "weightkg": 202,
+        "baseStats": {"hp": 100, "attack": 134, "defense": 110, "special-attack": 95,
+        "special-defense": 100, "speed": 61}}}"#;
+    let log = "|switch|p2a: Tyranitar|Tyranitar, L80, M|100/100";
+    assert_eq!(
+        Err("line 1: Pokedex entry has no types: Tyranitar".to_string()),
+        parse_battle_log(log, SideReference::SideOne, &known_team(), pokedex_json)
+            .map(|decision_points| decision_points.len())
+    );
+}
+
+#[test]
+fn test_slots_past_the_team_size_are_empty() {
+    let mut parser =
+        ShowdownLogParser::new(SideReference::SideOne, &known_team(), POKEDEX_JSON).unwrap();
+    assert_eq!(Ok(()), parser.parse_line("|teamsize|p2|3"));
+    let side_two = &parser.state.side_two;
+    assert!(side_two.pokemon[PokemonIndex::P2].hp > 0);
+    assert_eq!(0, side_two.pokemon[PokemonIndex::P3].hp);
+    assert_eq!(0, side_two.pokemon[PokemonIndex::P5].hp);
+
+    assert_eq!(
+        Ok(()),
+        parser.parse_line("|switch|p2a: Tyranitar|Tyranitar, L80, M|100/100")
+    );
+    assert_eq!(
+        Ok(()),
+        parser.parse_line("|switch|p2a: Pikachu|Pikachu, L80, M|100/100")
+    );
+    assert_eq!(
+        Ok(()),
+        parser.parse_line("|switch|p2a: Garchomp|Garchomp, L80, M|100/100")
+    );
+    assert_eq!(
+        Err("More pokemon revealed than fit on the team for SideTwo".to_string()),
+        parser.parse_line("|switch|p2a: Cresselia|Cresselia, L80, F|100/100")
+    );
+}
@@ -0,0 +1,242 @@
+#![cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+
+use poke_engine::choices::Choices;
+use poke_engine::engine::abilities::Abilities;
+use poke_engine::engine::battle_environment::{MoveDexEntry, PokemonDexEntry};
+use poke_engine::engine::items::Items;
+use poke_engine::pokemon::PokemonName;
+use poke_engine::showdown_team::{
+    parse_showdown_team, side_from_showdown_team, write_showdown_team, TeamParseError,
+};
+use poke_engine::state::{PokemonGender, PokemonIndex, PokemonMoveIndex, PokemonNature};
+use std::collections::HashMap;
+
+const POKEDEX_JSON: &str = include_str!("../data/pokedex.json");
+const MOVEDEX_JSON: &str = include_str!("../data/moves.json");
+
+const TEAM: &str = "Garchomp @ Choice Scarf
+Ability: Rough Skin
+Tera Type: Ground
+EVs: 252 Atk / 4 SpD / 252 Spe
+Jolly Nature
+- Earthquake
+- Outrage
+- Stone Edge
+- Fire Fang
+
+Toxapex @ Black Sludge
+Ability: Regenerator
+EVs: 252 HP / 252 Def / 4 SpD
+Bold Nature
+IVs: 0 Atk
+- Scald
+- Recover
+- Toxic Spikes
+- Haze
+";
+
+fn dex() -> (
+    HashMap<String, PokemonDexEntry>,
+    HashMap<String, MoveDexEntry>,
+) {
+    (
+        serde_json::from_str(POKEDEX_JSON).unwrap(),
+        serde_json::from_str(MOVEDEX_JSON).unwrap(),
+    )
+}
+
+fn error_lines(errors: &[TeamParseError]) -> Vec<usize> {
+    errors.iter().map(|e| e.line).collect()
+}
+
+#[test]
+fn test_parsing_an_exported_team() {
+    let team = parse_showdown_team(TEAM).unwrap();
+
+    assert_eq!(2, team.len());
+    assert_eq!("garchomp", team[0].nickname);
+    assert_eq!(None, team[0].species);
+    assert_eq!("choicescarf", team[0].item);
+    assert_eq!("roughskin", team[0].ability);
+    assert_eq!("ground", team[0].tera_type);
+    assert_eq!(vec![0, 252, 0, 0, 4, 252], team[0].evs);
+    assert_eq!("jolly", team[0].nature);
+    assert_eq!(
+        vec!["earthquake", "outrage", "stoneedge", "firefang"],
+        team[0].moves
+    );
+    assert_eq!("toxapex", team[1].nickname);
+    assert_eq!(vec![252, 0, 252, 0, 4, 0], team[1].evs);
+}
+
+#[test]
+fn test_parsing_a_nickname_and_gender() {
+    let team = parse_showdown_team(
+        "Chompy (Garchomp) (F) @ Life Orb
+- Hidden Power [Fire]",
+    )
+    .unwrap();
+
+    assert_eq!("chompy", team[0].nickname);
+    assert_eq!(Some("garchomp".to_string()), team[0].species);
+    assert_eq!("lifeorb", team[0].item);
+    assert_eq!("F", team[0].gender);
+    assert_eq!(1, team[0].moves.len());
+    assert!(team[0].moves[0].starts_with("hiddenpowerfire"));
+}
+
+#[test]
+fn test_every_bad_line_is_reported() {
+    let errors = parse_showdown_team(
+        "Garchomp @ Choice Scraf
+Ability: Rough Skin
+EVs: 252 Atk / 4 SpD / 300 Spe
+Jolly Nature
+- Earthquake
+
+Notapokemon @ Leftovers
+- Scald
+
+Toxapex
+Grumpy Nature
+- Scald
+- Recover
+- Toxic Spikes
+- Haze
+- Knock Off
+Favourite Colour: Blue",
+    )
+    .unwrap_err();
+
+    assert_eq!(vec![1, 7, 11, 16, 17], error_lines(&errors));
+    assert_eq!("Unknown item: Choice Scraf", errors[0].message);
+    assert_eq!("Unknown species: notapokemon", errors[1].message);
+    assert_eq!("Unknown nature: Grumpy", errors[2].message);
+    assert_eq!(
+        "line 16: A pokemon cannot have more than 4 moves",
+        errors[3].to_string()
+    );
+}
+
+#[test]
+fn test_bad_evs_are_reported() {
+    for evs in [
+        "EVs: 300 Atk",
+        "EVs: 252 Atk / 252 SpA / 252 Spe",
+        "EVs: 252 Attack",
+        "EVs: 252",
+    ] {
+        let errors = parse_showdown_team(&format!("Garchomp\n{}\n- Earthquake", evs)).unwrap_err();
+        assert_eq!(vec![2], error_lines(&errors), "{}", evs);
+    }
+}
+
+#[test]
+fn test_ivs_and_gender_are_used_when_building_the_side() {
+    let (pokedex, movedex) = dex();
+
+    let side = side_from_showdown_team(TEAM, &pokedex, &movedex).unwrap();
+
+    let toxapex = &side.pokemon[PokemonIndex::P1];
+    assert_eq!((31, 0, 31, 31, 31, 31), toxapex.ivs);
+    let without_ivs =
+        side_from_showdown_team(&TEAM.replace("IVs: 0 Atk\n", ""), &pokedex, &movedex).unwrap();
+    assert_eq!(
+        without_ivs.pokemon[PokemonIndex::P1].attack - 31,
+        toxapex.attack
+    );
+    let written = write_showdown_team(&side);
+    assert!(written.contains("IVs: 0 Atk"));
+
+    let side = side_from_showdown_team(
+        "Gardevoir (F)\n- Moonblast\n\nGallade\n- Sacred Sword\n\nMagnezone\n- Thunderbolt",
+        &pokedex,
+        &movedex,
+    )
+    .unwrap();
+    assert_eq!(PokemonGender::FEMALE, side.pokemon[PokemonIndex::P0].gender);
+    // species that are always the same gender do not need it to be given
+    assert_eq!(PokemonGender::MALE, side.pokemon[PokemonIndex::P1].gender);
+    assert_eq!(
+        PokemonGender::GENDERLESS,
+        side.pokemon[PokemonIndex::P2].gender
+    );
+    assert!(write_showdown_team(&side).starts_with("gardevoir (F)"));
+}
+
+#[test]
+fn test_bad_ivs_are_reported() {
+    for ivs in ["IVs: 32 Spe", "IVs: 0 Speed"] {
+        let errors = parse_showdown_team(&format!("Garchomp\n{}\n- Earthquake", ivs)).unwrap_err();
+        assert_eq!(vec![2], error_lines(&errors), "{}", ivs);
+    }
+}
+
+#[test]
+fn test_more_than_six_pokemon_is_an_error() {
+    let team = ["Garchomp\n- Earthquake"; 7].join("\n\n");
+
+    let errors = parse_showdown_team(&team).unwrap_err();
+
+    assert_eq!(vec![19], error_lines(&errors));
+}
+
+#[test]
+fn test_an_empty_team_is_an_error() {
+    assert!(parse_showdown_team("\n\n").is_err());
+}
+
+#[test]
+fn test_side_from_a_team_pads_it_with_fainted_pokemon() {
+    let (pokedex, movedex) = dex();
+
+    let side = side_from_showdown_team(TEAM, &pokedex, &movedex).unwrap();
+
+    let garchomp = &side.pokemon[PokemonIndex::P0];
+    assert_eq!(PokemonName::GARCHOMP, garchomp.id);
+    assert_eq!(Items::CHOICESCARF, garchomp.item);
+    assert_eq!(Abilities::ROUGHSKIN, garchomp.ability);
+    assert_eq!(PokemonNature::JOLLY, garchomp.nature);
+    assert_eq!(Choices::STONEEDGE, garchomp.moves[&PokemonMoveIndex::M2].id);
+    assert_eq!(PokemonName::TOXAPEX, side.pokemon[PokemonIndex::P1].id);
+    for pkmn_index in [
+        PokemonIndex::P2,
+        PokemonIndex::P3,
+        PokemonIndex::P4,
+        PokemonIndex::P5,
+    ] {
+        assert_eq!(0, side.pokemon[pkmn_index].hp);
+    }
+}
+
+#[test]
+fn test_writing_a_side_and_parsing_it_again() {
+    let (pokedex, movedex) = dex();
+    let side = side_from_showdown_team(TEAM, &pokedex, &movedex).unwrap();
+
+    let written = write_showdown_team(&side);
+    let reparsed = side_from_showdown_team(&written, &pokedex, &movedex).unwrap();
+
+    assert_eq!(format!("{:?}", side), format!("{:?}", reparsed));
+}
+
+#[test]
+fn test_gigantamax_is_parsed_and_written() {
+    let (pokedex, movedex) = dex();
+    let side = side_from_showdown_team(
+        "Charizard\nGigantamax: Yes\n- Flamethrower\n\nVenusaur\nGigantamax: No\n- Giga Drain",
+        &pokedex,
+        &movedex,
+    )
+    .unwrap();
+    assert!(side.pokemon[PokemonIndex::P0].gigantamax);
+    assert!(!side.pokemon[PokemonIndex::P1].gigantamax);
+
+    let written = write_showdown_team(&side);
+    assert_eq!(1, written.matches("Gigantamax: Yes").count());
+    let reparsed = side_from_showdown_team(&written, &pokedex, &movedex).unwrap();
+    assert_eq!(format!("{:?}", side), format!("{:?}", reparsed));
+
+    let errors = parse_showdown_team("Charizard\nGigantamax: Maybe\n- Flamethrower").unwrap_err();
+    assert_eq!(vec![2], error_lines(&errors));
+}
@@ -0,0 +1,112 @@
+#![cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+
+use poke_engine::choices::Choices;
+use poke_engine::engine::battle_environment::initialize_battle_state;
+use poke_engine::engine::state::{MoveChoice, PokemonVolatileStatus, Weather};
+use poke_engine::rng::seeded_rng;
+use poke_engine::state::{
+    Move, Pokemon, PokemonIndex, PokemonMoveIndex, PokemonStatus, PositionState, State,
+};
+
+const RANDOM_TEAMS_JSON: &str = include_str!("../data/random_teams.json");
+const POKEDEX_JSON: &str = include_str!("../data/pokedex.json");
+const MOVEDEX_JSON: &str = include_str!("../data/moves.json");
+
+fn state_mid_battle() -> State {
+    let mut state = initialize_battle_state(
+        RANDOM_TEAMS_JSON,
+        POKEDEX_JSON,
+        MOVEDEX_JSON,
+        &mut seeded_rng(1),
+    );
+    state.weather.weather_type = Weather::RAIN;
+    state.weather.turns_remaining = 3;
+    state.side_one.get_active().status = PokemonStatus::BURN;
+    state.side_one.get_active().moves[&PokemonMoveIndex::M1].disabled = true;
+    state.side_one.attack_boost = -2;
+    state.side_one.wish = (2, 150);
+    state.side_one.side_conditions.stealth_rock = 1;
+    state
+        .side_two
+        .volatile_statuses
+        .insert(PokemonVolatileStatus::CONFUSION);
+    state.side_two.volatile_status_durations.confusion = 2;
+    state
+}
+
+#[test]
+fn test_state_round_trips_through_json() {
+    let state = state_mid_battle();
+
+    let from_json = State::from_json(&state.to_json()).unwrap();
+
+    assert_eq!(state.serialize(), from_json.serialize());
+    assert_eq!(format!("{:?}", state), format!("{:?}", from_json));
+}
+
+#[test]
+fn test_doubles_state_round_trips_through_json() {
+    let mut state = state_mid_battle();
+    let mut ally_position = PositionState::new(PokemonIndex::P1);
+    ally_position.speed_boost = 1;
+    state.side_one.ally_position = Some(ally_position);
+    state.side_two.ally_position = Some(PositionState::new(PokemonIndex::P1));
+
+    let from_json = State::from_json(&state.to_json()).unwrap();
+
+    assert_eq!(state.serialize(), from_json.serialize());
+    assert_eq!(
+        1,
+        from_json
+            .side_one
+            .ally_position
+            .as_ref()
+            .unwrap()
+            .speed_boost
+    );
+}
+
+#[test]
+fn test_json_state_has_the_same_hash() {
+    let mut state = state_mid_battle();
+
+    let mut from_json = State::from_json(&state.to_json()).unwrap();
+
+    assert_eq!(state.rehash(), from_json.get_hash());
+}
+
+#[test]
+fn test_moves_are_written_without_their_choice() {
+    let mut pokemon = Pokemon::default();
+    pokemon.replace_move(PokemonMoveIndex::M0, Choices::THUNDERBOLT);
+    let thunderbolt = &pokemon.moves[&PokemonMoveIndex::M0];
+
+    let json = serde_json::to_string(thunderbolt).unwrap();
+    let from_json: Move = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(r#"{"id":"THUNDERBOLT","disabled":false,"pp":32}"#, json);
+    assert_eq!(format!("{:?}", thunderbolt), format!("{:?}", from_json));
+}
+
+#[test]
+fn test_move_choices_round_trip_through_json() {
+    for move_choice in [
+        MoveChoice::Move(PokemonMoveIndex::M2),
+        MoveChoice::Switch(PokemonIndex::P4),
+        MoveChoice::None,
+    ] {
+        let json = serde_json::to_string(&move_choice).unwrap();
+        assert_eq!(move_choice, serde_json::from_str(&json).unwrap());
+    }
+}
+
+#[test]
+fn test_invalid_json_is_an_error() {
+    assert!(State::from_json("{}").is_err());
+    assert!(State::from_json("not json").is_err());
+
+    let json = State::default()
+        .to_json()
+        .replace("\"NONE\"", "\"NOTAWEATHER\"");
+    assert!(State::from_json(&json).is_err());
+}
@@ -0,0 +1,136 @@
+#![cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+
+use poke_engine::engine::battle_environment::initialize_battle_state;
+use poke_engine::rng::seeded_rng;
+use poke_engine::state::{PokemonIndex, SideReference, State, StateParseError};
+
+const RANDOM_TEAMS_JSON: &str = include_str!("../data/random_teams.json");
+const POKEDEX_JSON: &str = include_str!("../data/pokedex.json");
+const MOVEDEX_JSON: &str = include_str!("../data/moves.json");
+
+fn serialized_state() -> String {
+    initialize_battle_state(
+        RANDOM_TEAMS_JSON,
+        POKEDEX_JSON,
+        MOVEDEX_JSON,
+        &mut seeded_rng(1),
+    )
+    .serialize()
+}
+
+// replaces one `,` separated field of a pokemon in a serialized state
+fn with_pokemon_field(
+    serialized: &str,
+    side_index: usize,
+    pkmn_index: usize,
+    field_index: usize,
+    value: &str,
+) -> String {
+    let mut sides: Vec<String> = serialized.split("/").map(|s| s.to_string()).collect();
+    let mut side: Vec<String> = sides[side_index]
+        .split("=")
+        .map(|s| s.to_string())
+        .collect();
+    let mut pokemon: Vec<&str> = side[pkmn_index].split(",").collect();
+    pokemon[field_index] = value;
+    side[pkmn_index] = pokemon.join(",");
+    sides[side_index] = side.join("=");
+    sides.join("/")
+}
+
+#[test]
+fn test_a_valid_state_deserializes() {
+    let serialized = serialized_state();
+
+    let state = State::try_deserialize(&serialized).unwrap();
+
+    assert_eq!(serialized, state.serialize());
+}
+
+#[test]
+fn test_invalid_pokemon_field_names_the_side_and_pokemon() {
+    let serialized = with_pokemon_field(&serialized_state(), 1, 3, 6, "abc");
+
+    let error = State::try_deserialize(&serialized).unwrap_err();
+
+    assert_eq!(
+        StateParseError {
+            side: Some(SideReference::SideTwo),
+            pokemon_index: Some(PokemonIndex::P3),
+            field: "hp".to_string(),
+            token: "abc".to_string(),
+        },
+        error
+    );
+    assert_eq!("side two, pokemon 3, invalid hp: abc", error.to_string());
+}
+
+#[test]
+fn test_invalid_move_names_the_move_slot() {
+    let serialized = with_pokemon_field(&serialized_state(), 0, 0, 23, "TACKLE;false;lots");
+
+    let error = State::try_deserialize(&serialized).unwrap_err();
+
+    assert_eq!(
+        "side one, pokemon 0, invalid m1.pp: lots",
+        error.to_string()
+    );
+}
+
+#[test]
+fn test_missing_fields_are_errors() {
+    let serialized = serialized_state();
+    let without_team_preview = serialized.rsplit_once("/").unwrap().0;
+
+    let error = State::try_deserialize(without_team_preview).unwrap_err();
+
+    assert_eq!("missing team_preview", error.to_string());
+
+    let side_one = serialized.split("/").next().unwrap();
+    assert_eq!(
+        "missing side_two",
+        State::try_deserialize(side_one).unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn test_invalid_enum_values_are_errors() {
+    let serialized = serialized_state();
+
+    let bad_weather = serialized.replacen("/NONE;", "/NOTAWEATHER;", 1);
+    assert_eq!(
+        "invalid weather.weather_type: NOTAWEATHER",
+        State::try_deserialize(&bad_weather)
+            .unwrap_err()
+            .to_string()
+    );
+
+    let bad_status = with_pokemon_field(&serialized, 0, 1, 18, "SLEEPY");
+    assert_eq!(
+        "side one, pokemon 1, invalid status: SLEEPY",
+        State::try_deserialize(&bad_status).unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn test_invalid_side_field_names_the_side() {
+    let serialized = serialized_state();
+    let mut sides: Vec<String> = serialized.split("/").map(|s| s.to_string()).collect();
+    let mut side: Vec<&str> = sides[0].split("=").collect();
+    side[27] = "move:7";
+    sides[0] = side.join("=");
+
+    let error = State::try_deserialize(&sides.join("/")).unwrap_err();
+
+    assert_eq!(
+        "side one, invalid last_used_move: move:7",
+        error.to_string()
+    );
+}
+
+#[test]
+#[should_panic(expected = "side one, pokemon 0, invalid level: high")]
+fn test_deserialize_panics_with_the_parse_error() {
+    let serialized = with_pokemon_field(&serialized_state(), 0, 0, 1, "high");
+    State::deserialize(&serialized);
+}
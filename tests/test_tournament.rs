@@ -0,0 +1,175 @@
+#![cfg(not(any(feature = "gen1", feature = "gen2", feature = "gen3")))]
+
+use poke_engine::engine::battle_environment::{FirstMovePlayer, RandomPlayer};
+use poke_engine::state::SideReference;
+use poke_engine::tournament::{
+    run_tournament, HeadToHead, TournamentConfig, TournamentGame, TournamentPlayer,
+    TournamentResults, INITIAL_RATING,
+};
+
+const RANDOM_TEAMS_JSON: &str = include_str!("../data/random_teams.json");
+const POKEDEX_JSON: &str = include_str!("../data/pokedex.json");
+const MOVEDEX_JSON: &str = include_str!("../data/moves.json");
+
+fn game(side_one: usize, side_two: usize, winner: Option<SideReference>) -> TournamentGame {
+    TournamentGame {
+        side_one,
+        side_two,
+        state_index: 0,
+        seed: 0,
+        winner,
+        turn_count: 10,
+    }
+}
+
+fn players() -> Vec<TournamentPlayer> {
+    vec![
+        TournamentPlayer::new("random", || {
+            Box::new(RandomPlayer::new("random".to_string()))
+        }),
+        TournamentPlayer::new("firstmove", || {
+            Box::new(FirstMovePlayer::new("firstmove".to_string()))
+        }),
+        TournamentPlayer::new("random-2", || {
+            Box::new(RandomPlayer::new("random-2".to_string()))
+        }),
+    ]
+}
+
+fn config(state_count: usize, seed: u64) -> TournamentConfig {
+    let mut config = TournamentConfig::with_random_states(
+        state_count,
+        RANDOM_TEAMS_JSON,
+        POKEDEX_JSON,
+        MOVEDEX_JSON,
+        seed,
+    );
+    config.threads = 2;
+    config
+}
+
+#[test]
+fn test_head_to_head_records_are_counted_from_both_sides() {
+    let names = vec!["a".to_string(), "b".to_string()];
+    let games = vec![
+        game(0, 1, Some(SideReference::SideOne)),
+        game(1, 0, Some(SideReference::SideOne)),
+        game(1, 0, Some(SideReference::SideTwo)),
+        game(0, 1, None),
+    ];
+
+    let results = TournamentResults::from_games(names, games);
+
+    let expected = HeadToHead {
+        wins: 2,
+        losses: 1,
+        draws: 1,
+    };
+    assert_eq!(expected, results.head_to_head[0][1]);
+    assert_eq!(
+        HeadToHead {
+            wins: 1,
+            losses: 2,
+            draws: 1,
+        },
+        results.head_to_head[1][0]
+    );
+    assert_eq!(expected, results.rating("a").unwrap().record);
+    assert_eq!(2.5, expected.score());
+}
+
+#[test]
+fn test_even_players_are_rated_the_same() {
+    let names = vec!["a".to_string(), "b".to_string()];
+    let games = vec![
+        game(0, 1, Some(SideReference::SideOne)),
+        game(1, 0, Some(SideReference::SideOne)),
+    ];
+
+    let results = TournamentResults::from_games(names, games);
+
+    for rating in results.ratings.iter() {
+        assert!((rating.elo - INITIAL_RATING).abs() < 0.001);
+        assert!((rating.glicko - INITIAL_RATING).abs() < 0.001);
+    }
+}
+
+#[test]
+fn test_the_stronger_player_is_rated_higher() {
+    let names = vec![
+        "weak".to_string(),
+        "strong".to_string(),
+        "perfect".to_string(),
+    ];
+    let mut games = vec![];
+    for _ in 0..10 {
+        games.push(game(0, 1, Some(SideReference::SideTwo)));
+        games.push(game(1, 0, Some(SideReference::SideOne)));
+        games.push(game(0, 1, Some(SideReference::SideOne)));
+        games.push(game(2, 0, Some(SideReference::SideOne)));
+        games.push(game(2, 1, Some(SideReference::SideOne)));
+    }
+
+    let results = TournamentResults::from_games(names, games);
+
+    let ranked: Vec<&str> = results.ratings.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(vec!["perfect", "strong", "weak"], ranked);
+    let strong = results.rating("strong").unwrap();
+    let weak = results.rating("weak").unwrap();
+    assert!(strong.glicko > weak.glicko);
+    // a player who won every game still gets a finite rating
+    assert!(results.rating("perfect").unwrap().elo.is_finite());
+    for rating in results.ratings.iter() {
+        assert!(rating.elo_interval.0 < rating.elo && rating.elo < rating.elo_interval.1);
+        let glicko_interval = rating.glicko_interval();
+        assert!(glicko_interval.0 < rating.glicko && rating.glicko < glicko_interval.1);
+    }
+}
+
+#[test]
+fn test_every_pairing_plays_every_state_from_both_sides() {
+    let players = players();
+
+    let results = run_tournament(&players, &config(2, 5));
+
+    assert_eq!(3 * 2 * 2, results.games.len());
+    for first in 0..players.len() {
+        for second in 0..players.len() {
+            if first == second {
+                continue;
+            }
+            for state_index in 0..2 {
+                let count = results
+                    .games
+                    .iter()
+                    .filter(|g| {
+                        g.side_one == first && g.side_two == second && g.state_index == state_index
+                    })
+                    .count();
+                assert_eq!(1, count);
+            }
+            assert_eq!(4, results.head_to_head[first][second].games());
+        }
+    }
+}
+
+#[test]
+fn test_the_same_seed_gives_the_same_tournament() {
+    let first = run_tournament(&players(), &config(2, 9));
+    let second = run_tournament(&players(), &config(2, 9));
+
+    assert_eq!(first.games, second.games);
+    assert_eq!(first.ratings, second.ratings);
+}
+
+#[test]
+fn test_report_has_every_player() {
+    let results = run_tournament(&players(), &config(1, 1));
+
+    let report = results.report();
+
+    for name in ["random", "firstmove", "random-2"] {
+        assert!(report.lines().any(|line| line.starts_with(name)));
+    }
+    assert!(report.contains("Head to head"));
+}
@@ -0,0 +1,367 @@
+#![cfg(feature = "gen7")]
+
+use poke_engine::choices::Choices;
+use poke_engine::engine::generate_instructions::generate_instructions_from_move_pair;
+use poke_engine::engine::items::Items;
+use poke_engine::engine::state::{MoveChoice, PokemonVolatileStatus, Terrain};
+use poke_engine::instruction::{
+    ApplyVolatileStatusInstruction, BoostInstruction, ChangeTerrain, DamageInstruction,
+    HealInstruction, Instruction, StateInstructions, ToggleZMoveUsedInstruction,
+};
+use poke_engine::pokemon::PokemonName;
+use poke_engine::state::{PokemonBoostableStat, PokemonMoveIndex, SideReference, State};
+
+fn z_crystal_state(item: Items, z_move: Choices) -> State {
+    let mut state = State::default();
+    let active = state.side_one.get_active();
+    active.item = item;
+    active.replace_move(PokemonMoveIndex::M0, z_move);
+    active.replace_move(PokemonMoveIndex::M1, Choices::EMBER);
+    state
+        .side_two
+        .get_active()
+        .replace_move(PokemonMoveIndex::M0, Choices::SPLASH);
+    state
+}
+
+fn generate_instructions(state: &mut State, side_one_move: MoveChoice) -> Vec<StateInstructions> {
+    let before = format!("{:?}", state);
+    let instructions = generate_instructions_from_move_pair(
+        state,
+        &side_one_move,
+        &MoveChoice::Move(PokemonMoveIndex::M0),
+        false,
+    );
+    assert_eq!(before, format!("{:?}", state));
+    instructions
+}
+
+fn z_move_used() -> Instruction {
+    Instruction::ToggleZMoveUsed(ToggleZMoveUsedInstruction {
+        side_ref: SideReference::SideOne,
+    })
+}
+
+fn boost(stat: PokemonBoostableStat, amount: i8) -> Instruction {
+    Instruction::Boost(BoostInstruction {
+        side_ref: SideReference::SideOne,
+        stat,
+        amount,
+    })
+}
+
+// the damage side one's M0 does when it is a regular move with `base_power`
+fn damage_with_base_power(mut state: State, base_power: f32) -> Vec<Instruction> {
+    state.side_one.get_active().moves[&PokemonMoveIndex::M0]
+        .choice
+        .base_power = base_power;
+    let instructions = generate_instructions(&mut state, MoveChoice::Move(PokemonMoveIndex::M0));
+    assert_eq!(1, instructions.len());
+    instructions[0].instruction_list.clone()
+}
+
+fn damage_dealt_to_side_two(instruction_list: &[Instruction]) -> i16 {
+    instruction_list
+        .iter()
+        .filter_map(|i| match i {
+            Instruction::Damage(d) if d.side_ref == SideReference::SideTwo => Some(d.damage_amount),
+            _ => None,
+        })
+        .sum()
+}
+
+#[test]
+fn test_z_move_is_offered_for_moves_matching_the_z_crystal() {
+    let state = z_crystal_state(Items::NORMALIUMZ, Choices::TACKLE);
+    let (side_one_options, side_two_options) = state.get_all_options();
+
+    assert!(side_one_options.contains(&MoveChoice::MoveZ(PokemonMoveIndex::M0)));
+    assert!(!side_one_options.contains(&MoveChoice::MoveZ(PokemonMoveIndex::M1)));
+    assert!(!side_two_options
+        .iter()
+        .any(|m| matches!(m, MoveChoice::MoveZ(_))));
+    assert_eq!(
+        "tackle-zmove",
+        MoveChoice::MoveZ(PokemonMoveIndex::M0).to_string(&state.side_one)
+    );
+    assert_eq!(
+        Some(MoveChoice::MoveZ(PokemonMoveIndex::M0)),
+        MoveChoice::from_string("tackle-zmove", &state.side_one)
+    );
+}
+
+#[test]
+fn test_z_move_can_only_be_used_once_per_side() {
+    let mut state = z_crystal_state(Items::NORMALIUMZ, Choices::TACKLE);
+    let instructions = generate_instructions(&mut state, MoveChoice::MoveZ(PokemonMoveIndex::M0));
+    assert_eq!(z_move_used(), instructions[0].instruction_list[0]);
+
+    state.apply_instructions(&instructions[0].instruction_list);
+    assert!(state.side_one.z_move_used);
+    let (side_one_options, _) = state.get_all_options();
+    assert!(!side_one_options
+        .iter()
+        .any(|m| matches!(m, MoveChoice::MoveZ(_))));
+
+    state.reverse_instructions(&instructions[0].instruction_list);
+    assert!(!state.side_one.z_move_used);
+}
+
+#[test]
+fn test_damaging_z_move_base_power_comes_from_the_move_it_replaces() {
+    let mut state = z_crystal_state(Items::NORMALIUMZ, Choices::TACKLE);
+    let mut expected_instructions = vec![z_move_used()];
+    // tackle has 40 base power so breakneck blitz has 100
+    expected_instructions.extend(damage_with_base_power(state.clone(), 100.0));
+
+    let instructions = generate_instructions(&mut state, MoveChoice::MoveZ(PokemonMoveIndex::M0));
+    assert_eq!(1, instructions.len());
+    assert_eq!(expected_instructions, instructions[0].instruction_list);
+}
+
+#[test]
+fn test_signature_z_move_needs_its_pokemon_and_move() {
+    let mut state = z_crystal_state(Items::PIKANIUMZ, Choices::VOLTTACKLE);
+    let (side_one_options, _) = state.get_all_options();
+    assert!(!side_one_options
+        .iter()
+        .any(|m| matches!(m, MoveChoice::MoveZ(_))));
+
+    state.side_one.get_active().id = PokemonName::PIKACHU;
+    let (side_one_options, _) = state.get_all_options();
+    assert!(side_one_options.contains(&MoveChoice::MoveZ(PokemonMoveIndex::M0)));
+
+    let instructions = generate_instructions(&mut state, MoveChoice::MoveZ(PokemonMoveIndex::M0));
+    assert_eq!(1, instructions.len());
+    // catastropika has no recoil
+    assert_eq!(
+        vec![
+            z_move_used(),
+            Instruction::Damage(DamageInstruction {
+                side_ref: SideReference::SideTwo,
+                damage_amount: 100,
+            }),
+        ],
+        instructions[0].instruction_list
+    );
+}
+
+#[test]
+fn test_z_splash_boosts_attack() {
+    let mut state = z_crystal_state(Items::NORMALIUMZ, Choices::SPLASH);
+    let instructions = generate_instructions(&mut state, MoveChoice::MoveZ(PokemonMoveIndex::M0));
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![z_move_used(), boost(PokemonBoostableStat::Attack, 3)],
+    }];
+    assert_eq!(expected_instructions, instructions);
+}
+
+#[test]
+fn test_z_swords_dance_clears_negative_boosts_before_boosting() {
+    let mut state = z_crystal_state(Items::NORMALIUMZ, Choices::SWORDSDANCE);
+    state.side_one.attack_boost = -2;
+    state.side_one.speed_boost = -1;
+    state.side_one.special_attack_boost = 1;
+    let instructions = generate_instructions(&mut state, MoveChoice::MoveZ(PokemonMoveIndex::M0));
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![
+            z_move_used(),
+            boost(PokemonBoostableStat::Attack, 2),
+            boost(PokemonBoostableStat::Speed, 1),
+            boost(PokemonBoostableStat::Attack, 2),
+        ],
+    }];
+    assert_eq!(expected_instructions, instructions);
+}
+
+#[test]
+fn test_z_belly_drum_heals_before_cutting_hp() {
+    let mut state = z_crystal_state(Items::NORMALIUMZ, Choices::BELLYDRUM);
+    state.side_one.get_active().hp = 40;
+    let instructions = generate_instructions(&mut state, MoveChoice::MoveZ(PokemonMoveIndex::M0));
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![
+            z_move_used(),
+            Instruction::Heal(HealInstruction {
+                side_ref: SideReference::SideOne,
+                heal_amount: 60,
+            }),
+            Instruction::Damage(DamageInstruction {
+                side_ref: SideReference::SideOne,
+                damage_amount: 50,
+            }),
+            boost(PokemonBoostableStat::Attack, 6),
+        ],
+    }];
+    assert_eq!(expected_instructions, instructions);
+}
+
+#[test]
+fn test_regular_status_move_does_not_get_the_z_power_effect() {
+    let mut state = z_crystal_state(Items::NORMALIUMZ, Choices::SPLASH);
+    let instructions = generate_instructions(&mut state, MoveChoice::Move(PokemonMoveIndex::M0));
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![],
+    }];
+    assert_eq!(expected_instructions, instructions);
+}
+
+#[test]
+fn test_extreme_evoboost_boosts_every_stat() {
+    let mut state = z_crystal_state(Items::EEVIUMZ, Choices::LASTRESORT);
+    state.side_one.get_active().id = PokemonName::EEVEE;
+    let instructions = generate_instructions(&mut state, MoveChoice::MoveZ(PokemonMoveIndex::M0));
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![
+            z_move_used(),
+            boost(PokemonBoostableStat::Attack, 2),
+            boost(PokemonBoostableStat::Defense, 2),
+            boost(PokemonBoostableStat::SpecialAttack, 2),
+            boost(PokemonBoostableStat::SpecialDefense, 2),
+            boost(PokemonBoostableStat::Speed, 2),
+        ],
+    }];
+    assert_eq!(expected_instructions, instructions);
+}
+
+#[test]
+fn test_guardian_of_alola_deals_three_quarters_of_the_current_hp() {
+    let mut state = z_crystal_state(Items::TAPUNIUMZ, Choices::NATURESMADNESS);
+    state.side_one.get_active().id = PokemonName::TAPUKOKO;
+    state.side_two.get_active().hp = 80;
+    let instructions = generate_instructions(&mut state, MoveChoice::MoveZ(PokemonMoveIndex::M0));
+
+    let expected_instructions = vec![StateInstructions {
+        percentage: 100.0,
+        instruction_list: vec![
+            z_move_used(),
+            Instruction::Damage(DamageInstruction {
+                side_ref: SideReference::SideTwo,
+                damage_amount: 60,
+            }),
+        ],
+    }];
+    assert_eq!(expected_instructions, instructions);
+}
+
+#[test]
+fn test_genesis_supernova_sets_psychic_terrain() {
+    let mut state = z_crystal_state(Items::MEWNIUMZ, Choices::PSYCHIC);
+    state.side_one.get_active().id = PokemonName::MEW;
+    state.side_two.get_active().maxhp = 500;
+    state.side_two.get_active().hp = 500;
+    let instructions = generate_instructions(&mut state, MoveChoice::MoveZ(PokemonMoveIndex::M0));
+
+    assert_eq!(1, instructions.len());
+    assert!(instructions[0]
+        .instruction_list
+        .contains(&Instruction::ChangeTerrain(ChangeTerrain {
+            new_terrain: Terrain::PSYCHICTERRAIN,
+            new_terrain_turns_remaining: 5,
+            previous_terrain: Terrain::NONE,
+            previous_terrain_turns_remaining: 0,
+        })));
+}
+
+#[test]
+fn test_z_crystals_cannot_be_removed() {
+    let mut state = z_crystal_state(Items::NORMALIUMZ, Choices::TACKLE);
+    assert!(state.side_one.get_active_immutable().item_is_permanent());
+    state.side_one.get_active().item = Items::LEFTOVERS;
+    assert!(!state.side_one.get_active_immutable().item_is_permanent());
+}
+
+#[test]
+fn test_z_moves_break_through_protect_for_a_quarter_of_their_damage() {
+    let mut state = z_crystal_state(Items::NORMALIUMZ, Choices::TACKLE);
+    // breakneck blitz has 100 base power
+    let expected_damage = damage_dealt_to_side_two(&damage_with_base_power(state.clone(), 25.0));
+    state
+        .side_two
+        .get_active()
+        .replace_move(PokemonMoveIndex::M0, Choices::PROTECT);
+    let instructions = generate_instructions(&mut state, MoveChoice::MoveZ(PokemonMoveIndex::M0));
+
+    assert_eq!(1, instructions.len());
+    assert!(expected_damage > 0);
+    assert_eq!(
+        expected_damage,
+        damage_dealt_to_side_two(&instructions[0].instruction_list)
+    );
+}
+
+#[test]
+fn test_z_status_moves_are_still_stopped_by_protect() {
+    let mut state = z_crystal_state(Items::ELECTRIUMZ, Choices::THUNDERWAVE);
+    state
+        .side_two
+        .get_active()
+        .replace_move(PokemonMoveIndex::M0, Choices::PROTECT);
+    let instructions = generate_instructions(&mut state, MoveChoice::MoveZ(PokemonMoveIndex::M0));
+
+    assert_eq!(1, instructions.len());
+    assert!(instructions[0]
+        .instruction_list
+        .contains(&boost(PokemonBoostableStat::SpecialDefense, 1)));
+    assert!(!instructions[0]
+        .instruction_list
+        .iter()
+        .any(|i| matches!(i, Instruction::ChangeStatus(_))));
+}
+
+#[test]
+fn test_guardian_of_alola_deals_a_quarter_of_its_damage_through_protect() {
+    let mut state = z_crystal_state(Items::TAPUNIUMZ, Choices::NATURESMADNESS);
+    state.side_one.get_active().id = PokemonName::TAPUKOKO;
+    state.side_two.get_active().hp = 80;
+    state
+        .side_two
+        .get_active()
+        .replace_move(PokemonMoveIndex::M0, Choices::PROTECT);
+    let instructions = generate_instructions(&mut state, MoveChoice::MoveZ(PokemonMoveIndex::M0));
+
+    assert_eq!(1, instructions.len());
+    // three quarters of 80 is 60, and a quarter of that goes through
+    assert_eq!(
+        15,
+        damage_dealt_to_side_two(&instructions[0].instruction_list)
+    );
+}
+
+#[test]
+fn test_z_destiny_bond_draws_attacks_like_follow_me() {
+    let mut state = z_crystal_state(Items::GHOSTIUMZ, Choices::DESTINYBOND);
+    let instructions = generate_instructions(&mut state, MoveChoice::MoveZ(PokemonMoveIndex::M0));
+
+    assert_eq!(1, instructions.len());
+    assert_eq!(z_move_used(), instructions[0].instruction_list[0]);
+    assert!(instructions[0]
+        .instruction_list
+        .contains(&Instruction::ApplyVolatileStatus(
+            ApplyVolatileStatusInstruction {
+                side_ref: SideReference::SideOne,
+                volatile_status: PokemonVolatileStatus::FOLLOWME,
+            }
+        )));
+}
+
+#[test]
+fn test_status_moves_with_an_unsupported_z_power_effect_are_not_offered_as_z_moves() {
+    let state = z_crystal_state(Items::DARKINIUMZ, Choices::MEMENTO);
+    let (side_one_options, _) = state.get_all_options();
+
+    assert!(side_one_options.contains(&MoveChoice::Move(PokemonMoveIndex::M0)));
+    assert!(!side_one_options
+        .iter()
+        .any(|m| matches!(m, MoveChoice::MoveZ(_))));
+}